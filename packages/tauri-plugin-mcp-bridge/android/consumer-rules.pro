@@ -0,0 +1,2 @@
+# Loaded by name and called over JNI from the Rust capture code
+-keep class app.tauri.mcpbridge.PixelCopyCapture { *; }
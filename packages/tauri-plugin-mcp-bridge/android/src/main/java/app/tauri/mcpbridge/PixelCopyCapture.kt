@@ -0,0 +1,87 @@
+package app.tauri.mcpbridge
+
+import android.app.Activity
+import android.graphics.Bitmap
+import android.graphics.Matrix
+import android.graphics.Rect
+import android.os.Build
+import android.os.Handler
+import android.os.HandlerThread
+import android.view.PixelCopy
+import android.view.View
+import java.io.ByteArrayOutputStream
+
+/**
+ * Copies the rendered pixels of a view from its activity window with
+ * [PixelCopy], for the `pixelCopy` Android capture strategy.
+ *
+ * Called from Rust over JNI. Each result is reported through
+ * [onCaptureFinished], which the plugin registers as a native method before
+ * the first request.
+ */
+object PixelCopyCapture {
+    private val handler: Handler by lazy {
+        val thread = HandlerThread("mcp-bridge-pixel-copy")
+        thread.start()
+        Handler(thread.looper)
+    }
+
+    /** Delivers the PNG bytes, or an error message, for [requestId]. */
+    @JvmStatic
+    external fun onCaptureFinished(requestId: Long, png: ByteArray?, error: String?)
+
+    /**
+     * Requests a copy of [view]'s area of the activity window, turned a
+     * quarter clockwise when [rotate] is set.
+     *
+     * Returns false without requesting anything below Android 8.0, where
+     * PixelCopy can't copy from a window.
+     */
+    @JvmStatic
+    fun request(activity: Activity, view: View, rotate: Boolean, requestId: Long): Boolean {
+        if (Build.VERSION.SDK_INT < Build.VERSION_CODES.O) {
+            return false
+        }
+
+        val location = IntArray(2)
+        view.getLocationInWindow(location)
+        val source = Rect(
+            location[0],
+            location[1],
+            location[0] + view.width,
+            location[1] + view.height
+        )
+        val bitmap = Bitmap.createBitmap(view.width, view.height, Bitmap.Config.ARGB_8888)
+
+        PixelCopy.request(activity.window, source, bitmap, { result ->
+            try {
+                if (result == PixelCopy.SUCCESS) {
+                    onCaptureFinished(requestId, encodePng(bitmap, rotate), null)
+                } else {
+                    onCaptureFinished(requestId, null, "PixelCopy failed with result $result")
+                }
+            } catch (e: Exception) {
+                onCaptureFinished(requestId, null, "Failed to encode PixelCopy result: $e")
+            } finally {
+                bitmap.recycle()
+            }
+        }, handler)
+        return true
+    }
+
+    private fun encodePng(bitmap: Bitmap, rotate: Boolean): ByteArray {
+        val output = if (rotate) {
+            val matrix = Matrix().apply { postRotate(90f) }
+            Bitmap.createBitmap(bitmap, 0, 0, bitmap.width, bitmap.height, matrix, true)
+        } else {
+            bitmap
+        }
+
+        val stream = ByteArrayOutputStream()
+        output.compress(Bitmap.CompressFormat.PNG, 100, stream)
+        if (output !== bitmap) {
+            output.recycle()
+        }
+        return stream.toByteArray()
+    }
+}
@@ -7,6 +7,15 @@ fn main() {
         "start_ipc_monitor",
         "stop_ipc_monitor",
         "get_ipc_events",
+        "capture_screen",
+        "start_screenshot_stream",
+        "stop_screenshot_stream",
+        "start_capture_stream",
+        "ack_capture_frame",
+        "stop_capture_stream",
+        "record_network_entry",
+        "record_console_entries",
     ])
+    .android_path("android")
     .build();
 }
@@ -0,0 +1,1440 @@
+// This file was generated by tauri-plugin-mcp-bridge. Do not edit it by hand.
+
+export type ScriptType = "inline" | "url" | "module";
+
+export type InjectionPhase = "setup" | "main" | "teardown";
+
+export type RunAt = "documentEnd" | "documentStart";
+
+export type ScriptEntry = { 
+/**
+ * Unique identifier for this script.
+ */
+id: string, 
+/**
+ * Type of script (inline code or external URL).
+ */
+script_type: ScriptType, 
+/**
+ * The script content (JavaScript code) or URL.
+ */
+content: string, 
+/**
+ * When the script was added to the registry (epoch millis).
+ */
+created_at: number | null, 
+/**
+ * When the script was last injected into a webview (epoch millis).
+ */
+last_injected_at: number | null, 
+/**
+ * Cache the fetched source of a URL script in the page's session
+ * storage, keyed by [`ScriptEntry::content_hash`], so same-origin
+ * navigations re-inject it without downloading it again.
+ */
+cache: boolean, 
+/**
+ * Phase the script is injected in.
+ */
+phase: InjectionPhase, 
+/**
+ * Inject the script once per document instead of on every navigation.
+ * See [`ScopedScriptRegistry::scripts_for_document`].
+ */
+run_once: boolean, 
+/**
+ * When the script runs; see [`RunAt`].
+ */
+run_at: RunAt, 
+/**
+ * Position within its phase: lower values are injected first, and
+ * scripts with the same order are injected by ID.
+ */
+order: number, 
+/**
+ * URL patterns of the pages the script is injected into, where `*`
+ * matches any run of characters, e.g. `https://*.example.com/*`. Empty
+ * means every page. See [`ScriptEntry::matches_url`].
+ */
+matches: Array<string>, };
+
+export type ScriptScope = "global" | { "window": string };
+
+export type ScriptSummary = { 
+/**
+ * Unique identifier of the script.
+ */
+id: string, 
+/**
+ * Type of script (inline code or external URL).
+ */
+script_type: ScriptType, 
+/**
+ * Phase the script is injected in.
+ */
+phase: InjectionPhase, 
+/**
+ * When the script runs.
+ */
+run_at: RunAt, 
+/**
+ * Position within its phase.
+ */
+order: number, 
+/**
+ * Whether the script is injected once per document.
+ */
+run_once: boolean, 
+/**
+ * Whether a URL script's source is cached in the page.
+ */
+cache: boolean, 
+/**
+ * URL patterns of the pages the script is injected into.
+ */
+matches: Array<string>, 
+/**
+ * Size of the script content or URL in bytes.
+ */
+content_bytes: number, 
+/**
+ * When the script was last injected into a webview (epoch millis).
+ */
+last_injected_at: number | null, };
+
+export type RegistryExport = { 
+/**
+ * Schema version of this document.
+ */
+version: number, 
+/**
+ * All registered scripts, sorted by ID.
+ */
+scripts: Array<ScriptEntry>, 
+/**
+ * Scripts scoped to a single window, keyed by window label.
+ */
+windows?: { [key in string]?: Array<ScriptEntry> }, };
+
+export type ImportMode = "replace" | "merge";
+
+export type RejectedScript = { 
+/**
+ * Position of the entry in the manifest
+ */
+index: number, 
+/**
+ * The entry's ID, when it has one
+ */
+id: string | null, reason: string, };
+
+export type ScriptManifestResult = { url: string, 
+/**
+ * IDs of the scripts added to the registry
+ */
+registered: Array<string>, rejected: Array<RejectedScript>, };
+
+export type InjectionStrategy = "direct" | "chunked";
+
+export type ScriptInjectionStatus = "injected" | "not_found";
+
+export type ScriptInjectionOutcome = { id: string, status: ScriptInjectionStatus, };
+
+export type InjectScriptsResult = { 
+/**
+ * One outcome per requested ID, in the order requested
+ */
+scripts: Array<ScriptInjectionOutcome>, 
+/**
+ * How the payload was delivered; `None` when no ID was found
+ */
+strategy: InjectionStrategy | null, 
+/**
+ * Whether the injected code was confirmed to have run in the page;
+ * `None` when verification wasn't requested or no ID was found
+ */
+verified: boolean | null, 
+/**
+ * Cost of each script the page ran
+ */
+metrics: Array<ScriptInjectionMetric>, };
+
+export type InjectionResult = { 
+/**
+ * Number of scripts injected
+ */
+injected: number, 
+/**
+ * IDs of the injected scripts, in injection order
+ */
+scriptIds: Array<string>, 
+/**
+ * IDs of the scripts that would have been injected, when injection is
+ * paused or a re-application was rate-limited
+ */
+skipped: Array<string>, 
+/**
+ * Why nothing was injected
+ */
+message: string | null, 
+/**
+ * How the payload was delivered; `None` when nothing was injected
+ */
+strategy: InjectionStrategy | null, 
+/**
+ * Cost of each script the page ran
+ */
+metrics: Array<ScriptInjectionMetric>, };
+
+export type ScriptInjectionMetric = { id: string, 
+/**
+ * How long the script took to run, in milliseconds; `None` for URL and
+ * module scripts, which load after injection returns
+ */
+durationMs: number | null, 
+/**
+ * Size of the script's content (code or URL) in bytes
+ */
+bytes: number, };
+
+export type ScriptCost = { 
+/**
+ * Mean run time over the recent injections, in milliseconds
+ */
+averageDurationMs: number, 
+/**
+ * Number of injections the average covers
+ */
+samples: number, };
+
+export type WindowInfo = { 
+/**
+ * The unique label/identifier for this window
+ */
+label: string, 
+/**
+ * The window title (if available)
+ */
+title: string | null, 
+/**
+ * The current URL loaded in the webview (if available)
+ */
+url: string | null, 
+/**
+ * Inner (content area) width in physical pixels (if available)
+ */
+innerWidth: number | null, 
+/**
+ * Inner (content area) height in physical pixels (if available)
+ */
+innerHeight: number | null, 
+/**
+ * Whether this window currently has focus
+ */
+focused: boolean, 
+/**
+ * Whether this window is visible
+ */
+visible: boolean, 
+/**
+ * Whether this is the main window (label == "main")
+ */
+isMain: boolean, };
+
+export type WindowContext = { 
+/**
+ * The label of the window that was used
+ */
+windowLabel: string, 
+/**
+ * Total number of windows available
+ */
+totalWindows: number, 
+/**
+ * Warning message if multiple windows exist but none was specified
+ */
+warning: string | null, };
+
+export type NetworkIdleResult = { 
+/**
+ * Whether the network stayed idle for the requested time
+ */
+idle: boolean, 
+/**
+ * How long the wait took, in milliseconds
+ */
+elapsedMs: number, 
+/**
+ * Requests still in flight when the wait ended
+ */
+pendingRequests: number, };
+
+export type PerformanceTiming = { 
+/**
+ * Duration of the DNS lookup; zero when cached or for local pages
+ */
+dnsMs: number | null, 
+/**
+ * Duration of the TCP and TLS connection setup
+ */
+connectMs: number | null, 
+/**
+ * Time to the first byte of the response
+ */
+ttfbMs: number | null, 
+/**
+ * Time until `DOMContentLoaded` handlers finished
+ */
+domContentLoadedMs: number | null, 
+/**
+ * Time until `load` handlers finished
+ */
+loadMs: number | null, 
+/**
+ * Time to the first paint
+ */
+firstPaintMs: number | null, 
+/**
+ * Time to the first paint with text, images or canvas content
+ */
+firstContentfulPaintMs: number | null, 
+/**
+ * Which API the navigation timings came from: `navigation` for
+ * Navigation Timing Level 2, `timing` for the deprecated
+ * `performance.timing`, or `None` if neither is available
+ */
+source: string | null, };
+
+export type FrameSelector = { "index": number } | { "name": string } | { "selector": string };
+
+export type FrameInfo = { 
+/**
+ * Position in the top frame's `window.frames`
+ */
+index: number | null, name: string | null, 
+/**
+ * The `src` attribute of the `<iframe>`
+ */
+src: string | null, 
+/**
+ * The frame document's URL
+ */
+url: string | null, };
+
+export type ConditionResult = { 
+/**
+ * Whether the condition was truthy when the wait ended
+ */
+satisfied: boolean, 
+/**
+ * How long the wait took, in milliseconds
+ */
+elapsedMs: number, 
+/**
+ * How many times the condition was evaluated
+ */
+checks: number, };
+
+export type WaitUntil = "domContentLoaded" | "load";
+
+export type ReloadResult = { 
+/**
+ * URL of the reloaded document, which differs from the original one
+ * if the server redirected
+ */
+url: string, 
+/**
+ * Whether cached resources were refreshed before reloading
+ */
+hard: boolean, 
+/**
+ * How many cached resources a hard reload refreshed
+ */
+refreshedResources: number, 
+/**
+ * How long the reload took, in milliseconds
+ */
+elapsedMs: number, };
+
+export type NetworkEntry = { 
+/**
+ * `fetch` or `xhr`
+ */
+kind: string, 
+/**
+ * Uppercase HTTP method
+ */
+method: string, 
+/**
+ * Absolute request URL
+ */
+url: string, 
+/**
+ * HTTP status, `None` when the request failed before a response
+ */
+status: number | null, 
+/**
+ * When the request started, as Unix milliseconds
+ */
+startedAt: number, 
+/**
+ * Time from start to response (or failure), in milliseconds
+ */
+durationMs: number, 
+/**
+ * Request body, when bodies are captured and the body is text
+ */
+requestBody: string | null, 
+/**
+ * Response body, when bodies are captured and the body is text
+ */
+responseBody: string | null, 
+/**
+ * Whether a body was cut to the configured maximum size
+ */
+bodyTruncated: boolean, 
+/**
+ * Network error message, when no response arrived
+ */
+error: string | null, };
+
+export type NetworkLogFilter = { 
+/**
+ * Substring the URL must contain
+ */
+url: string | null, 
+/**
+ * HTTP method, compared case-insensitively
+ */
+method: string | null, 
+/**
+ * Exact HTTP status
+ */
+status: number | null, 
+/**
+ * Only failed requests: network errors and 4xx/5xx responses
+ */
+failedOnly: boolean, };
+
+export type WindowState = { 
+/**
+ * The window that was targeted
+ */
+windowLabel: string, 
+/**
+ * Whether the window has focus
+ */
+focused: boolean, 
+/**
+ * Whether the window is minimized
+ */
+minimized: boolean, 
+/**
+ * Whether the window is visible
+ */
+visible: boolean, 
+/**
+ * Whether the window was closed
+ */
+closed: boolean, };
+
+export type WindowSize = { 
+/**
+ * Width in pixels
+ */
+width: number, 
+/**
+ * Height in pixels
+ */
+height: number, };
+
+export type ResizeWindowParams = { 
+/**
+ * Width in pixels
+ */
+width: number, 
+/**
+ * Height in pixels
+ */
+height: number, 
+/**
+ * Optional window label (defaults to "main")
+ */
+windowId: string | null, 
+/**
+ * Whether to use logical (true) or physical (false) pixels. Defaults to logical.
+ */
+logical: boolean, };
+
+export type ResizeWindowResult = { 
+/**
+ * Whether the resize was successful
+ */
+success: boolean, 
+/**
+ * The window that was resized
+ */
+windowLabel: string, 
+/**
+ * The new width
+ */
+width: number, 
+/**
+ * The new height
+ */
+height: number, 
+/**
+ * Whether logical pixels were used
+ */
+logical: boolean, 
+/**
+ * The inner size actually applied, which the OS may have clamped
+ */
+appliedSize: WindowSize | null, 
+/**
+ * Error message if resize failed
+ */
+error: string | null, };
+
+export type ContentEncoding = "gzip";
+
+export type CaptureSource = "native" | "dom";
+
+export type CaptureBounds = { x: number, y: number, width: number, height: number, 
+/**
+ * Whether the area includes the window's title bar and frame
+ */
+includesChrome: boolean, };
+
+export type BudgetFit = { 
+/**
+ * JPEG quality the image was re-encoded at
+ */
+quality: number | null, 
+/**
+ * Width PNG images were downscaled to
+ */
+width: number | null, 
+/**
+ * Whether even the smallest attempt is larger than `max_bytes`; the
+ * smallest attempt is returned
+ */
+overBudget: boolean, };
+
+export type ImageFormat = "png" | "jpeg" | "auto";
+
+export type PngCompression = "fast" | "default" | "best";
+
+export type AndroidCaptureStrategy = "draw" | "pixelCopy";
+
+export type CaptureOrientation = "natural" | "portrait" | "landscape";
+
+export type ElementRect = { x: number, y: number, width: number, height: number, };
+
+export type InteractiveElement = { 
+/**
+ * Position in the list, matching the number drawn on overlays
+ */
+index: number, 
+/**
+ * CSS selector that locates the element
+ */
+selector: string, 
+/**
+ * Bounding box in CSS pixels, relative to the viewport
+ */
+rect: ElementRect, 
+/**
+ * ARIA role, explicit or implied by the tag
+ */
+role: string, 
+/**
+ * Accessible label or visible text, truncated
+ */
+label: string, };
+
+export type MarkedElement = { 
+/**
+ * Position in the list
+ */
+index: number, 
+/**
+ * CSS selector that locates the element
+ */
+selector: string, 
+/**
+ * Bounding box in CSS pixels, relative to the viewport
+ */
+rect: ElementRect, 
+/**
+ * ARIA role, explicit or implied by the tag
+ */
+role: string, 
+/**
+ * Accessible label or visible text, truncated
+ */
+label: string, 
+/**
+ * Whether the element is rendered: it has a size and isn't hidden by
+ * `display`, `visibility` or `opacity`
+ */
+visible: boolean, 
+/**
+ * Whether any part of the element is inside the viewport
+ */
+inViewport: boolean, };
+
+export type HighlightResult = { 
+/**
+ * Whether an element matched and was outlined
+ */
+highlighted: boolean, 
+/**
+ * How many elements match the selector; only the first is outlined
+ */
+matchCount: number, 
+/**
+ * Viewport-relative box of the outlined element, in CSS pixels
+ */
+rect: ElementRect | null, };
+
+export type DevicePreset = "iphone" | "pixel" | "desktop";
+
+export type EmulationOptions = { 
+/**
+ * Device profile to start from
+ */
+preset: DevicePreset | null, 
+/**
+ * `navigator.userAgent` reported to page scripts
+ */
+userAgent: string | null, 
+/**
+ * `window.devicePixelRatio` reported to page scripts
+ */
+devicePixelRatio: number | null, 
+/**
+ * Logical inner size to resize the window to
+ */
+viewport: WindowSize | null, };
+
+export type EmulationResult = { 
+/**
+ * `navigator.userAgent` as page scripts now see it
+ */
+userAgent: string, 
+/**
+ * `window.devicePixelRatio` as page scripts now see it
+ */
+devicePixelRatio: number, 
+/**
+ * Logical inner size the window took, when a viewport was emulated
+ */
+viewport: WindowSize | null, 
+/**
+ * Properties the webview wouldn't let the shim override
+ */
+unsupported: Array<string>, };
+
+export type ColorScheme = "light" | "dark" | "auto";
+
+export type ColorSchemeState = { 
+/**
+ * The scheme forced on the page, or `auto` when it follows the system
+ */
+scheme: ColorScheme, 
+/**
+ * Whether `(prefers-color-scheme: dark)` matches for page scripts
+ */
+prefersDark: boolean, 
+/**
+ * The native window theme, `light` or `dark`, when the platform
+ * reports one
+ */
+windowTheme: ColorScheme | null, };
+
+export type ZoomMethod = "native" | "cssZoom" | "cssTransform";
+
+export type ZoomResult = { 
+/**
+ * Zoom factor in effect
+ */
+zoom: number, method: ZoomMethod, 
+/**
+ * Why native zoom wasn't used, when CSS scaling was applied instead
+ */
+fallbackReason: string | null, };
+
+export type UserAgentInfo = { 
+/**
+ * `navigator.userAgent` as seen by the page
+ */
+userAgent: string, 
+/**
+ * Whether [`set_user_agent`] works on this platform
+ */
+overrideSupported: boolean, };
+
+export type SetUserAgentResult = { 
+/**
+ * The User-Agent the webview was given
+ */
+userAgent: string, 
+/**
+ * Always `true`: the page must reload to see the new value
+ */
+requiresReload: boolean, note: string, };
+
+export type DragPoint = { x: number, y: number, };
+
+export type DropTarget = string | DragPoint;
+
+export type DragResult = { 
+/**
+ * Whether the pointer was released at the drop point. For native
+ * drag-and-drop sources, whether the drop target accepted the drop.
+ */
+completed: boolean, 
+/**
+ * Whether the source has `draggable="true"`, so HTML drag-and-drop
+ * events were dispatched instead of pointer events
+ */
+native: boolean, 
+/**
+ * Where the drag started, after scrolling the source into view
+ */
+from: DragPoint, 
+/**
+ * Where the pointer was released
+ */
+to: DragPoint, 
+/**
+ * Tag and ID of the element under the drop point
+ */
+dropTarget: string | null, };
+
+export type HoverResult = { 
+/**
+ * Whether an element matched the selector and was hovered
+ */
+hovered: boolean, 
+/**
+ * Point the pointer was moved to, in CSS pixels relative to the
+ * viewport
+ */
+point: DragPoint | null, 
+/**
+ * Tag and ID of the element under the point, which may be a child of
+ * the matched element
+ */
+target: string | null, };
+
+export type FocusResult = { 
+/**
+ * Whether focus ended where it was asked to: on the element for
+ * [`focus_element`], off the previously focused element for
+ * [`blur_active`]. `false` when the element can't take focus, such
+ * as a `div` without `tabindex`.
+ */
+changed: boolean, 
+/**
+ * Whether the element already had focus, so nothing was done
+ */
+alreadyFocused: boolean, 
+/**
+ * Tag and ID of the element that gained or lost focus
+ */
+target: string | null, };
+
+export type OptionMatch = { "value": string } | { "label": string } | { "index": number };
+
+export type SelectOptionResult = { 
+/**
+ * The select's `value` after the change
+ */
+value: string, 
+/**
+ * Visible text of the chosen option
+ */
+label: string, 
+/**
+ * Position of the chosen option
+ */
+index: number, 
+/**
+ * Whether the option wasn't already selected; `change` is only
+ * dispatched when it wasn't
+ */
+changed: boolean, };
+
+export type AttachedFile = { 
+/**
+ * File name the page sees, without the directory
+ */
+name: string, 
+/**
+ * Size in bytes
+ */
+size: number, 
+/**
+ * MIME type guessed from the extension
+ */
+mimeType: string, };
+
+export type KeyModifiers = { ctrl: boolean, shift: boolean, alt: boolean, 
+/**
+ * Command on macOS, the Windows key elsewhere
+ */
+meta: boolean, };
+
+export type KeyPressResult = { 
+/**
+ * `KeyboardEvent.key` that was dispatched
+ */
+key: string, 
+/**
+ * `KeyboardEvent.code` that was dispatched
+ */
+code: string, 
+/**
+ * Tag and ID of the element the events were dispatched to
+ */
+target: string, 
+/**
+ * Whether a handler called `preventDefault()` on the `keydown`, which
+ * usually means the page handled the key
+ */
+defaultPrevented: boolean, };
+
+export type HighlightOptions = { 
+/**
+ * Any CSS color; invalid colors fall back to the default (default:
+ * "#ff2d55")
+ */
+color: string | null, 
+/**
+ * How long the outlines stay, or `0` to keep them until
+ * [`clear_highlights`] is called (default: 3000)
+ */
+durationMs: bigint | null, };
+
+export type HighlightMatches = { 
+/**
+ * How many elements were outlined; elements that aren't rendered are
+ * skipped
+ */
+highlighted: number, 
+/**
+ * How many elements match the selector
+ */
+matchCount: number, };
+
+export type BoundingRect = { x: number, y: number, width: number, height: number, top: number, left: number, 
+/**
+ * Whether the element is rendered, not hidden by `display` or
+ * `visibility`, and intersects the viewport
+ */
+visible: boolean, };
+
+export type BoundingRectsResult = { 
+/**
+ * Rects of the first `max_nodes` matches, in document order
+ */
+rects: Array<BoundingRect>, 
+/**
+ * Total number of matching elements
+ */
+total: number, 
+/**
+ * Whether matches were left out because of `max_nodes`
+ */
+truncated: boolean, };
+
+export type AccessibilityNode = { 
+/**
+ * ARIA role, explicit or implied by the element; `text` for text runs
+ */
+role: string, 
+/**
+ * Accessible name, from `aria-labelledby`, `aria-label`, labels, `alt`
+ * or content
+ */
+name: string | null, 
+/**
+ * Current value of form controls, sliders and progress bars
+ */
+value: string | null, 
+/**
+ * Active states, e.g. "disabled", "checked", "expanded", "focused"
+ */
+state: Array<string>, 
+/**
+ * Heading level, for headings
+ */
+level?: number, children: Array<AccessibilityNode>, };
+
+export type AccessibilityTree = { root: AccessibilityNode, 
+/**
+ * Number of nodes in the tree
+ */
+nodeCount: number, 
+/**
+ * Whether nodes were left out because of the depth or node limit
+ */
+truncated: boolean, };
+
+export type ScreenshotResponse = { 
+/**
+ * Base64-encoded image data URL
+ */
+data: string, 
+/**
+ * Set when `data` holds compressed bytes the client must decode first
+ */
+contentEncoding: ContentEncoding | null, 
+/**
+ * Format of the encoded image, resolved when `"auto"` was requested
+ */
+format: ImageFormat, 
+/**
+ * Whether the image came from native capture or the DOM fallback
+ */
+source: CaptureSource, 
+/**
+ * Screen area the capture covers, so callers can tell whether the
+ * window chrome was included
+ */
+bounds: CaptureBounds | null, 
+/**
+ * Logical viewport size used for the capture, when a viewport was requested
+ */
+viewport: WindowSize | null, 
+/**
+ * Visible interactive elements, when requested with `include_elements`
+ * or `annotate_elements`
+ */
+elements: Array<InteractiveElement> | null, 
+/**
+ * Whether the image was reused from the capture cache
+ */
+cacheHit: boolean, 
+/**
+ * Whether the awaited fonts loaded before the capture; `None` when
+ * `wait_for_fonts` wasn't set
+ */
+fontsReady: boolean | null, 
+/**
+ * How the image was shrunk to fit `max_bytes`; `None` when it already
+ * fit or no limit was set
+ */
+budgetFit: BudgetFit | null, 
+/**
+ * What the capture leaves out, when it covers less than was asked for
+ */
+warning: string | null, };
+
+export type ThumbnailResponse = { 
+/**
+ * The full capture
+ */
+full: ScreenshotResponse, 
+/**
+ * The same capture scaled to fit the requested square
+ */
+thumbnail: ScreenshotResponse, };
+
+export type WidthCaptureResponse = { 
+/**
+ * The capture
+ */
+screenshot: ScreenshotResponse, 
+/**
+ * CSS width that was requested
+ */
+requestedWidth: number, 
+/**
+ * CSS viewport size the page had during the capture; `None` when it
+ * couldn't be read
+ */
+actual: WindowSize | null, 
+/**
+ * Why the window couldn't be resized, in which case the capture is at
+ * its unchanged size
+ */
+resizeError: string | null, };
+
+export type WindowCapture = { 
+/**
+ * Label of the captured window
+ */
+windowLabel: string, 
+/**
+ * The capture, or `None` if the window was skipped or the capture failed
+ */
+screenshot: ScreenshotResponse | null, 
+/**
+ * Whether the window was skipped because it is hidden or minimized
+ */
+skipped: boolean, 
+/**
+ * Why the window was skipped or the capture failed
+ */
+error: string | null, };
+
+export type ScreenshotFrame = { 
+/**
+ * Capture sequence number. Gaps mean frames were dropped.
+ */
+sequence: number, 
+/**
+ * When the frame was captured (epoch millis)
+ */
+timestamp: number, 
+/**
+ * Base64-encoded image data URL
+ */
+data: string, 
+/**
+ * Set when `data` holds compressed bytes the client must decode first
+ */
+contentEncoding: ContentEncoding | null, };
+
+export type CaptureStreamFrame = { 
+/**
+ * The stream that produced the frame
+ */
+streamId: string, 
+/**
+ * Label of the captured window
+ */
+windowLabel: string, 
+/**
+ * Capture sequence number. Gaps mean frames were dropped.
+ */
+sequence: number, 
+/**
+ * When the frame was captured (epoch millis)
+ */
+timestamp: number, 
+/**
+ * Base64-encoded image data URL
+ */
+data: string, 
+/**
+ * Set when `data` holds compressed bytes the client must decode first
+ */
+contentEncoding: ContentEncoding | null, };
+
+export type VisualDiffOptions = { 
+/**
+ * Maximum per-channel difference (0-255) before a pixel counts as changed
+ */
+tolerance: number, 
+/**
+ * Highest percentage of changed pixels that still counts as a match
+ */
+threshold: number, 
+/**
+ * Whether to return the diff image
+ */
+includeDiffImage: boolean, };
+
+export type SizeMismatch = { 
+/**
+ * Size of the baseline image in pixels
+ */
+baseline: WindowSize, 
+/**
+ * Size of the fresh capture in pixels
+ */
+actual: WindowSize, };
+
+export type VisualDiffResult = { 
+/**
+ * Whether the capture matches the baseline within the threshold
+ */
+match: boolean, 
+/**
+ * Percentage of changed pixels (100 when the sizes differ)
+ */
+diffPercent: number, 
+/**
+ * Base64-encoded PNG highlighting changed pixels in red
+ */
+diffImageBase64: string | null, 
+/**
+ * Set when the images have different dimensions and weren't compared
+ */
+sizeMismatch: SizeMismatch | null, };
+
+export type ChangedRegion = { x: number, y: number, width: number, height: number, };
+
+export type DiffResult = { 
+/**
+ * Whether any pixel changed
+ */
+changed: boolean, 
+/**
+ * Number of pixels that differ by more than the tolerance
+ */
+changedPixels: number, 
+/**
+ * Percentage of changed pixels (0-100) of the compared area
+ */
+diffPercent: number, 
+/**
+ * Where the changes are, when there are any
+ */
+changedRegion: ChangedRegion | null, 
+/**
+ * Whether the screenshots had different dimensions
+ */
+sizeChanged: boolean, };
+
+export type CaptureTextOptions = { 
+/**
+ * Tesseract language codes, e.g. "eng" or "eng+deu" (default: "eng")
+ */
+language: string | null, 
+/**
+ * Drop lines whose mean word confidence (0-100) is below this
+ */
+minConfidence: number | null, };
+
+export type TextBlock = { 
+/**
+ * Words of the line joined with spaces
+ */
+text: string, 
+/**
+ * Left edge in image pixels
+ */
+x: number, 
+/**
+ * Top edge in image pixels
+ */
+y: number, width: number, height: number, 
+/**
+ * Mean word confidence, 0-100
+ */
+confidence: number, };
+
+export type TextCapture = { 
+/**
+ * Lines in reading order
+ */
+blocks: Array<TextBlock>, 
+/**
+ * Capture width in pixels
+ */
+width: number, 
+/**
+ * Capture height in pixels
+ */
+height: number, 
+/**
+ * Window scale factor; divide block coordinates by it for CSS pixels
+ */
+scaleFactor: number, };
+
+export type CookieInfo = { 
+/**
+ * Cookie name
+ */
+name: string, 
+/**
+ * Cookie value
+ */
+value: string, 
+/**
+ * Domain the cookie applies to (if set)
+ */
+domain: string | null, 
+/**
+ * Path the cookie applies to (if set)
+ */
+path: string | null, 
+/**
+ * Expiry as seconds since the Unix epoch, `None` for session cookies
+ */
+expires: number | null, 
+/**
+ * Whether the cookie is hidden from JavaScript
+ */
+httpOnly: boolean, 
+/**
+ * Whether the cookie is only sent over HTTPS
+ */
+secure: boolean, };
+
+export type StorageArea = "local" | "session";
+
+export type ClearBrowsingDataOptions = { 
+/**
+ * Clear `window.localStorage`
+ */
+localStorage: boolean, 
+/**
+ * Clear `window.sessionStorage`
+ */
+sessionStorage: boolean, 
+/**
+ * Delete all cookies in the webview's cookie store
+ */
+cookies: boolean, 
+/**
+ * Delete every Cache Storage cache
+ */
+cache: boolean, };
+
+export type ClearOutcome = { "status": "cleared" } | { "status": "unsupported" } | { "status": "failed", "error": string };
+
+export type ConsoleEntry = { 
+/**
+ * Console method: "log", "debug", "info", "warn" or "error"
+ */
+level: string, 
+/**
+ * Arguments joined with spaces
+ */
+message: string, 
+/**
+ * Each argument stringified, objects as JSON
+ */
+args: Array<string>, 
+/**
+ * When the call was made (epoch millis)
+ */
+timestamp: number, };
+
+export type ClearBrowsingDataResult = { 
+/**
+ * Outcome for `localStorage`
+ */
+localStorage: ClearOutcome | null, 
+/**
+ * Outcome for `sessionStorage`
+ */
+sessionStorage: ClearOutcome | null, 
+/**
+ * Outcome for the cookie store
+ */
+cookies: ClearOutcome | null, 
+/**
+ * Outcome for Cache Storage
+ */
+cache: ClearOutcome | null, };
+
+export type WindowTarget = { windowLabel?: string };
+
+export interface McpBridgeCommands {
+  list_windows: { args: Record<string, never>; result: WindowInfo[] };
+  focus_window: { args: { windowId?: string }; result: WindowState };
+  minimize_window: { args: { windowId?: string }; result: WindowState };
+  close_window: { args: { windowId?: string }; result: WindowState };
+  resize_window: { args: ResizeWindowParams; result: ResizeWindowResult };
+  set_window_size: { args: ResizeWindowParams; result: ResizeWindowResult };
+  capture_native_screenshot: {
+    args: WindowTarget & {
+      format?: ImageFormat;
+      quality?: number;
+      pngCompression?: PngCompression;
+      maxWidth?: number;
+      maxBytes?: number;
+      compressTransport?: boolean;
+      forceCompression?: boolean;
+      waitForPaint?: boolean;
+      waitForFonts?: boolean;
+      fontFamilies?: string[];
+      fontTimeoutMs?: number;
+      captureStrategy?: AndroidCaptureStrategy;
+      orientation?: CaptureOrientation;
+      includeChrome?: boolean;
+      hideScrollbars?: boolean;
+      hideSelectors?: string[];
+      stripMetadata?: boolean;
+      viewport?: WindowSize;
+      includeElements?: boolean;
+      annotateElements?: boolean;
+      keepHighlights?: boolean;
+    };
+    result: ScreenshotResponse;
+  };
+  capture_screen: {
+    args: McpBridgeCommands["capture_native_screenshot"]["args"];
+    result: ScreenshotResponse;
+  };
+  capture_with_thumbnail: {
+    args: McpBridgeCommands["capture_native_screenshot"]["args"] & { thumbMaxDim: number };
+    result: ThumbnailResponse;
+  };
+  capture_at_width: {
+    args: McpBridgeCommands["capture_native_screenshot"]["args"] & { width: number };
+    result: WidthCaptureResponse;
+  };
+  get_viewport_metrics: {
+    args: WindowTarget;
+    result: {
+      innerWidth: number;
+      innerHeight: number;
+      scrollWidth: number;
+      scrollHeight: number;
+      scrollX: number;
+      scrollY: number;
+      devicePixelRatio: number;
+    };
+  };
+  get_performance_timing: { args: WindowTarget; result: PerformanceTiming };
+  capture_all_windows: {
+    args: Omit<McpBridgeCommands["capture_native_screenshot"]["args"], "windowLabel">;
+    result: WindowCapture[];
+  };
+  visual_diff: {
+    args: WindowTarget & Partial<VisualDiffOptions> & { baselinePath: string };
+    result: VisualDiffResult;
+  };
+  diff_screenshots: {
+    args: { imageA: string; imageB: string; tolerance?: number };
+    result: DiffResult;
+  };
+  get_bounding_rects: {
+    args: WindowTarget & { selector: string; maxNodes?: number };
+    result: BoundingRectsResult;
+  };
+  get_computed_style: {
+    args: WindowTarget & { selector: string; properties?: string[] };
+    result: Record<string, string | null>;
+  };
+  capture_text: {
+    args: WindowTarget & CaptureTextOptions;
+    result: TextCapture;
+  };
+  get_interactive_elements: { args: WindowTarget; result: MarkedElement[] };
+  get_accessibility_tree: {
+    args: WindowTarget & { selector?: string; maxDepth?: number; maxNodes?: number };
+    result: AccessibilityTree;
+  };
+  get_console_logs: {
+    args: WindowTarget & { clear?: boolean };
+    result: ConsoleEntry[];
+  };
+  inject_once: {
+    args: WindowTarget & { type: ScriptType; content: string };
+    result: { result: unknown; persistent: false; note: string };
+  };
+  list_scripts: {
+    args: { includeContent?: boolean };
+    result: {
+      scripts: (ScriptSummary & {
+        scope: ScriptScope;
+        content?: string | null;
+        cost: ScriptCost | null;
+      })[];
+      injectionEnabled: boolean;
+    };
+  };
+  inject_scripts: {
+    args: WindowTarget & { ids: string[]; verify?: boolean };
+    result: InjectScriptsResult;
+  };
+  highlight_element: {
+    args: WindowTarget & { selector: string; durationMs?: number };
+    result: HighlightResult;
+  };
+  highlight: {
+    args: WindowTarget & { selector: string } & Partial<HighlightOptions>;
+    result: HighlightMatches;
+  };
+  clear_highlights: { args: WindowTarget; result: { removed: number } };
+  emulate: {
+    args: WindowTarget & Partial<EmulationOptions>;
+    result: EmulationResult;
+  };
+  reset_emulation: { args: WindowTarget; result: { reset: boolean } };
+  get_color_scheme: { args: WindowTarget; result: ColorSchemeState };
+  set_color_scheme: { args: WindowTarget & { scheme: ColorScheme }; result: ColorSchemeState };
+  set_zoom: { args: WindowTarget & { factor: number }; result: ZoomResult };
+  get_user_agent: { args: WindowTarget; result: UserAgentInfo };
+  set_user_agent: { args: WindowTarget & { userAgent: string }; result: SetUserAgentResult };
+  hover_element: { args: WindowTarget & { selector: string }; result: HoverResult };
+  focus_element: { args: WindowTarget & { selector: string }; result: FocusResult };
+  blur_active: { args: WindowTarget; result: FocusResult };
+  select_option: {
+    args: WindowTarget & { selector: string; by: OptionMatch };
+    result: SelectOptionResult;
+  };
+  set_input_files: {
+    args: WindowTarget & { selector: string; paths: string[] };
+    result: AttachedFile[];
+  };
+  press_key: {
+    args: WindowTarget & { key: string; modifiers?: Partial<KeyModifiers>; selector?: string };
+    result: KeyPressResult;
+  };
+  drag_element: {
+    args: WindowTarget & { fromSelector: string; to: DropTarget; steps?: number };
+    result: DragResult;
+  };
+  start_network_log: {
+    args: WindowTarget & { captureBodies?: boolean };
+    result: { recording: boolean };
+  };
+  stop_network_log: { args: WindowTarget; result: { stopped: boolean } };
+  get_network_log: {
+    args: WindowTarget & { filter?: Partial<NetworkLogFilter>; limit?: number };
+    result: NetworkEntry[];
+  };
+  evaluate_script: {
+    args: WindowTarget & { script: string; timeoutMs?: number; frame?: FrameSelector };
+    result: unknown;
+  };
+  eval_async: {
+    args: McpBridgeCommands["evaluate_script"]["args"];
+    result: unknown;
+  };
+  wait_for_network_idle: {
+    args: WindowTarget & { idleMs?: number; timeoutMs?: number };
+    result: NetworkIdleResult;
+  };
+  wait_for_condition: {
+    args: WindowTarget & {
+      predicate: string;
+      timeoutMs?: number;
+      mode?: "poll" | "observe";
+      intervalMs?: number;
+    };
+    result: ConditionResult;
+  };
+  reload: {
+    args: WindowTarget & { hard?: boolean; waitUntil?: WaitUntil; timeoutMs?: number };
+    result: ReloadResult;
+  };
+  get_cookies: { args: WindowTarget & { url?: string }; result: CookieInfo[] };
+  set_cookie: { args: WindowTarget & { cookie: Partial<CookieInfo> | string }; result: CookieInfo };
+  storage_get: { args: WindowTarget & { area: StorageArea; key: string }; result: string | null };
+  storage_set: {
+    args: WindowTarget & { area: StorageArea; key: string; value: string };
+    result: string | null;
+  };
+  clear_browsing_data: {
+    args: WindowTarget & Partial<ClearBrowsingDataOptions>;
+    result: ClearBrowsingDataResult;
+  };
+  register_script: {
+    args: WindowTarget & { id?: string; type: ScriptType; content: string; scope?: "global" | "window"; cache?: boolean; phase?: InjectionPhase; runOnce?: boolean; runAt?: RunAt; order?: number; matches?: string[] };
+    result: {
+      registered: boolean;
+      scriptId: string;
+      paused?: boolean;
+      strategy?: InjectionStrategy;
+      warning?: string | null;
+    };
+  };
+  export_registry: { args: Record<string, never>; result: RegistryExport };
+  import_registry: {
+    args: { json: RegistryExport | string; mode: ImportMode };
+    result: { imported: number; registered: number };
+  };
+  load_script_manifest: { args: { url: string }; result: ScriptManifestResult };
+  set_injection_enabled: { args: { enabled: boolean }; result: { enabled: boolean; registered: number } };
+}
@@ -1,4 +1,5 @@
-import { invoke } from '@tauri-apps/api/core';
+import { Channel, invoke } from '@tauri-apps/api/core';
+import { listen, type UnlistenFn } from '@tauri-apps/api/event';
 
 export interface WindowInfo {
    width: number;
@@ -15,6 +16,24 @@ export interface BackendState {
    windows: number;
 }
 
+export interface ScreenshotFrame {
+   sequence: number;
+   timestamp: number;
+   data: string;
+   contentEncoding: 'gzip' | null;
+}
+
+export interface CaptureStreamFrame extends ScreenshotFrame {
+   streamId: string;
+   windowLabel: string;
+}
+
+export interface ScreenshotStreamOptions {
+   format?: 'png' | 'jpeg' | 'auto';
+   quality?: number;
+   maxWidth?: number;
+}
+
 export interface IPCEvent {
    timestamp: number;
    command: string;
@@ -72,3 +91,63 @@ export async function stopIPCMonitor(): Promise<string> {
 export async function getIPCEvents(): Promise<IPCEvent[]> {
    return await invoke('plugin:mcp-bridge|get_ipc_events');
 }
+
+/**
+ * Start pushing viewport screenshots to `onFrame` every `intervalMs`.
+ * Returns the stream ID to pass to `stopScreenshotStream`.
+ */
+export async function startScreenshotStream(
+   intervalMs: number,
+   onFrame: (frame: ScreenshotFrame) => void,
+   options: ScreenshotStreamOptions = {}
+): Promise<string> {
+   const channel = new Channel<ScreenshotFrame>();
+   channel.onmessage = onFrame;
+   return await invoke('plugin:mcp-bridge|start_screenshot_stream', { intervalMs, channel, ...options });
+}
+
+/**
+ * Stop a screenshot stream
+ */
+export async function stopScreenshotStream(streamId: string): Promise<boolean> {
+   return await invoke('plugin:mcp-bridge|stop_screenshot_stream', { streamId });
+}
+
+/**
+ * Start emitting viewport screenshots as `mcp://frame` events every `intervalMs`.
+ * The next frame is only captured once the previous one is acknowledged,
+ * see `onCaptureFrame`. Returns the stream ID to pass to `stopCaptureStream`.
+ */
+export async function startCaptureStream(
+   intervalMs: number,
+   options: ScreenshotStreamOptions = {}
+): Promise<string> {
+   return await invoke('plugin:mcp-bridge|start_capture_stream', { intervalMs, ...options });
+}
+
+/**
+ * Listen for frames of a capture stream, acknowledging each one once
+ * `onFrame` returns (or its promise settles).
+ */
+export async function onCaptureFrame(
+   streamId: string,
+   onFrame: (frame: CaptureStreamFrame) => void | Promise<void>
+): Promise<UnlistenFn> {
+   return await listen<CaptureStreamFrame>('mcp://frame', async (event) => {
+      if (event.payload.streamId !== streamId) {
+         return;
+      }
+      try {
+         await onFrame(event.payload);
+      } finally {
+         await invoke('plugin:mcp-bridge|ack_capture_frame', { streamId });
+      }
+   });
+}
+
+/**
+ * Stop a capture stream
+ */
+export async function stopCaptureStream(streamId: string): Promise<boolean> {
+   return await invoke('plugin:mcp-bridge|stop_capture_stream', { streamId });
+}
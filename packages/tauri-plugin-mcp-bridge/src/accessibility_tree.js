@@ -0,0 +1,326 @@
+/**
+ * Accessibility tree walker for the MCP Bridge plugin.
+ *
+ * Run by `get_accessibility_tree`. Walks the DOM from the root element and
+ * returns the semantic nodes: elements with a role other than `generic`,
+ * plus text. Generic wrappers are skipped and their children hoisted, so
+ * layout markup doesn't take up the depth or node budget. `options` is
+ * substituted by Rust.
+ *
+ * Roles and names follow a simplified version of the ARIA implicit role
+ * mapping and accessible name computation.
+ */
+(function(options) {
+   'use strict';
+
+   var MAX_NAME_LENGTH = 200,
+       nodeCount = 0,
+       truncated = false,
+       root = document.querySelector(options.selector || 'body');
+
+   if (!root) {
+      throw new Error('No element matches selector: ' + options.selector);
+   }
+
+   // Roles whose accessible name comes from their content
+   var NAME_FROM_CONTENT = [
+      'button', 'cell', 'checkbox', 'columnheader', 'heading', 'link', 'listitem', 'menuitem',
+      'option', 'radio', 'row', 'rowheader', 'switch', 'tab', 'tooltip', 'treeitem',
+   ];
+
+   var SKIPPED_TAGS = [ 'SCRIPT', 'STYLE', 'NOSCRIPT', 'TEMPLATE', 'META', 'LINK' ];
+
+   var TAG_ROLES = {
+      ARTICLE: 'article',
+      ASIDE: 'complementary',
+      BUTTON: 'button',
+      DIALOG: 'dialog',
+      FIELDSET: 'group',
+      FIGURE: 'figure',
+      FORM: 'form',
+      H1: 'heading',
+      H2: 'heading',
+      H3: 'heading',
+      H4: 'heading',
+      H5: 'heading',
+      H6: 'heading',
+      HR: 'separator',
+      LI: 'listitem',
+      MAIN: 'main',
+      MENU: 'list',
+      NAV: 'navigation',
+      OL: 'list',
+      OPTION: 'option',
+      P: 'paragraph',
+      PROGRESS: 'progressbar',
+      TABLE: 'table',
+      TD: 'cell',
+      TEXTAREA: 'textbox',
+      TH: 'columnheader',
+      TR: 'row',
+      UL: 'list',
+   };
+
+   var INPUT_ROLES = {
+      button: 'button',
+      checkbox: 'checkbox',
+      email: 'textbox',
+      image: 'button',
+      number: 'spinbutton',
+      radio: 'radio',
+      range: 'slider',
+      reset: 'button',
+      search: 'searchbox',
+      submit: 'button',
+      tel: 'textbox',
+      text: 'textbox',
+      url: 'textbox',
+   };
+
+   function collapse(text) {
+      text = (text || '').replace(/\s+/g, ' ').trim();
+      return text.length > MAX_NAME_LENGTH ? text.slice(0, MAX_NAME_LENGTH) + '…' : text;
+   }
+
+   function isHidden(el) {
+      var style;
+
+      if (el.hidden || el.getAttribute('aria-hidden') === 'true') {
+         return true;
+      }
+      style = getComputedStyle(el);
+      return style.display === 'none' || style.visibility === 'hidden' || style.visibility === 'collapse';
+   }
+
+   function roleOf(el) {
+      var explicit = (el.getAttribute('role') || '').trim().split(/\s+/)[0],
+          tag = el.tagName,
+          type;
+
+      if (explicit) {
+         return explicit;
+      }
+
+      if (tag === 'A' || tag === 'AREA') {
+         return el.hasAttribute('href') ? 'link' : 'generic';
+      }
+      if (tag === 'IMG') {
+         return el.getAttribute('alt') === '' ? 'presentation' : 'img';
+      }
+      if (tag === 'INPUT') {
+         type = (el.getAttribute('type') || 'text').toLowerCase();
+         if (type === 'hidden') {
+            return 'none';
+         }
+         if ((type === 'text' || type === 'search' || type === 'email' || type === 'tel' || type === 'url')
+            && el.hasAttribute('list')) {
+            return 'combobox';
+         }
+         return INPUT_ROLES[type] || 'textbox';
+      }
+      if (tag === 'SELECT') {
+         return el.multiple || el.size > 1 ? 'listbox' : 'combobox';
+      }
+      if (tag === 'HEADER') {
+         return el.closest('article, aside, main, nav, section') ? 'generic' : 'banner';
+      }
+      if (tag === 'FOOTER') {
+         return el.closest('article, aside, main, nav, section') ? 'generic' : 'contentinfo';
+      }
+      if (tag === 'SECTION') {
+         return el.hasAttribute('aria-label') || el.hasAttribute('aria-labelledby') ? 'region' : 'generic';
+      }
+      return TAG_ROLES[tag] || 'generic';
+   }
+
+   function textOf(el) {
+      return collapse(el.innerText !== undefined ? el.innerText : el.textContent);
+   }
+
+   function nameOf(el, role) {
+      var labelledBy = el.getAttribute('aria-labelledby'),
+          label = el.getAttribute('aria-label'),
+          labels;
+
+      if (labelledBy) {
+         label = labelledBy.split(/\s+/).map(function(id) {
+            var ref = document.getElementById(id);
+            return ref ? textOf(ref) : '';
+         }).join(' ');
+         if (collapse(label)) {
+            return collapse(label);
+         }
+      }
+      if (label && collapse(label)) {
+         return collapse(label);
+      }
+
+      if (el.tagName === 'INPUT' && /^(button|submit|reset)$/i.test(el.type)) {
+         return collapse(el.value || el.type);
+      }
+      if (el.tagName === 'INPUT' || el.tagName === 'TEXTAREA' || el.tagName === 'SELECT') {
+         labels = el.labels ? Array.prototype.map.call(el.labels, textOf).join(' ') : '';
+         return collapse(labels || el.getAttribute('placeholder') || el.getAttribute('title'));
+      }
+      if (el.tagName === 'IMG' || (el.tagName === 'INPUT' && el.type === 'image')) {
+         return collapse(el.getAttribute('alt') || el.getAttribute('title'));
+      }
+      if (el.tagName === 'FIELDSET' && el.querySelector('legend')) {
+         return textOf(el.querySelector('legend'));
+      }
+      if (el.tagName === 'TABLE' && el.caption) {
+         return textOf(el.caption);
+      }
+      if (NAME_FROM_CONTENT.indexOf(role) !== -1) {
+         return textOf(el) || collapse(el.getAttribute('title'));
+      }
+      return collapse(el.getAttribute('title'));
+   }
+
+   function valueOf(el, role) {
+      if (el.tagName === 'INPUT') {
+         if (el.type === 'password') {
+            return el.value ? '••••' : '';
+         }
+         if (role === 'checkbox' || role === 'radio' || role === 'button') {
+            return null;
+         }
+         return el.value;
+      }
+      if (el.tagName === 'TEXTAREA') {
+         return el.value;
+      }
+      if (el.tagName === 'SELECT') {
+         return Array.prototype.filter.call(el.options, function(option) {
+            return option.selected;
+         }).map(function(option) {
+            return collapse(option.textContent);
+         }).join(', ');
+      }
+      if (el.tagName === 'PROGRESS') {
+         return String(el.value);
+      }
+      return el.getAttribute('aria-valuetext') || el.getAttribute('aria-valuenow');
+   }
+
+   function stateOf(el, role) {
+      var state = [],
+          checked = el.getAttribute('aria-checked'),
+          expanded = el.getAttribute('aria-expanded');
+
+      if (el.disabled || el.getAttribute('aria-disabled') === 'true') {
+         state.push('disabled');
+      }
+      if (el.tagName === 'INPUT' && (el.type === 'checkbox' || el.type === 'radio')) {
+         checked = el.indeterminate ? 'mixed' : String(el.checked);
+      }
+      if (checked === 'true') {
+         state.push('checked');
+      } else if (checked === 'mixed') {
+         state.push('mixed');
+      } else if (checked === 'false' || role === 'checkbox' || role === 'radio' || role === 'switch') {
+         state.push('unchecked');
+      }
+      if (expanded === 'true') {
+         state.push('expanded');
+      } else if (expanded === 'false') {
+         state.push('collapsed');
+      }
+      if (el.selected || el.getAttribute('aria-selected') === 'true') {
+         state.push('selected');
+      }
+      if (el.getAttribute('aria-pressed') === 'true') {
+         state.push('pressed');
+      }
+      if (el.required || el.getAttribute('aria-required') === 'true') {
+         state.push('required');
+      }
+      if (el.readOnly || el.getAttribute('aria-readonly') === 'true') {
+         state.push('readonly');
+      }
+      if (document.activeElement === el) {
+         state.push('focused');
+      }
+      return state;
+   }
+
+   // Appends the semantic nodes found under `parent` to `into`
+   function walkChildren(parent, depth, into) {
+      var child, text;
+
+      for (child = parent.firstChild; child; child = child.nextSibling) {
+         if (nodeCount >= options.maxNodes) {
+            truncated = true;
+            return;
+         }
+
+         if (child.nodeType === Node.TEXT_NODE) {
+            text = collapse(child.textContent);
+            if (text) {
+               nodeCount++;
+               into.push({ role: 'text', name: text, value: null, state: [], children: [] });
+            }
+         } else if (child.nodeType === Node.ELEMENT_NODE) {
+            walkElement(child, depth, into);
+         }
+      }
+   }
+
+   function walkElement(el, depth, into) {
+      var role, name, node;
+
+      if (SKIPPED_TAGS.indexOf(el.tagName) !== -1 || isHidden(el)) {
+         return;
+      }
+
+      role = roleOf(el);
+      if (role === 'none' && el.tagName === 'INPUT') {
+         return;
+      }
+      name = nameOf(el, role);
+
+      if ((role === 'generic' || role === 'none' || role === 'presentation') && !name) {
+         walkChildren(el, depth, into);
+         return;
+      }
+
+      nodeCount++;
+      node = {
+         role: role,
+         name: name || null,
+         value: valueOf(el, role),
+         state: stateOf(el, role),
+         children: [],
+      };
+      if (role === 'heading') {
+         node.level = Number(el.getAttribute('aria-level') || el.tagName.slice(1)) || null;
+      }
+      into.push(node);
+
+      // Text of name-from-content roles is already in the name
+      if (NAME_FROM_CONTENT.indexOf(role) !== -1 && !el.querySelector('*')) {
+         return;
+      }
+      if (depth >= options.maxDepth) {
+         truncated = truncated || el.childElementCount > 0;
+         return;
+      }
+      walkChildren(el, depth + 1, node.children);
+   }
+
+   nodeCount++;
+   var tree = {
+      role: roleOf(root),
+      name: nameOf(root, roleOf(root)) || null,
+      value: valueOf(root, roleOf(root)),
+      state: stateOf(root, roleOf(root)),
+      children: [],
+   };
+   if (options.maxDepth > 0) {
+      walkChildren(root, 1, tree.children);
+   } else {
+      truncated = root.childElementCount > 0;
+   }
+
+   return { root: tree, nodeCount: nodeCount, truncated: truncated };
+})(__MCP_A11Y_OPTIONS__);
@@ -0,0 +1,384 @@
+//! TypeScript definitions for the plugin's public types and command surface.
+//!
+//! Enabled with the `ts-bindings` feature. Running
+//! `cargo test --features ts-bindings` regenerates `guest-js/bindings.ts`,
+//! so the frontend and MCP client can import these shapes instead of
+//! redefining them by hand.
+
+use ts_rs::TS;
+
+use crate::commands::{
+    AccessibilityNode, AccessibilityTree, AttachedFile, BoundingRect, BoundingRectsResult,
+    CaptureStreamFrame, CaptureTextOptions, ClearBrowsingDataOptions, ClearBrowsingDataResult,
+    ClearOutcome, ColorScheme, ColorSchemeState, ConditionResult, ConsoleEntry, CookieInfo,
+    DevicePreset, DragPoint, DragResult, DropTarget, ElementRect, EmulationOptions,
+    EmulationResult, FocusResult, FrameInfo, FrameSelector, HighlightMatches, HighlightOptions,
+    HighlightResult, HoverResult, InjectScriptsResult, InjectionResult, InjectionStrategy,
+    InteractiveElement, KeyModifiers, KeyPressResult, MarkedElement, NetworkIdleResult,
+    OptionMatch, PerformanceTiming, RejectedScript, ReloadResult, ResizeWindowParams,
+    ResizeWindowResult, ScreenshotFrame, ScreenshotResponse, ScriptCost, ScriptInjectionMetric,
+    ScriptInjectionOutcome, ScriptInjectionStatus, ScriptManifestResult, SelectOptionResult,
+    SetUserAgentResult, SizeMismatch, StorageArea, TextBlock, TextCapture, ThumbnailResponse,
+    UserAgentInfo, VisualDiffOptions, VisualDiffResult, WaitUntil, WidthCaptureResponse,
+    WindowCapture, WindowContext, WindowInfo, WindowSize, WindowState, ZoomMethod, ZoomResult,
+};
+use crate::network_log::{NetworkEntry, NetworkLogFilter};
+use crate::screenshot::diff::{ChangedRegion, DiffResult};
+use crate::screenshot::{
+    AndroidCaptureStrategy, BudgetFit, CaptureBounds, CaptureOrientation, CaptureSource,
+    ContentEncoding, ImageFormat, PngCompression,
+};
+use crate::script_registry::{
+    ImportMode, InjectionPhase, RegistryExport, RunAt, ScriptEntry, ScriptScope, ScriptSummary,
+    ScriptType,
+};
+
+/// Path of the generated bindings, relative to the crate root.
+pub const BINDINGS_PATH: &str = "guest-js/bindings.ts";
+
+/// Arguments and results of the WebSocket commands, keyed by command name.
+const COMMAND_SIGNATURES: &str = r#"export type WindowTarget = { windowLabel?: string };
+
+export interface McpBridgeCommands {
+  list_windows: { args: Record<string, never>; result: WindowInfo[] };
+  focus_window: { args: { windowId?: string }; result: WindowState };
+  minimize_window: { args: { windowId?: string }; result: WindowState };
+  close_window: { args: { windowId?: string }; result: WindowState };
+  resize_window: { args: ResizeWindowParams; result: ResizeWindowResult };
+  set_window_size: { args: ResizeWindowParams; result: ResizeWindowResult };
+  capture_native_screenshot: {
+    args: WindowTarget & {
+      format?: ImageFormat;
+      quality?: number;
+      pngCompression?: PngCompression;
+      maxWidth?: number;
+      maxBytes?: number;
+      compressTransport?: boolean;
+      forceCompression?: boolean;
+      waitForPaint?: boolean;
+      waitForFonts?: boolean;
+      fontFamilies?: string[];
+      fontTimeoutMs?: number;
+      captureStrategy?: AndroidCaptureStrategy;
+      orientation?: CaptureOrientation;
+      includeChrome?: boolean;
+      hideScrollbars?: boolean;
+      hideSelectors?: string[];
+      stripMetadata?: boolean;
+      viewport?: WindowSize;
+      includeElements?: boolean;
+      annotateElements?: boolean;
+      keepHighlights?: boolean;
+    };
+    result: ScreenshotResponse;
+  };
+  capture_screen: {
+    args: McpBridgeCommands["capture_native_screenshot"]["args"];
+    result: ScreenshotResponse;
+  };
+  capture_with_thumbnail: {
+    args: McpBridgeCommands["capture_native_screenshot"]["args"] & { thumbMaxDim: number };
+    result: ThumbnailResponse;
+  };
+  capture_at_width: {
+    args: McpBridgeCommands["capture_native_screenshot"]["args"] & { width: number };
+    result: WidthCaptureResponse;
+  };
+  get_viewport_metrics: {
+    args: WindowTarget;
+    result: {
+      innerWidth: number;
+      innerHeight: number;
+      scrollWidth: number;
+      scrollHeight: number;
+      scrollX: number;
+      scrollY: number;
+      devicePixelRatio: number;
+    };
+  };
+  get_performance_timing: { args: WindowTarget; result: PerformanceTiming };
+  capture_all_windows: {
+    args: Omit<McpBridgeCommands["capture_native_screenshot"]["args"], "windowLabel">;
+    result: WindowCapture[];
+  };
+  visual_diff: {
+    args: WindowTarget & Partial<VisualDiffOptions> & { baselinePath: string };
+    result: VisualDiffResult;
+  };
+  diff_screenshots: {
+    args: { imageA: string; imageB: string; tolerance?: number };
+    result: DiffResult;
+  };
+  get_bounding_rects: {
+    args: WindowTarget & { selector: string; maxNodes?: number };
+    result: BoundingRectsResult;
+  };
+  get_computed_style: {
+    args: WindowTarget & { selector: string; properties?: string[] };
+    result: Record<string, string | null>;
+  };
+  capture_text: {
+    args: WindowTarget & CaptureTextOptions;
+    result: TextCapture;
+  };
+  get_interactive_elements: { args: WindowTarget; result: MarkedElement[] };
+  get_accessibility_tree: {
+    args: WindowTarget & { selector?: string; maxDepth?: number; maxNodes?: number };
+    result: AccessibilityTree;
+  };
+  get_console_logs: {
+    args: WindowTarget & { clear?: boolean };
+    result: ConsoleEntry[];
+  };
+  inject_once: {
+    args: WindowTarget & { type: ScriptType; content: string };
+    result: { result: unknown; persistent: false; note: string };
+  };
+  list_scripts: {
+    args: { includeContent?: boolean };
+    result: {
+      scripts: (ScriptSummary & {
+        scope: ScriptScope;
+        content?: string | null;
+        cost: ScriptCost | null;
+      })[];
+      injectionEnabled: boolean;
+    };
+  };
+  inject_scripts: {
+    args: WindowTarget & { ids: string[]; verify?: boolean };
+    result: InjectScriptsResult;
+  };
+  highlight_element: {
+    args: WindowTarget & { selector: string; durationMs?: number };
+    result: HighlightResult;
+  };
+  highlight: {
+    args: WindowTarget & { selector: string } & Partial<HighlightOptions>;
+    result: HighlightMatches;
+  };
+  clear_highlights: { args: WindowTarget; result: { removed: number } };
+  emulate: {
+    args: WindowTarget & Partial<EmulationOptions>;
+    result: EmulationResult;
+  };
+  reset_emulation: { args: WindowTarget; result: { reset: boolean } };
+  get_color_scheme: { args: WindowTarget; result: ColorSchemeState };
+  set_color_scheme: { args: WindowTarget & { scheme: ColorScheme }; result: ColorSchemeState };
+  set_zoom: { args: WindowTarget & { factor: number }; result: ZoomResult };
+  get_user_agent: { args: WindowTarget; result: UserAgentInfo };
+  set_user_agent: { args: WindowTarget & { userAgent: string }; result: SetUserAgentResult };
+  hover_element: { args: WindowTarget & { selector: string }; result: HoverResult };
+  focus_element: { args: WindowTarget & { selector: string }; result: FocusResult };
+  blur_active: { args: WindowTarget; result: FocusResult };
+  select_option: {
+    args: WindowTarget & { selector: string; by: OptionMatch };
+    result: SelectOptionResult;
+  };
+  set_input_files: {
+    args: WindowTarget & { selector: string; paths: string[] };
+    result: AttachedFile[];
+  };
+  press_key: {
+    args: WindowTarget & { key: string; modifiers?: Partial<KeyModifiers>; selector?: string };
+    result: KeyPressResult;
+  };
+  drag_element: {
+    args: WindowTarget & { fromSelector: string; to: DropTarget; steps?: number };
+    result: DragResult;
+  };
+  start_network_log: {
+    args: WindowTarget & { captureBodies?: boolean };
+    result: { recording: boolean };
+  };
+  stop_network_log: { args: WindowTarget; result: { stopped: boolean } };
+  get_network_log: {
+    args: WindowTarget & { filter?: Partial<NetworkLogFilter>; limit?: number };
+    result: NetworkEntry[];
+  };
+  evaluate_script: {
+    args: WindowTarget & { script: string; timeoutMs?: number; frame?: FrameSelector };
+    result: unknown;
+  };
+  eval_async: {
+    args: McpBridgeCommands["evaluate_script"]["args"];
+    result: unknown;
+  };
+  wait_for_network_idle: {
+    args: WindowTarget & { idleMs?: number; timeoutMs?: number };
+    result: NetworkIdleResult;
+  };
+  wait_for_condition: {
+    args: WindowTarget & {
+      predicate: string;
+      timeoutMs?: number;
+      mode?: "poll" | "observe";
+      intervalMs?: number;
+    };
+    result: ConditionResult;
+  };
+  reload: {
+    args: WindowTarget & { hard?: boolean; waitUntil?: WaitUntil; timeoutMs?: number };
+    result: ReloadResult;
+  };
+  get_cookies: { args: WindowTarget & { url?: string }; result: CookieInfo[] };
+  set_cookie: { args: WindowTarget & { cookie: Partial<CookieInfo> | string }; result: CookieInfo };
+  storage_get: { args: WindowTarget & { area: StorageArea; key: string }; result: string | null };
+  storage_set: {
+    args: WindowTarget & { area: StorageArea; key: string; value: string };
+    result: string | null;
+  };
+  clear_browsing_data: {
+    args: WindowTarget & Partial<ClearBrowsingDataOptions>;
+    result: ClearBrowsingDataResult;
+  };
+  register_script: {
+    args: WindowTarget & { id?: string; type: ScriptType; content: string; scope?: "global" | "window"; cache?: boolean; phase?: InjectionPhase; runOnce?: boolean; runAt?: RunAt; order?: number; matches?: string[] };
+    result: {
+      registered: boolean;
+      scriptId: string;
+      paused?: boolean;
+      strategy?: InjectionStrategy;
+      warning?: string | null;
+    };
+  };
+  export_registry: { args: Record<string, never>; result: RegistryExport };
+  import_registry: {
+    args: { json: RegistryExport | string; mode: ImportMode };
+    result: { imported: number; registered: number };
+  };
+  load_script_manifest: { args: { url: string }; result: ScriptManifestResult };
+  set_injection_enabled: { args: { enabled: boolean }; result: { enabled: boolean; registered: number } };
+}
+"#;
+
+/// Renders the TypeScript definitions for all exported types.
+pub fn typescript_bindings() -> String {
+    let declarations = [
+        ScriptType::decl(),
+        InjectionPhase::decl(),
+        RunAt::decl(),
+        ScriptEntry::decl(),
+        ScriptScope::decl(),
+        ScriptSummary::decl(),
+        RegistryExport::decl(),
+        ImportMode::decl(),
+        RejectedScript::decl(),
+        ScriptManifestResult::decl(),
+        InjectionStrategy::decl(),
+        ScriptInjectionStatus::decl(),
+        ScriptInjectionOutcome::decl(),
+        InjectScriptsResult::decl(),
+        InjectionResult::decl(),
+        ScriptInjectionMetric::decl(),
+        ScriptCost::decl(),
+        WindowInfo::decl(),
+        WindowContext::decl(),
+        NetworkIdleResult::decl(),
+        PerformanceTiming::decl(),
+        FrameSelector::decl(),
+        FrameInfo::decl(),
+        ConditionResult::decl(),
+        WaitUntil::decl(),
+        ReloadResult::decl(),
+        NetworkEntry::decl(),
+        NetworkLogFilter::decl(),
+        WindowState::decl(),
+        WindowSize::decl(),
+        ResizeWindowParams::decl(),
+        ResizeWindowResult::decl(),
+        ContentEncoding::decl(),
+        CaptureSource::decl(),
+        CaptureBounds::decl(),
+        BudgetFit::decl(),
+        ImageFormat::decl(),
+        PngCompression::decl(),
+        AndroidCaptureStrategy::decl(),
+        CaptureOrientation::decl(),
+        ElementRect::decl(),
+        InteractiveElement::decl(),
+        MarkedElement::decl(),
+        HighlightResult::decl(),
+        DevicePreset::decl(),
+        EmulationOptions::decl(),
+        EmulationResult::decl(),
+        ColorScheme::decl(),
+        ColorSchemeState::decl(),
+        ZoomMethod::decl(),
+        ZoomResult::decl(),
+        UserAgentInfo::decl(),
+        SetUserAgentResult::decl(),
+        DragPoint::decl(),
+        DropTarget::decl(),
+        DragResult::decl(),
+        HoverResult::decl(),
+        FocusResult::decl(),
+        OptionMatch::decl(),
+        SelectOptionResult::decl(),
+        AttachedFile::decl(),
+        KeyModifiers::decl(),
+        KeyPressResult::decl(),
+        HighlightOptions::decl(),
+        HighlightMatches::decl(),
+        BoundingRect::decl(),
+        BoundingRectsResult::decl(),
+        AccessibilityNode::decl(),
+        AccessibilityTree::decl(),
+        ScreenshotResponse::decl(),
+        ThumbnailResponse::decl(),
+        WidthCaptureResponse::decl(),
+        WindowCapture::decl(),
+        ScreenshotFrame::decl(),
+        CaptureStreamFrame::decl(),
+        VisualDiffOptions::decl(),
+        SizeMismatch::decl(),
+        VisualDiffResult::decl(),
+        ChangedRegion::decl(),
+        DiffResult::decl(),
+        CaptureTextOptions::decl(),
+        TextBlock::decl(),
+        TextCapture::decl(),
+        CookieInfo::decl(),
+        StorageArea::decl(),
+        ClearBrowsingDataOptions::decl(),
+        ClearOutcome::decl(),
+        ConsoleEntry::decl(),
+        ClearBrowsingDataResult::decl(),
+    ];
+
+    let mut output = String::from(
+        "// This file was generated by tauri-plugin-mcp-bridge. Do not edit it by hand.\n\n",
+    );
+    for declaration in declarations {
+        output.push_str("export ");
+        output.push_str(&declaration);
+        output.push_str("\n\n");
+    }
+    output.push_str(COMMAND_SIGNATURES);
+
+    output
+}
+
+/// Writes the TypeScript definitions to `path`.
+pub fn export_typescript_bindings(path: impl AsRef<std::path::Path>) -> std::io::Result<()> {
+    std::fs::write(path, typescript_bindings())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn export_bindings() {
+        let path = std::path::Path::new(env!("CARGO_MANIFEST_DIR")).join(BINDINGS_PATH);
+        export_typescript_bindings(path).unwrap();
+    }
+
+    #[test]
+    fn test_script_type_uses_lowercase_variants() {
+        assert_eq!(
+            ScriptType::decl(),
+            r#"type ScriptType = "inline" | "url" | "module";"#
+        );
+    }
+}
@@ -36,10 +36,34 @@
 
    bridgeLogger = createMcpLogger('BRIDGE');
 
+   // Caps on buffered console output, so a chatty page can't exhaust memory
+   var MAX_CONSOLE_LOGS = 1000,
+       MAX_CONSOLE_ARG_LENGTH = 4096;
+
+   // How often and in what batch sizes console calls are forwarded to
+   // Rust, substituted when the plugin is built
+   var CONSOLE_BATCH = __MCP_CONSOLE_BATCH_OPTIONS__;
+
+   function stringifyConsoleArg(arg) {
+      var text;
+
+      try {
+         text = typeof arg === 'object' ? JSON.stringify(arg) : String(arg);
+      } catch(e) {
+         text = String(arg);
+      }
+
+      if (text === undefined) {
+         text = String(arg);
+      }
+
+      return text.length > MAX_CONSOLE_ARG_LENGTH
+         ? text.slice(0, MAX_CONSOLE_ARG_LENGTH) + '…'
+         : text;
+   }
+
    // Initialize console capture so logs are captured from app startup
    function initConsoleCapture() {
-      var args, message;
-
       if (window.__MCP_CONSOLE_LOGS__) {
          return; // Already initialized
       }
@@ -52,26 +76,76 @@
 
       window.__MCP_CONSOLE_LOGS__ = [];
 
+      var pending = [],
+          flushTimer = null;
+
+      // Forwards the pending batch; resolves once Rust has recorded it.
+      // Calls made before the Tauri API is ready stay pending for the next
+      // flush.
+      function flushConsole() {
+         var batch;
+
+         if (flushTimer !== null) {
+            clearTimeout(flushTimer);
+            flushTimer = null;
+         }
+         if (!pending.length) {
+            return Promise.resolve();
+         }
+         if (!(window.__TAURI__ && window.__TAURI__.core)) {
+            flushTimer = setTimeout(flushConsole, CONSOLE_BATCH.intervalMs);
+            return Promise.resolve();
+         }
+
+         batch = pending;
+         pending = [];
+         try {
+            return window.__TAURI__.core.invoke('plugin:mcp-bridge|record_console_entries', { entries: batch })
+               .catch(function() {
+                  // The log is best effort; never surface errors to the page
+               });
+         } catch(e) {
+            return Promise.resolve();
+         }
+      }
+
+      function queueConsoleEntry(entry) {
+         pending.push(entry);
+         if (pending.length > MAX_CONSOLE_LOGS) {
+            pending.splice(0, pending.length - MAX_CONSOLE_LOGS);
+         }
+
+         if (pending.length >= CONSOLE_BATCH.size) {
+            flushConsole();
+         } else if (flushTimer === null) {
+            flushTimer = setTimeout(flushConsole, CONSOLE_BATCH.intervalMs);
+         }
+      }
+
       function captureLog(level, origFn) {
          return function() {
-            args = Array.prototype.slice.call(arguments);
-
-            try {
-               message = args
-                  .map(function(a) {
-                     return typeof a === 'object' ? JSON.stringify(a) : String(a);
-                  })
-                  .join(' ');
-            } catch(e) {
-               message = args.map(String).join(' ');
+            var args = Array.prototype.slice.call(arguments),
+                logs = window.__MCP_CONSOLE_LOGS__,
+                stringified = args.map(stringifyConsoleArg),
+                entry = {
+                   level: level,
+                   message: stringified.join(' '),
+                   args: stringified,
+                   timestamp: Date.now(),
+                };
+
+            queueConsoleEntry(entry);
+
+            // Kept for MCP servers that read the page buffer directly. The
+            // buffer is replaced, not mutated, when it is drained
+            if (Array.isArray(logs)) {
+               logs.push(entry);
+
+               if (logs.length > MAX_CONSOLE_LOGS) {
+                  logs.splice(0, logs.length - MAX_CONSOLE_LOGS);
+               }
             }
 
-            window.__MCP_CONSOLE_LOGS__.push({
-               level: level,
-               message: message,
-               timestamp: Date.now(),
-            });
-
             origFn.apply(console, args);
          };
       }
@@ -82,9 +156,33 @@
       console.warn = captureLog('warn', origWarn);
       console.error = captureLog('error', origError);
 
+      window.__MCP_FLUSH_CONSOLE__ = flushConsole;
+      // Send what is pending before the page goes away
+      window.addEventListener('pagehide', flushConsole);
+
       bridgeLogger.info('Console capture initialized');
    }
 
+   // Track a DOM version that changes on every navigation and mutation, so
+   // the plugin can tell whether a cached capture is still current
+   function initDomVersion() {
+      var token, mutations = 0;
+
+      if (window.__MCP_DOM_VERSION__ || typeof MutationObserver === 'undefined') {
+         return;
+      }
+
+      token = Date.now().toString(36) + Math.random().toString(36).slice(2);
+
+      new MutationObserver(function() {
+         mutations += 1;
+      }).observe(document, { subtree: true, childList: true, attributes: true, characterData: true });
+
+      window.__MCP_DOM_VERSION__ = function() {
+         return token + ':' + mutations;
+      };
+   }
+
    // Wait for Tauri API to be available
    function waitForTauri(callback) {
       if (window.__TAURI__ && window.__TAURI__.core && window.__TAURI__.core.invoke) {
@@ -102,6 +200,7 @@
 
       // Initialize console capture immediately so logs are captured from the start
       initConsoleCapture();
+      initDomVersion();
 
       // Capture unhandled JS errors and promise rejections while preserving
       // default behavior
@@ -195,6 +294,49 @@
       notifyPageLoaded();
    });
 
+   // =========================================================================
+   // Script Evaluation
+   // =========================================================================
+   /**
+    * Runs an evaluation from Rust, awaiting any returned Promise, and posts the
+    * settled value back through `script_result` under the given call ID.
+    * @param {string} callId
+    * @param {function(): *} evaluate
+    */
+   window.__MCP_EVALUATE__ = function(callId, evaluate) {
+      function post(payload) {
+         payload.execId = callId;
+         return window.__TAURI__.core.invoke('plugin:mcp-bridge|script_result', payload);
+      }
+
+      function settle(payload) {
+         waitForTauri(function() {
+            post(payload).catch(function(err) {
+               bridgeLogger.error('Failed to post evaluation result:', err);
+
+               // Usually a value that can't be serialized; report that instead
+               if (payload.success) {
+                  post({ success: false, error: 'Result could not be serialized: ' + (err.message || String(err)) });
+               }
+            });
+         });
+      }
+
+      Promise.resolve()
+         .then(evaluate)
+         .then(function(value) {
+            settle({ success: true, data: value === undefined ? null : value });
+         }, function(error) {
+            var isError = error instanceof Error;
+
+            settle({
+               success: false,
+               error: isError ? error.message : String(error),
+               stack: isError && error.stack ? error.stack : null,
+            });
+         });
+   };
+
    // =========================================================================
    // Script Injection Functions
    // =========================================================================
@@ -203,19 +345,135 @@
     * Injects scripts into the DOM. Called by Rust when scripts need to be injected.
     * @param {Array<{id: string, type: 'inline'|'url', content: string}>} scripts
     */
-   window.__MCP_INJECT_SCRIPTS__ = function(scripts) {
-      var script;
+   var SCRIPT_CACHE_PREFIX = '__mcp_script_cache__:';
+
+   /**
+    * Reads a cached script source by content hash, if storage is available.
+    * @param {string} hash
+    * @returns {string|null}
+    */
+   function readScriptCache(hash) {
+      try {
+         return window.sessionStorage.getItem(SCRIPT_CACHE_PREFIX + hash);
+      } catch (e) {
+         return null;
+      }
+   }
+
+   /**
+    * Stores a fetched script source by content hash. Quota errors are ignored.
+    * @param {string} hash
+    * @param {string} source
+    */
+   function writeScriptCache(hash, source) {
+      try {
+         window.sessionStorage.setItem(SCRIPT_CACHE_PREFIX + hash, source);
+      } catch (e) {
+         bridgeLogger.warn('Failed to cache script:', e.message || e);
+      }
+   }
+
+   /**
+    * Injects a URL script from the session cache, fetching and caching its
+    * source on first use. Falls back to a regular script tag if the source
+    * can't be fetched, e.g. because of CORS.
+    * @param {{id: string, content: string, hash: string}} entry
+    */
+   function injectCachedUrlScript(entry) {
+      var isInjected = function() {
+         return document.querySelector('script[data-mcp-script-id="' + entry.id + '"]') !== null;
+      };
+
+      var appendInline = function(source) {
+         var inline;
+
+         // Another injection may have finished while the source was fetched
+         if (isInjected()) {
+            return;
+         }
+
+         inline = document.createElement('script');
+
+         inline.setAttribute('data-mcp-script-id', entry.id);
+         inline.textContent = source;
+         document.head.appendChild(inline);
+         bridgeLogger.info('Injected cached script:', entry.id);
+      };
+
+      var cached = readScriptCache(entry.hash);
+
+      if (cached !== null) {
+         appendInline(cached);
+         return;
+      }
+
+      fetch(entry.content)
+         .then(function(response) {
+            if (!response.ok) {
+               throw new Error('HTTP ' + response.status);
+            }
+            return response.text();
+         })
+         .then(function(source) {
+            writeScriptCache(entry.hash, source);
+            appendInline(source);
+         })
+         .catch(function(err) {
+            var fallback;
+
+            if (isInjected()) {
+               return;
+            }
+
+            fallback = document.createElement('script');
+            bridgeLogger.warn('Script cache fetch failed, loading directly:', entry.id, err.message || err);
+            fallback.setAttribute('data-mcp-script-id', entry.id);
+            fallback.src = entry.content;
+            fallback.async = true;
+            document.head.appendChild(fallback);
+         });
+   }
+
+   window.__MCP_INJECT_SCRIPTS__ = function(scripts, options) {
+      var script,
+          reapply = !!(options && options.reapply),
+          inlineAllowed = true,
+          // Read back by the plugin along with any injection error
+          metrics = [];
+
+      window.__MCP_INJECTION_METRICS__ = metrics;
 
       if (!Array.isArray(scripts)) {
          bridgeLogger.error('Invalid scripts array');
          return;
       }
 
+      // Scripts that already ran as initialization scripts
+      if (Array.isArray(window.__MCP_DOCUMENT_START_IDS__)) {
+         scripts = scripts.filter(function(entry) {
+            return !entry || window.__MCP_DOCUMENT_START_IDS__.indexOf(entry.id) === -1;
+         });
+      }
+
+      if (scripts.some(function(entry) { return entry && entry.type === 'inline'; })) {
+         inlineAllowed = inlineScriptsAllowed();
+      }
+
       scripts.forEach(function(entry) {
+         var existing, started;
+
          if (!entry || !entry.id) {
             return;
          }
 
+         // Re-applying runs scripts again, so drop the previous tags
+         if (reapply) {
+            existing = document.querySelector('script[data-mcp-script-id="' + entry.id + '"]');
+            if (existing) {
+               existing.remove();
+            }
+         }
+
          // Check if script already exists
          if (document.querySelector('script[data-mcp-script-id="' + entry.id + '"]')) {
             bridgeLogger.info('Script already exists:', entry.id);
@@ -226,25 +484,80 @@
 
          script.setAttribute('data-mcp-script-id', entry.id);
 
-         if (entry.type === 'url') {
+         if (entry.type === 'module') {
+            // Inline module code is loaded from a blob URL
+            var moduleSrc = entry.external
+               ? entry.content
+               : URL.createObjectURL(new Blob([ entry.content ], { type: 'text/javascript' }));
+
+            script.type = 'module';
+            script.src = moduleSrc;
+            script.onload = function() {
+               if (!entry.external) {
+                  URL.revokeObjectURL(moduleSrc);
+               }
+               bridgeLogger.info('Module script loaded:', entry.id);
+            };
+            script.onerror = function() {
+               if (!entry.external) {
+                  URL.revokeObjectURL(moduleSrc);
+               }
+               bridgeLogger.error('Failed to load module script:', entry.id);
+            };
+         } else if (entry.type === 'url' && entry.cache && entry.hash) {
+            injectCachedUrlScript(entry);
+            metrics.push({ id: entry.id, durationMs: null });
+            return;
+         } else if (entry.type === 'url') {
             script.src = entry.content;
-            script.async = true;
+            // Run in injection order so setup scripts load before the rest
+            script.async = false;
             script.onload = function() {
                bridgeLogger.info('URL script loaded:', entry.id);
             };
             script.onerror = function() {
                bridgeLogger.error('Failed to load URL script:', entry.id);
             };
+         } else if (!inlineAllowed) {
+            // The plugin reports this as a CSP error
+            window.__MCP_INJECTION_ERROR__ = 'Refused to execute inline script ' + entry.id
+               + ' because it violates the Content Security Policy (no \'unsafe-inline\' in script-src)';
+            bridgeLogger.error('Inline script blocked by Content Security Policy:', entry.id);
+            return;
          } else {
             // Inline script
             script.textContent = entry.content;
          }
 
+         // Inline scripts run while being appended; the others load later
+         started = performance.now();
          document.head.appendChild(script);
+         metrics.push({
+            id: entry.id,
+            durationMs: entry.type === 'inline' ? performance.now() - started : null,
+         });
          bridgeLogger.info('Injected script:', entry.id);
       });
    };
 
+   /**
+    * Whether the page's CSP lets inline scripts run. Blocked scripts fail
+    * silently, so a probe script checks that it actually executed.
+    * @returns {boolean}
+    */
+   function inlineScriptsAllowed() {
+      var probe = document.createElement('script'),
+          allowed;
+
+      probe.textContent = 'window.__MCP_INLINE_PROBE__ = true;';
+      document.head.appendChild(probe);
+      probe.remove();
+
+      allowed = window.__MCP_INLINE_PROBE__ === true;
+      delete window.__MCP_INLINE_PROBE__;
+      return allowed;
+   }
+
    /**
     * Removes a script from the DOM by ID.
     * @param {string} scriptId
@@ -274,11 +587,15 @@
     * Notifies Rust that the page has loaded and scripts should be re-injected.
     * Uses the Tauri event system to communicate with the plugin.
     */
-   function notifyPageLoaded() {
+   // Identifies this JavaScript context; a reload or new document gets a
+   // new one, so the plugin can re-run scripts registered with runOnce
+   var documentId = Date.now().toString(36) + Math.random().toString(36).slice(2);
+
+   function notifyPageLoaded(reapply) {
       // Use Tauri's invoke to request script re-injection.
       // The plugin responds by calling __MCP_INJECT_SCRIPTS__ with registered scripts.
       if (window.__TAURI__ && window.__TAURI__.core && window.__TAURI__.core.invoke) {
-         window.__TAURI__.core.invoke('plugin:mcp-bridge|request_script_injection')
+         window.__TAURI__.core.invoke('plugin:mcp-bridge|request_script_injection', { reapply: !!reapply, documentId: documentId })
             .catch(function(err) {
                // This command may not exist in older versions, which is fine
                bridgeLogger.warn('Script injection request:', err.message || 'not available');
@@ -286,6 +603,67 @@
       }
    }
 
+   // Mutations removing at least this many elements count as a replacement
+   var DOM_REPLACEMENT_MIN_ELEMENTS = 50,
+       DOM_REPLACEMENT_DEBOUNCE_MS = 100;
+
+   /**
+    * Re-applies registered scripts whenever most of `rootSelector`'s content
+    * is replaced, as client-side routers do. Falls back to `body` when
+    * nothing matches. Called by the plugin on page load; later calls are
+    * ignored.
+    * @param {string} rootSelector
+    */
+   window.__MCP_WATCH_DOM_REPLACEMENT__ = function(rootSelector) {
+      var root, timer;
+
+      if (window.__MCP_DOM_REPLACEMENT_OBSERVER__) {
+         return;
+      }
+
+      try {
+         root = document.querySelector(rootSelector);
+      } catch(e) {
+         bridgeLogger.warn('Invalid re-injection root:', rootSelector);
+      }
+      root = root || document.body;
+      if (!root || typeof MutationObserver === 'undefined') {
+         return;
+      }
+
+      function removedElements(mutation) {
+         var count = 0;
+
+         Array.prototype.forEach.call(mutation.removedNodes, function(node) {
+            if (node.nodeType === 1) {
+               count += 1 + node.getElementsByTagName('*').length;
+            }
+         });
+         return count;
+      }
+
+      window.__MCP_DOM_REPLACEMENT_OBSERVER__ = new MutationObserver(function(mutations) {
+         var removed = 0;
+
+         mutations.forEach(function(mutation) {
+            if (mutation.addedNodes.length > 0) {
+               removed += removedElements(mutation);
+            }
+         });
+
+         if (removed < DOM_REPLACEMENT_MIN_ELEMENTS) {
+            return;
+         }
+
+         clearTimeout(timer);
+         timer = setTimeout(function() {
+            bridgeLogger.info('DOM replacement detected, re-applying scripts');
+            notifyPageLoaded(true);
+         }, DOM_REPLACEMENT_DEBOUNCE_MS);
+      });
+      window.__MCP_DOM_REPLACEMENT_OBSERVER__.observe(root, { childList: true, subtree: true });
+   };
+
    // Also listen for navigation events to re-inject scripts
    // This handles SPA-style navigation where the page doesn't fully reload
    window.addEventListener('popstate', function() {
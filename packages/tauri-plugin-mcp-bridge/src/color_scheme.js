@@ -0,0 +1,206 @@
+/**
+ * Color scheme override for the MCP Bridge plugin.
+ *
+ * Registered as a bridge script by `set_color_scheme`. Makes
+ * `prefers-color-scheme` report `scheme` to page scripts through
+ * `matchMedia`, rewrites the condition in same-origin stylesheet media
+ * rules, and sets the root `color-scheme` so built-in controls and
+ * `light-dark()` follow, keeping what it changed so `restore` can undo it.
+ * `scheme` is substituted by Rust when the script is registered.
+ *
+ * Best effort: cross-origin stylesheets keep their rules, and lists from
+ * `matchMedia` are stand-ins that fail `instanceof MediaQueryList`.
+ */
+(function(scheme) {
+   'use strict';
+
+   var CONDITION = /\(\s*prefers-color-scheme\s*:\s*(light|dark)\s*\)/gi,
+       MENTIONS_SCHEME = /prefers-color-scheme/i,
+       ALWAYS = '(min-width: 0px)',
+       NEVER = '(max-width: 0px) and (min-width: 1px)',
+       shared = window.__MCP_COLOR_SCHEME_SHARED__,
+       state, observer;
+
+   if (window.__MCP_COLOR_SCHEME__) {
+      // Re-registered on the same page; start over from the real values
+      window.__MCP_COLOR_SCHEME__.restore();
+   }
+
+   if (!shared) {
+      // Kept across re-registrations, so lists handed out earlier follow
+      // later changes
+      shared = { matchMedia: window.matchMedia, scheme: null, lists: [] };
+      window.__MCP_COLOR_SCHEME_SHARED__ = shared;
+   }
+
+   // Replaces each prefers-color-scheme condition with one that always or
+   // never matches. Media Queries 3 syntax, so `not` at the start of a
+   // query still negates the whole query.
+   function resolve(query) {
+      if (!shared.scheme) {
+         return query;
+      }
+      return query.replace(CONDITION, function(_, value) {
+         return value.toLowerCase() === shared.scheme ? ALWAYS : NEVER;
+      });
+   }
+
+   function evaluate(query) {
+      return shared.matchMedia.call(window, resolve(query)).matches;
+   }
+
+   function createList(query) {
+      var native = shared.matchMedia.call(window, query),
+          last = evaluate(query),
+          list;
+
+      try {
+         list = new EventTarget();
+      } catch(e) {
+         // No constructible EventTarget; the list won't follow later changes
+         return shared.matchMedia.call(window, resolve(query));
+      }
+
+      Object.defineProperty(list, 'media', { value: native.media });
+      Object.defineProperty(list, 'matches', {
+         get: function() {
+            return evaluate(query);
+         },
+      });
+      list.onchange = null;
+      list.addListener = function(listener) {
+         list.addEventListener('change', listener);
+      };
+      list.removeListener = function(listener) {
+         list.removeEventListener('change', listener);
+      };
+      list.notify = function() {
+         var matches = evaluate(query),
+             event;
+
+         if (matches === last) {
+            return;
+         }
+         last = matches;
+         try {
+            event = new MediaQueryListEvent('change', { matches: matches, media: native.media });
+         } catch(e) {
+            event = new Event('change');
+         }
+         if (typeof list.onchange === 'function') {
+            list.onchange.call(list, event);
+         }
+         list.dispatchEvent(event);
+      };
+
+      // The system scheme and the query's other features still change natively
+      if (native.addEventListener) {
+         native.addEventListener('change', list.notify);
+      } else {
+         native.addListener(list.notify);
+      }
+      shared.lists.push(list);
+      return list;
+   }
+
+   function rewriteRules(rules) {
+      var i, rule, original;
+
+      for (i = 0; i < rules.length; i++) {
+         rule = rules[i];
+         if (rule.media && rule.cssRules) {
+            original = rule.__mcpMediaText || rule.media.mediaText;
+            if (MENTIONS_SCHEME.test(original)) {
+               rule.__mcpMediaText = original;
+               rule.media.mediaText = resolve(original);
+            }
+         }
+         if (rule.cssRules) {
+            rewriteRules(rule.cssRules);
+         }
+      }
+   }
+
+   function rewriteStyleSheets() {
+      var i;
+
+      for (i = 0; i < document.styleSheets.length; i++) {
+         try {
+            rewriteRules(document.styleSheets[i].cssRules);
+         } catch(e) {
+            // Cross-origin stylesheets can't be read
+         }
+      }
+   }
+
+   function applyRootScheme() {
+      var root = document.documentElement;
+
+      if (root && !('originalRootScheme' in state)) {
+         state.originalRootScheme = root.style.colorScheme;
+         root.style.colorScheme = scheme;
+      }
+   }
+
+   function onReady() {
+      applyRootScheme();
+      rewriteStyleSheets();
+   }
+
+   // Stylesheets finish loading after they are added
+   function onLoad(event) {
+      if (event.target && event.target.tagName === 'LINK') {
+         rewriteStyleSheets();
+      }
+   }
+
+   function notifyLists() {
+      shared.lists.forEach(function(list) {
+         list.notify();
+      });
+   }
+
+   state = { scheme: scheme };
+   shared.scheme = scheme;
+
+   window.matchMedia = function(query) {
+      var text = String(query);
+
+      return MENTIONS_SCHEME.test(text) ? createList(text) : shared.matchMedia.call(window, query);
+   };
+
+   observer = new MutationObserver(function(mutations) {
+      var added = mutations.some(function(mutation) {
+         return Array.prototype.some.call(mutation.addedNodes, function(node) {
+            return node.tagName === 'STYLE' || node.tagName === 'LINK';
+         });
+      });
+
+      if (added) {
+         rewriteStyleSheets();
+      }
+   });
+   observer.observe(document, { childList: true, subtree: true });
+   document.addEventListener('DOMContentLoaded', onReady);
+   document.addEventListener('load', onLoad, true);
+   onReady();
+   notifyLists();
+
+   state.restore = function() {
+      var root = document.documentElement;
+
+      observer.disconnect();
+      document.removeEventListener('DOMContentLoaded', onReady);
+      document.removeEventListener('load', onLoad, true);
+      window.matchMedia = shared.matchMedia;
+      shared.scheme = null;
+      rewriteStyleSheets();
+      if (root && 'originalRootScheme' in state) {
+         root.style.colorScheme = state.originalRootScheme;
+      }
+      notifyLists();
+      delete window.__MCP_COLOR_SCHEME__;
+   };
+
+   window.__MCP_COLOR_SCHEME__ = state;
+}(__MCP_COLOR_SCHEME_VALUE__));
@@ -0,0 +1,125 @@
+//! Semantic view of a page built from roles, names and states.
+
+use serde::{Deserialize, Serialize};
+use tauri::{Runtime, WebviewWindow};
+
+use super::execute_js::run_script;
+
+/// Levels walked below the root by default.
+pub const DEFAULT_A11Y_MAX_DEPTH: u32 = 12;
+
+/// Nodes returned at most by default, including the root.
+pub const DEFAULT_A11Y_MAX_NODES: u32 = 500;
+
+/// One node of the accessibility tree.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[cfg_attr(feature = "ts-bindings", derive(ts_rs::TS))]
+pub struct AccessibilityNode {
+    /// ARIA role, explicit or implied by the element; `text` for text runs
+    pub role: String,
+    /// Accessible name, from `aria-labelledby`, `aria-label`, labels, `alt`
+    /// or content
+    pub name: Option<String>,
+    /// Current value of form controls, sliders and progress bars
+    pub value: Option<String>,
+    /// Active states, e.g. "disabled", "checked", "expanded", "focused"
+    pub state: Vec<String>,
+    /// Heading level, for headings
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[cfg_attr(feature = "ts-bindings", ts(optional))]
+    pub level: Option<u32>,
+    pub children: Vec<AccessibilityNode>,
+}
+
+/// Outcome of [`get_accessibility_tree`].
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "ts-bindings", derive(ts_rs::TS))]
+#[serde(rename_all = "camelCase")]
+pub struct AccessibilityTree {
+    pub root: AccessibilityNode,
+    /// Number of nodes in the tree
+    pub node_count: u32,
+    /// Whether nodes were left out because of the depth or node limit
+    pub truncated: bool,
+}
+
+fn tree_script(selector: Option<&str>, max_depth: u32, max_nodes: u32) -> String {
+    let options = serde_json::json!({
+        "selector": selector,
+        "maxDepth": max_depth,
+        "maxNodes": max_nodes,
+    });
+    let walker = include_str!("../accessibility_tree.js")
+        .replace("__MCP_A11Y_OPTIONS__", &options.to_string());
+    format!("const tree = {walker}\nreturn tree;")
+}
+
+/// Returns the accessibility tree of the page, or of the element matching
+/// `selector`.
+///
+/// Elements without a role or name, such as layout `div`s, are skipped and
+/// their children attached to the nearest semantic ancestor. Hidden
+/// elements (`display: none`, `visibility: hidden`, `aria-hidden`) are left
+/// out.
+///
+/// # Arguments
+///
+/// * `window` - The webview window to inspect
+/// * `selector` - CSS selector of the root element; `body` when `None`
+/// * `max_depth` - Levels of semantic nodes walked below the root
+/// * `max_nodes` - Stop after this many nodes
+///
+/// # Returns
+///
+/// * `Ok(AccessibilityTree)` - The tree, with whether it was truncated
+/// * `Err(String)` - Error message if nothing matches the selector or the
+///   script could not be evaluated
+pub async fn get_accessibility_tree<R: Runtime>(
+    window: &WebviewWindow<R>,
+    selector: Option<&str>,
+    max_depth: u32,
+    max_nodes: u32,
+) -> Result<AccessibilityTree, String> {
+    let result = run_script(window, &tree_script(selector, max_depth, max_nodes)).await?;
+    serde_json::from_value(result).map_err(|e| format!("Unexpected accessibility tree: {e}"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parses_script_output() {
+        let tree: AccessibilityTree = serde_json::from_value(serde_json::json!({
+            "root": {
+                "role": "generic", "name": null, "value": null, "state": [],
+                "children": [
+                    {
+                        "role": "heading", "name": "Settings", "value": null,
+                        "state": [], "level": 1, "children": []
+                    },
+                    {
+                        "role": "checkbox", "name": "Dark mode", "value": null,
+                        "state": ["checked", "focused"], "children": []
+                    }
+                ]
+            },
+            "nodeCount": 3,
+            "truncated": false
+        }))
+        .unwrap();
+
+        assert_eq!(tree.root.children[0].level, Some(1));
+        assert_eq!(tree.root.children[1].state, ["checked", "focused"]);
+        assert_eq!(tree.node_count, 3);
+    }
+
+    #[test]
+    fn test_script_embeds_options() {
+        let script = tree_script(Some("#app"), 4, 50);
+        assert!(script.starts_with("const tree = "));
+        assert!(script.contains(r##""selector":"#app""##));
+        assert!(script.contains(r#""maxDepth":4"#));
+        assert!(!script.contains("__MCP_A11Y_OPTIONS__"));
+    }
+}
@@ -0,0 +1,128 @@
+//! Geometry of every element matching a selector, for layout analysis.
+
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use tauri::{Runtime, WebviewWindow};
+
+use super::execute_js::run_script;
+
+/// Matches returned by default when no limit is given.
+pub const DEFAULT_MAX_NODES: u32 = 100;
+
+/// Bounding box of one matched element in CSS pixels, relative to the
+/// viewport.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[cfg_attr(feature = "ts-bindings", derive(ts_rs::TS))]
+pub struct BoundingRect {
+    pub x: f64,
+    pub y: f64,
+    pub width: f64,
+    pub height: f64,
+    pub top: f64,
+    pub left: f64,
+    /// Whether the element is rendered, not hidden by `display` or
+    /// `visibility`, and intersects the viewport
+    pub visible: bool,
+}
+
+/// Outcome of [`get_bounding_rects`].
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "ts-bindings", derive(ts_rs::TS))]
+#[serde(rename_all = "camelCase")]
+pub struct BoundingRectsResult {
+    /// Rects of the first `max_nodes` matches, in document order
+    pub rects: Vec<BoundingRect>,
+    /// Total number of matching elements
+    pub total: u32,
+    /// Whether matches were left out because of `max_nodes`
+    pub truncated: bool,
+}
+
+fn rects_script(selector: &str, max_nodes: u32) -> String {
+    format!(
+        r#"return (() => {{
+    const matches = document.querySelectorAll({selector});
+    const maxNodes = {max_nodes};
+    const viewportWidth = window.innerWidth;
+    const viewportHeight = window.innerHeight;
+    const rects = [];
+
+    for (let i = 0; i < matches.length && i < maxNodes; i++) {{
+        const el = matches[i];
+        const rect = el.getBoundingClientRect();
+        const style = getComputedStyle(el);
+        const rendered = el.getClientRects().length > 0 &&
+            style.display !== 'none' &&
+            style.visibility !== 'hidden' &&
+            style.visibility !== 'collapse';
+        const inViewport = rect.right > 0 && rect.bottom > 0 &&
+            rect.left < viewportWidth && rect.top < viewportHeight;
+
+        rects.push({{
+            x: rect.x,
+            y: rect.y,
+            width: rect.width,
+            height: rect.height,
+            top: rect.top,
+            left: rect.left,
+            visible: rendered && inViewport,
+        }});
+    }}
+
+    return {{ rects, total: matches.length, truncated: matches.length > maxNodes }};
+}})();"#,
+        selector = Value::String(selector.to_string()),
+    )
+}
+
+/// Returns the bounding rects of every element matching `selector`.
+///
+/// # Arguments
+///
+/// * `window` - The webview window to inspect
+/// * `selector` - CSS selector passed to `querySelectorAll`
+/// * `max_nodes` - Return at most this many rects
+///
+/// # Returns
+///
+/// * `Ok(BoundingRectsResult)` - Rects in document order, with the total
+///   match count and whether it was truncated
+/// * `Err(String)` - Error message if the selector is invalid or the script
+///   could not be evaluated
+pub async fn get_bounding_rects<R: Runtime>(
+    window: &WebviewWindow<R>,
+    selector: &str,
+    max_nodes: u32,
+) -> Result<BoundingRectsResult, String> {
+    let result = run_script(window, &rects_script(selector, max_nodes)).await?;
+    serde_json::from_value(result).map_err(|e| format!("Unexpected rect data: {e}"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parses_script_output() {
+        let result: BoundingRectsResult = serde_json::from_value(serde_json::json!({
+            "rects": [{
+                "x": 8, "y": -20.5, "width": 100, "height": 40,
+                "top": -20.5, "left": 8, "visible": true
+            }],
+            "total": 3,
+            "truncated": true
+        }))
+        .unwrap();
+
+        assert_eq!(result.rects[0].y, -20.5);
+        assert_eq!(result.total, 3);
+        assert!(result.truncated);
+    }
+
+    #[test]
+    fn test_script_embeds_selector_and_limit() {
+        let script = rects_script("li[data-id='1']", 5);
+        assert!(script.contains(r#"document.querySelectorAll("li[data-id='1']")"#));
+        assert!(script.contains("const maxNodes = 5;"));
+    }
+}
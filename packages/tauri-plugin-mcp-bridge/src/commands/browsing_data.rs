@@ -0,0 +1,193 @@
+//! Clearing web storage, cookies and caches between test scenarios.
+//!
+//! Each category is cleared independently so one failure or platform gap
+//! doesn't abort the others. Cookies go through the native cookie store,
+//! which isn't exposed on Android. `cache` clears the Cache Storage API
+//! (`window.caches`); the webview's HTTP cache has no per-category API.
+
+use serde::{Deserialize, Serialize};
+use tauri::{Runtime, WebviewWindow};
+
+use super::execute_js::run_script;
+
+/// Which categories of browsing data to clear.
+#[derive(Debug, Clone, Default, Deserialize)]
+#[cfg_attr(feature = "ts-bindings", derive(ts_rs::TS))]
+#[serde(rename_all = "camelCase", default)]
+pub struct ClearBrowsingDataOptions {
+    /// Clear `window.localStorage`
+    pub local_storage: bool,
+    /// Clear `window.sessionStorage`
+    pub session_storage: bool,
+    /// Delete all cookies in the webview's cookie store
+    pub cookies: bool,
+    /// Delete every Cache Storage cache
+    pub cache: bool,
+}
+
+impl ClearBrowsingDataOptions {
+    /// Options clearing every category.
+    pub fn all() -> Self {
+        Self {
+            local_storage: true,
+            session_storage: true,
+            cookies: true,
+            cache: true,
+        }
+    }
+
+    /// Whether no category was requested.
+    pub fn is_empty(&self) -> bool {
+        !(self.local_storage || self.session_storage || self.cookies || self.cache)
+    }
+}
+
+/// Outcome of clearing one category.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[cfg_attr(feature = "ts-bindings", derive(ts_rs::TS))]
+#[serde(tag = "status", content = "error", rename_all = "lowercase")]
+pub enum ClearOutcome {
+    /// The category was cleared
+    Cleared,
+    /// The category can't be cleared on this platform or page
+    Unsupported,
+    /// Clearing was attempted but failed
+    Failed(String),
+}
+
+/// Per-category results. Categories that weren't requested are `None`.
+#[derive(Debug, Clone, Default, Serialize)]
+#[cfg_attr(feature = "ts-bindings", derive(ts_rs::TS))]
+#[serde(rename_all = "camelCase")]
+pub struct ClearBrowsingDataResult {
+    /// Outcome for `localStorage`
+    pub local_storage: Option<ClearOutcome>,
+    /// Outcome for `sessionStorage`
+    pub session_storage: Option<ClearOutcome>,
+    /// Outcome for the cookie store
+    pub cookies: Option<ClearOutcome>,
+    /// Outcome for Cache Storage
+    pub cache: Option<ClearOutcome>,
+}
+
+/// Clears the requested categories of browsing data in a window.
+///
+/// # Arguments
+///
+/// * `window` - The webview window whose data should be cleared
+/// * `options` - Which categories to clear
+///
+/// # Returns
+///
+/// The outcome of each requested category. Failures are reported per
+/// category rather than failing the whole call.
+pub async fn clear_browsing_data<R: Runtime>(
+    window: &WebviewWindow<R>,
+    options: &ClearBrowsingDataOptions,
+) -> ClearBrowsingDataResult {
+    let mut result = ClearBrowsingDataResult::default();
+
+    if options.local_storage {
+        result.local_storage = Some(clear_storage(window, "localStorage").await);
+    }
+    if options.session_storage {
+        result.session_storage = Some(clear_storage(window, "sessionStorage").await);
+    }
+    if options.cookies {
+        result.cookies = Some(clear_cookies(window));
+    }
+    if options.cache {
+        result.cache = Some(clear_cache_storage(window).await);
+    }
+
+    result
+}
+
+async fn clear_storage<R: Runtime>(window: &WebviewWindow<R>, object: &str) -> ClearOutcome {
+    // Storage is unavailable (and throws on access) for opaque origins
+    let script = format!(
+        r#"try {{
+    window.{object}.clear();
+    return true;
+}} catch (e) {{
+    return false;
+}}"#
+    );
+
+    match run_script(window, &script).await {
+        Ok(cleared) if cleared.as_bool() == Some(true) => ClearOutcome::Cleared,
+        Ok(_) => ClearOutcome::Unsupported,
+        Err(e) => ClearOutcome::Failed(e),
+    }
+}
+
+#[cfg(target_os = "android")]
+fn clear_cookies<R: Runtime>(_window: &WebviewWindow<R>) -> ClearOutcome {
+    ClearOutcome::Unsupported
+}
+
+#[cfg(not(target_os = "android"))]
+fn clear_cookies<R: Runtime>(window: &WebviewWindow<R>) -> ClearOutcome {
+    let cookies = match window.cookies() {
+        Ok(cookies) => cookies,
+        Err(e) => return ClearOutcome::Failed(format!("Failed to read cookies: {e}")),
+    };
+
+    for cookie in cookies {
+        if let Err(e) = window.delete_cookie(cookie) {
+            return ClearOutcome::Failed(format!("Failed to delete cookie: {e}"));
+        }
+    }
+
+    ClearOutcome::Cleared
+}
+
+async fn clear_cache_storage<R: Runtime>(window: &WebviewWindow<R>) -> ClearOutcome {
+    // `caches` only exists in secure contexts
+    let script = r#"if (typeof caches === 'undefined') {
+    return false;
+}
+const names = await caches.keys();
+await Promise.all(names.map((name) => caches.delete(name)));
+return true;"#;
+
+    match run_script(window, script).await {
+        Ok(cleared) if cleared.as_bool() == Some(true) => ClearOutcome::Cleared,
+        Ok(_) => ClearOutcome::Unsupported,
+        Err(e) => ClearOutcome::Failed(e),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_options_default_to_nothing() {
+        let options: ClearBrowsingDataOptions =
+            serde_json::from_value(json!({ "localStorage": true })).unwrap();
+        assert!(options.local_storage);
+        assert!(!options.cookies);
+        assert!(!options.is_empty());
+        assert!(ClearBrowsingDataOptions::default().is_empty());
+    }
+
+    #[test]
+    fn test_result_serialization() {
+        let result = ClearBrowsingDataResult {
+            cookies: Some(ClearOutcome::Unsupported),
+            cache: Some(ClearOutcome::Failed("boom".to_string())),
+            ..Default::default()
+        };
+        assert_eq!(
+            serde_json::to_value(result).unwrap(),
+            json!({
+                "localStorage": null,
+                "sessionStorage": null,
+                "cookies": { "status": "unsupported" },
+                "cache": { "status": "failed", "error": "boom" }
+            })
+        );
+    }
+}
@@ -0,0 +1,251 @@
+//! Text extraction from viewport captures.
+//!
+//! With the `ocr` feature, captures are piped through the `tesseract`
+//! command-line tool, which must be installed and on `PATH`. Nothing is
+//! linked, so builds without Tesseract installed still succeed and report
+//! the missing backend when the command runs.
+
+use std::collections::BTreeMap;
+
+use serde::{Deserialize, Serialize};
+use tauri::{Manager, Runtime, WebviewWindow};
+
+use crate::config::Config;
+use crate::error::CommandError;
+use crate::screenshot::{self, CaptureOptions, ScreenshotOptions};
+
+/// Options for [`capture_text`].
+#[derive(Debug, Clone, Default, Deserialize)]
+#[cfg_attr(feature = "ts-bindings", derive(ts_rs::TS))]
+#[serde(rename_all = "camelCase", default)]
+pub struct CaptureTextOptions {
+    /// Tesseract language codes, e.g. "eng" or "eng+deu" (default: "eng")
+    pub language: Option<String>,
+    /// Drop lines whose mean word confidence (0-100) is below this
+    pub min_confidence: Option<f32>,
+}
+
+/// One line of recognized text.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[cfg_attr(feature = "ts-bindings", derive(ts_rs::TS))]
+#[serde(rename_all = "camelCase")]
+pub struct TextBlock {
+    /// Words of the line joined with spaces
+    pub text: String,
+    /// Left edge in image pixels
+    pub x: u32,
+    /// Top edge in image pixels
+    pub y: u32,
+    pub width: u32,
+    pub height: u32,
+    /// Mean word confidence, 0-100
+    pub confidence: f32,
+}
+
+/// Text recognized in a viewport capture.
+#[derive(Debug, Clone, Serialize)]
+#[cfg_attr(feature = "ts-bindings", derive(ts_rs::TS))]
+#[serde(rename_all = "camelCase")]
+pub struct TextCapture {
+    /// Lines in reading order
+    pub blocks: Vec<TextBlock>,
+    /// Capture width in pixels
+    pub width: u32,
+    /// Capture height in pixels
+    pub height: u32,
+    /// Window scale factor; divide block coordinates by it for CSS pixels
+    pub scale_factor: f64,
+}
+
+/// Groups the words of Tesseract's TSV output into lines.
+///
+/// Columns are `level page_num block_num par_num line_num word_num left top
+/// width height conf text`; only word rows (level 5) carry text.
+fn parse_tsv(tsv: &str) -> Vec<TextBlock> {
+    struct Line {
+        words: Vec<String>,
+        left: u32,
+        top: u32,
+        right: u32,
+        bottom: u32,
+        confidence_sum: f32,
+    }
+
+    let mut lines: BTreeMap<[u32; 4], Line> = BTreeMap::new();
+
+    for row in tsv.lines().skip(1) {
+        let columns: Vec<&str> = row.splitn(12, '\t').collect();
+        if columns.len() < 12 || columns[0] != "5" {
+            continue;
+        }
+        let text = columns[11].trim();
+        if text.is_empty() {
+            continue;
+        }
+
+        let number = |i: usize| columns[i].parse::<u32>().unwrap_or(0);
+        let key = [number(1), number(2), number(3), number(4)];
+        let (left, top) = (number(6), number(7));
+        let (right, bottom) = (left + number(8), top + number(9));
+        let confidence = columns[10].parse::<f32>().unwrap_or(0.0).max(0.0);
+
+        let line = lines.entry(key).or_insert(Line {
+            words: Vec::new(),
+            left,
+            top,
+            right,
+            bottom,
+            confidence_sum: 0.0,
+        });
+        line.words.push(text.to_string());
+        line.left = line.left.min(left);
+        line.top = line.top.min(top);
+        line.right = line.right.max(right);
+        line.bottom = line.bottom.max(bottom);
+        line.confidence_sum += confidence;
+    }
+
+    lines
+        .into_values()
+        .map(|line| TextBlock {
+            confidence: line.confidence_sum / line.words.len() as f32,
+            text: line.words.join(" "),
+            x: line.left,
+            y: line.top,
+            width: line.right - line.left,
+            height: line.bottom - line.top,
+        })
+        .collect()
+}
+
+/// Runs `tesseract` on a PNG and returns its TSV output.
+#[cfg(feature = "ocr")]
+async fn run_tesseract(png: &[u8], language: &str) -> Result<String, String> {
+    use std::process::Stdio;
+    use tokio::io::AsyncWriteExt;
+
+    let mut child = tokio::process::Command::new("tesseract")
+        .args(["stdin", "stdout", "-l", language, "tsv"])
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .kill_on_drop(true)
+        .spawn()
+        .map_err(|e| match e.kind() {
+            std::io::ErrorKind::NotFound => {
+                "OCR backend unavailable: `tesseract` was not found on PATH".to_string()
+            }
+            _ => format!("OCR backend unavailable: failed to start `tesseract`: {e}"),
+        })?;
+
+    let mut stdin = child.stdin.take().ok_or("Failed to open tesseract input")?;
+    stdin
+        .write_all(png)
+        .await
+        .map_err(|e| format!("Failed to send capture to tesseract: {e}"))?;
+    drop(stdin);
+
+    let output = child
+        .wait_with_output()
+        .await
+        .map_err(|e| format!("Failed to run tesseract: {e}"))?;
+    if !output.status.success() {
+        return Err(format!(
+            "OCR failed: {}",
+            String::from_utf8_lossy(&output.stderr).trim()
+        ));
+    }
+
+    String::from_utf8(output.stdout).map_err(|e| format!("Invalid tesseract output: {e}"))
+}
+
+#[cfg(not(feature = "ocr"))]
+async fn run_tesseract(_png: &[u8], _language: &str) -> Result<String, String> {
+    Err("OCR backend unavailable: the plugin was built without the `ocr` feature".to_string())
+}
+
+/// Captures the viewport and extracts its text with bounding boxes.
+///
+/// The capture is taken as a full-resolution PNG, subject to the max width
+/// environment variable, so coordinates are in physical pixels.
+///
+/// # Arguments
+///
+/// * `window` - The window to capture
+/// * `options` - OCR language and confidence filter
+///
+/// # Returns
+///
+/// * `Ok(TextCapture)` - The recognized lines and the capture's size
+/// * `Err(CommandError)` - Error if the capture fails, the plugin was built
+///   without `ocr`, or Tesseract is missing or fails
+pub async fn capture_text<R: Runtime>(
+    window: &WebviewWindow<R>,
+    options: &CaptureTextOptions,
+) -> Result<TextCapture, CommandError> {
+    let capture_options = ScreenshotOptions {
+        capture: CaptureOptions {
+            timeout: window.state::<Config>().capture_timeout,
+            ..Default::default()
+        },
+        ..Default::default()
+    };
+    let capture = screenshot::capture_viewport_screenshot(window, &capture_options).await?;
+    let (width, height) = image::load_from_memory(&capture.data)
+        .map(|image| (image.width(), image.height()))
+        .map_err(|e| format!("Failed to decode capture: {e}"))?;
+
+    let language = options.language.as_deref().unwrap_or("eng");
+    let tsv = run_tesseract(&capture.data, language).await?;
+
+    let mut blocks = parse_tsv(&tsv);
+    if let Some(min_confidence) = options.min_confidence {
+        blocks.retain(|block| block.confidence >= min_confidence);
+    }
+
+    Ok(TextCapture {
+        blocks,
+        width,
+        height,
+        scale_factor: window.scale_factor().unwrap_or(1.0),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_tsv_groups_words_into_lines() {
+        let tsv = "level\tpage_num\tblock_num\tpar_num\tline_num\tword_num\tleft\ttop\twidth\theight\tconf\ttext\n\
+                   1\t1\t0\t0\t0\t0\t0\t0\t800\t600\t-1\t\n\
+                   4\t1\t1\t1\t1\t0\t10\t20\t90\t12\t-1\t\n\
+                   5\t1\t1\t1\t1\t1\t10\t20\t40\t12\t90\tHello\n\
+                   5\t1\t1\t1\t1\t2\t60\t18\t40\t14\t80\tworld\n\
+                   5\t1\t1\t1\t1\t3\t110\t20\t5\t12\t10\t \n\
+                   5\t1\t2\t1\t1\t1\t10\t50\t30\t10\t70\tSave\n";
+
+        let blocks = parse_tsv(tsv);
+        assert_eq!(
+            blocks,
+            [
+                TextBlock {
+                    text: "Hello world".into(),
+                    x: 10,
+                    y: 18,
+                    width: 90,
+                    height: 14,
+                    confidence: 85.0,
+                },
+                TextBlock {
+                    text: "Save".into(),
+                    x: 10,
+                    y: 50,
+                    width: 30,
+                    height: 10,
+                    confidence: 70.0,
+                },
+            ]
+        );
+    }
+}
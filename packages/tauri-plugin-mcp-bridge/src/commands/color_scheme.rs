@@ -0,0 +1,171 @@
+//! Light and dark theme switching, for capturing both variants of a UI.
+//!
+//! `set_color_scheme` sets the native window theme where Tauri supports it
+//! (desktop; app-wide on macOS and Linux), which webviews that follow the
+//! system appearance pass on to `prefers-color-scheme`. It also registers a
+//! shim as a window-scoped bridge script, so the override survives
+//! navigations. The shim is best effort: it covers `matchMedia`,
+//! same-origin stylesheet media rules and the root `color-scheme`, but
+//! pages that pick their theme some other way, such as from a stored
+//! preference, ignore it.
+
+use serde::{Deserialize, Serialize};
+use tauri::{Runtime, Theme, WebviewWindow};
+
+use super::execute_js::run_script;
+use crate::monitor::current_timestamp;
+use crate::script_registry::{
+    InjectionPhase, ScriptEntry, ScriptScope, ScriptType, SharedScriptRegistry,
+};
+
+/// Registry ID prefix of the shim script; the window label is appended.
+const COLOR_SCHEME_SCRIPT_ID: &str = "__mcp_color_scheme__";
+
+/// A color scheme to force, or `Auto` to follow the system.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "ts-bindings", derive(ts_rs::TS))]
+#[serde(rename_all = "lowercase")]
+pub enum ColorScheme {
+    Light,
+    Dark,
+    Auto,
+}
+
+impl ColorScheme {
+    #[cfg_attr(mobile, allow(dead_code))]
+    fn theme(self) -> Option<Theme> {
+        match self {
+            ColorScheme::Light => Some(Theme::Light),
+            ColorScheme::Dark => Some(Theme::Dark),
+            ColorScheme::Auto => None,
+        }
+    }
+}
+
+/// Color scheme of a window, from [`get_color_scheme`] and
+/// [`set_color_scheme`].
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "ts-bindings", derive(ts_rs::TS))]
+#[serde(rename_all = "camelCase")]
+pub struct ColorSchemeState {
+    /// The scheme forced on the page, or `auto` when it follows the system
+    pub scheme: ColorScheme,
+    /// Whether `(prefers-color-scheme: dark)` matches for page scripts
+    pub prefers_dark: bool,
+    /// The native window theme, `light` or `dark`, when the platform
+    /// reports one
+    #[serde(default)]
+    pub window_theme: Option<ColorScheme>,
+}
+
+fn script_id(window_label: &str) -> String {
+    format!("{COLOR_SCHEME_SCRIPT_ID}:{window_label}")
+}
+
+/// Builds the shim script with the scheme substituted.
+fn color_scheme_script(scheme: ColorScheme) -> String {
+    let scheme = serde_json::to_value(scheme).unwrap_or_default();
+    include_str!("../color_scheme.js").replace("__MCP_COLOR_SCHEME_VALUE__", &scheme.to_string())
+}
+
+const READ_COLOR_SCHEME_SCRIPT: &str = r#"return {
+    scheme: window.__MCP_COLOR_SCHEME__ ? window.__MCP_COLOR_SCHEME__.scheme : 'auto',
+    prefersDark: window.matchMedia('(prefers-color-scheme: dark)').matches,
+};"#;
+
+/// Returns the color scheme the window's page sees.
+///
+/// # Arguments
+///
+/// * `window` - The webview window to inspect
+///
+/// # Returns
+///
+/// * `Ok(ColorSchemeState)` - The override and what the page sees
+/// * `Err(String)` - Error message if the script could not be evaluated
+pub async fn get_color_scheme<R: Runtime>(
+    window: &WebviewWindow<R>,
+) -> Result<ColorSchemeState, String> {
+    let mut state: ColorSchemeState =
+        serde_json::from_value(run_script(window, READ_COLOR_SCHEME_SCRIPT).await?)
+            .map_err(|e| format!("Unexpected color scheme result: {e}"))?;
+    state.window_theme = match window.theme() {
+        Ok(Theme::Light) => Some(ColorScheme::Light),
+        Ok(Theme::Dark) => Some(ColorScheme::Dark),
+        _ => None,
+    };
+    Ok(state)
+}
+
+/// Forces the window into a light or dark color scheme, or with `Auto`
+/// removes the override.
+///
+/// # Arguments
+///
+/// * `window` - The webview window to theme
+/// * `registry` - Script registry holding the shim across navigations
+/// * `scheme` - The scheme to force
+///
+/// # Returns
+///
+/// * `Ok(ColorSchemeState)` - The color scheme the page now sees
+/// * `Err(String)` - Error message if the shim could not be installed or
+///   removed
+pub async fn set_color_scheme<R: Runtime>(
+    window: &WebviewWindow<R>,
+    registry: &SharedScriptRegistry,
+    scheme: ColorScheme,
+) -> Result<ColorSchemeState, String> {
+    let label = window.label().to_string();
+    let id = script_id(&label);
+
+    // Mobile windows have no theme setter; the shim still applies there
+    #[cfg(desktop)]
+    window
+        .set_theme(scheme.theme())
+        .map_err(|e| format!("Failed to set window theme: {e}"))?;
+
+    if scheme == ColorScheme::Auto {
+        registry.lock().unwrap().remove(&id);
+        window
+            .eval(format!(
+                "window.__MCP_COLOR_SCHEME__ && window.__MCP_COLOR_SCHEME__.restore();\
+                 window.__MCP_REMOVE_SCRIPT__ && window.__MCP_REMOVE_SCRIPT__({});",
+                serde_json::Value::String(id)
+            ))
+            .map_err(|e| format!("Failed to remove color scheme shim: {e}"))?;
+    } else {
+        let script = color_scheme_script(scheme);
+        {
+            let mut entry = ScriptEntry::new(&id, ScriptType::Inline, &script);
+            entry.phase = InjectionPhase::Setup;
+
+            let mut registry = registry.lock().unwrap();
+            registry
+                .add(ScriptScope::Window(label.clone()), entry)
+                .map_err(|e| e.to_string())?;
+            registry.mark_injected(&label, [id.as_str()], current_timestamp());
+        }
+        window
+            .eval(&script)
+            .map_err(|e| format!("Failed to install color scheme shim: {e}"))?;
+    }
+
+    get_color_scheme(window).await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_script_substitutes_scheme() {
+        let script = color_scheme_script(ColorScheme::Dark);
+        assert!(!script.contains("__MCP_COLOR_SCHEME_VALUE__"));
+        assert!(script.contains(r#"}("dark"));"#));
+
+        let scheme: ColorScheme = serde_json::from_value(serde_json::json!("auto")).unwrap();
+        assert_eq!(scheme.theme(), None);
+        assert_eq!(ColorScheme::Light.theme(), Some(Theme::Light));
+    }
+}
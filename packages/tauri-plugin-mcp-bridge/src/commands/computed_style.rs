@@ -0,0 +1,159 @@
+//! Computed CSS of an element, for layout debugging alongside
+//! [`get_bounding_rects`](super::get_bounding_rects) and for deciding
+//! whether it is visible and clickable, which its box alone doesn't tell.
+
+use std::collections::BTreeMap;
+
+use serde_json::Value;
+use tauri::{Runtime, WebviewWindow};
+
+use super::execute_js::run_script;
+use crate::error::{CommandError, ELEMENT_NOT_FOUND};
+
+/// Properties returned when none are requested: the ones that decide box
+/// size, position, visibility (`display`, `visibility`, `opacity`,
+/// `pointer-events`, `z-index`) and text layout. The full computed style has
+/// hundreds of entries.
+pub const DEFAULT_STYLE_PROPERTIES: &[&str] = &[
+    "display",
+    "position",
+    "top",
+    "right",
+    "bottom",
+    "left",
+    "z-index",
+    "box-sizing",
+    "width",
+    "height",
+    "min-width",
+    "min-height",
+    "max-width",
+    "max-height",
+    "margin",
+    "padding",
+    "border-width",
+    "border-style",
+    "border-color",
+    "overflow",
+    "flex",
+    "flex-direction",
+    "flex-wrap",
+    "justify-content",
+    "align-items",
+    "align-self",
+    "gap",
+    "grid-template-columns",
+    "grid-template-rows",
+    "transform",
+    "opacity",
+    "visibility",
+    "pointer-events",
+    "color",
+    "background-color",
+    "font-family",
+    "font-size",
+    "font-weight",
+    "line-height",
+    "text-align",
+    "white-space",
+];
+
+/// Computed values by property name, as requested. Unknown properties map
+/// to `None`.
+pub type ComputedStyle = BTreeMap<String, Option<String>>;
+
+fn style_script(selector: &str, properties: &[String]) -> String {
+    format!(
+        r#"return (() => {{
+    const el = document.querySelector({selector});
+    if (!el) return null;
+
+    const style = getComputedStyle(el);
+    const result = {{}};
+    for (const name of {properties}) {{
+        // Kebab-case and custom properties, or camelCase as in element.style
+        const known = name.startsWith('--') || name in style;
+        const value = name.includes('-') ? style.getPropertyValue(name) : style[name];
+        result[name] = known && typeof value === 'string' ? value : null;
+    }}
+    return result;
+}})();"#,
+        selector = Value::String(selector.to_string()),
+        properties = Value::from(properties),
+    )
+}
+
+/// Returns computed CSS properties of the first element matching
+/// `selector`.
+///
+/// # Arguments
+///
+/// * `window` - The webview window to inspect
+/// * `selector` - CSS selector passed to `querySelector`
+/// * `properties` - Property names, kebab-case (`background-color`),
+///   camelCase (`backgroundColor`) or custom (`--accent`), returned under
+///   the name given; [`DEFAULT_STYLE_PROPERTIES`] when empty
+///
+/// # Returns
+///
+/// * `Ok(ComputedStyle)` - The requested values
+/// * `Err(CommandError)` - `ELEMENT_NOT_FOUND` if nothing matches the
+///   selector, or `COMMAND_FAILED` if the selector is invalid or the script
+///   could not be evaluated
+pub async fn get_computed_style<R: Runtime>(
+    window: &WebviewWindow<R>,
+    selector: &str,
+    properties: &[String],
+) -> Result<ComputedStyle, CommandError> {
+    let properties = if properties.is_empty() {
+        DEFAULT_STYLE_PROPERTIES
+            .iter()
+            .map(|p| p.to_string())
+            .collect()
+    } else {
+        properties.to_vec()
+    };
+
+    let result = run_script(window, &style_script(selector, &properties)).await?;
+    parse_style(result, selector)
+}
+
+fn parse_style(result: Value, selector: &str) -> Result<ComputedStyle, CommandError> {
+    if result.is_null() {
+        return Err(CommandError::new(
+            ELEMENT_NOT_FOUND,
+            format!("No element matches selector: {selector}"),
+        ));
+    }
+    serde_json::from_value(result).map_err(|e| format!("Unexpected style data: {e}").into())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parses_match_and_miss() {
+        let style = parse_style(
+            serde_json::json!({
+                "backgroundColor": "rgb(255, 255, 255)",
+                "--accent": " #09f",
+                "colour": null
+            }),
+            "#app",
+        )
+        .unwrap();
+        assert_eq!(
+            style["backgroundColor"].as_deref(),
+            Some("rgb(255, 255, 255)")
+        );
+        assert_eq!(style["colour"], None);
+
+        let miss = parse_style(Value::Null, "#missing").unwrap_err();
+        assert_eq!(miss.code(), ELEMENT_NOT_FOUND);
+        assert!(!miss.is_retryable());
+
+        let script = style_script("#app", &["gap".to_string()]);
+        assert!(script.contains(r#"for (const name of ["gap"])"#));
+    }
+}
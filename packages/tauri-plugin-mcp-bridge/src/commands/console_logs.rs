@@ -0,0 +1,112 @@
+//! Console output captured from the page.
+//!
+//! bridge.js wraps `console.log`, `debug`, `info`, `warn` and `error` once
+//! at startup, truncating each argument to 4 KiB, and forwards the calls in
+//! batches to the per-window [`ConsoleLog`](crate::console_log::ConsoleLog).
+//! The batch interval and size are set with
+//! [`Builder::console_batch_interval`](crate::Builder::console_batch_interval)
+//! and [`Builder::console_batch_size`](crate::Builder::console_batch_size).
+//! The last 1000 calls are also kept in `window.__MCP_CONSOLE_LOGS__` for
+//! MCP servers that read the page directly.
+
+use serde::{Deserialize, Serialize};
+use tauri::{command, Runtime, State, WebviewWindow};
+
+use super::execute_js::run_script;
+use crate::console_log::ConsoleLogState;
+
+/// One captured console call.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[cfg_attr(feature = "ts-bindings", derive(ts_rs::TS))]
+pub struct ConsoleEntry {
+    /// Console method: "log", "debug", "info", "warn" or "error"
+    pub level: String,
+    /// Arguments joined with spaces
+    pub message: String,
+    /// Each argument stringified, objects as JSON
+    #[serde(default)]
+    pub args: Vec<String>,
+    /// When the call was made (epoch millis)
+    #[cfg_attr(feature = "ts-bindings", ts(type = "number"))]
+    pub timestamp: u64,
+}
+
+/// Sends the page's pending batch to the log before it is read. Pages
+/// without batched capture, such as those still running a capture installed
+/// by an older MCP server, return their own buffer instead.
+fn flush_console_script(clear: bool) -> String {
+    format!(
+        r#"return (async () => {{
+    const logs = Array.isArray(window.__MCP_CONSOLE_LOGS__) ? window.__MCP_CONSOLE_LOGS__ : [];
+    const flush = window.__MCP_FLUSH_CONSOLE__;
+    if (typeof flush === 'function') {{
+        await flush();
+    }}
+    if ({clear}) {{
+        window.__MCP_CONSOLE_LOGS__ = [];
+    }}
+    return typeof flush === 'function' ? null : logs;
+}})();"#
+    )
+}
+
+/// Returns the console output of a page, oldest first.
+///
+/// # Arguments
+///
+/// * `window` - The webview window to read from
+/// * `log` - Console log the page's batches are recorded in
+/// * `clear` - Drain the buffer after reading it
+///
+/// # Returns
+///
+/// * `Ok(Vec<ConsoleEntry>)` - The captured calls, at most the last 1000
+/// * `Err(String)` - Error message if the script failed
+pub async fn get_console_logs<R: Runtime>(
+    window: &WebviewWindow<R>,
+    log: &ConsoleLogState,
+    clear: bool,
+) -> Result<Vec<ConsoleEntry>, String> {
+    let value = run_script(window, &flush_console_script(clear)).await?;
+    if !value.is_null() {
+        return serde_json::from_value(value)
+            .map_err(|e| format!("Invalid console log entries: {e}"));
+    }
+
+    Ok(log
+        .lock()
+        .map_err(|e| format!("Failed to lock console log: {e}"))?
+        .entries(window.label(), clear))
+}
+
+/// Records a batch of console calls forwarded by the bridge script.
+#[command]
+pub async fn record_console_entries<R: Runtime>(
+    window: WebviewWindow<R>,
+    log: State<'_, ConsoleLogState>,
+    entries: Vec<ConsoleEntry>,
+) -> Result<(), String> {
+    log.lock()
+        .map_err(|e| format!("Failed to lock console log: {e}"))?
+        .record(window.label(), entries);
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_entries_without_args_deserialize() {
+        // Capture installed by older MCP servers doesn't record `args`
+        let entries: Vec<ConsoleEntry> = serde_json::from_value(json!([
+            { "level": "warn", "message": "low disk", "timestamp": 1 },
+            { "level": "log", "message": "a 1", "args": ["a", "1"], "timestamp": 2 }
+        ]))
+        .unwrap();
+
+        assert!(entries[0].args.is_empty());
+        assert_eq!(entries[1].args, ["a", "1"]);
+    }
+}
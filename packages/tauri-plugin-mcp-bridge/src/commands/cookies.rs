@@ -0,0 +1,250 @@
+//! Cookie inspection and seeding for authenticated flows.
+//!
+//! Cookies are read and written through the webview's native cookie store,
+//! which includes HttpOnly and secure cookies. Android does not expose the
+//! native store, so there the commands fall back to `document.cookie` via
+//! injected JavaScript. That fallback **cannot read or write HttpOnly
+//! cookies** and only sees cookies for the currently loaded page.
+
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use tauri::webview::cookie::time::OffsetDateTime;
+use tauri::webview::Cookie;
+use tauri::{Runtime, Url, WebviewWindow};
+
+/// A cookie as exchanged with MCP clients.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "ts-bindings", derive(ts_rs::TS))]
+#[serde(rename_all = "camelCase")]
+pub struct CookieInfo {
+    /// Cookie name
+    pub name: String,
+    /// Cookie value
+    pub value: String,
+    /// Domain the cookie applies to (if set)
+    #[serde(default)]
+    pub domain: Option<String>,
+    /// Path the cookie applies to (if set)
+    #[serde(default)]
+    pub path: Option<String>,
+    /// Expiry as seconds since the Unix epoch, `None` for session cookies
+    #[serde(default)]
+    #[cfg_attr(feature = "ts-bindings", ts(type = "number | null"))]
+    pub expires: Option<i64>,
+    /// Whether the cookie is hidden from JavaScript
+    #[serde(default)]
+    pub http_only: bool,
+    /// Whether the cookie is only sent over HTTPS
+    #[serde(default)]
+    pub secure: bool,
+}
+
+impl CookieInfo {
+    fn from_cookie(cookie: &Cookie<'_>) -> Self {
+        Self {
+            name: cookie.name().to_string(),
+            value: cookie.value().to_string(),
+            domain: cookie.domain().map(|d| d.to_string()),
+            path: cookie.path().map(|p| p.to_string()),
+            expires: cookie.expires_datetime().map(|t| t.unix_timestamp()),
+            http_only: cookie.http_only().unwrap_or(false),
+            secure: cookie.secure().unwrap_or(false),
+        }
+    }
+
+    fn to_cookie(&self) -> Result<Cookie<'static>, String> {
+        let mut cookie = Cookie::new(self.name.clone(), self.value.clone());
+
+        if let Some(domain) = &self.domain {
+            cookie.set_domain(domain.clone());
+        }
+        if let Some(path) = &self.path {
+            cookie.set_path(path.clone());
+        }
+        if let Some(expires) = self.expires {
+            let expires = OffsetDateTime::from_unix_timestamp(expires)
+                .map_err(|e| format!("Invalid cookie expiry {expires}: {e}"))?;
+            cookie.set_expires(expires);
+        }
+        cookie.set_http_only(self.http_only);
+        cookie.set_secure(self.secure);
+
+        Ok(cookie)
+    }
+}
+
+/// Parses a cookie spec, either a `CookieInfo` object or a `Set-Cookie`
+/// style string such as `"session=abc; Path=/; HttpOnly"`.
+///
+/// Malformed specs are rejected instead of being silently dropped.
+pub fn parse_cookie_spec(spec: &Value) -> Result<CookieInfo, String> {
+    let info = match spec {
+        Value::String(raw) => {
+            let cookie =
+                Cookie::parse(raw.clone()).map_err(|e| format!("Malformed cookie '{raw}': {e}"))?;
+            CookieInfo::from_cookie(&cookie)
+        }
+        Value::Object(_) => serde_json::from_value::<CookieInfo>(spec.clone())
+            .map_err(|e| format!("Malformed cookie: {e}"))?,
+        _ => return Err("Cookie must be an object or a cookie string".to_string()),
+    };
+
+    validate_cookie(&info)?;
+    Ok(info)
+}
+
+/// Rejects names and values that would corrupt the cookie header.
+fn validate_cookie(cookie: &CookieInfo) -> Result<(), String> {
+    let invalid_name = cookie.name.is_empty()
+        || cookie
+            .name
+            .chars()
+            .any(|c| c.is_whitespace() || c.is_control() || "()<>@,;:\\\"/[]?={}".contains(c));
+    if invalid_name {
+        return Err(format!("Malformed cookie: invalid name '{}'", cookie.name));
+    }
+
+    if cookie
+        .value
+        .chars()
+        .any(|c| c == ';' || c == ',' || c.is_control())
+    {
+        return Err(format!(
+            "Malformed cookie: invalid value for '{}'",
+            cookie.name
+        ));
+    }
+
+    Ok(())
+}
+
+/// Returns the cookies that apply to `url`.
+///
+/// # Arguments
+///
+/// * `window` - The webview window whose cookie store is read
+/// * `url` - An http(s) URL to match cookies against
+///
+/// # Returns
+///
+/// * `Ok(Vec<CookieInfo>)` - Matching cookies, including HttpOnly cookies on
+///   platforms with native cookie store access
+/// * `Err(String)` - Error message if the URL is invalid or reading fails
+pub async fn get_cookies<R: Runtime>(
+    window: &WebviewWindow<R>,
+    url: &str,
+) -> Result<Vec<CookieInfo>, String> {
+    let url = Url::parse(url).map_err(|e| format!("Invalid URL '{url}': {e}"))?;
+
+    #[cfg(target_os = "android")]
+    {
+        let _ = url;
+        let cookies = super::execute_js::run_script(window, "return document.cookie;").await?;
+        Ok(parse_document_cookie(cookies.as_str().unwrap_or_default()))
+    }
+
+    #[cfg(not(target_os = "android"))]
+    {
+        let cookies = window
+            .cookies_for_url(url)
+            .map_err(|e| format!("Failed to read cookies: {e}"))?;
+        Ok(cookies.iter().map(CookieInfo::from_cookie).collect())
+    }
+}
+
+/// Sets a cookie in the webview's cookie store.
+///
+/// # Returns
+///
+/// * `Ok(CookieInfo)` - The cookie as it was written
+/// * `Err(String)` - Error message if the cookie is invalid or writing fails
+pub async fn set_cookie<R: Runtime>(
+    window: &WebviewWindow<R>,
+    cookie: CookieInfo,
+) -> Result<CookieInfo, String> {
+    validate_cookie(&cookie)?;
+    let native = cookie.to_cookie()?;
+
+    #[cfg(target_os = "android")]
+    {
+        if cookie.http_only {
+            return Err("HttpOnly cookies cannot be set through document.cookie".to_string());
+        }
+        let script = format!("document.cookie = {};", Value::String(native.to_string()));
+        super::execute_js::run_script(window, &script).await?;
+    }
+
+    #[cfg(not(target_os = "android"))]
+    window
+        .set_cookie(native)
+        .map_err(|e| format!("Failed to set cookie: {e}"))?;
+
+    Ok(cookie)
+}
+
+/// Parses a `document.cookie` string. Only names and values are available.
+#[cfg_attr(not(target_os = "android"), allow(dead_code))]
+fn parse_document_cookie(raw: &str) -> Vec<CookieInfo> {
+    raw.split(';')
+        .filter_map(|pair| {
+            let (name, value) = pair.trim().split_once('=')?;
+            Some(CookieInfo {
+                name: name.to_string(),
+                value: value.to_string(),
+                domain: None,
+                path: None,
+                expires: None,
+                http_only: false,
+                secure: false,
+            })
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_parse_cookie_string_spec() {
+        let cookie = parse_cookie_spec(&json!("session=abc; Path=/; HttpOnly; Secure")).unwrap();
+        assert_eq!(cookie.name, "session");
+        assert_eq!(cookie.value, "abc");
+        assert_eq!(cookie.path.as_deref(), Some("/"));
+        assert!(cookie.http_only);
+        assert!(cookie.secure);
+    }
+
+    #[test]
+    fn test_parse_cookie_object_spec_round_trips() {
+        let cookie = parse_cookie_spec(&json!({
+            "name": "token",
+            "value": "xyz",
+            "domain": "example.com",
+            "expires": 1_700_000_000
+        }))
+        .unwrap();
+
+        let native = cookie.to_cookie().unwrap();
+        assert_eq!(CookieInfo::from_cookie(&native), cookie);
+    }
+
+    #[test]
+    fn test_malformed_cookie_specs_are_rejected() {
+        assert!(parse_cookie_spec(&json!("no-equals-sign")).is_err());
+        assert!(parse_cookie_spec(&json!({ "name": "", "value": "x" })).is_err());
+        assert!(parse_cookie_spec(&json!({ "name": "a b", "value": "x" })).is_err());
+        assert!(parse_cookie_spec(&json!({ "name": "a", "value": "x;y" })).is_err());
+        assert!(parse_cookie_spec(&json!({ "value": "x" })).is_err());
+        assert!(parse_cookie_spec(&json!(42)).is_err());
+    }
+
+    #[test]
+    fn test_parse_document_cookie() {
+        let cookies = parse_document_cookie("a=1; b=two=2; broken");
+        assert_eq!(cookies.len(), 2);
+        assert_eq!(cookies[1].name, "b");
+        assert_eq!(cookies[1].value, "two=2");
+    }
+}
@@ -0,0 +1,154 @@
+//! Best-effort DOM screenshot for platforms without native capture.
+//!
+//! The page is cloned with computed styles inlined, rendered into an SVG
+//! `<foreignObject>`, drawn onto a canvas and returned as a data URL. This
+//! misses cross-origin images, video frames, and anything the browser
+//! refuses to draw from an SVG image, so results are marked
+//! [`CaptureSource::Dom`].
+
+use base64::Engine as _;
+use tauri::{Runtime, WebviewWindow};
+
+use super::execute_js::run_script;
+use crate::screenshot::{
+    self, CaptureSource, ImageFormat, Screenshot, ScreenshotError, ScreenshotOptions,
+};
+
+fn render_script(mime_type: &str, quality: u8) -> String {
+    format!(
+        r#"return await (async () => {{
+    const mimeType = '{mime_type}';
+    const quality = {quality} / 100;
+    const width = window.innerWidth;
+    const height = window.innerHeight;
+    const scale = window.devicePixelRatio || 1;
+
+    // Clone the page, inlining computed styles since stylesheets don't load in SVG images
+    const inline = (source, target) => {{
+        if (source.nodeType !== 1) return;
+        const style = getComputedStyle(source);
+        let css = style.cssText;
+        if (!css) {{
+            css = Array.from(style).map((name) => name + ':' + style.getPropertyValue(name) + ';').join('');
+        }}
+        target.setAttribute('style', css);
+
+        if (source instanceof HTMLCanvasElement) {{
+            try {{
+                const img = document.createElement('img');
+                img.src = source.toDataURL();
+                img.setAttribute('style', css);
+                target.replaceWith(img);
+                return;
+            }} catch (e) {{}}
+        }}
+        if (source instanceof HTMLInputElement || source instanceof HTMLTextAreaElement) {{
+            target.setAttribute('value', source.value);
+        }}
+
+        const sourceChildren = source.children;
+        const targetChildren = target.children;
+        for (let i = 0; i < sourceChildren.length; i++) {{
+            inline(sourceChildren[i], targetChildren[i]);
+        }}
+    }};
+
+    const clone = document.documentElement.cloneNode(true);
+    inline(document.documentElement, clone);
+    clone.querySelectorAll('script, noscript').forEach((el) => el.remove());
+    clone.style.transform = 'translate(' + (-window.scrollX) + 'px, ' + (-window.scrollY) + 'px)';
+
+    const markup = new XMLSerializer().serializeToString(clone);
+    const svg = '<svg xmlns="http://www.w3.org/2000/svg" width="' + width + '" height="' + height + '">' +
+        '<foreignObject x="0" y="0" width="100%" height="100%">' + markup + '</foreignObject></svg>';
+
+    const image = new Image();
+    image.src = 'data:image/svg+xml;charset=utf-8,' + encodeURIComponent(svg);
+    await new Promise((resolve, reject) => {{
+        image.onload = resolve;
+        image.onerror = () => reject(new Error('Failed to render page as SVG image'));
+    }});
+
+    const canvas = document.createElement('canvas');
+    canvas.width = Math.round(width * scale);
+    canvas.height = Math.round(height * scale);
+    const context = canvas.getContext('2d');
+    context.scale(scale, scale);
+    if (mimeType === 'image/jpeg') {{
+        context.fillStyle = '#ffffff';
+        context.fillRect(0, 0, width, height);
+    }}
+    context.drawImage(image, 0, 0);
+
+    return canvas.toDataURL(mimeType, quality);
+}})();"#
+    )
+}
+
+/// Renders the viewport from the DOM and encodes it like a native capture.
+///
+/// Only meant as a fallback when native capture returns
+/// [`ScreenshotError::PlatformUnsupported`].
+pub async fn capture_dom_screenshot<R: Runtime>(
+    window: &WebviewWindow<R>,
+    options: &ScreenshotOptions,
+) -> Result<Screenshot, ScreenshotError> {
+    // Canvas can only pick a fixed format, so `Auto` renders PNG and lets
+    // `encode` decide.
+    let format = match options.format {
+        ImageFormat::Jpeg => ImageFormat::Jpeg,
+        ImageFormat::Png | ImageFormat::Auto => ImageFormat::Png,
+    };
+    let data_url = run_script(window, &render_script(format.mime_type(), options.quality))
+        .await
+        .map_err(ScreenshotError::CaptureFailed)?;
+
+    let data = decode_data_url(data_url.as_str().unwrap_or_default())?;
+    // Engines without JPEG canvas encoding silently return PNG
+    let format = screenshot::detect_mime_type(&data)
+        .and_then(ImageFormat::from_mime_type)
+        .ok_or_else(|| {
+            ScreenshotError::EncodeFailed("DOM render returned an unsupported image".to_string())
+        })?;
+    screenshot::encode(
+        Screenshot {
+            data,
+            content_encoding: None,
+            format,
+            source: CaptureSource::Dom,
+            bounds: screenshot::capture_bounds(window, false),
+            budget_fit: None,
+        },
+        options,
+    )
+}
+
+/// Decodes the bytes of a base64 data URL.
+fn decode_data_url(data_url: &str) -> Result<Vec<u8>, ScreenshotError> {
+    let (_, encoded) = data_url
+        .split_once(";base64,")
+        .filter(|(header, _)| header.starts_with("data:image/"))
+        .ok_or_else(|| {
+            ScreenshotError::CaptureFailed("DOM render returned no image".to_string())
+        })?;
+
+    base64::engine::general_purpose::STANDARD
+        .decode(encoded)
+        .map_err(|e| ScreenshotError::EncodeFailed(format!("Invalid DOM render data: {e}")))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_decode_data_url() {
+        assert_eq!(
+            decode_data_url("data:image/png;base64,aGVsbG8=").unwrap(),
+            b"hello"
+        );
+        // A canvas that failed to render returns an empty "data:," URL
+        assert!(decode_data_url("data:,").is_err());
+        assert!(decode_data_url("data:image/png;base64,!!!").is_err());
+    }
+}
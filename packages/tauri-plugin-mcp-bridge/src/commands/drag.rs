@@ -0,0 +1,113 @@
+//! Drag-and-drop between elements, for sliders, sortable lists and boards.
+
+use serde::{Deserialize, Serialize};
+use tauri::{Runtime, WebviewWindow};
+
+use super::execute_js::run_script;
+
+/// Pointer moves dispatched between press and release by default.
+pub const DEFAULT_DRAG_STEPS: u32 = 10;
+
+/// Upper bound on `steps`, so a drag can't outlast the script timeout.
+pub const MAX_DRAG_STEPS: u32 = 100;
+
+/// A point in CSS pixels, relative to the viewport.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[cfg_attr(feature = "ts-bindings", derive(ts_rs::TS))]
+pub struct DragPoint {
+    pub x: f64,
+    pub y: f64,
+}
+
+/// Where [`drag_element`] releases the pointer.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[cfg_attr(feature = "ts-bindings", derive(ts_rs::TS))]
+#[serde(untagged)]
+pub enum DropTarget {
+    /// Center of the element matching this selector
+    Selector(String),
+    /// A point in the viewport
+    Point(DragPoint),
+}
+
+/// Outcome of [`drag_element`].
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "ts-bindings", derive(ts_rs::TS))]
+#[serde(rename_all = "camelCase")]
+pub struct DragResult {
+    /// Whether the pointer was released at the drop point. For native
+    /// drag-and-drop sources, whether the drop target accepted the drop.
+    pub completed: bool,
+    /// Whether the source has `draggable="true"`, so HTML drag-and-drop
+    /// events were dispatched instead of pointer events
+    pub native: bool,
+    /// Where the drag started, after scrolling the source into view
+    pub from: DragPoint,
+    /// Where the pointer was released
+    pub to: DragPoint,
+    /// Tag and ID of the element under the drop point
+    pub drop_target: Option<String>,
+}
+
+fn drag_script(from_selector: &str, to: &DropTarget, steps: u32) -> String {
+    let options = serde_json::json!({
+        "from": from_selector,
+        "to": to,
+        "steps": steps,
+    });
+    let drag = include_str!("../drag.js").replace("__MCP_DRAG_OPTIONS__", &options.to_string());
+    format!("const drag = {drag}\nreturn await drag;")
+}
+
+/// Drags the element matching `from_selector` onto another element or a
+/// point.
+///
+/// The source is scrolled into view first, then pressed at its center and
+/// moved to the drop point in `steps` moves, one frame apart, before being
+/// released.
+///
+/// # Arguments
+///
+/// * `window` - The webview window to drag in
+/// * `from_selector` - CSS selector of the element to drag
+/// * `to` - Selector of the element to drop on, or a viewport point
+/// * `steps` - Pointer moves between press and release, from 1 to
+///   [`MAX_DRAG_STEPS`]
+///
+/// # Returns
+///
+/// * `Ok(DragResult)` - Whether the drag completed, and its start and end
+/// * `Err(String)` - Error message if a selector matches nothing, the drop
+///   point is outside the viewport, or the script could not be evaluated
+pub async fn drag_element<R: Runtime>(
+    window: &WebviewWindow<R>,
+    from_selector: &str,
+    to: &DropTarget,
+    steps: u32,
+) -> Result<DragResult, String> {
+    if !(1..=MAX_DRAG_STEPS).contains(&steps) {
+        return Err(format!("steps must be between 1 and {MAX_DRAG_STEPS}"));
+    }
+
+    let result = run_script(window, &drag_script(from_selector, to, steps)).await?;
+    serde_json::from_value(result).map_err(|e| format!("Unexpected drag result: {e}"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_drop_target_accepts_selector_or_point() {
+        let target: DropTarget = serde_json::from_value(serde_json::json!("#done")).unwrap();
+        assert_eq!(target, DropTarget::Selector("#done".into()));
+
+        let target: DropTarget =
+            serde_json::from_value(serde_json::json!({ "x": 120, "y": 40.5 })).unwrap();
+        assert_eq!(target, DropTarget::Point(DragPoint { x: 120.0, y: 40.5 }));
+
+        let script = drag_script(".card", &target, 5);
+        assert!(!script.contains("__MCP_DRAG_OPTIONS__"));
+        assert!(script.contains(r#""to":{"x":120.0,"y":40.5}"#));
+    }
+}
@@ -0,0 +1,296 @@
+//! Device emulation for responsive testing.
+//!
+//! `emulate` registers the shim script as a window-scoped bridge script, so
+//! the overrides are re-applied after navigations, and resizes the window
+//! to the device's viewport. Overriding the user agent from JavaScript is
+//! best effort: page scripts see the new value, but network requests keep
+//! the webview's real User-Agent header.
+
+use std::collections::HashMap;
+use std::sync::Mutex;
+
+use serde::{Deserialize, Serialize};
+use tauri::{PhysicalSize, Runtime, WebviewWindow};
+
+use super::execute_js::run_script;
+use super::resize_window::{apply_window_size, WindowSize};
+use crate::monitor::current_timestamp;
+use crate::script_registry::{
+    InjectionPhase, ScriptEntry, ScriptScope, ScriptType, SharedScriptRegistry,
+};
+
+/// Registry ID prefix of the shim script; the window label is appended.
+const EMULATION_SCRIPT_ID: &str = "__mcp_emulation__";
+
+const IPHONE_USER_AGENT: &str = "Mozilla/5.0 (iPhone; CPU iPhone OS 17_5 like Mac OS X) \
+    AppleWebKit/605.1.15 (KHTML, like Gecko) Version/17.5 Mobile/15E148 Safari/604.1";
+
+const PIXEL_USER_AGENT: &str = "Mozilla/5.0 (Linux; Android 14; Pixel 8) \
+    AppleWebKit/537.36 (KHTML, like Gecko) Chrome/126.0.0.0 Mobile Safari/537.36";
+
+const DESKTOP_USER_AGENT: &str = "Mozilla/5.0 (Windows NT 10.0; Win64; x64) \
+    AppleWebKit/537.36 (KHTML, like Gecko) Chrome/126.0.0.0 Safari/537.36";
+
+/// Built-in device profiles.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "ts-bindings", derive(ts_rs::TS))]
+#[serde(rename_all = "lowercase")]
+pub enum DevicePreset {
+    /// iPhone 15: 390x844 at 3x, Mobile Safari
+    Iphone,
+    /// Pixel 8: 412x915 at 2.625x, Chrome for Android
+    Pixel,
+    /// Laptop: 1280x800 at 1x, desktop Chrome
+    Desktop,
+}
+
+impl DevicePreset {
+    fn user_agent(self) -> &'static str {
+        match self {
+            DevicePreset::Iphone => IPHONE_USER_AGENT,
+            DevicePreset::Pixel => PIXEL_USER_AGENT,
+            DevicePreset::Desktop => DESKTOP_USER_AGENT,
+        }
+    }
+
+    fn device_pixel_ratio(self) -> f64 {
+        match self {
+            DevicePreset::Iphone => 3.0,
+            DevicePreset::Pixel => 2.625,
+            DevicePreset::Desktop => 1.0,
+        }
+    }
+
+    fn viewport(self) -> WindowSize {
+        let (width, height) = match self {
+            DevicePreset::Iphone => (390, 844),
+            DevicePreset::Pixel => (412, 915),
+            DevicePreset::Desktop => (1280, 800),
+        };
+        WindowSize { width, height }
+    }
+}
+
+/// Options for [`emulate`]. Explicit values override the preset's.
+#[derive(Debug, Clone, Default, Deserialize)]
+#[cfg_attr(feature = "ts-bindings", derive(ts_rs::TS))]
+#[serde(rename_all = "camelCase", default)]
+pub struct EmulationOptions {
+    /// Device profile to start from
+    pub preset: Option<DevicePreset>,
+    /// `navigator.userAgent` reported to page scripts
+    pub user_agent: Option<String>,
+    /// `window.devicePixelRatio` reported to page scripts
+    pub device_pixel_ratio: Option<f64>,
+    /// Logical inner size to resize the window to
+    pub viewport: Option<WindowSize>,
+}
+
+impl EmulationOptions {
+    /// Merges the explicit values over the preset.
+    fn resolve(&self) -> Result<EmulationOptions, String> {
+        let resolved = EmulationOptions {
+            preset: self.preset,
+            user_agent: self
+                .user_agent
+                .clone()
+                .or_else(|| self.preset.map(|p| p.user_agent().to_string())),
+            device_pixel_ratio: self
+                .device_pixel_ratio
+                .or_else(|| self.preset.map(DevicePreset::device_pixel_ratio)),
+            viewport: self
+                .viewport
+                .or_else(|| self.preset.map(DevicePreset::viewport)),
+        };
+
+        if resolved.user_agent.is_none()
+            && resolved.device_pixel_ratio.is_none()
+            && resolved.viewport.is_none()
+        {
+            return Err(
+                "Nothing to emulate: pass a preset, userAgent, devicePixelRatio or viewport"
+                    .to_string(),
+            );
+        }
+        if matches!(resolved.device_pixel_ratio, Some(ratio) if ratio.is_nan() || ratio <= 0.0) {
+            return Err("devicePixelRatio must be greater than 0".to_string());
+        }
+        Ok(resolved)
+    }
+}
+
+/// Outcome of [`emulate`], read back from the page.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "ts-bindings", derive(ts_rs::TS))]
+#[serde(rename_all = "camelCase")]
+pub struct EmulationResult {
+    /// `navigator.userAgent` as page scripts now see it
+    pub user_agent: String,
+    /// `window.devicePixelRatio` as page scripts now see it
+    pub device_pixel_ratio: f64,
+    /// Logical inner size the window took, when a viewport was emulated
+    #[serde(default)]
+    pub viewport: Option<WindowSize>,
+    /// Properties the webview wouldn't let the shim override
+    #[serde(default)]
+    pub unsupported: Vec<String>,
+}
+
+/// Window sizes from before emulation, restored by [`reset_emulation`].
+#[derive(Debug, Default)]
+pub struct EmulationState {
+    original_sizes: Mutex<HashMap<String, PhysicalSize<u32>>>,
+}
+
+fn script_id(window_label: &str) -> String {
+    format!("{EMULATION_SCRIPT_ID}:{window_label}")
+}
+
+/// Builds the shim script with its options substituted.
+fn emulation_script(options: &EmulationOptions) -> String {
+    let options = serde_json::json!({
+        "userAgent": options.user_agent,
+        "devicePixelRatio": options.device_pixel_ratio,
+    });
+    include_str!("../emulation.js").replace("__MCP_EMULATION_OPTIONS__", &options.to_string())
+}
+
+const READ_EMULATION_SCRIPT: &str = r#"return {
+    userAgent: navigator.userAgent,
+    devicePixelRatio: window.devicePixelRatio,
+    unsupported: window.__MCP_EMULATION__ ? window.__MCP_EMULATION__.unsupported : [],
+};"#;
+
+/// Emulates a device in a window.
+///
+/// # Arguments
+///
+/// * `window` - The webview window to emulate the device in
+/// * `registry` - Script registry holding the shim across navigations
+/// * `state` - Where the window's size before emulation is kept
+/// * `options` - Preset and overrides
+///
+/// # Returns
+///
+/// * `Ok(EmulationResult)` - The values page scripts now see
+/// * `Err(String)` - Error message if there is nothing to emulate, or the
+///   window can't be resized or the shim evaluated
+pub async fn emulate<R: Runtime>(
+    window: &WebviewWindow<R>,
+    registry: &SharedScriptRegistry,
+    state: &EmulationState,
+    options: &EmulationOptions,
+) -> Result<EmulationResult, String> {
+    let options = options.resolve()?;
+    let label = window.label().to_string();
+
+    let viewport = match options.viewport {
+        Some(viewport) => {
+            let original = window
+                .inner_size()
+                .map_err(|e| format!("Failed to read window size: {e}"))?;
+            // Emulating again keeps the size from before the first emulation
+            state
+                .original_sizes
+                .lock()
+                .unwrap()
+                .entry(label.clone())
+                .or_insert(original);
+            Some(apply_window_size(
+                window,
+                viewport.width,
+                viewport.height,
+                true,
+            )?)
+        }
+        None => None,
+    };
+
+    let script = emulation_script(&options);
+    let id = script_id(&label);
+    {
+        let mut entry = ScriptEntry::new(&id, ScriptType::Inline, &script);
+        entry.phase = InjectionPhase::Setup;
+
+        let mut registry = registry.lock().unwrap();
+        registry
+            .add(ScriptScope::Window(label.clone()), entry)
+            .map_err(|e| e.to_string())?;
+        registry.mark_injected(&label, [id.as_str()], current_timestamp());
+    }
+    window
+        .eval(&script)
+        .map_err(|e| format!("Failed to install emulation shims: {e}"))?;
+
+    let mut result: EmulationResult =
+        serde_json::from_value(run_script(window, READ_EMULATION_SCRIPT).await?)
+            .map_err(|e| format!("Unexpected emulation result: {e}"))?;
+    result.viewport = viewport;
+    Ok(result)
+}
+
+/// Removes the shims installed by [`emulate`] and restores the window's
+/// size from before emulation.
+///
+/// Returns whether the window was being emulated.
+pub fn reset_emulation<R: Runtime>(
+    window: &WebviewWindow<R>,
+    registry: &SharedScriptRegistry,
+    state: &EmulationState,
+) -> Result<bool, String> {
+    let id = script_id(window.label());
+    let removed = registry.lock().unwrap().remove(&id).is_some();
+    let original = state.original_sizes.lock().unwrap().remove(window.label());
+
+    window
+        .eval(format!(
+            "window.__MCP_EMULATION__ && window.__MCP_EMULATION__.restore();\
+             window.__MCP_REMOVE_SCRIPT__ && window.__MCP_REMOVE_SCRIPT__({});",
+            serde_json::Value::String(id)
+        ))
+        .map_err(|e| format!("Failed to remove emulation shims: {e}"))?;
+
+    if let Some(original) = original {
+        window
+            .set_size(original)
+            .map_err(|e| format!("Failed to restore window size: {e}"))?;
+    }
+
+    Ok(removed || original.is_some())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_explicit_values_override_preset() {
+        let options: EmulationOptions = serde_json::from_value(serde_json::json!({
+            "preset": "iphone",
+            "devicePixelRatio": 2.0
+        }))
+        .unwrap();
+        let resolved = options.resolve().unwrap();
+
+        assert_eq!(resolved.user_agent.as_deref(), Some(IPHONE_USER_AGENT));
+        assert_eq!(resolved.device_pixel_ratio, Some(2.0));
+        assert_eq!(
+            resolved.viewport.map(|v| (v.width, v.height)),
+            Some((390, 844))
+        );
+
+        assert!(EmulationOptions::default().resolve().is_err());
+    }
+
+    #[test]
+    fn test_script_substitutes_options() {
+        let options = EmulationOptions {
+            user_agent: Some("Test/1.0".into()),
+            ..Default::default()
+        };
+        let script = emulation_script(&options);
+        assert!(!script.contains("__MCP_EMULATION_OPTIONS__"));
+        assert!(script.contains(r#""userAgent":"Test/1.0""#));
+        assert!(script.contains(r#""devicePixelRatio":null"#));
+    }
+}
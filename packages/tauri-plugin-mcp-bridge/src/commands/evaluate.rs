@@ -0,0 +1,347 @@
+//! Script evaluation that awaits returned Promises.
+//!
+//! The script runs inside `window.__MCP_EVALUATE__` from bridge.js, which
+//! awaits the result and posts it back through the `script_result` command
+//! with the call ID. Pending calls are correlated through the
+//! [`ScriptExecutor`] oneshot senders, like [`execute_js`](super::execute_js).
+//!
+//! Exposed over the WebSocket as `evaluate_script`, and as `eval_async` for
+//! clients that name it after the `eval` it replaces. With a `frame`
+//! argument the script runs in a same-origin iframe instead of the top
+//! frame; see [`evaluate_in_frame`].
+
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::time::Duration;
+use tauri::{Manager, Runtime, WebviewWindow};
+use tokio::sync::oneshot;
+use uuid::Uuid;
+
+use super::execute_js::prepare_script;
+use super::list_windows::is_window_available;
+use super::script_executor::ScriptExecutor;
+
+/// Evaluation error types
+#[derive(Debug, thiserror::Error)]
+pub enum EvaluationError {
+    #[error("Window '{0}' is closed or being destroyed")]
+    WindowUnavailable(String),
+
+    #[error("Failed to evaluate script: {0}")]
+    EvalFailed(String),
+
+    #[error("Script did not resolve or reject within {}ms", .0.as_millis())]
+    EvaluationTimeout(Duration),
+
+    #[error("{0}")]
+    FrameUnavailable(String),
+
+    #[error("{message}")]
+    Rejected {
+        message: String,
+        stack: Option<String>,
+    },
+}
+
+impl EvaluationError {
+    /// Machine-readable error code for clients.
+    pub fn code(&self) -> &'static str {
+        match self {
+            EvaluationError::WindowUnavailable(_) => "WINDOW_UNAVAILABLE",
+            EvaluationError::EvalFailed(_) => "EVAL_FAILED",
+            EvaluationError::EvaluationTimeout(_) => "EVALUATION_TIMEOUT",
+            EvaluationError::FrameUnavailable(_) => "FRAME_UNAVAILABLE",
+            EvaluationError::Rejected { .. } => "EVALUATION_REJECTED",
+        }
+    }
+
+    /// JavaScript stack trace of a rejection, if the page provided one.
+    pub fn stack(&self) -> Option<&str> {
+        match self {
+            EvaluationError::Rejected { stack, .. } => stack.as_deref(),
+            _ => None,
+        }
+    }
+}
+
+/// Evaluates a script and returns its value once any returned Promise settles.
+///
+/// # Arguments
+///
+/// * `window` - The webview window to evaluate in
+/// * `script` - JavaScript code; a bare expression is returned as the value
+/// * `timeout` - How long to wait for the result to settle
+///
+/// # Returns
+///
+/// * `Ok(Value)` - The resolved value, `null` for `undefined`
+/// * `Err(EvaluationError::Rejected)` - The script threw or its Promise
+///   rejected, with the error's message and stack
+/// * `Err(EvaluationError::EvaluationTimeout)` - Nothing came back in time,
+///   including when the script fails to parse
+pub async fn evaluate_script<R: Runtime>(
+    window: &WebviewWindow<R>,
+    script: &str,
+    timeout: Duration,
+) -> Result<Value, EvaluationError> {
+    if !is_window_available(window) {
+        return Err(EvaluationError::WindowUnavailable(
+            window.label().to_string(),
+        ));
+    }
+
+    let executor = window.state::<ScriptExecutor>();
+    let call_id = Uuid::new_v4().to_string();
+    let (tx, rx) = oneshot::channel();
+    executor
+        .pending_results
+        .lock()
+        .await
+        .insert(call_id.clone(), tx);
+
+    if let Err(e) = window.eval(evaluation_script(&call_id, script)) {
+        executor.pending_results.lock().await.remove(&call_id);
+        return Err(EvaluationError::EvalFailed(e.to_string()));
+    }
+
+    match tokio::time::timeout(timeout, rx).await {
+        Ok(Ok(result)) => settled_value(result),
+        Ok(Err(_)) => Err(EvaluationError::EvalFailed(
+            "result channel closed".to_string(),
+        )),
+        Err(_) => {
+            executor.pending_results.lock().await.remove(&call_id);
+            Err(EvaluationError::EvaluationTimeout(timeout))
+        }
+    }
+}
+
+/// Which frame of the page [`evaluate_in_frame`] runs in, as
+/// `{ "index": 0 }`, `{ "name": "editor" }` or `{ "selector": "#editor" }`.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "ts-bindings", derive(ts_rs::TS))]
+#[serde(rename_all = "camelCase")]
+pub enum FrameSelector {
+    /// Position in the top frame's `window.frames`
+    Index(usize),
+    /// The `name` attribute of the `<iframe>`
+    Name(String),
+    /// CSS selector matching the `<iframe>` element
+    Selector(String),
+}
+
+/// The frame a script was evaluated in.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "ts-bindings", derive(ts_rs::TS))]
+#[serde(rename_all = "camelCase")]
+pub struct FrameInfo {
+    /// Position in the top frame's `window.frames`
+    pub index: Option<usize>,
+    pub name: Option<String>,
+    /// The `src` attribute of the `<iframe>`
+    pub src: Option<String>,
+    /// The frame document's URL
+    pub url: Option<String>,
+}
+
+/// Value of a script evaluated by [`evaluate_in_frame`].
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+pub struct FrameEvaluation {
+    pub value: Value,
+    pub frame: FrameInfo,
+}
+
+/// Evaluates a script in an iframe of the page, like [`evaluate_script`].
+///
+/// The frame is looked up from the top frame, and the script runs through
+/// the frame's own `eval`, so its globals and `document` are the frame's.
+/// Cross-origin frames can't be scripted from the page and fail with
+/// [`EvaluationError::FrameUnavailable`], as do selectors that match no
+/// frame.
+///
+/// # Arguments
+///
+/// * `window` - The webview window whose page holds the frame
+/// * `script` - JavaScript code; a bare expression is returned as the value
+/// * `frame` - Which frame to evaluate in
+/// * `timeout` - How long to wait for the result to settle
+///
+/// # Returns
+///
+/// * `Ok(FrameEvaluation)` - The resolved value and the frame it came from
+/// * `Err(EvaluationError)` - As for [`evaluate_script`], or
+///   `FrameUnavailable` when the frame can't be reached
+pub async fn evaluate_in_frame<R: Runtime>(
+    window: &WebviewWindow<R>,
+    script: &str,
+    frame: &FrameSelector,
+    timeout: Duration,
+) -> Result<FrameEvaluation, EvaluationError> {
+    let result = evaluate_script(window, &frame_script(script, frame), timeout).await?;
+    frame_evaluation(result)
+}
+
+/// Builds a script that finds the frame and evaluates `script` in it,
+/// resolving to `{ value, frame }` or `{ frameError }`.
+fn frame_script(script: &str, frame: &FrameSelector) -> String {
+    let selector = serde_json::to_string(frame).unwrap_or_else(|_| "{}".to_string());
+    let source = Value::String(format!(
+        "(async () => {{\n{}\n}})()",
+        prepare_script(script)
+    ));
+
+    format!(
+        r#"return (async () => {{
+    const selector = {selector};
+    const elements = Array.from(document.querySelectorAll('iframe, frame'));
+    let element = null;
+    if ('index' in selector) {{
+        const target = window.frames[selector.index];
+        element = elements.find((el) => target && el.contentWindow === target) || null;
+    }} else if ('name' in selector) {{
+        element = elements.find((el) => el.name === selector.name) || null;
+    }} else {{
+        let match;
+        try {{
+            match = document.querySelector(selector.selector);
+        }} catch (e) {{
+            return {{ frameError: 'Invalid frame selector: ' + e.message }};
+        }}
+        element = elements.includes(match) ? match : null;
+    }}
+    if (!element || !element.contentWindow) {{
+        return {{ frameError: 'No frame matches ' + JSON.stringify(selector) }};
+    }}
+
+    const target = element.contentWindow;
+    let index = null;
+    for (let i = 0; i < window.frames.length; i++) {{
+        if (window.frames[i] === target) {{
+            index = i;
+            break;
+        }}
+    }}
+    const frame = {{
+        index,
+        name: element.name || null,
+        src: element.getAttribute('src'),
+        url: null,
+    }};
+    try {{
+        frame.url = target.location.href;
+        void target.document;
+    }} catch (e) {{
+        return {{
+            frameError: 'Frame ' + JSON.stringify(frame.src || frame.name || selector)
+                + ' is cross-origin and cannot be scripted: ' + (e.message || e),
+        }};
+    }}
+
+    try {{
+        const value = await target.eval({source});
+        return {{ value: value === undefined ? null : value, frame }};
+    }} catch (e) {{
+        // Errors from the frame's realm aren't instances of this realm's Error
+        const error = new Error(e && e.message !== undefined ? e.message : String(e));
+        if (e && e.stack) error.stack = e.stack;
+        throw error;
+    }}
+}})();"#
+    )
+}
+
+/// Converts the result of [`frame_script`] into the evaluation outcome.
+fn frame_evaluation(result: Value) -> Result<FrameEvaluation, EvaluationError> {
+    if let Some(message) = result.get("frameError").and_then(|v| v.as_str()) {
+        return Err(EvaluationError::FrameUnavailable(message.to_string()));
+    }
+    serde_json::from_value(result)
+        .map_err(|e| EvaluationError::EvalFailed(format!("Unexpected frame result: {e}")))
+}
+
+/// Wraps a script so bridge.js awaits it and reports back under `call_id`.
+fn evaluation_script(call_id: &str, script: &str) -> String {
+    format!(
+        r#"window.__MCP_EVALUATE__('{call_id}', async () => {{
+{}
+}});"#,
+        prepare_script(script)
+    )
+}
+
+/// Converts a `script_result` payload into the evaluation outcome.
+fn settled_value(result: Value) -> Result<Value, EvaluationError> {
+    if result
+        .get("success")
+        .and_then(|v| v.as_bool())
+        .unwrap_or(false)
+    {
+        return Ok(result.get("data").cloned().unwrap_or(Value::Null));
+    }
+
+    Err(EvaluationError::Rejected {
+        message: result
+            .get("error")
+            .and_then(|v| v.as_str())
+            .unwrap_or("Unknown error")
+            .to_string(),
+        stack: result
+            .get("stack")
+            .and_then(|v| v.as_str())
+            .map(|s| s.to_string()),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_evaluation_script_wraps_expression() {
+        let script = evaluation_script("abc", "fetch('/api').then((r) => r.status)");
+        assert!(script.starts_with("window.__MCP_EVALUATE__('abc', async () => {"));
+        assert!(script.contains("return fetch('/api')"));
+    }
+
+    #[test]
+    fn test_frame_script_and_outcome() {
+        let script = frame_script("document.title", &FrameSelector::Name("editor".to_string()));
+        assert!(script.starts_with("return (async () => {"));
+        assert!(script.contains(r#"const selector = {"name":"editor"};"#));
+        assert!(script.contains(r#"target.eval("(async () => {\nreturn document.title\n})()")"#));
+
+        let evaluation = frame_evaluation(json!({
+            "value": "Editor",
+            "frame": { "index": 0, "name": "editor", "src": "/editor.html", "url": null }
+        }))
+        .unwrap();
+        assert_eq!(evaluation.value, json!("Editor"));
+        assert_eq!(evaluation.frame.index, Some(0));
+
+        let error = frame_evaluation(json!({ "frameError": "cross-origin" })).unwrap_err();
+        assert_eq!(error.code(), "FRAME_UNAVAILABLE");
+    }
+
+    #[test]
+    fn test_settled_value() {
+        assert_eq!(
+            settled_value(json!({ "success": true, "data": 42 })).unwrap(),
+            json!(42)
+        );
+        assert_eq!(
+            settled_value(json!({ "success": true })).unwrap(),
+            Value::Null
+        );
+
+        let error = settled_value(json!({
+            "success": false,
+            "error": "boom",
+            "stack": "Error: boom\n    at <anonymous>:1:7"
+        }))
+        .unwrap_err();
+        assert_eq!(error.to_string(), "boom");
+        assert_eq!(error.code(), "EVALUATION_REJECTED");
+        assert_eq!(error.stack(), Some("Error: boom\n    at <anonymous>:1:7"));
+    }
+}
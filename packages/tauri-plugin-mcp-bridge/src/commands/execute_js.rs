@@ -1,12 +1,18 @@
 //! JavaScript execution in webview.
 
+use super::list_windows::is_window_available;
 use super::script_executor::ScriptExecutor;
+use crate::config::{Config, DEFAULT_SCRIPT_TIMEOUT};
 use crate::logging::mcp_log_error;
 use serde_json::Value;
-use tauri::{command, Listener, Runtime, State, WebviewWindow};
+use std::time::Duration;
+use tauri::{command, Listener, Manager, Runtime, State, WebviewWindow};
 use tokio::sync::oneshot;
 use uuid::Uuid;
 
+/// Event emitted to a window once it has been destroyed.
+const WINDOW_DESTROYED_EVENT: &str = "tauri://destroyed";
+
 /// Executes JavaScript code in the webview context.
 ///
 /// This command evaluates arbitrary JavaScript in the webview and returns the result.
@@ -39,6 +45,29 @@ pub async fn execute_js<R: Runtime>(
     script: String,
     state: State<'_, ScriptExecutor>,
 ) -> Result<Value, String> {
+    let timeout = window
+        .try_state::<Config>()
+        .map(|config| config.script_timeout)
+        .unwrap_or(DEFAULT_SCRIPT_TIMEOUT);
+
+    execute_js_with_timeout(window, script, state, timeout).await
+}
+
+/// Executes JavaScript like [`execute_js`], waiting up to `timeout` for the
+/// result instead of the configured script timeout.
+pub(crate) async fn execute_js_with_timeout<R: Runtime>(
+    window: WebviewWindow<R>,
+    script: String,
+    state: State<'_, ScriptExecutor>,
+    timeout: Duration,
+) -> Result<Value, String> {
+    if !is_window_available(&window) {
+        return Ok(serde_json::json!({
+            "success": false,
+            "error": format!("Window '{}' is closed or being destroyed", window.label())
+        }));
+    }
+
     // Generate unique execution ID
     let exec_id = Uuid::new_v4().to_string();
 
@@ -103,6 +132,24 @@ pub async fn execute_js<R: Runtime>(
         }
     });
 
+    // Fail the pending request right away if the window goes away mid-execution
+    let closed_exec_id = exec_id.clone();
+    let closed_pending = state.pending_results.clone();
+
+    let unlisten_destroyed = window.listen(WINDOW_DESTROYED_EVENT, move |_| {
+        let pending = closed_pending.clone();
+        let exec_id = closed_exec_id.clone();
+
+        tokio::spawn(async move {
+            if let Some(sender) = pending.lock().await.remove(&exec_id) {
+                let _ = sender.send(serde_json::json!({
+                    "success": false,
+                    "error": "Window was closed before the script completed"
+                }));
+            }
+        });
+    });
+
     // Prepare the script with appropriate return handling
     let prepared_script = prepare_script(&script);
 
@@ -158,14 +205,17 @@ pub async fn execute_js<R: Runtime>(
         let mut pending = state.pending_results.lock().await;
         pending.remove(&exec_id);
 
+        window.unlisten(unlisten);
+        window.unlisten(unlisten_destroyed);
+
         return Ok(serde_json::json!({
             "success": false,
             "error": format!("Failed to execute script: {}", e)
         }));
     }
 
-    // Wait for result with timeout
-    let result = match tokio::time::timeout(std::time::Duration::from_secs(5), rx).await {
+    // Wait for result with the given timeout
+    let result = match tokio::time::timeout(timeout, rx).await {
         Ok(Ok(result)) => Ok(result),
         Ok(Err(_)) => {
             // Channel was dropped
@@ -186,14 +236,57 @@ pub async fn execute_js<R: Runtime>(
         }
     };
 
-    // Clean up event listener
+    // Clean up event listeners
     window.unlisten(unlisten);
+    window.unlisten(unlisten_destroyed);
 
     result
 }
 
+/// Runs a script through [`execute_js`] and unwraps its result.
+///
+/// Used by commands implemented on top of injected JavaScript. Returns the
+/// script's value on success, or the reported error message.
+pub(crate) async fn run_script<R: Runtime>(
+    window: &WebviewWindow<R>,
+    script: &str,
+) -> Result<Value, String> {
+    let timeout = window
+        .try_state::<Config>()
+        .map(|config| config.script_timeout)
+        .unwrap_or(DEFAULT_SCRIPT_TIMEOUT);
+
+    run_script_with_timeout(window, script, timeout).await
+}
+
+/// Runs a script like [`run_script`], for scripts that are expected to take
+/// longer than the configured script timeout.
+pub(crate) async fn run_script_with_timeout<R: Runtime>(
+    window: &WebviewWindow<R>,
+    script: &str,
+    timeout: Duration,
+) -> Result<Value, String> {
+    let state = window.state::<ScriptExecutor>();
+    let result =
+        execute_js_with_timeout(window.clone(), script.to_string(), state, timeout).await?;
+
+    if result
+        .get("success")
+        .and_then(|v| v.as_bool())
+        .unwrap_or(false)
+    {
+        Ok(result.get("data").cloned().unwrap_or(Value::Null))
+    } else {
+        Err(result
+            .get("error")
+            .and_then(|v| v.as_str())
+            .unwrap_or("Unknown error")
+            .to_string())
+    }
+}
+
 /// Prepare script by adding return statement if needed.
-fn prepare_script(script: &str) -> String {
+pub(super) fn prepare_script(script: &str) -> String {
     let trimmed = script.trim();
     let needs_return = !trimmed.starts_with("return ");
 
@@ -0,0 +1,193 @@
+//! Attaching files from disk to `<input type="file">` elements, for
+//! automating uploads.
+//!
+//! Pages can't set a file input's value, and the webviews offer no API to
+//! answer their file dialogs. Instead the files are read in Rust, sent to
+//! the page, and attached as `File` objects through a `DataTransfer`, which
+//! is what drag and drop does. The page sees the same `files` list and
+//! `input` and `change` events as when a user picks the files.
+//!
+//! This needs a `DataTransfer` constructor and an assignable
+//! `HTMLInputElement.files`: WebView2 on Windows, Android System WebView,
+//! and WebKit on macOS 11.3, iOS 14.5 and WebKitGTK 2.32 or later. Older
+//! webviews fail with an error rather than silently attaching nothing.
+
+use std::path::{Path, PathBuf};
+
+use base64::Engine as _;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use tauri::{Runtime, WebviewWindow};
+
+use super::execute_js::run_script;
+
+/// Largest total size of the files attached in one call. The contents
+/// travel to the page inside a script, base64-encoded.
+pub const MAX_INPUT_FILES_BYTES: u64 = 10 * 1024 * 1024;
+
+/// A file attached by [`set_input_files`].
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "ts-bindings", derive(ts_rs::TS))]
+#[serde(rename_all = "camelCase")]
+pub struct AttachedFile {
+    /// File name the page sees, without the directory
+    pub name: String,
+    /// Size in bytes
+    #[cfg_attr(feature = "ts-bindings", ts(type = "number"))]
+    pub size: u64,
+    /// MIME type guessed from the extension
+    pub mime_type: String,
+}
+
+/// MIME type of a file, from its extension.
+fn mime_type(path: &Path) -> &'static str {
+    let extension = path
+        .extension()
+        .and_then(|ext| ext.to_str())
+        .map(str::to_ascii_lowercase);
+    match extension.as_deref() {
+        Some("png") => "image/png",
+        Some("jpg" | "jpeg") => "image/jpeg",
+        Some("gif") => "image/gif",
+        Some("webp") => "image/webp",
+        Some("svg") => "image/svg+xml",
+        Some("pdf") => "application/pdf",
+        Some("json") => "application/json",
+        Some("zip") => "application/zip",
+        Some("txt") => "text/plain",
+        Some("csv") => "text/csv",
+        Some("html" | "htm") => "text/html",
+        _ => "application/octet-stream",
+    }
+}
+
+/// Checks that every path is a readable file and that together they fit
+/// [`MAX_INPUT_FILES_BYTES`].
+fn check_files(paths: &[PathBuf]) -> Result<Vec<AttachedFile>, String> {
+    let mut total = 0u64;
+    let files = paths
+        .iter()
+        .map(|path| {
+            let metadata = std::fs::metadata(path)
+                .map_err(|e| format!("Cannot read {}: {e}", path.display()))?;
+            if !metadata.is_file() {
+                return Err(format!("{} is not a file", path.display()));
+            }
+            total += metadata.len();
+            Ok(AttachedFile {
+                name: path
+                    .file_name()
+                    .map(|name| name.to_string_lossy().into_owned())
+                    .unwrap_or_default(),
+                size: metadata.len(),
+                mime_type: mime_type(path).to_string(),
+            })
+        })
+        .collect::<Result<Vec<_>, String>>()?;
+
+    if total > MAX_INPUT_FILES_BYTES {
+        return Err(format!(
+            "Files total {total} bytes, more than the {MAX_INPUT_FILES_BYTES} bytes that can be attached at once"
+        ));
+    }
+    Ok(files)
+}
+
+fn attach_script(selector: &str, files: &Value) -> String {
+    format!(
+        r#"return (() => {{
+    const input = document.querySelector({selector});
+    if (!input) throw new Error('No element matches selector: ' + {selector});
+    if (!(input instanceof HTMLInputElement) || input.type !== 'file') {{
+        throw new Error('Element is not an <input type="file">');
+    }}
+    const files = {files};
+    if (files.length > 1 && !input.multiple) {{
+        throw new Error('Input accepts a single file; ' + files.length + ' were given');
+    }}
+    if (typeof DataTransfer !== 'function') {{
+        throw new Error('This webview cannot attach files: DataTransfer is not constructible');
+    }}
+
+    const transfer = new DataTransfer();
+    for (const file of files) {{
+        const bytes = Uint8Array.from(atob(file.data), (c) => c.charCodeAt(0));
+        transfer.items.add(new File([bytes], file.name, {{ type: file.mimeType, lastModified: Date.now() }}));
+    }}
+    input.files = transfer.files;
+    if (input.files.length !== files.length) {{
+        throw new Error('This webview cannot attach files: HTMLInputElement.files is read-only');
+    }}
+
+    input.dispatchEvent(new Event('input', {{ bubbles: true, composed: true }}));
+    input.dispatchEvent(new Event('change', {{ bubbles: true }}));
+    return input.files.length;
+}})();"#,
+        selector = Value::String(selector.to_string()),
+    )
+}
+
+/// Attaches files from disk to the `<input type="file">` matching
+/// `selector`, replacing its current files, and dispatches `input` and
+/// `change`.
+///
+/// # Arguments
+///
+/// * `window` - The webview window containing the input
+/// * `selector` - CSS selector of the file input
+/// * `paths` - Files to attach; more than one needs a `multiple` input, and
+///   none clears the input
+///
+/// # Returns
+///
+/// * `Ok(Vec<AttachedFile>)` - The attached files
+/// * `Err(String)` - Error message if a path isn't a readable file, the
+///   files are larger than [`MAX_INPUT_FILES_BYTES`], the element isn't a
+///   file input, or the webview can't attach files
+pub async fn set_input_files<R: Runtime>(
+    window: &WebviewWindow<R>,
+    selector: &str,
+    paths: &[PathBuf],
+) -> Result<Vec<AttachedFile>, String> {
+    let files = check_files(paths)?;
+
+    let payload = paths
+        .iter()
+        .zip(&files)
+        .map(|(path, file)| {
+            let data =
+                std::fs::read(path).map_err(|e| format!("Cannot read {}: {e}", path.display()))?;
+            Ok(serde_json::json!({
+                "name": file.name,
+                "mimeType": file.mime_type,
+                "data": base64::engine::general_purpose::STANDARD.encode(data),
+            }))
+        })
+        .collect::<Result<Vec<_>, String>>()?;
+
+    run_script(window, &attach_script(selector, &Value::Array(payload))).await?;
+    Ok(files)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_check_files_validates_paths() {
+        let dir = std::env::temp_dir().join(format!("mcp-file-input-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let file = dir.join("Report.PDF");
+        std::fs::write(&file, b"%PDF-1.7").unwrap();
+
+        let files = check_files(std::slice::from_ref(&file)).unwrap();
+        assert_eq!(files[0].name, "Report.PDF");
+        assert_eq!(files[0].size, 8);
+        assert_eq!(files[0].mime_type, "application/pdf");
+
+        assert!(check_files(&[dir.join("missing.txt")]).is_err());
+        assert!(check_files(std::slice::from_ref(&dir)).is_err());
+
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+}
@@ -0,0 +1,154 @@
+//! Explicit focus control, for form automation that shouldn't depend on
+//! clicks landing on the right element.
+//!
+//! Focus is moved with the element's own `focus()` and `blur()`. Webviews
+//! whose window isn't focused by the OS change `document.activeElement`
+//! without firing focus events, so any that didn't fire are dispatched
+//! from script.
+
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use tauri::{Runtime, WebviewWindow};
+
+use super::execute_js::run_script;
+
+/// Outcome of [`focus_element`] and [`blur_active`].
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "ts-bindings", derive(ts_rs::TS))]
+#[serde(rename_all = "camelCase")]
+pub struct FocusResult {
+    /// Whether focus ended where it was asked to: on the element for
+    /// [`focus_element`], off the previously focused element for
+    /// [`blur_active`]. `false` when the element can't take focus, such
+    /// as a `div` without `tabindex`.
+    pub changed: bool,
+    /// Whether the element already had focus, so nothing was done
+    #[serde(default)]
+    pub already_focused: bool,
+    /// Tag and ID of the element that gained or lost focus
+    #[serde(default)]
+    pub target: Option<String>,
+}
+
+/// Shared by both scripts: moves focus with `move`, then dispatches the
+/// focus events the browser didn't.
+const FOCUS_HELPERS: &str = r#"
+    const describe = (el) => el.tagName.toLowerCase() + (el.id ? '#' + el.id : '');
+    const moveFocus = (move) => {
+        const previous = document.activeElement;
+        const fired = new Set();
+        const record = (event) => fired.add(event.type + ':' + (event.target === previous ? 'previous' : 'next'));
+        const types = ['focus', 'blur', 'focusin', 'focusout'];
+        for (const type of types) document.addEventListener(type, record, true);
+        try {
+            move();
+        } finally {
+            for (const type of types) document.removeEventListener(type, record, true);
+        }
+
+        const next = document.activeElement;
+        if (next === previous) return;
+        const fire = (el, type, bubbles, related) => el.dispatchEvent(
+            new FocusEvent(type, { bubbles, composed: true, relatedTarget: related, view: window })
+        );
+        if (previous && previous !== document.body) {
+            if (!fired.has('blur:previous')) fire(previous, 'blur', false, next);
+            if (!fired.has('focusout:previous')) fire(previous, 'focusout', true, next);
+        }
+        if (next && next !== document.body) {
+            if (!fired.has('focus:next')) fire(next, 'focus', false, previous);
+            if (!fired.has('focusin:next')) fire(next, 'focusin', true, previous);
+        }
+    };
+"#;
+
+fn focus_script(selector: &str) -> String {
+    format!(
+        r#"return (() => {{
+    {FOCUS_HELPERS}
+    const el = document.querySelector({selector});
+    if (!el) throw new Error('No element matches selector: ' + {selector});
+    if (document.activeElement === el) {{
+        return {{ changed: true, alreadyFocused: true, target: describe(el) }};
+    }}
+
+    moveFocus(() => el.focus());
+    return {{ changed: document.activeElement === el, target: describe(el) }};
+}})();"#,
+        selector = Value::String(selector.to_string()),
+    )
+}
+
+fn blur_script() -> String {
+    format!(
+        r#"return (() => {{
+    {FOCUS_HELPERS}
+    const el = document.activeElement;
+    if (!el || el === document.body) return {{ changed: false }};
+
+    moveFocus(() => el.blur());
+    return {{ changed: document.activeElement !== el, target: describe(el) }};
+}})();"#
+    )
+}
+
+/// Focuses the element matching `selector`.
+///
+/// Focusing the element that already has focus does nothing and reports
+/// `already_focused`.
+///
+/// # Arguments
+///
+/// * `window` - The webview window to focus in
+/// * `selector` - CSS selector of the element to focus
+///
+/// # Returns
+///
+/// * `Ok(FocusResult)` - `changed: false` if the element can't take focus
+/// * `Err(String)` - Error message if nothing matches the selector, or the
+///   script could not be evaluated
+pub async fn focus_element<R: Runtime>(
+    window: &WebviewWindow<R>,
+    selector: &str,
+) -> Result<FocusResult, String> {
+    let result = run_script(window, &focus_script(selector)).await?;
+    serde_json::from_value(result).map_err(|e| format!("Unexpected focus result: {e}"))
+}
+
+/// Removes focus from the focused element, if any.
+///
+/// # Arguments
+///
+/// * `window` - The webview window to blur in
+///
+/// # Returns
+///
+/// * `Ok(FocusResult)` - `changed: false` if no element had focus
+/// * `Err(String)` - Error message if the script could not be evaluated
+pub async fn blur_active<R: Runtime>(window: &WebviewWindow<R>) -> Result<FocusResult, String> {
+    let result = run_script(window, &blur_script()).await?;
+    serde_json::from_value(result).map_err(|e| format!("Unexpected blur result: {e}"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parses_results() {
+        let blurred: FocusResult =
+            serde_json::from_value(serde_json::json!({ "changed": false })).unwrap();
+        assert!(!blurred.changed && !blurred.already_focused);
+        assert!(blurred.target.is_none());
+
+        let focused: FocusResult = serde_json::from_value(serde_json::json!({
+            "changed": true,
+            "alreadyFocused": true,
+            "target": "input#email"
+        }))
+        .unwrap();
+        assert!(focused.already_focused);
+
+        assert!(focus_script("#email").contains(r##"document.querySelector("#email")"##));
+    }
+}
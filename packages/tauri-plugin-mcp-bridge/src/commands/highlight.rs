@@ -0,0 +1,289 @@
+//! Temporary element highlights for visually confirming selectors.
+//!
+//! Highlights are absolutely positioned boxes inside a shadow root attached
+//! to a single host element, so page styles can't restyle them and they
+//! can't restyle the page. They scroll with the element but don't follow
+//! later layout changes. Removing the host removes every highlight.
+
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use tauri::{Runtime, WebviewWindow};
+
+use super::execute_js::run_script;
+use super::interactive_elements::ElementRect;
+
+/// Outline color used when none is given.
+pub const DEFAULT_HIGHLIGHT_COLOR: &str = "#ff2d55";
+
+/// How long highlights stay by default, in milliseconds.
+pub const DEFAULT_HIGHLIGHT_DURATION_MS: u64 = 3000;
+
+/// Elements outlined at most by one [`highlight`] call.
+pub const MAX_HIGHLIGHTS: u32 = 500;
+
+/// Outcome of [`highlight_element`].
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "ts-bindings", derive(ts_rs::TS))]
+#[serde(rename_all = "camelCase")]
+pub struct HighlightResult {
+    /// Whether an element matched and was outlined
+    pub highlighted: bool,
+    /// How many elements match the selector; only the first is outlined
+    pub match_count: u32,
+    /// Viewport-relative box of the outlined element, in CSS pixels
+    pub rect: Option<ElementRect>,
+}
+
+/// Options for [`highlight`].
+#[derive(Debug, Clone, Default, Deserialize)]
+#[cfg_attr(feature = "ts-bindings", derive(ts_rs::TS))]
+#[serde(rename_all = "camelCase", default)]
+pub struct HighlightOptions {
+    /// Any CSS color; invalid colors fall back to the default (default:
+    /// "#ff2d55")
+    pub color: Option<String>,
+    /// How long the outlines stay, or `0` to keep them until
+    /// [`clear_highlights`] is called (default: 3000)
+    pub duration_ms: Option<u64>,
+}
+
+/// Outcome of [`highlight`].
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "ts-bindings", derive(ts_rs::TS))]
+#[serde(rename_all = "camelCase")]
+pub struct HighlightMatches {
+    /// How many elements were outlined; elements that aren't rendered are
+    /// skipped
+    pub highlighted: u32,
+    /// How many elements match the selector
+    pub match_count: u32,
+}
+
+/// Defines `drawBox(rect, color, durationMs)`, which adds a box to the
+/// shared highlight layer, creating the layer on first use.
+///
+/// The host resets every property with `all: initial` and `!important`, so
+/// neither inherited nor page styles reach into the shadow root.
+const HIGHLIGHT_LAYER: &str = r#"const drawBox = (() => {
+    const layer = () => {
+        let host = document.querySelector('[data-mcp-highlight-root]');
+        if (!host) {
+            host = document.createElement('div');
+            host.setAttribute('data-mcp-highlight-root', '');
+            host.style.cssText = 'all:initial !important;position:absolute !important;' +
+                'left:0 !important;top:0 !important;width:0 !important;height:0 !important;' +
+                'overflow:visible !important;z-index:2147483647 !important;pointer-events:none !important;';
+            host.attachShadow({ mode: 'open' });
+            document.documentElement.appendChild(host);
+        }
+        return host.shadowRoot;
+    };
+
+    return (rect, color, durationMs) => {
+        const root = layer();
+        const box = document.createElement('div');
+        box.style.cssText = 'position:absolute;box-sizing:border-box;border:2px solid;border-radius:2px;' +
+            'left:' + (rect.left + window.scrollX) + 'px;top:' + (rect.top + window.scrollY) + 'px;' +
+            'width:' + rect.width + 'px;height:' + rect.height + 'px;';
+        box.style.borderColor = color;
+        const fill = document.createElement('div');
+        fill.style.cssText = 'position:absolute;inset:0;opacity:0.15;';
+        fill.style.background = color;
+        box.appendChild(fill);
+        root.appendChild(box);
+
+        if (durationMs > 0) {
+            setTimeout(() => {
+                box.remove();
+                if (root.host.isConnected && root.childElementCount === 0) {
+                    root.host.remove();
+                }
+            }, durationMs);
+        }
+    };
+})();"#;
+
+/// Quotes `color` for the page, falling back to the default for anything
+/// the webview doesn't parse as a color.
+fn color_expression(color: Option<&str>) -> String {
+    let fallback = Value::String(DEFAULT_HIGHLIGHT_COLOR.to_string());
+    match color {
+        Some(color) => format!(
+            "(CSS.supports('color', {color}) ? {color} : {fallback})",
+            color = Value::String(color.to_string()),
+        ),
+        None => fallback.to_string(),
+    }
+}
+
+fn highlight_script(selector: &str, duration_ms: u64) -> String {
+    format!(
+        r#"{HIGHLIGHT_LAYER}
+return (() => {{
+    const selector = {selector};
+    const durationMs = {duration_ms};
+    const matches = document.querySelectorAll(selector);
+    const el = matches[0];
+    if (!el) {{
+        return {{ highlighted: false, matchCount: 0, rect: null }};
+    }}
+
+    const rect = el.getBoundingClientRect();
+    drawBox(rect, {color}, durationMs);
+
+    return {{
+        highlighted: true,
+        matchCount: matches.length,
+        rect: {{ x: rect.x, y: rect.y, width: rect.width, height: rect.height }},
+    }};
+}})();"#,
+        selector = Value::String(selector.to_string()),
+        color = color_expression(None),
+    )
+}
+
+fn highlight_all_script(selector: &str, options: &HighlightOptions) -> String {
+    format!(
+        r#"{HIGHLIGHT_LAYER}
+return (() => {{
+    const selector = {selector};
+    const color = {color};
+    const durationMs = {duration_ms};
+    const matches = document.querySelectorAll(selector);
+    let highlighted = 0;
+    for (const el of matches) {{
+        if (highlighted >= {MAX_HIGHLIGHTS}) {{
+            break;
+        }}
+        const rect = el.getBoundingClientRect();
+        if (rect.width === 0 && rect.height === 0) {{
+            continue;
+        }}
+        drawBox(rect, color, durationMs);
+        highlighted++;
+    }}
+    return {{ highlighted, matchCount: matches.length }};
+}})();"#,
+        selector = Value::String(selector.to_string()),
+        color = color_expression(options.color.as_deref()),
+        duration_ms = options.duration_ms.unwrap_or(DEFAULT_HIGHLIGHT_DURATION_MS),
+    )
+}
+
+const CLEAR_HIGHLIGHTS_SCRIPT: &str = r#"return (() => {
+    const host = document.querySelector('[data-mcp-highlight-root]');
+    const count = host && host.shadowRoot ? host.shadowRoot.childElementCount : 0;
+    if (host) {
+        host.remove();
+    }
+    return count;
+})();"#;
+
+const HIDE_HIGHLIGHTS_SCRIPT: &str = r#"return (() => {
+    const host = document.querySelector('[data-mcp-highlight-root]');
+    if (!host) {
+        return false;
+    }
+    host.style.setProperty('display', 'none', 'important');
+    return true;
+})();"#;
+
+const SHOW_HIGHLIGHTS_SCRIPT: &str = r#"return (() => {
+    const host = document.querySelector('[data-mcp-highlight-root]');
+    if (host) {
+        host.style.removeProperty('display');
+    }
+    return null;
+})();"#;
+
+/// Outlines the first element matching `selector`.
+///
+/// # Arguments
+///
+/// * `window` - The webview window to draw in
+/// * `selector` - CSS selector of the element to outline
+/// * `duration_ms` - How long the outline stays, or `0` to keep it until
+///   [`clear_highlights`] is called
+///
+/// # Returns
+///
+/// * `Ok(HighlightResult)` - Whether an element was outlined, and where
+/// * `Err(String)` - Error message if the selector is invalid or the script
+///   could not be evaluated
+pub async fn highlight_element<R: Runtime>(
+    window: &WebviewWindow<R>,
+    selector: &str,
+    duration_ms: u64,
+) -> Result<HighlightResult, String> {
+    let result = run_script(window, &highlight_script(selector, duration_ms)).await?;
+    serde_json::from_value(result).map_err(|e| format!("Unexpected highlight result: {e}"))
+}
+
+/// Outlines every rendered element matching `selector`, up to
+/// [`MAX_HIGHLIGHTS`].
+///
+/// # Arguments
+///
+/// * `window` - The webview window to draw in
+/// * `selector` - CSS selector of the elements to outline
+/// * `options` - Outline color and how long the outlines stay
+///
+/// # Returns
+///
+/// * `Ok(HighlightMatches)` - How many elements matched and were outlined
+/// * `Err(String)` - Error message if the selector is invalid or the script
+///   could not be evaluated
+pub async fn highlight<R: Runtime>(
+    window: &WebviewWindow<R>,
+    selector: &str,
+    options: &HighlightOptions,
+) -> Result<HighlightMatches, String> {
+    let result = run_script(window, &highlight_all_script(selector, options)).await?;
+    serde_json::from_value(result).map_err(|e| format!("Unexpected highlight result: {e}"))
+}
+
+/// Removes every highlight drawn by [`highlight`] or [`highlight_element`].
+///
+/// Returns how many highlights were removed.
+pub async fn clear_highlights<R: Runtime>(window: &WebviewWindow<R>) -> Result<u64, String> {
+    let removed = run_script(window, CLEAR_HIGHLIGHTS_SCRIPT).await?;
+    Ok(removed.as_u64().unwrap_or(0))
+}
+
+/// Hides the highlight layer, e.g. for the duration of a capture.
+///
+/// Returns whether there was a layer to hide.
+pub(crate) async fn hide_highlights<R: Runtime>(window: &WebviewWindow<R>) -> Result<bool, String> {
+    let hidden = run_script(window, HIDE_HIGHLIGHTS_SCRIPT).await?;
+    Ok(hidden.as_bool().unwrap_or(false))
+}
+
+/// Shows the highlight layer hidden by [`hide_highlights`] again.
+pub(crate) async fn show_highlights<R: Runtime>(window: &WebviewWindow<R>) -> Result<(), String> {
+    run_script(window, SHOW_HIGHLIGHTS_SCRIPT).await.map(|_| ())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_highlight_script_escapes_selector() {
+        let script = highlight_script(r#"button[name="it's \"ok\""]"#, 1500);
+        assert!(script.contains(r#"const selector = "button[name=\"it's \\\"ok\\\"\"]";"#));
+        assert!(script.contains("const durationMs = 1500;"));
+    }
+
+    #[test]
+    fn test_highlight_all_script_embeds_options() {
+        let options: HighlightOptions =
+            serde_json::from_value(serde_json::json!({ "color": "rgb(0, 128, 255)" })).unwrap();
+
+        let script = highlight_all_script(".item", &options);
+        assert!(script.contains(r#"CSS.supports('color', "rgb(0, 128, 255)")"#));
+        assert!(script.contains("const durationMs = 3000;"));
+
+        let script = highlight_all_script(".item", &HighlightOptions::default());
+        assert!(script.contains(r##"const color = "#ff2d55";"##));
+    }
+}
@@ -0,0 +1,144 @@
+//! Hovering elements, to reveal menus and tooltips that open on hover.
+//!
+//! Synthetic events run the page's hover handlers but don't set the CSS
+//! `:hover` state, so UI revealed purely by `:hover` rules stays hidden.
+
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use tauri::{Runtime, WebviewWindow};
+
+use super::drag::DragPoint;
+use super::execute_js::run_script;
+
+/// Outcome of [`hover_element`].
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "ts-bindings", derive(ts_rs::TS))]
+#[serde(rename_all = "camelCase")]
+pub struct HoverResult {
+    /// Whether an element matched the selector and was hovered
+    pub hovered: bool,
+    /// Point the pointer was moved to, in CSS pixels relative to the
+    /// viewport
+    #[serde(default)]
+    pub point: Option<DragPoint>,
+    /// Tag and ID of the element under the point, which may be a child of
+    /// the matched element
+    #[serde(default)]
+    pub target: Option<String>,
+}
+
+fn hover_script(selector: &str) -> String {
+    format!(
+        r#"return (() => {{
+    const el = document.querySelector({selector});
+    if (!el) return {{ hovered: false }};
+
+    el.scrollIntoView({{ block: 'center', inline: 'center' }});
+    const rect = el.getBoundingClientRect();
+    const x = rect.left + rect.width / 2;
+    const y = rect.top + rect.height / 2;
+    let target = document.elementFromPoint(x, y);
+    // Hover the topmost element at the center when it is inside the match,
+    // such as a button's icon, and the match itself when an overlay covers it
+    if (!target || !el.contains(target)) target = el;
+
+    const init = (related) => ({{
+        bubbles: true,
+        cancelable: true,
+        composed: true,
+        view: window,
+        clientX: x,
+        clientY: y,
+        screenX: window.screenX + x,
+        screenY: window.screenY + y,
+        relatedTarget: related,
+        pointerId: 1,
+        pointerType: 'mouse',
+        isPrimary: true
+    }});
+    const fire = (node, Event, type, related, bubbles) =>
+        node.dispatchEvent(new Event(type, {{ ...init(related), bubbles }}));
+    const chain = (node) => {{
+        const nodes = [];
+        for (; node; node = node.parentElement) nodes.push(node);
+        return nodes;
+    }};
+
+    // Leave the previously hovered element, like a real pointer would
+    const previous = window.__MCP_HOVERED__;
+    if (previous && previous !== target && previous.isConnected) {{
+        fire(previous, PointerEvent, 'pointerout', target, true);
+        fire(previous, MouseEvent, 'mouseout', target, true);
+        const staying = chain(target);
+        for (const node of chain(previous).filter((node) => !staying.includes(node))) {{
+            fire(node, PointerEvent, 'pointerleave', target, false);
+            fire(node, MouseEvent, 'mouseleave', target, false);
+        }}
+    }}
+
+    if (previous !== target) {{
+        const related = previous && previous.isConnected ? previous : null;
+        fire(target, PointerEvent, 'pointerover', related, true);
+        fire(target, MouseEvent, 'mouseover', related, true);
+        const leaving = related ? chain(related) : [];
+        for (const node of chain(target).filter((node) => !leaving.includes(node)).reverse()) {{
+            fire(node, PointerEvent, 'pointerenter', related, false);
+            fire(node, MouseEvent, 'mouseenter', related, false);
+        }}
+    }}
+    fire(target, PointerEvent, 'pointermove', null, true);
+    fire(target, MouseEvent, 'mousemove', null, true);
+    window.__MCP_HOVERED__ = target;
+
+    const id = target.id ? '#' + target.id : '';
+    return {{ hovered: true, point: {{ x, y }}, target: target.tagName.toLowerCase() + id }};
+}})();"#,
+        selector = Value::String(selector.to_string()),
+    )
+}
+
+/// Moves the pointer over the element matching `selector`.
+///
+/// The element is scrolled into view and hover events are dispatched at
+/// its center. It stays hovered until the next hover moves the pointer
+/// elsewhere, which dispatches the matching leave events.
+///
+/// # Arguments
+///
+/// * `window` - The webview window to hover in
+/// * `selector` - CSS selector of the element to hover
+///
+/// # Returns
+///
+/// * `Ok(HoverResult)` - `hovered: false` if nothing matches the selector
+/// * `Err(String)` - Error message if the selector is invalid or the script
+///   could not be evaluated
+pub async fn hover_element<R: Runtime>(
+    window: &WebviewWindow<R>,
+    selector: &str,
+) -> Result<HoverResult, String> {
+    let result = run_script(window, &hover_script(selector)).await?;
+    serde_json::from_value(result).map_err(|e| format!("Unexpected hover result: {e}"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parses_miss_and_hit() {
+        let miss: HoverResult =
+            serde_json::from_value(serde_json::json!({ "hovered": false })).unwrap();
+        assert!(!miss.hovered);
+        assert!(miss.point.is_none());
+
+        let hit: HoverResult = serde_json::from_value(serde_json::json!({
+            "hovered": true,
+            "point": { "x": 40, "y": 12.5 },
+            "target": "button#menu"
+        }))
+        .unwrap();
+        assert_eq!(hit.point, Some(DragPoint { x: 40.0, y: 12.5 }));
+        assert_eq!(hit.target.as_deref(), Some("button#menu"));
+    }
+}
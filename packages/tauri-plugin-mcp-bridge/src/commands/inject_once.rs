@@ -0,0 +1,100 @@
+//! One-shot script injection that bypasses the script registry.
+//!
+//! Scripts injected here run once on the current page. They are never stored,
+//! so they are not re-injected after navigation or reload.
+
+use serde_json::Value;
+use tauri::{Manager, Runtime, WebviewWindow};
+
+use super::evaluate::evaluate_script;
+use crate::script_registry::{ScriptEntry, ScriptType};
+
+/// Builds a script that appends a `<script>` tag and resolves once it loads.
+fn script_tag_script(src: &str, module: bool) -> String {
+    format!(
+        r#"return new Promise((resolve, reject) => {{
+    const script = document.createElement('script');
+    if ({module}) {{
+        script.type = 'module';
+    }}
+    script.src = {src};
+    script.onload = () => resolve(true);
+    script.onerror = () => reject(new Error('Failed to load script: ' + script.src));
+    document.head.appendChild(script);
+}});"#,
+        src = Value::String(src.to_string()),
+    )
+}
+
+/// Builds a script that loads inline module code from a `blob:` URL.
+fn inline_module_script(code: &str) -> String {
+    format!(
+        r#"const url = URL.createObjectURL(new Blob([{code}], {{ type: 'text/javascript' }}));
+try {{
+    await import(url);
+    return true;
+}} finally {{
+    URL.revokeObjectURL(url);
+}}"#,
+        code = Value::String(code.to_string()),
+    )
+}
+
+/// Injects a script into the current page once, without registering it.
+///
+/// Inline scripts are evaluated and their (awaited) value returned. URL and
+/// module scripts are loaded through a `<script>` tag or dynamic import and
+/// report `true` once loaded. Waits up to the configured script timeout.
+///
+/// # Arguments
+///
+/// * `window` - The webview window to inject into
+/// * `script_type` - How to interpret `content`
+/// * `content` - JavaScript code, or a script or module URL
+///
+/// # Returns
+///
+/// * `Ok(Value)` - `{ result, persistent: false }`; the script won't survive
+///   navigation
+/// * `Err(String)` - Error message if the script failed, was rejected or timed out
+pub async fn inject_once<R: Runtime>(
+    window: &WebviewWindow<R>,
+    script_type: ScriptType,
+    content: &str,
+) -> Result<Value, String> {
+    let script = match script_type {
+        ScriptType::Inline => content.to_string(),
+        ScriptType::Url => script_tag_script(content, false),
+        ScriptType::Module => {
+            let entry = ScriptEntry::new("", ScriptType::Module, content);
+            if entry.is_module_url() {
+                script_tag_script(content, true)
+            } else {
+                inline_module_script(content)
+            }
+        }
+    };
+
+    let timeout = window.state::<crate::Config>().script_timeout;
+    let result = evaluate_script(window, &script, timeout)
+        .await
+        .map_err(|e| e.to_string())?;
+
+    Ok(serde_json::json!({
+        "result": result,
+        "persistent": false,
+        "note": "Injected once; not re-injected after navigation"
+    }))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_script_tag_script_escapes_src() {
+        let script = script_tag_script("https://cdn.example.com/a.js?x='1'", true);
+        assert!(script.contains(r#"script.src = "https://cdn.example.com/a.js?x='1'";"#));
+        assert!(script.contains("if (true)"));
+    }
+}
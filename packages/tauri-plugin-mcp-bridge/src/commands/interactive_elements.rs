@@ -0,0 +1,256 @@
+//! Interactive element discovery for "set of marks" style screenshots.
+//!
+//! Collects the visible clickable elements of a page with their bounding
+//! boxes, and optionally draws numbered overlays so a screenshot can be
+//! acted on by element index. [`get_interactive_elements`] returns the same
+//! data as plain JSON, for models that take coordinates alongside a raw
+//! screenshot, without touching the page.
+
+use serde::{Deserialize, Serialize};
+use tauri::{Runtime, WebviewWindow};
+
+use super::execute_js::run_script;
+
+/// Bounding box of an element in CSS pixels, relative to the viewport.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[cfg_attr(feature = "ts-bindings", derive(ts_rs::TS))]
+pub struct ElementRect {
+    pub x: f64,
+    pub y: f64,
+    pub width: f64,
+    pub height: f64,
+}
+
+/// A visible interactive element.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "ts-bindings", derive(ts_rs::TS))]
+pub struct InteractiveElement {
+    /// Position in the list, matching the number drawn on overlays
+    pub index: u32,
+    /// CSS selector that locates the element
+    pub selector: String,
+    /// Bounding box in CSS pixels, relative to the viewport
+    pub rect: ElementRect,
+    /// ARIA role, explicit or implied by the tag
+    pub role: String,
+    /// Accessible label or visible text, truncated
+    pub label: String,
+}
+
+/// An interactive element with its visibility, from
+/// [`get_interactive_elements`].
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "ts-bindings", derive(ts_rs::TS))]
+#[serde(rename_all = "camelCase")]
+pub struct MarkedElement {
+    /// Position in the list
+    pub index: u32,
+    /// CSS selector that locates the element
+    pub selector: String,
+    /// Bounding box in CSS pixels, relative to the viewport
+    pub rect: ElementRect,
+    /// ARIA role, explicit or implied by the tag
+    pub role: String,
+    /// Accessible label or visible text, truncated
+    pub label: String,
+    /// Whether the element is rendered: it has a size and isn't hidden by
+    /// `display`, `visibility` or `opacity`
+    pub visible: bool,
+    /// Whether any part of the element is inside the viewport
+    pub in_viewport: bool,
+}
+
+/// Finds interactive elements and, when `annotate` is set, draws a numbered
+/// box over each one.
+///
+/// Only visible elements inside the viewport are returned unless
+/// `include_hidden` is set.
+fn collect_script(annotate: bool, include_hidden: bool) -> String {
+    format!(
+        r#"return (() => {{
+    const annotate = {annotate};
+    const includeHidden = {include_hidden};
+    const candidates = document.querySelectorAll(
+        'a[href], button, input:not([type="hidden"]), select, textarea, summary, ' +
+        '[role="button"], [role="link"], [role="checkbox"], [role="radio"], [role="tab"], ' +
+        '[role="menuitem"], [role="option"], [role="switch"], [onclick], [contenteditable="true"]'
+    );
+
+    const implicitRole = (el) => {{
+        const tag = el.tagName.toLowerCase();
+        if (tag === 'a') return 'link';
+        if (tag === 'select') return 'combobox';
+        if (tag === 'textarea') return 'textbox';
+        if (tag === 'summary' || tag === 'button') return 'button';
+        if (tag === 'input') {{
+            const type = (el.getAttribute('type') || 'text').toLowerCase();
+            if (type === 'checkbox' || type === 'radio') return type;
+            if (['button', 'submit', 'reset', 'image'].includes(type)) return 'button';
+            return 'textbox';
+        }}
+        return 'generic';
+    }};
+
+    const selectorFor = (el) => {{
+        if (el.id) return '#' + CSS.escape(el.id);
+        const parts = [];
+        let node = el;
+        while (node && node.nodeType === 1 && node !== document.body) {{
+            if (node.id) {{
+                parts.unshift('#' + CSS.escape(node.id));
+                break;
+            }}
+            let part = node.tagName.toLowerCase();
+            const siblings = node.parentElement
+                ? Array.from(node.parentElement.children).filter((c) => c.tagName === node.tagName)
+                : [];
+            if (siblings.length > 1) part += ':nth-of-type(' + (siblings.indexOf(node) + 1) + ')';
+            parts.unshift(part);
+            node = node.parentElement;
+        }}
+        if (node === document.body) parts.unshift('body');
+        return parts.join(' > ');
+    }};
+
+    const labelFor = (el) => {{
+        const labelledBy = el.getAttribute('aria-labelledby');
+        const text = el.getAttribute('aria-label')
+            || (labelledBy && labelledBy.split(/\s+/)
+                .map((id) => document.getElementById(id))
+                .filter(Boolean)
+                .map((node) => node.textContent)
+                .join(' '))
+            || (el.labels && el.labels[0] && el.labels[0].textContent)
+            || el.innerText
+            || el.value
+            || el.getAttribute('placeholder')
+            || el.getAttribute('title')
+            || el.getAttribute('alt')
+            || '';
+        return String(text).replace(/\s+/g, ' ').trim().slice(0, 80);
+    }};
+
+    const isVisible = (el, rect) => {{
+        if (rect.width <= 0 || rect.height <= 0) return false;
+        const style = getComputedStyle(el);
+        return style.visibility !== 'hidden' && style.display !== 'none' && style.opacity !== '0';
+    }};
+
+    const isInViewport = (rect) =>
+        rect.bottom >= 0 && rect.right >= 0 && rect.top <= window.innerHeight && rect.left <= window.innerWidth;
+
+    const elements = [];
+    for (const el of candidates) {{
+        const rect = el.getBoundingClientRect();
+        const visible = isVisible(el, rect);
+        const inViewport = isInViewport(rect);
+        if (!includeHidden && !(visible && inViewport)) continue;
+        elements.push({{
+            index: elements.length,
+            selector: selectorFor(el),
+            rect: {{ x: rect.x, y: rect.y, width: rect.width, height: rect.height }},
+            role: el.getAttribute('role') || implicitRole(el),
+            label: labelFor(el),
+            visible,
+            inViewport
+        }});
+    }}
+
+    if (annotate) {{
+        const layer = document.createElement('div');
+        layer.setAttribute('data-mcp-element-overlay', '');
+        layer.style.cssText = 'position:fixed;inset:0;pointer-events:none;z-index:2147483647;';
+        for (const item of elements) {{
+            const box = document.createElement('div');
+            box.style.cssText = 'position:absolute;border:2px solid #e0218a;box-sizing:border-box;' +
+                'left:' + item.rect.x + 'px;top:' + item.rect.y + 'px;' +
+                'width:' + item.rect.width + 'px;height:' + item.rect.height + 'px;';
+            const badge = document.createElement('span');
+            badge.textContent = String(item.index);
+            badge.style.cssText = 'position:absolute;left:-2px;top:-2px;background:#e0218a;color:#fff;' +
+                'font:bold 11px/14px monospace;padding:0 3px;';
+            box.appendChild(badge);
+            layer.appendChild(box);
+        }}
+        document.documentElement.appendChild(layer);
+    }}
+
+    return elements;
+}})();"#
+    )
+}
+
+const REMOVE_OVERLAYS_SCRIPT: &str = r#"return (() => {
+    document.querySelectorAll('[data-mcp-element-overlay]').forEach((el) => el.remove());
+    return true;
+})();"#;
+
+/// Collects the visible interactive elements of a window.
+///
+/// # Arguments
+///
+/// * `window` - The webview window to inspect
+/// * `annotate` - Whether to draw numbered overlays over each element. Call
+///   [`remove_element_overlays`] to remove them.
+///
+/// # Returns
+///
+/// * `Ok(Vec<InteractiveElement>)` - Elements in document order
+/// * `Err(String)` - Error message if the script could not be evaluated
+pub async fn collect_interactive_elements<R: Runtime>(
+    window: &WebviewWindow<R>,
+    annotate: bool,
+) -> Result<Vec<InteractiveElement>, String> {
+    let result = run_script(window, &collect_script(annotate, false)).await?;
+    serde_json::from_value(result).map_err(|e| format!("Unexpected element data: {e}"))
+}
+
+/// Lists the interactive elements of a window, including hidden and
+/// off-screen ones, without modifying the page.
+///
+/// # Arguments
+///
+/// * `window` - The webview window to inspect
+///
+/// # Returns
+///
+/// * `Ok(Vec<MarkedElement>)` - Elements in document order, numbered
+/// * `Err(String)` - Error message if the script could not be evaluated
+pub async fn get_interactive_elements<R: Runtime>(
+    window: &WebviewWindow<R>,
+) -> Result<Vec<MarkedElement>, String> {
+    let result = run_script(window, &collect_script(false, true)).await?;
+    serde_json::from_value(result).map_err(|e| format!("Unexpected element data: {e}"))
+}
+
+/// Removes overlays drawn by [`collect_interactive_elements`].
+pub async fn remove_element_overlays<R: Runtime>(window: &WebviewWindow<R>) -> Result<(), String> {
+    run_script(window, REMOVE_OVERLAYS_SCRIPT).await.map(|_| ())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parses_script_output() {
+        let elements: Vec<InteractiveElement> = serde_json::from_value(serde_json::json!([{
+            "index": 0,
+            "selector": "#submit",
+            "rect": { "x": 10.5, "y": 20, "width": 80, "height": 24 },
+            "role": "button",
+            "label": "Submit"
+        }]))
+        .unwrap();
+
+        assert_eq!(elements[0].selector, "#submit");
+        assert_eq!(elements[0].rect.x, 10.5);
+    }
+
+    #[test]
+    fn test_script_toggles_annotation() {
+        assert!(collect_script(true, false).contains("const annotate = true;"));
+        assert!(collect_script(false, false).contains("const annotate = false;"));
+        assert!(collect_script(false, true).contains("const includeHidden = true;"));
+    }
+}
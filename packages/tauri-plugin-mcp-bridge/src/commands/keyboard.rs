@@ -0,0 +1,262 @@
+//! Key presses, for keyboard shortcuts, navigation and form submission.
+//!
+//! Events are dispatched from script, so the page's key handlers run but
+//! the browser's default actions don't: a synthetic Tab doesn't move focus
+//! and a synthetic Enter doesn't submit a form. Pages that handle keys
+//! themselves, as most shortcut and menu code does, react as usual.
+
+use serde::{Deserialize, Serialize};
+use tauri::{Runtime, WebviewWindow};
+
+use super::execute_js::run_script;
+
+/// Modifier keys held during a key press.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize)]
+#[cfg_attr(feature = "ts-bindings", derive(ts_rs::TS))]
+#[serde(default)]
+pub struct KeyModifiers {
+    pub ctrl: bool,
+    pub shift: bool,
+    pub alt: bool,
+    /// Command on macOS, the Windows key elsewhere
+    pub meta: bool,
+}
+
+impl KeyModifiers {
+    /// Modifier keys held, as `(key, code, keyCode)` in press order.
+    fn held(&self) -> Vec<(&'static str, &'static str, u32)> {
+        [
+            (self.ctrl, ("Control", "ControlLeft", 17)),
+            (self.shift, ("Shift", "ShiftLeft", 16)),
+            (self.alt, ("Alt", "AltLeft", 18)),
+            (self.meta, ("Meta", "MetaLeft", 91)),
+        ]
+        .into_iter()
+        .filter_map(|(held, key)| held.then_some(key))
+        .collect()
+    }
+}
+
+/// Outcome of [`press_key`].
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "ts-bindings", derive(ts_rs::TS))]
+#[serde(rename_all = "camelCase")]
+pub struct KeyPressResult {
+    /// `KeyboardEvent.key` that was dispatched
+    pub key: String,
+    /// `KeyboardEvent.code` that was dispatched
+    pub code: String,
+    /// Tag and ID of the element the events were dispatched to
+    pub target: String,
+    /// Whether a handler called `preventDefault()` on the `keydown`, which
+    /// usually means the page handled the key
+    pub default_prevented: bool,
+}
+
+/// `key`, `code` and legacy `keyCode` of a key.
+#[derive(Debug, PartialEq, Eq)]
+struct KeyInfo {
+    key: String,
+    code: String,
+    key_code: u32,
+}
+
+/// Resolves a key name, as in `KeyboardEvent.key`, to its event fields.
+///
+/// Accepts the common aliases `Esc`, `Space`, `Return`, `Del` and `Up`,
+/// `Down`, `Left`, `Right`. Shift turns letters uppercase.
+fn key_info(key: &str, modifiers: &KeyModifiers) -> Result<KeyInfo, String> {
+    let named = |key: &str, code: &str, key_code: u32| KeyInfo {
+        key: key.to_string(),
+        code: code.to_string(),
+        key_code,
+    };
+
+    let info = match key {
+        "Enter" | "Return" => named("Enter", "Enter", 13),
+        "Escape" | "Esc" => named("Escape", "Escape", 27),
+        "Tab" => named("Tab", "Tab", 9),
+        "Backspace" => named("Backspace", "Backspace", 8),
+        "Delete" | "Del" => named("Delete", "Delete", 46),
+        " " | "Space" => named(" ", "Space", 32),
+        "ArrowUp" | "Up" => named("ArrowUp", "ArrowUp", 38),
+        "ArrowDown" | "Down" => named("ArrowDown", "ArrowDown", 40),
+        "ArrowLeft" | "Left" => named("ArrowLeft", "ArrowLeft", 37),
+        "ArrowRight" | "Right" => named("ArrowRight", "ArrowRight", 39),
+        "Home" => named("Home", "Home", 36),
+        "End" => named("End", "End", 35),
+        "PageUp" => named("PageUp", "PageUp", 33),
+        "PageDown" => named("PageDown", "PageDown", 34),
+        _ => {
+            if let Some(n) = key
+                .strip_prefix('F')
+                .and_then(|n| n.parse::<u32>().ok())
+                .filter(|n| (1..=12).contains(n))
+            {
+                return Ok(named(key, key, 111 + n));
+            }
+
+            let mut chars = key.chars();
+            let (Some(c), None) = (chars.next(), chars.next()) else {
+                return Err(format!(
+                    "Unknown key '{key}': use a single character or a key name such as Enter, Escape, Tab or ArrowDown"
+                ));
+            };
+
+            if c.is_ascii_alphabetic() {
+                let upper = c.to_ascii_uppercase();
+                let key = if modifiers.shift { upper } else { c };
+                named(&key.to_string(), &format!("Key{upper}"), upper as u32)
+            } else if c.is_ascii_digit() {
+                named(key, &format!("Digit{c}"), c as u32)
+            } else {
+                // Punctuation depends on the keyboard layout; leave `code` empty
+                named(key, "", 0)
+            }
+        }
+    };
+    Ok(info)
+}
+
+fn press_script(info: &KeyInfo, modifiers: &KeyModifiers, selector: Option<&str>) -> String {
+    // Browsers send keypress only for keys that produce a character
+    let keypress = (info.key.chars().count() == 1 || info.key == "Enter")
+        && !modifiers.ctrl
+        && !modifiers.meta;
+    let options = serde_json::json!({
+        "key": info.key,
+        "code": info.code,
+        "keyCode": info.key_code,
+        "ctrlKey": modifiers.ctrl,
+        "shiftKey": modifiers.shift,
+        "altKey": modifiers.alt,
+        "metaKey": modifiers.meta,
+        "modifiers": modifiers.held(),
+        "keypress": keypress,
+        "selector": selector,
+    });
+
+    format!(
+        r#"return (() => {{
+    const options = {options};
+    let target = document.activeElement || document.body;
+    if (options.selector) {{
+        target = document.querySelector(options.selector);
+        if (!target) throw new Error('No element matches selector: ' + options.selector);
+        target.focus();
+    }}
+
+    const flags = {{
+        ctrlKey: options.ctrlKey,
+        shiftKey: options.shiftKey,
+        altKey: options.altKey,
+        metaKey: options.metaKey
+    }};
+    const fire = (type, key, code, keyCode, charCode = 0) => target.dispatchEvent(new KeyboardEvent(type, {{
+        ...flags,
+        key,
+        code,
+        keyCode,
+        which: keyCode,
+        charCode,
+        bubbles: true,
+        cancelable: true,
+        composed: true,
+        view: window
+    }}));
+
+    // Press the modifiers first and release them last, as a keyboard does
+    for (const [key, code, keyCode] of options.modifiers) fire('keydown', key, code, keyCode);
+    const notPrevented = fire('keydown', options.key, options.code, options.keyCode);
+    if (notPrevented && options.keypress) {{
+        const charCode = (options.key === 'Enter' ? '\r' : options.key).charCodeAt(0);
+        fire('keypress', options.key, options.code, charCode, charCode);
+    }}
+    fire('keyup', options.key, options.code, options.keyCode);
+    for (const [key, code, keyCode] of options.modifiers.reverse()) fire('keyup', key, code, keyCode);
+
+    const id = target.id ? '#' + target.id : '';
+    return {{
+        key: options.key,
+        code: options.code,
+        target: target.tagName.toLowerCase() + id,
+        defaultPrevented: !notPrevented
+    }};
+}})();"#
+    )
+}
+
+/// Presses a key, with modifiers held, in the focused element or the
+/// element matching `selector`.
+///
+/// Dispatches `keydown`, `keypress` for keys that produce a character, and
+/// `keyup`, wrapped in the modifiers' own `keydown` and `keyup`.
+///
+/// # Arguments
+///
+/// * `window` - The webview window to press the key in
+/// * `key` - Key name as in `KeyboardEvent.key`, such as `Enter`, `a` or
+///   `ArrowDown`
+/// * `modifiers` - Modifier keys held during the press
+/// * `selector` - CSS selector of the element to focus and press the key
+///   in; the focused element when `None`
+///
+/// # Returns
+///
+/// * `Ok(KeyPressResult)` - The dispatched key and whether the page
+///   prevented its default
+/// * `Err(String)` - Error message if the key is unknown, the selector
+///   matches nothing, or the script could not be evaluated
+pub async fn press_key<R: Runtime>(
+    window: &WebviewWindow<R>,
+    key: &str,
+    modifiers: &KeyModifiers,
+    selector: Option<&str>,
+) -> Result<KeyPressResult, String> {
+    let info = key_info(key, modifiers)?;
+    let result = run_script(window, &press_script(&info, modifiers, selector)).await?;
+    serde_json::from_value(result).map_err(|e| format!("Unexpected key press result: {e}"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_key_info_resolves_names_and_characters() {
+        let none = KeyModifiers::default();
+        let shift = KeyModifiers {
+            shift: true,
+            ..Default::default()
+        };
+
+        assert_eq!(
+            key_info("Esc", &none).unwrap(),
+            key_info("Escape", &none).unwrap()
+        );
+        assert_eq!(key_info("Up", &none).unwrap().code, "ArrowUp");
+        assert_eq!(key_info("F5", &none).unwrap().key_code, 116);
+
+        let a = key_info("a", &shift).unwrap();
+        assert_eq!(
+            (a.key.as_str(), a.code.as_str(), a.key_code),
+            ("A", "KeyA", 65)
+        );
+        assert_eq!(key_info("7", &none).unwrap().code, "Digit7");
+        assert_eq!(key_info("/", &none).unwrap().code, "");
+
+        assert!(key_info("Hyper", &none).is_err());
+        assert!(key_info("F13", &none).is_err());
+    }
+
+    #[test]
+    fn test_modifiers_press_in_order() {
+        let modifiers: KeyModifiers =
+            serde_json::from_value(serde_json::json!({ "meta": true, "ctrl": true })).unwrap();
+        let keys: Vec<_> = modifiers.held().iter().map(|m| m.0).collect();
+        assert_eq!(keys, ["Control", "Meta"]);
+
+        let script = press_script(&key_info("s", &modifiers).unwrap(), &modifiers, None);
+        assert!(script.contains(r#""keypress":false"#));
+    }
+}
@@ -1,11 +1,11 @@
 //! Window listing and discovery.
 
 use serde::Serialize;
-use serde_json::Value;
-use tauri::{command, AppHandle, Manager, Runtime};
+use tauri::{command, AppHandle, Manager, Runtime, WebviewWindow};
 
 /// Information about a webview window.
 #[derive(Debug, Clone, Serialize)]
+#[cfg_attr(feature = "ts-bindings", derive(ts_rs::TS))]
 #[serde(rename_all = "camelCase")]
 pub struct WindowInfo {
     /// The unique label/identifier for this window
@@ -14,6 +14,10 @@ pub struct WindowInfo {
     pub title: Option<String>,
     /// The current URL loaded in the webview (if available)
     pub url: Option<String>,
+    /// Inner (content area) width in physical pixels (if available)
+    pub inner_width: Option<u32>,
+    /// Inner (content area) height in physical pixels (if available)
+    pub inner_height: Option<u32>,
     /// Whether this window currently has focus
     pub focused: bool,
     /// Whether this window is visible
@@ -25,7 +29,8 @@ pub struct WindowInfo {
 /// Lists all open webview windows in the application.
 ///
 /// Returns detailed information about each window including its label, title,
-/// URL, focus state, and visibility.
+/// URL, inner size, focus state, and visibility. Windows are ordered with
+/// "main" first, then by label.
 ///
 /// # Arguments
 ///
@@ -33,7 +38,7 @@ pub struct WindowInfo {
 ///
 /// # Returns
 ///
-/// * `Ok(Value)` - JSON array of WindowInfo objects
+/// * `Ok(Vec<WindowInfo>)` - One entry per open webview window
 /// * `Err(String)` - Error message if retrieval fails
 ///
 /// # Examples
@@ -45,7 +50,7 @@ pub struct WindowInfo {
 /// console.log(`Found ${windows.length} windows`);
 /// ```
 #[command]
-pub async fn list_windows<R: Runtime>(app: AppHandle<R>) -> Result<Value, String> {
+pub async fn list_windows<R: Runtime>(app: AppHandle<R>) -> Result<Vec<WindowInfo>, String> {
     let windows = app.webview_windows();
     let mut window_list: Vec<WindowInfo> = Vec::new();
 
@@ -54,20 +59,29 @@ pub async fn list_windows<R: Runtime>(app: AppHandle<R>) -> Result<Value, String
         let url = window.url().ok().map(|u| u.to_string());
         let focused = window.is_focused().unwrap_or(false);
         let visible = window.is_visible().unwrap_or(false);
+        let inner_size = window.inner_size().ok();
         let is_main = label == "main";
 
         window_list.push(WindowInfo {
             label: label.clone(),
             title,
             url,
+            inner_width: inner_size.map(|size| size.width),
+            inner_height: inner_size.map(|size| size.height),
             focused,
             visible,
             is_main,
         });
     }
 
-    // Sort by label for consistent ordering, with "main" first
-    window_list.sort_by(|a, b| {
+    sort_windows(&mut window_list);
+
+    Ok(window_list)
+}
+
+/// Sort by label for consistent ordering, with "main" first.
+fn sort_windows(windows: &mut [WindowInfo]) {
+    windows.sort_by(|a, b| {
         if a.is_main {
             std::cmp::Ordering::Less
         } else if b.is_main {
@@ -76,12 +90,11 @@ pub async fn list_windows<R: Runtime>(app: AppHandle<R>) -> Result<Value, String
             a.label.cmp(&b.label)
         }
     });
-
-    serde_json::to_value(&window_list).map_err(|e| format!("Failed to serialize windows: {e}"))
 }
 
 /// Context about which window was used for an operation.
 #[derive(Debug, Clone, Serialize)]
+#[cfg_attr(feature = "ts-bindings", derive(ts_rs::TS))]
 #[serde(rename_all = "camelCase")]
 pub struct WindowContext {
     /// The label of the window that was used
@@ -164,3 +177,51 @@ pub fn resolve_window<R: Runtime>(
     app.get_webview_window(&label)
         .ok_or_else(|| format!("Window '{label}' not found"))
 }
+
+/// Returns whether a window is still open.
+///
+/// Windows that are closing or already destroyed are removed from the app's
+/// window registry and their native handle stops answering queries, so work
+/// scheduled on them may never run.
+pub fn is_window_available<R: Runtime>(window: &WebviewWindow<R>) -> bool {
+    window
+        .app_handle()
+        .get_webview_window(window.label())
+        .is_some()
+        && window.is_visible().is_ok()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn window(label: &str) -> WindowInfo {
+        WindowInfo {
+            label: label.to_string(),
+            title: None,
+            url: None,
+            inner_width: Some(800),
+            inner_height: Some(600),
+            focused: false,
+            visible: true,
+            is_main: label == "main",
+        }
+    }
+
+    #[test]
+    fn test_sort_windows_puts_main_first() {
+        let mut windows = vec![window("settings"), window("main"), window("about")];
+        sort_windows(&mut windows);
+
+        let labels: Vec<_> = windows.iter().map(|w| w.label.as_str()).collect();
+        assert_eq!(labels, vec!["main", "about", "settings"]);
+    }
+
+    #[test]
+    fn test_window_info_serializes_inner_size() {
+        let value = serde_json::to_value(window("main")).unwrap();
+        assert_eq!(value["innerWidth"], 800);
+        assert_eq!(value["innerHeight"], 600);
+        assert_eq!(value["isMain"], true);
+    }
+}
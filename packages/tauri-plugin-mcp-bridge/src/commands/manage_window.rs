@@ -0,0 +1,164 @@
+//! Window management operations (focus, minimize, close).
+
+use serde::Serialize;
+use tauri::{AppHandle, Manager, Runtime, WebviewWindow};
+
+use crate::config::Config;
+
+/// Window management error types
+#[derive(Debug, thiserror::Error)]
+pub enum WindowError {
+    #[error("Window '{0}' not found")]
+    WindowNotFound(String),
+
+    #[error("Closing windows is disabled. Enable it with Builder::allow_close(true)")]
+    CloseNotAllowed,
+
+    #[error("Window operation failed: {0}")]
+    OperationFailed(String),
+}
+
+impl WindowError {
+    /// Machine-readable error code for clients.
+    pub fn code(&self) -> &'static str {
+        match self {
+            WindowError::WindowNotFound(_) => "WINDOW_NOT_FOUND",
+            WindowError::CloseNotAllowed => "CLOSE_NOT_ALLOWED",
+            WindowError::OperationFailed(_) => "OPERATION_FAILED",
+        }
+    }
+}
+
+/// State of a window after a management operation.
+#[derive(Debug, Serialize)]
+#[cfg_attr(feature = "ts-bindings", derive(ts_rs::TS))]
+#[serde(rename_all = "camelCase")]
+pub struct WindowState {
+    /// The window that was targeted
+    pub window_label: String,
+    /// Whether the window has focus
+    pub focused: bool,
+    /// Whether the window is minimized
+    pub minimized: bool,
+    /// Whether the window is visible
+    pub visible: bool,
+    /// Whether the window was closed
+    pub closed: bool,
+}
+
+impl WindowState {
+    fn of<R: Runtime>(window: &WebviewWindow<R>) -> Self {
+        Self {
+            window_label: window.label().to_string(),
+            focused: window.is_focused().unwrap_or(false),
+            minimized: window.is_minimized().unwrap_or(false),
+            visible: window.is_visible().unwrap_or(false),
+            closed: false,
+        }
+    }
+}
+
+/// Resolves a window by label, defaulting to "main" if not specified.
+fn find_window<R: Runtime>(
+    app: &AppHandle<R>,
+    label: Option<String>,
+) -> Result<WebviewWindow<R>, WindowError> {
+    let label = label.unwrap_or_else(|| "main".to_string());
+    app.get_webview_window(&label)
+        .ok_or(WindowError::WindowNotFound(label))
+}
+
+/// Brings a window to the front and gives it focus.
+///
+/// Minimized windows are restored first.
+///
+/// # Arguments
+///
+/// * `app` - The Tauri application handle
+/// * `label` - Optional window label (defaults to "main")
+///
+/// # Returns
+///
+/// * `Ok(WindowState)` - The window state after focusing
+/// * `Err(WindowError)` - If the window doesn't exist or can't be focused
+pub async fn focus_window<R: Runtime>(
+    app: AppHandle<R>,
+    label: Option<String>,
+) -> Result<WindowState, WindowError> {
+    let window = find_window(&app, label)?;
+
+    if window.is_minimized().unwrap_or(false) {
+        window
+            .unminimize()
+            .map_err(|e| WindowError::OperationFailed(format!("Failed to restore window: {e}")))?;
+    }
+    window
+        .set_focus()
+        .map_err(|e| WindowError::OperationFailed(format!("Failed to focus window: {e}")))?;
+
+    Ok(WindowState::of(&window))
+}
+
+/// Minimizes a window.
+///
+/// # Arguments
+///
+/// * `app` - The Tauri application handle
+/// * `label` - Optional window label (defaults to "main")
+///
+/// # Returns
+///
+/// * `Ok(WindowState)` - The window state after minimizing
+/// * `Err(WindowError)` - If the window doesn't exist or can't be minimized
+pub async fn minimize_window<R: Runtime>(
+    app: AppHandle<R>,
+    label: Option<String>,
+) -> Result<WindowState, WindowError> {
+    let window = find_window(&app, label)?;
+
+    window
+        .minimize()
+        .map_err(|e| WindowError::OperationFailed(format!("Failed to minimize window: {e}")))?;
+
+    Ok(WindowState::of(&window))
+}
+
+/// Closes a window.
+///
+/// Only allowed when the plugin was built with `allow_close(true)`, so
+/// agents can't close the main window by accident.
+///
+/// # Arguments
+///
+/// * `app` - The Tauri application handle
+/// * `label` - Optional window label (defaults to "main")
+///
+/// # Returns
+///
+/// * `Ok(WindowState)` - The window state with `closed` set
+/// * `Err(WindowError)` - If closing is disabled, the window doesn't exist, or
+///   closing fails
+pub async fn close_window<R: Runtime>(
+    app: AppHandle<R>,
+    label: Option<String>,
+) -> Result<WindowState, WindowError> {
+    let allow_close = app
+        .try_state::<Config>()
+        .map(|config| config.allow_close)
+        .unwrap_or(false);
+    if !allow_close {
+        return Err(WindowError::CloseNotAllowed);
+    }
+
+    let window = find_window(&app, label)?;
+    let mut state = WindowState::of(&window);
+
+    window
+        .close()
+        .map_err(|e| WindowError::OperationFailed(format!("Failed to close window: {e}")))?;
+
+    state.closed = true;
+    state.focused = false;
+    state.visible = false;
+    Ok(state)
+}
@@ -5,33 +5,130 @@
 //! when invoked from the frontend.
 
 // Individual command modules
+pub mod accessibility;
 pub mod backend_state;
+pub mod bounding_rects;
+pub mod browsing_data;
+pub mod capture_text;
+pub mod color_scheme;
+pub mod computed_style;
+pub mod console_logs;
+pub mod cookies;
+pub mod dom_capture;
+pub mod drag;
 pub mod emit_event;
+pub mod emulation;
+pub mod evaluate;
 pub mod execute_command;
 pub mod execute_js;
+pub mod file_input;
+pub mod focus;
+pub mod highlight;
+pub mod hover;
+pub mod inject_once;
+pub mod interactive_elements;
 pub mod ipc_monitor;
+pub mod keyboard;
 pub mod list_windows;
+pub mod manage_window;
+pub mod network_idle;
+pub mod network_log;
+pub mod performance;
+pub mod reload;
 pub mod resize_window;
 pub mod screenshot;
+pub mod screenshot_stream;
 pub mod script_executor;
 pub mod script_injection;
+pub mod script_manifest;
+pub mod select_option;
+pub mod storage;
+pub mod user_agent;
+pub mod viewport;
+pub mod visual_diff;
+pub mod wait_for_condition;
 pub mod window_info;
+pub mod zoom;
 
 // Re-export types and commands for convenience
 pub use script_executor::ScriptExecutor;
 
 // Re-export command functions (needed for generate_handler! macro)
+pub use accessibility::{get_accessibility_tree, AccessibilityNode, AccessibilityTree};
 pub use backend_state::get_backend_state;
+pub use bounding_rects::{get_bounding_rects, BoundingRect, BoundingRectsResult};
+pub use browsing_data::{
+    clear_browsing_data, ClearBrowsingDataOptions, ClearBrowsingDataResult, ClearOutcome,
+};
+pub use capture_text::{capture_text, CaptureTextOptions, TextBlock, TextCapture};
+pub use color_scheme::{get_color_scheme, set_color_scheme, ColorScheme, ColorSchemeState};
+pub use computed_style::{get_computed_style, ComputedStyle, DEFAULT_STYLE_PROPERTIES};
+pub use console_logs::{get_console_logs, record_console_entries, ConsoleEntry};
+pub use cookies::{get_cookies, parse_cookie_spec, set_cookie, CookieInfo};
+pub use dom_capture::capture_dom_screenshot;
+pub use drag::{drag_element, DragPoint, DragResult, DropTarget, DEFAULT_DRAG_STEPS};
 pub use emit_event::emit_event;
+pub use emulation::{
+    emulate, reset_emulation, DevicePreset, EmulationOptions, EmulationResult, EmulationState,
+};
+pub use evaluate::{
+    evaluate_in_frame, evaluate_script, EvaluationError, FrameEvaluation, FrameInfo, FrameSelector,
+};
 pub use execute_command::execute_command;
 pub use execute_js::execute_js;
+pub use file_input::{set_input_files, AttachedFile, MAX_INPUT_FILES_BYTES};
+pub use focus::{blur_active, focus_element, FocusResult};
+pub use highlight::{
+    clear_highlights, highlight, highlight_element, HighlightMatches, HighlightOptions,
+    HighlightResult, DEFAULT_HIGHLIGHT_DURATION_MS,
+};
+pub use hover::{hover_element, HoverResult};
+pub use inject_once::inject_once;
+pub use interactive_elements::{
+    collect_interactive_elements, get_interactive_elements, remove_element_overlays, ElementRect,
+    InteractiveElement, MarkedElement,
+};
 pub use ipc_monitor::{get_ipc_events, start_ipc_monitor, stop_ipc_monitor};
+pub use keyboard::{press_key, KeyModifiers, KeyPressResult};
 pub use list_windows::{
-    list_windows, resolve_window, resolve_window_with_context, ResolvedWindow, WindowContext,
-    WindowInfo,
+    is_window_available, list_windows, resolve_window, resolve_window_with_context, ResolvedWindow,
+    WindowContext, WindowInfo,
+};
+pub use manage_window::{close_window, focus_window, minimize_window, WindowError, WindowState};
+pub use network_idle::{wait_for_network_idle, NetworkIdleResult};
+pub use network_log::{get_network_log, record_network_entry, start_network_log, stop_network_log};
+pub use performance::{get_performance_timing, PerformanceTiming};
+pub use reload::{reload, ReloadResult, WaitUntil};
+pub use resize_window::{
+    apply_window_size, resize_window, ResizeWindowParams, ResizeWindowResult, WindowSize,
+};
+pub use screenshot::{
+    capture_all_windows, capture_at_width, capture_native_screenshot, capture_screen,
+    capture_with_thumbnail, take_screen_screenshot, take_viewport_screenshot, ScreenshotResponse,
+    ThumbnailResponse, WidthCaptureResponse, WindowCapture,
+};
+pub use screenshot_stream::{
+    ack_capture_frame, start_capture_stream, start_screenshot_stream, stop_capture_stream,
+    stop_screenshot_stream, CaptureStreamFrame, ScreenshotFrame, ScreenshotStreams,
+    CAPTURE_FRAME_EVENT,
 };
-pub use resize_window::{resize_window, ResizeWindowParams, ResizeWindowResult};
-pub use screenshot::capture_native_screenshot;
 pub use script_executor::script_result;
-pub use script_injection::request_script_injection;
+pub use script_injection::{
+    inject_scripts, request_script_injection, InjectScriptsResult, InjectionError,
+    InjectionMetrics, InjectionResult, InjectionStrategy, NavigationWatchdog, ReapplyGuard,
+    ScriptCost, ScriptInjectionMetric, ScriptInjectionOutcome, ScriptInjectionStatus,
+};
+pub use script_manifest::{load_script_manifest, RejectedScript, ScriptManifestResult};
+pub use select_option::{select_option, OptionMatch, SelectOptionResult};
+pub use storage::{storage_get, storage_set, StorageArea, StorageError};
+pub use user_agent::{
+    get_user_agent, set_user_agent, SetUserAgentResult, UserAgentInfo,
+    USER_AGENT_OVERRIDE_SUPPORTED,
+};
+pub use viewport::get_viewport_metrics;
+pub use visual_diff::{visual_diff, SizeMismatch, VisualDiffOptions, VisualDiffResult};
+pub use wait_for_condition::{
+    wait_for_condition, ConditionResult, ConditionWait, DEFAULT_POLL_INTERVAL,
+};
 pub use window_info::get_window_info;
+pub use zoom::{set_zoom, ZoomMethod, ZoomResult};
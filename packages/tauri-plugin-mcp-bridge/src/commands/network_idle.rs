@@ -0,0 +1,130 @@
+//! Waiting for fetch/XHR activity to settle before inspecting a page.
+//!
+//! While the network log is running, the wait reads the in-flight count kept
+//! by its hooks, which see every request since the page loaded. Otherwise it
+//! installs wrappers around `fetch` and `XMLHttpRequest.prototype.send` that
+//! count in-flight requests, and removes them once no wait is pending;
+//! requests started before the wrappers were installed are not counted.
+//! Tauri IPC calls are never counted.
+
+use std::time::Duration;
+
+use serde::{Deserialize, Serialize};
+use tauri::{Runtime, WebviewWindow};
+
+use super::execute_js::run_script_with_timeout;
+
+/// Extra time given to the script on top of the wait timeout, so the page's
+/// own timeout fires first and reports the in-flight count.
+const SCRIPT_TIMEOUT_MARGIN: Duration = Duration::from_secs(2);
+
+/// Outcome of [`wait_for_network_idle`].
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "ts-bindings", derive(ts_rs::TS))]
+#[serde(rename_all = "camelCase")]
+pub struct NetworkIdleResult {
+    /// Whether the network stayed idle for the requested time
+    pub idle: bool,
+    /// How long the wait took, in milliseconds
+    #[cfg_attr(feature = "ts-bindings", ts(type = "number"))]
+    pub elapsed_ms: u64,
+    /// Requests still in flight when the wait ended
+    pub pending_requests: u32,
+}
+
+fn wait_script(idle_ms: u128, timeout_ms: u128) -> String {
+    format!(
+        r#"return await (async () => {{
+    const idleMs = {idle_ms};
+    const timeoutMs = {timeout_ms};
+
+    const log = window.__MCP_NETWORK_LOG__;
+    const net = log || window.__MCP_NETWORK_IDLE__ || (() => {{
+        const state = {{ inflight: 0, waiters: 0, lastActivity: Date.now() }};
+        const settle = () => {{
+            state.inflight = Math.max(0, state.inflight - 1);
+            state.lastActivity = Date.now();
+        }};
+
+        const isIpc = (input) => {{
+            const url = String(input instanceof Request ? input.url : input);
+            return /^ipc:/.test(url) || /^https?:\/\/ipc\.localhost\//.test(url);
+        }};
+
+        const originalFetch = window.fetch;
+        const wrappedFetch = function(input) {{
+            if (isIpc(input)) return originalFetch.apply(this, arguments);
+            state.inflight++;
+            state.lastActivity = Date.now();
+            return originalFetch.apply(this, arguments).finally(settle);
+        }};
+
+        const originalSend = XMLHttpRequest.prototype.send;
+        const wrappedSend = function() {{
+            state.inflight++;
+            state.lastActivity = Date.now();
+            this.addEventListener('loadend', settle, {{ once: true }});
+            return originalSend.apply(this, arguments);
+        }};
+
+        window.fetch = wrappedFetch;
+        XMLHttpRequest.prototype.send = wrappedSend;
+
+        // Only restore what we replaced, in case the page wrapped them again
+        state.restore = () => {{
+            if (window.fetch === wrappedFetch) window.fetch = originalFetch;
+            if (XMLHttpRequest.prototype.send === wrappedSend) XMLHttpRequest.prototype.send = originalSend;
+            delete window.__MCP_NETWORK_IDLE__;
+        }};
+
+        window.__MCP_NETWORK_IDLE__ = state;
+        return state;
+    }})();
+
+    if (!log) net.waiters++;
+    const start = Date.now();
+    try {{
+        return await new Promise((resolve) => {{
+            const check = () => {{
+                const now = Date.now();
+                const done = (idle) => resolve({{
+                    idle,
+                    elapsedMs: now - start,
+                    pendingRequests: net.inflight
+                }});
+                if (net.inflight === 0 && now - net.lastActivity >= idleMs) return done(true);
+                if (now - start >= timeoutMs) return done(false);
+                setTimeout(check, Math.min(50, Math.max(idleMs, 10)));
+            }};
+            check();
+        }});
+    }} finally {{
+        if (!log && --net.waiters === 0) net.restore();
+    }}
+}})();"#
+    )
+}
+
+/// Waits until no fetch/XHR request has been in flight for `idle`.
+///
+/// # Arguments
+///
+/// * `window` - The webview window to watch
+/// * `idle` - How long the network must stay quiet
+/// * `timeout` - Maximum time to wait
+///
+/// # Returns
+///
+/// * `Ok(NetworkIdleResult)` - `idle: true` if the network settled, or
+///   `idle: false` with the in-flight count if the timeout fired first
+/// * `Err(String)` - Error message if the script could not be evaluated
+pub async fn wait_for_network_idle<R: Runtime>(
+    window: &WebviewWindow<R>,
+    idle: Duration,
+    timeout: Duration,
+) -> Result<NetworkIdleResult, String> {
+    let script = wait_script(idle.as_millis(), timeout.as_millis());
+    let result = run_script_with_timeout(window, &script, timeout + SCRIPT_TIMEOUT_MARGIN).await?;
+
+    serde_json::from_value(result).map_err(|e| format!("Unexpected network idle result: {e}"))
+}
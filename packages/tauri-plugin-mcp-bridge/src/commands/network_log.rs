@@ -0,0 +1,130 @@
+//! Commands for recording and reading the page network log.
+//!
+//! `start_network_log` registers the hook script as a window-scoped bridge
+//! script, so it is re-injected after navigations. Requests made before the
+//! bridge re-injects scripts on a new page are not recorded.
+
+use tauri::{command, Runtime, State, WebviewWindow};
+
+use crate::monitor::current_timestamp;
+use crate::network_log::{NetworkEntry, NetworkLogFilter, NetworkLogState};
+use crate::script_registry::{ScriptEntry, ScriptScope, ScriptType, SharedScriptRegistry};
+
+/// Registry ID prefix of the hook script; the window label is appended.
+const NETWORK_LOG_SCRIPT_ID: &str = "__mcp_network_log__";
+
+fn script_id(window_label: &str) -> String {
+    format!("{NETWORK_LOG_SCRIPT_ID}:{window_label}")
+}
+
+/// Builds the hook script with its options substituted.
+fn network_log_script(capture_bodies: bool, max_body_bytes: usize) -> String {
+    let options = serde_json::json!({
+        "captureBodies": capture_bodies,
+        "maxBodyBytes": max_body_bytes,
+    });
+    include_str!("../network_log.js").replace("__MCP_NETWORK_LOG_OPTIONS__", &options.to_string())
+}
+
+/// Starts recording fetch/XHR requests made by a window.
+///
+/// # Arguments
+///
+/// * `window` - The webview window to record
+/// * `registry` - Script registry holding the hook script across navigations
+/// * `log` - Network log whose body size limit applies
+/// * `capture_bodies` - Whether to record text request and response bodies
+///
+/// # Returns
+///
+/// * `Ok(())` - Hooks are installed in the current page
+/// * `Err(String)` - The script could not be registered or evaluated
+pub fn start_network_log<R: Runtime>(
+    window: &WebviewWindow<R>,
+    registry: &SharedScriptRegistry,
+    log: &NetworkLogState,
+    capture_bodies: bool,
+) -> Result<(), String> {
+    let max_body_bytes = log.lock().unwrap().max_body_bytes();
+    let script = network_log_script(capture_bodies, max_body_bytes);
+    let label = window.label().to_string();
+    let id = script_id(&label);
+
+    {
+        let mut registry = registry.lock().unwrap();
+        registry
+            .add(
+                ScriptScope::Window(label.clone()),
+                ScriptEntry::new(&id, ScriptType::Inline, &script),
+            )
+            .map_err(|e| e.to_string())?;
+        registry.mark_injected(&label, [id.as_str()], current_timestamp());
+    }
+
+    window
+        .eval(&script)
+        .map_err(|e| format!("Failed to install network hooks: {e}"))
+}
+
+/// Stops recording requests for a window and restores `fetch`/XHR.
+///
+/// Entries already recorded stay available until cleared.
+pub fn stop_network_log<R: Runtime>(
+    window: &WebviewWindow<R>,
+    registry: &SharedScriptRegistry,
+) -> Result<bool, String> {
+    let id = script_id(window.label());
+    let removed = registry.lock().unwrap().remove(&id).is_some();
+
+    window
+        .eval(format!(
+            "window.__MCP_NETWORK_LOG__ && window.__MCP_NETWORK_LOG__.stop();\
+             window.__MCP_REMOVE_SCRIPT__ && window.__MCP_REMOVE_SCRIPT__({});",
+            serde_json::Value::String(id)
+        ))
+        .map_err(|e| format!("Failed to remove network hooks: {e}"))?;
+
+    Ok(removed)
+}
+
+/// Returns recorded requests for a window, oldest first.
+///
+/// # Arguments
+///
+/// * `log` - Network log state
+/// * `window_label` - Window whose entries are returned
+/// * `filter` - Criteria entries must match
+/// * `limit` - Return at most this many of the most recent matches
+pub fn get_network_log(
+    log: &NetworkLogState,
+    window_label: &str,
+    filter: &NetworkLogFilter,
+    limit: Option<usize>,
+) -> Vec<NetworkEntry> {
+    log.lock().unwrap().entries(window_label, filter, limit)
+}
+
+/// Command called by the hook script for each finished request.
+#[command]
+pub async fn record_network_entry<R: Runtime>(
+    window: WebviewWindow<R>,
+    log: State<'_, NetworkLogState>,
+    entry: NetworkEntry,
+) -> Result<(), String> {
+    log.lock()
+        .map_err(|e| format!("Failed to lock network log: {e}"))?
+        .record(window.label(), entry);
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_network_log_script_substitutes_options() {
+        let script = network_log_script(true, 1024);
+        assert!(!script.contains("__MCP_NETWORK_LOG_OPTIONS__"));
+        assert!(script.contains(r#"{"captureBodies":true,"maxBodyBytes":1024}"#));
+    }
+}
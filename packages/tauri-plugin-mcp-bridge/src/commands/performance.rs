@@ -0,0 +1,112 @@
+//! Page load timings from the Navigation Timing and Paint Timing APIs.
+
+use serde::{Deserialize, Serialize};
+use tauri::{Runtime, WebviewWindow};
+
+use super::execute_js::run_script;
+
+/// Load timings of the current document, in milliseconds.
+///
+/// Durations cover one phase; the other fields are measured from the start
+/// of the navigation. A field is `None` when the webview doesn't report it
+/// or the page hasn't reached that point yet.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[cfg_attr(feature = "ts-bindings", derive(ts_rs::TS))]
+#[serde(rename_all = "camelCase", default)]
+pub struct PerformanceTiming {
+    /// Duration of the DNS lookup; zero when cached or for local pages
+    pub dns_ms: Option<f64>,
+    /// Duration of the TCP and TLS connection setup
+    pub connect_ms: Option<f64>,
+    /// Time to the first byte of the response
+    pub ttfb_ms: Option<f64>,
+    /// Time until `DOMContentLoaded` handlers finished
+    pub dom_content_loaded_ms: Option<f64>,
+    /// Time until `load` handlers finished
+    pub load_ms: Option<f64>,
+    /// Time to the first paint
+    pub first_paint_ms: Option<f64>,
+    /// Time to the first paint with text, images or canvas content
+    pub first_contentful_paint_ms: Option<f64>,
+    /// Which API the navigation timings came from: `navigation` for
+    /// Navigation Timing Level 2, `timing` for the deprecated
+    /// `performance.timing`, or `None` if neither is available
+    pub source: Option<String>,
+}
+
+/// Prefers the Level 2 navigation entry, whose times are relative to the
+/// navigation start, and falls back to `performance.timing`, whose times
+/// are epoch milliseconds. Event end times are zero until the event ran.
+const TIMING_SCRIPT: &str = r#"return (() => {
+    const perf = window.performance;
+    const result = {};
+    if (!perf) return result;
+
+    const round = (ms) => Math.round(ms * 10) / 10;
+    const [entry] = perf.getEntriesByType ? perf.getEntriesByType('navigation') : [];
+    const legacy = perf.timing;
+    const t = entry || legacy;
+    if (t) {
+        const start = entry ? 0 : legacy.navigationStart;
+        const span = (from, to) =>
+            typeof t[from] === 'number' && typeof t[to] === 'number' && t[to] >= t[from]
+                ? round(t[to] - t[from])
+                : null;
+        const since = (name) => (t[name] > 0 ? round(t[name] - start) : null);
+
+        result.source = entry ? 'navigation' : 'timing';
+        result.dnsMs = span('domainLookupStart', 'domainLookupEnd');
+        result.connectMs = span('connectStart', 'connectEnd');
+        result.ttfbMs = since('responseStart');
+        result.domContentLoadedMs = since('domContentLoadedEventEnd');
+        result.loadMs = since('loadEventEnd');
+    }
+
+    const paints = perf.getEntriesByType ? perf.getEntriesByType('paint') : [];
+    for (const paint of paints) {
+        if (paint.name === 'first-paint') result.firstPaintMs = round(paint.startTime);
+        if (paint.name === 'first-contentful-paint') result.firstContentfulPaintMs = round(paint.startTime);
+    }
+    return result;
+})();"#;
+
+/// Returns the load timings of the window's current document.
+///
+/// # Arguments
+///
+/// * `window` - The webview window to measure
+///
+/// # Returns
+///
+/// * `Ok(PerformanceTiming)` - The timings, with `None` for any the webview
+///   doesn't provide
+/// * `Err(String)` - Error message if the script could not be evaluated
+pub async fn get_performance_timing<R: Runtime>(
+    window: &WebviewWindow<R>,
+) -> Result<PerformanceTiming, String> {
+    let result = run_script(window, TIMING_SCRIPT).await?;
+    serde_json::from_value(result).map_err(|e| format!("Unexpected timing data: {e}"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_missing_timings_are_none() {
+        let timing: PerformanceTiming = serde_json::from_value(serde_json::json!({
+            "source": "navigation",
+            "dnsMs": 0,
+            "ttfbMs": 12.5,
+            "loadMs": null
+        }))
+        .unwrap();
+        assert_eq!(timing.dns_ms, Some(0.0));
+        assert_eq!(timing.ttfb_ms, Some(12.5));
+        assert_eq!(timing.load_ms, None);
+        assert_eq!(timing.first_contentful_paint_ms, None);
+
+        let empty: PerformanceTiming = serde_json::from_value(serde_json::json!({})).unwrap();
+        assert!(empty.source.is_none());
+    }
+}
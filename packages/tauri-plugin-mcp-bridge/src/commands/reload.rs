@@ -0,0 +1,186 @@
+//! Reloading the page and waiting for the new document to be ready, for
+//! isolating test scenarios.
+//!
+//! The page is marked before `location.reload()` runs, and the new document
+//! is probed through the script result channel until the mark is gone and
+//! the requested ready state is reached. Probes that land while the old
+//! document is unloading lose their result and are retried.
+//!
+//! Webviews have no portable way to bypass the HTTP cache on reload:
+//! `location.reload(true)` is ignored outside Firefox, and only WebKit has
+//! a native cache-bypassing reload. A hard reload therefore re-fetches the
+//! page and the resources it loaded with `cache: 'reload'` first, which
+//! refreshes the HTTP cache before reloading. Resources served by a service
+//! worker or from the Cache Storage API are not refreshed; clear them with
+//! [`clear_browsing_data`](super::clear_browsing_data).
+
+use std::time::{Duration, Instant};
+
+use serde::{Deserialize, Serialize};
+use tauri::{Runtime, WebviewWindow};
+
+use super::execute_js::run_script_with_timeout;
+
+/// How long a single readiness probe may take before it is retried.
+const PROBE_TIMEOUT: Duration = Duration::from_secs(1);
+
+/// Pause between probes that found the old document still loaded.
+const PROBE_INTERVAL: Duration = Duration::from_millis(50);
+
+/// Most resources re-fetched by a hard reload.
+const MAX_REFRESHED_RESOURCES: usize = 200;
+
+/// Document readiness [`reload`] waits for.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "ts-bindings", derive(ts_rs::TS))]
+#[serde(rename_all = "camelCase")]
+pub enum WaitUntil {
+    /// The document is parsed (`DOMContentLoaded`)
+    DomContentLoaded,
+    /// The document and its subresources have loaded (`load`)
+    #[default]
+    Load,
+}
+
+/// Outcome of [`reload`].
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "ts-bindings", derive(ts_rs::TS))]
+#[serde(rename_all = "camelCase")]
+pub struct ReloadResult {
+    /// URL of the reloaded document, which differs from the original one
+    /// if the server redirected
+    pub url: String,
+    /// Whether cached resources were refreshed before reloading
+    pub hard: bool,
+    /// How many cached resources a hard reload refreshed
+    pub refreshed_resources: u32,
+    /// How long the reload took, in milliseconds
+    #[cfg_attr(feature = "ts-bindings", ts(type = "number"))]
+    pub elapsed_ms: u64,
+}
+
+fn trigger_script(token: &str, hard: bool) -> String {
+    format!(
+        r#"return await (async () => {{
+    const hard = {hard};
+    let refreshed = 0;
+    if (hard) {{
+        const isIpc = (url) => /^ipc:/.test(url) || /^https?:\/\/ipc\.localhost\//.test(url);
+        const urls = [location.href, ...performance.getEntriesByType('resource').map((e) => e.name)]
+            .filter((url, i, all) => /^https?:/.test(url) && !isIpc(url) && all.indexOf(url) === i)
+            .slice(0, {MAX_REFRESHED_RESOURCES});
+        const results = await Promise.allSettled(urls.map((url) => fetch(url, {{
+            cache: 'reload',
+            credentials: 'include',
+            mode: new URL(url).origin === location.origin ? 'same-origin' : 'no-cors'
+        }})));
+        refreshed = results.filter((r) => r.status === 'fulfilled').length;
+    }}
+
+    window.__MCP_RELOAD_PENDING__ = {token};
+    // Reload once this result is on its way
+    setTimeout(() => location.reload(), 50);
+    return {{ refreshed }};
+}})();"#,
+        token = serde_json::Value::String(token.to_string()),
+    )
+}
+
+fn probe_script(token: &str, wait_until: WaitUntil) -> String {
+    let (event, ready) = match wait_until {
+        WaitUntil::DomContentLoaded => ("DOMContentLoaded", "document.readyState !== 'loading'"),
+        WaitUntil::Load => ("load", "document.readyState === 'complete'"),
+    };
+    format!(
+        r#"return await (async () => {{
+    if (window.__MCP_RELOAD_PENDING__ === {token}) return {{ reloaded: false }};
+    if (!({ready})) {{
+        await new Promise((resolve) => window.addEventListener('{event}', resolve, {{ once: true }}));
+    }}
+    return {{ reloaded: true, url: location.href }};
+}})();"#,
+        token = serde_json::Value::String(token.to_string()),
+    )
+}
+
+/// Reloads the page and waits until the new document reaches `wait_until`.
+///
+/// # Arguments
+///
+/// * `window` - The webview window to reload
+/// * `hard` - Refresh the page's cached resources before reloading; see the
+///   module docs for what this covers
+/// * `wait_until` - Readiness of the new document to wait for
+/// * `timeout` - Maximum time for the whole reload
+///
+/// # Returns
+///
+/// * `Ok(ReloadResult)` - The reloaded document's URL
+/// * `Err(String)` - Error message if the reload could not be started, or
+///   the new document wasn't ready within the timeout
+pub async fn reload<R: Runtime>(
+    window: &WebviewWindow<R>,
+    hard: bool,
+    wait_until: WaitUntil,
+    timeout: Duration,
+) -> Result<ReloadResult, String> {
+    let start = Instant::now();
+    let token = uuid::Uuid::new_v4().to_string();
+
+    let triggered = run_script_with_timeout(window, &trigger_script(&token, hard), timeout)
+        .await
+        .map_err(|e| format!("Failed to start reload: {e}"))?;
+    let refreshed_resources = triggered
+        .get("refreshed")
+        .and_then(|v| v.as_u64())
+        .unwrap_or(0) as u32;
+
+    let probe = probe_script(&token, wait_until);
+    loop {
+        let remaining = timeout.saturating_sub(start.elapsed());
+        if remaining.is_zero() {
+            return Err(format!(
+                "Page was not ready after {}ms",
+                timeout.as_millis()
+            ));
+        }
+
+        // Errors are expected while the old document unloads
+        if let Ok(state) =
+            run_script_with_timeout(window, &probe, remaining.min(PROBE_TIMEOUT)).await
+        {
+            if state.get("reloaded").and_then(|v| v.as_bool()) == Some(true) {
+                return Ok(ReloadResult {
+                    url: state
+                        .get("url")
+                        .and_then(|v| v.as_str())
+                        .unwrap_or_default()
+                        .to_string(),
+                    hard,
+                    refreshed_resources,
+                    elapsed_ms: start.elapsed().as_millis() as u64,
+                });
+            }
+        }
+        tokio::time::sleep(PROBE_INTERVAL).await;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_scripts_embed_token_and_ready_state() {
+        let script = trigger_script("abc", false);
+        assert!(script.contains(r#"window.__MCP_RELOAD_PENDING__ = "abc";"#));
+        assert!(script.contains("const hard = false;"));
+
+        let probe = probe_script("abc", WaitUntil::DomContentLoaded);
+        assert!(probe.contains("'DOMContentLoaded'"));
+        assert!(probe.contains(r#"=== "abc""#));
+
+        let wait_until: WaitUntil = serde_json::from_str(r#""domContentLoaded""#).unwrap();
+        assert_eq!(wait_until, WaitUntil::DomContentLoaded);
+    }
+}
@@ -1,12 +1,13 @@
 //! Window resize functionality.
 
 use serde::{Deserialize, Serialize};
-use tauri::{AppHandle, LogicalSize, PhysicalSize, Runtime};
+use tauri::{AppHandle, LogicalSize, PhysicalSize, Runtime, WebviewWindow};
 
 use super::list_windows::resolve_window;
 
 /// Parameters for resizing a window.
 #[derive(Debug, Deserialize)]
+#[cfg_attr(feature = "ts-bindings", derive(ts_rs::TS))]
 #[serde(rename_all = "camelCase")]
 pub struct ResizeWindowParams {
     /// Width in pixels
@@ -24,8 +25,19 @@ fn default_logical() -> bool {
     true
 }
 
+/// Inner size of a window.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "ts-bindings", derive(ts_rs::TS))]
+pub struct WindowSize {
+    /// Width in pixels
+    pub width: u32,
+    /// Height in pixels
+    pub height: u32,
+}
+
 /// Result of a window resize operation.
 #[derive(Debug, Serialize)]
+#[cfg_attr(feature = "ts-bindings", derive(ts_rs::TS))]
 #[serde(rename_all = "camelCase")]
 pub struct ResizeWindowResult {
     /// Whether the resize was successful
@@ -38,6 +50,8 @@ pub struct ResizeWindowResult {
     pub height: u32,
     /// Whether logical pixels were used
     pub logical: bool,
+    /// The inner size actually applied, which the OS may have clamped
+    pub applied_size: Option<WindowSize>,
     /// Error message if resize failed
     pub error: Option<String>,
 }
@@ -78,24 +92,20 @@ pub async fn resize_window<R: Runtime>(
             width: params.width,
             height: params.height,
             logical: params.logical,
+            applied_size: None,
             error: Some("Window is not resizable".to_string()),
         });
     }
 
     // Perform the resize
-    let result = if params.logical {
-        window.set_size(LogicalSize::new(params.width, params.height))
-    } else {
-        window.set_size(PhysicalSize::new(params.width, params.height))
-    };
-
-    match result {
-        Ok(()) => Ok(ResizeWindowResult {
+    match apply_window_size(&window, params.width, params.height, params.logical) {
+        Ok(applied) => Ok(ResizeWindowResult {
             success: true,
             window_label,
             width: params.width,
             height: params.height,
             logical: params.logical,
+            applied_size: Some(applied),
             error: None,
         }),
         Err(e) => Ok(ResizeWindowResult {
@@ -104,7 +114,46 @@ pub async fn resize_window<R: Runtime>(
             width: params.width,
             height: params.height,
             logical: params.logical,
-            error: Some(format!("Failed to resize window: {e}")),
+            applied_size: None,
+            error: Some(e),
         }),
     }
 }
+
+/// Sets a window's inner size and returns the size it actually took.
+///
+/// The returned size uses the same units as the request (logical or
+/// physical pixels).
+pub fn apply_window_size<R: Runtime>(
+    window: &WebviewWindow<R>,
+    width: u32,
+    height: u32,
+    logical: bool,
+) -> Result<WindowSize, String> {
+    let result = if logical {
+        window.set_size(LogicalSize::new(width, height))
+    } else {
+        window.set_size(PhysicalSize::new(width, height))
+    };
+    result.map_err(|e| format!("Failed to resize window: {e}"))?;
+
+    let size = window
+        .inner_size()
+        .map_err(|e| format!("Failed to read window size: {e}"))?;
+    if !logical {
+        return Ok(WindowSize {
+            width: size.width,
+            height: size.height,
+        });
+    }
+
+    let scale_factor = window
+        .scale_factor()
+        .map_err(|e| format!("Failed to read scale factor: {e}"))?;
+    let size = size.to_logical::<u32>(scale_factor);
+
+    Ok(WindowSize {
+        width: size.width,
+        height: size.height,
+    })
+}
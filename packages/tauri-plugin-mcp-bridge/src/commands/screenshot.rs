@@ -1,6 +1,683 @@
 //! Native screenshot capture.
 
-use tauri::{command, Runtime, WebviewWindow};
+use std::time::Duration;
+
+use serde::Serialize;
+use tauri::{command, AppHandle, Manager, Runtime, WebviewWindow};
+
+use super::dom_capture::capture_dom_screenshot;
+use super::execute_js::{run_script, run_script_with_timeout};
+use super::highlight::{hide_highlights, show_highlights};
+use super::interactive_elements::{
+    collect_interactive_elements, remove_element_overlays, InteractiveElement,
+};
+use super::resize_window::{apply_window_size, WindowSize};
+use crate::config::Config;
+use crate::error::CommandError;
+use crate::logging::mcp_log_error;
+use crate::screenshot::cache::CaptureCache;
+use crate::screenshot::{
+    self, BudgetFit, CaptureBounds, CaptureOptions, CaptureSource, ContentEncoding, ImageFormat,
+    Screenshot, ScreenshotError, ScreenshotOptions, ThumbnailCapture, DEFAULT_CAPTURE_TIMEOUT,
+};
+
+/// Screenshot encoded for transport back to the MCP server.
+#[derive(Debug, Serialize)]
+#[cfg_attr(feature = "ts-bindings", derive(ts_rs::TS))]
+#[serde(rename_all = "camelCase")]
+pub struct ScreenshotResponse {
+    /// Base64-encoded image data URL
+    pub data: String,
+    /// Set when `data` holds compressed bytes the client must decode first
+    pub content_encoding: Option<ContentEncoding>,
+    /// Format of the encoded image, resolved when `"auto"` was requested
+    pub format: ImageFormat,
+    /// Whether the image came from native capture or the DOM fallback
+    pub source: CaptureSource,
+    /// Screen area the capture covers, so callers can tell whether the
+    /// window chrome was included
+    pub bounds: Option<CaptureBounds>,
+    /// Logical viewport size used for the capture, when a viewport was requested
+    pub viewport: Option<WindowSize>,
+    /// Visible interactive elements, when requested with `include_elements`
+    /// or `annotate_elements`
+    pub elements: Option<Vec<InteractiveElement>>,
+    /// Whether the image was reused from the capture cache
+    pub cache_hit: bool,
+    /// Whether the awaited fonts loaded before the capture; `None` when
+    /// `wait_for_fonts` wasn't set
+    pub fonts_ready: Option<bool>,
+    /// How the image was shrunk to fit `max_bytes`; `None` when it already
+    /// fit or no limit was set
+    pub budget_fit: Option<BudgetFit>,
+    /// What the capture leaves out, when it covers less than was asked for
+    pub warning: Option<String>,
+}
+
+/// Reported with Android full screen captures, which can only draw the app.
+const ANDROID_SCREEN_WARNING: &str = "Android captures only this app's window: the status bar, \
+     navigation bar and other apps are not included";
+
+impl ScreenshotResponse {
+    fn from_capture(result: Result<Screenshot, ScreenshotError>) -> Result<Self, CommandError> {
+        let screenshot = result?;
+
+        Ok(Self {
+            data: screenshot.to_data_url(screenshot.mime_type()),
+            content_encoding: screenshot.content_encoding,
+            format: screenshot.format,
+            source: screenshot.source,
+            bounds: screenshot.bounds,
+            viewport: None,
+            elements: None,
+            cache_hit: false,
+            fonts_ready: None,
+            budget_fit: screenshot.budget_fit,
+            warning: None,
+        })
+    }
+}
+
+/// Resolves after two animation frames, so DOM changes made before the call
+/// have been painted. Falls back to a short timer because hidden webviews
+/// never fire `requestAnimationFrame`.
+const WAIT_FOR_PAINT_SCRIPT: &str = r#"return await new Promise((resolve) => {
+    const fallback = setTimeout(() => resolve(false), 500);
+    requestAnimationFrame(() => requestAnimationFrame(() => {
+        clearTimeout(fallback);
+        resolve(true);
+    }));
+});"#;
+
+/// Reads the page's DOM version, maintained by bridge.js.
+const DOM_VERSION_SCRIPT: &str =
+    "return window.__MCP_DOM_VERSION__ ? window.__MCP_DOM_VERSION__() : null";
+
+/// ID of the stylesheet added by [`hide_elements_script`].
+const HIDE_STYLE_ID: &str = "__mcp_capture_hide__";
+
+/// Adds a stylesheet hiding scrollbars and the elements matching
+/// `selectors`, one rule per selector so a selector the page can't parse
+/// doesn't void the others. Returns the selectors that were skipped.
+fn hide_elements_script(hide_scrollbars: bool, selectors: &[String]) -> String {
+    format!(
+        r#"return (() => {{
+    const previous = document.getElementById({id});
+    if (previous) previous.remove();
+
+    const style = document.createElement('style');
+    style.id = {id};
+    (document.head || document.documentElement).appendChild(style);
+
+    const rules = {hide_scrollbars}
+        ? ['* {{ scrollbar-width: none !important; }}', '*::-webkit-scrollbar {{ display: none !important; }}']
+        : [];
+    for (const rule of rules) {{
+        try {{
+            style.sheet.insertRule(rule, style.sheet.cssRules.length);
+        }} catch (e) {{
+            // Engines without the pseudo-element reject the rule
+        }}
+    }}
+
+    const skipped = [];
+    for (const selector of {selectors}) {{
+        try {{
+            style.sheet.insertRule(selector + ' {{ visibility: hidden !important; }}', style.sheet.cssRules.length);
+        }} catch (e) {{
+            skipped.push(selector);
+        }}
+    }}
+    return skipped;
+}})();"#,
+        id = serde_json::Value::String(HIDE_STYLE_ID.to_string()),
+        selectors = serde_json::Value::from(selectors),
+    )
+}
+
+/// Extra time given to the font script on top of the font timeout, so the
+/// page's own timeout fires first.
+const FONT_SCRIPT_TIMEOUT_MARGIN: Duration = Duration::from_secs(2);
+
+/// Resolves `true` once the fonts have loaded, or `false` when the timeout
+/// fires first or a listed family has no matching `@font-face`.
+fn fonts_script(families: &[String], timeout: Duration) -> String {
+    format!(
+        r#"return await (async () => {{
+    const families = {families};
+    const timeoutMs = {timeout_ms};
+    if (!document.fonts) {{
+        return true;
+    }}
+
+    const loaded = families.length
+        ? Promise.all(families.map((family) => document.fonts.load('16px "' + family.replace(/["\\]/g, '\\$&') + '"')))
+            .then((faces) => faces.every((matched) => matched.length > 0))
+        : document.fonts.ready.then(() => true);
+    const timeout = new Promise((resolve) => setTimeout(() => resolve(false), timeoutMs));
+    return await Promise.race([loaded.catch(() => false), timeout]);
+}})();"#,
+        families = serde_json::json!(families),
+        timeout_ms = timeout.as_millis(),
+    )
+}
+
+/// Waits for web fonts when `wait_for_fonts` is set, returning whether they
+/// loaded in time.
+///
+/// Like [`wait_for_paint`], a failed wait doesn't fail the capture; it is
+/// reported as fonts not being ready.
+async fn wait_for_fonts<R: Runtime>(
+    window: &WebviewWindow<R>,
+    options: &ScreenshotOptions,
+) -> Option<bool> {
+    if !options.wait_for_fonts {
+        return None;
+    }
+
+    let script = fonts_script(&options.font_families, options.font_timeout);
+    let timeout = options.font_timeout + FONT_SCRIPT_TIMEOUT_MARGIN;
+    match run_script_with_timeout(window, &script, timeout).await {
+        Ok(ready) => Some(ready.as_bool().unwrap_or(false)),
+        Err(e) => {
+            mcp_log_error("SCREENSHOT", &format!("Failed to wait for fonts: {e}"));
+            Some(false)
+        }
+    }
+}
+
+/// Waits for the next paint when `wait_for_paint` is set.
+///
+/// A failed wait is logged rather than returned, since a capture with
+/// possibly stale pixels is more useful than no capture at all.
+async fn wait_for_paint<R: Runtime>(window: &WebviewWindow<R>, options: &ScreenshotOptions) {
+    if !options.wait_for_paint {
+        return;
+    }
+
+    if let Err(e) = run_script(window, WAIT_FOR_PAINT_SCRIPT).await {
+        mcp_log_error("SCREENSHOT", &format!("Failed to wait for paint: {e}"));
+    }
+}
+
+/// Hides scrollbars and `hide_selectors` for the capture when asked,
+/// returning whether there is a stylesheet to remove afterwards.
+///
+/// Like highlights, elements that can't be hidden are logged rather than
+/// failing the capture.
+async fn hide_page_elements<R: Runtime>(
+    window: &WebviewWindow<R>,
+    options: &ScreenshotOptions,
+) -> bool {
+    let capture = &options.capture;
+    if !capture.hide_scrollbars && capture.hide_selectors.is_empty() {
+        return false;
+    }
+
+    let script = hide_elements_script(capture.hide_scrollbars, &capture.hide_selectors);
+    match run_script(window, &script).await {
+        Ok(skipped) => {
+            if skipped
+                .as_array()
+                .is_some_and(|skipped| !skipped.is_empty())
+            {
+                mcp_log_error(
+                    "SCREENSHOT",
+                    &format!("Skipped invalid selectors when hiding elements: {skipped}"),
+                );
+            }
+        }
+        Err(e) => mcp_log_error("SCREENSHOT", &format!("Failed to hide elements: {e}")),
+    }
+    // The stylesheet may have been added even if the script failed later
+    true
+}
+
+/// Removes the stylesheet added by [`hide_page_elements`].
+async fn restore_page_elements<R: Runtime>(window: &WebviewWindow<R>) {
+    let script = format!(
+        "const style = document.getElementById({}); if (style) style.remove(); return null;",
+        serde_json::Value::String(HIDE_STYLE_ID.to_string())
+    );
+    if let Err(e) = run_script(window, &script).await {
+        mcp_log_error(
+            "SCREENSHOT",
+            &format!("Failed to restore hidden elements: {e}"),
+        );
+    }
+}
+
+/// Capture the viewport with the given options.
+///
+/// When `options.viewport` is set the window is resized for the capture and
+/// restored to its original size afterwards, even if the capture fails.
+pub async fn take_viewport_screenshot<R: Runtime>(
+    window: &WebviewWindow<R>,
+    options: &ScreenshotOptions,
+) -> Result<ScreenshotResponse, CommandError> {
+    let Some(viewport) = options.viewport else {
+        return capture_viewport_with_elements(window, options).await;
+    };
+
+    let original = window
+        .inner_size()
+        .map_err(|e| format!("Failed to read window size: {e}"))?;
+    let applied = apply_window_size(window, viewport.width, viewport.height, true)?;
+
+    // Let the page lay out and paint at the new size before capturing
+    let resized_options = ScreenshotOptions {
+        wait_for_paint: true,
+        ..options.clone()
+    };
+    let result = capture_viewport_with_elements(window, &resized_options).await;
+
+    if let Err(e) = window.set_size(original) {
+        mcp_log_error(
+            "SCREENSHOT",
+            &format!("Failed to restore window size after capture: {e}"),
+        );
+    }
+
+    let mut response = result?;
+    response.viewport = Some(applied);
+    Ok(response)
+}
+
+/// A capture at a requested CSS width, from [`capture_at_width`].
+#[derive(Debug, Serialize)]
+#[cfg_attr(feature = "ts-bindings", derive(ts_rs::TS))]
+#[serde(rename_all = "camelCase")]
+pub struct WidthCaptureResponse {
+    /// The capture
+    pub screenshot: ScreenshotResponse,
+    /// CSS width that was requested
+    pub requested_width: u32,
+    /// CSS viewport size the page had during the capture; `None` when it
+    /// couldn't be read
+    pub actual: Option<WindowSize>,
+    /// Why the window couldn't be resized, in which case the capture is at
+    /// its unchanged size
+    pub resize_error: Option<String>,
+}
+
+/// Reads the page's CSS viewport size.
+const CSS_VIEWPORT_SCRIPT: &str =
+    "return { width: Math.round(window.innerWidth), height: Math.round(window.innerHeight) };";
+
+/// Capture the viewport as it looks at `width` CSS pixels wide, for
+/// responsive layouts.
+///
+/// The window is resized to the width, keeping its height, for the capture
+/// and restored afterwards, even if the capture fails. Platforms with fixed
+/// window sizes, such as Android and iOS, can't be resized; the capture is
+/// then taken at the current size with `resize_error` set rather than
+/// failing. Windows with a minimum size or an unusual page zoom may end up
+/// at a different width, so the page's actual viewport is reported too.
+pub async fn capture_at_width<R: Runtime>(
+    window: &WebviewWindow<R>,
+    width: u32,
+    options: &ScreenshotOptions,
+) -> Result<WidthCaptureResponse, CommandError> {
+    let original = window
+        .inner_size()
+        .map_err(|e| format!("Failed to read window size: {e}"))?;
+    let scale_factor = window
+        .scale_factor()
+        .map_err(|e| format!("Failed to read scale factor: {e}"))?;
+    let height = original.to_logical::<u32>(scale_factor).height;
+
+    let resize_error = apply_window_size(window, width, height, true).err();
+    if let Some(e) = &resize_error {
+        mcp_log_error("SCREENSHOT", &format!("Capturing at the current size: {e}"));
+    }
+
+    // Let the page lay out and paint at the new width before capturing
+    let resized_options = ScreenshotOptions {
+        wait_for_paint: true,
+        ..options.clone()
+    };
+    let result = capture_viewport_with_elements(window, &resized_options).await;
+    let actual = run_script(window, CSS_VIEWPORT_SCRIPT)
+        .await
+        .ok()
+        .and_then(|value| serde_json::from_value(value).ok());
+
+    if resize_error.is_none() {
+        if let Err(e) = window.set_size(original) {
+            mcp_log_error(
+                "SCREENSHOT",
+                &format!("Failed to restore window size after capture: {e}"),
+            );
+        }
+    }
+
+    Ok(WidthCaptureResponse {
+        screenshot: result?,
+        requested_width: width,
+        actual,
+        resize_error,
+    })
+}
+
+/// Capture the viewport, collecting interactive elements first when asked.
+///
+/// Falls back to rendering the DOM when the platform has no native capture.
+///
+/// Overlays drawn for `annotate_elements` are removed after the capture,
+/// even if it fails. Debug highlights are hidden for the capture unless
+/// `keep_highlights` is set, and shown again afterwards, as are scrollbars
+/// and elements hidden with `hide_scrollbars` and `hide_selectors`.
+async fn capture_viewport_with_elements<R: Runtime>(
+    window: &WebviewWindow<R>,
+    options: &ScreenshotOptions,
+) -> Result<ScreenshotResponse, CommandError> {
+    // Fonts can change layout, so wait for them before measuring elements
+    let fonts_ready = wait_for_fonts(window, options).await;
+
+    let annotate = options.annotate_elements;
+    let elements = if options.include_elements || annotate {
+        Some(collect_interactive_elements(window, annotate).await?)
+    } else {
+        None
+    };
+
+    // Overlays are transient, so annotated captures are never cached. Hiding
+    // highlights and elements bumps the DOM version, so it is read before
+    // the page is touched and a hit skips that work altogether
+    let cache = window
+        .try_state::<CaptureCache>()
+        .filter(|cache| cache.is_enabled() && !annotate);
+    let cache_key = cache_key(options);
+    let dom_version = match cache {
+        Some(_) => read_dom_version(window).await,
+        None => None,
+    };
+
+    if let (Some(cache), Some(version)) = (&cache, &dom_version) {
+        if let Some(screenshot) = cache.get(window.label(), &cache_key, version) {
+            let mut response = ScreenshotResponse::from_capture(Ok(screenshot))?;
+            response.elements = elements;
+            response.cache_hit = true;
+            response.fonts_ready = fonts_ready;
+            return Ok(response);
+        }
+    }
+
+    let highlights_hidden = !options.keep_highlights
+        && hide_highlights(window).await.unwrap_or_else(|e| {
+            mcp_log_error("SCREENSHOT", &format!("Failed to hide highlights: {e}"));
+            false
+        });
+    let elements_hidden = hide_page_elements(window, options).await;
+
+    // Overlays must be painted before they can show up in the capture, and
+    // hidden highlights and elements painted away before they can be left out
+    let paint_options = ScreenshotOptions {
+        wait_for_paint: options.wait_for_paint || annotate || highlights_hidden || elements_hidden,
+        ..options.clone()
+    };
+    wait_for_paint(window, &paint_options).await;
+
+    let result = match screenshot::capture_viewport_screenshot(window, options).await {
+        // Best effort where the platform has no native capture
+        Err(ScreenshotError::PlatformUnsupported(_)) => {
+            capture_dom_screenshot(window, options).await
+        }
+        result => result,
+    };
+
+    if annotate {
+        if let Err(e) = remove_element_overlays(window).await {
+            mcp_log_error("SCREENSHOT", &format!("Failed to remove overlays: {e}"));
+        }
+    }
+    if highlights_hidden {
+        restore_highlights(window).await;
+    }
+    if elements_hidden {
+        restore_page_elements(window).await;
+    }
+
+    if let (Some(cache), Ok(screenshot)) = (&cache, &result) {
+        // Store the capture under the version the restored page is at, which
+        // the next capture of the unchanged page reads before hiding anything
+        let version = if highlights_hidden || elements_hidden {
+            read_dom_version(window).await
+        } else {
+            dom_version
+        };
+        if let Some(version) = version {
+            cache.insert(window.label(), cache_key, version, screenshot.clone());
+        }
+    }
+
+    let mut response = ScreenshotResponse::from_capture(result)?;
+    response.elements = elements;
+    response.fonts_ready = fonts_ready;
+    Ok(response)
+}
+
+/// Reads the page's DOM version, or `None` when bridge.js isn't tracking it.
+async fn read_dom_version<R: Runtime>(window: &WebviewWindow<R>) -> Option<String> {
+    run_script(window, DOM_VERSION_SCRIPT)
+        .await
+        .ok()
+        .and_then(|v| v.as_str().map(|s| s.to_string()))
+}
+
+/// Cache key made of the options that change the captured image, so
+/// timeouts and other options that only affect how the capture is taken
+/// don't cause misses.
+fn cache_key(options: &ScreenshotOptions) -> String {
+    let capture = &options.capture;
+    format!(
+        "{:?}|{}|{:?}|{}|{:?}|{:?}|{}|{}|{}|{:?}|{}|{:?}|{:?}|{}|{}|{:?}",
+        options.format,
+        options.quality,
+        options.png_compression,
+        options.auto_format_threshold,
+        options.max_width,
+        options.max_bytes,
+        options.strip_metadata,
+        options.compress_transport,
+        options.force_compression,
+        options.viewport,
+        options.keep_highlights,
+        capture.strategy,
+        capture.orientation,
+        capture.include_chrome,
+        capture.hide_scrollbars,
+        capture.hide_selectors,
+    )
+}
+
+async fn restore_highlights<R: Runtime>(window: &WebviewWindow<R>) {
+    if let Err(e) = show_highlights(window).await {
+        mcp_log_error("SCREENSHOT", &format!("Failed to restore highlights: {e}"));
+    }
+}
+
+/// Capture the full screen with the given options.
+///
+/// On Android only the app's own window is captured, and the response's
+/// `warning` says so.
+///
+/// Scrollbars and elements hidden with `hide_scrollbars` and
+/// `hide_selectors` are shown again afterwards, even if the capture fails.
+pub async fn take_screen_screenshot<R: Runtime>(
+    window: &WebviewWindow<R>,
+    options: &ScreenshotOptions,
+) -> Result<ScreenshotResponse, CommandError> {
+    let fonts_ready = wait_for_fonts(window, options).await;
+    let elements_hidden = hide_page_elements(window, options).await;
+    let paint_options = ScreenshotOptions {
+        wait_for_paint: options.wait_for_paint || elements_hidden,
+        ..options.clone()
+    };
+    wait_for_paint(window, &paint_options).await;
+    let result = screenshot::capture_screen_screenshot(window, options).await;
+    if elements_hidden {
+        restore_page_elements(window).await;
+    }
+    let mut response = ScreenshotResponse::from_capture(result)?;
+    response.fonts_ready = fonts_ready;
+    if cfg!(target_os = "android") {
+        response.warning = Some(ANDROID_SCREEN_WARNING.to_string());
+    }
+    Ok(response)
+}
+
+/// A capture and its thumbnail, from [`capture_with_thumbnail`].
+#[derive(Debug, Serialize)]
+#[cfg_attr(feature = "ts-bindings", derive(ts_rs::TS))]
+#[serde(rename_all = "camelCase")]
+pub struct ThumbnailResponse {
+    /// The full capture
+    pub full: ScreenshotResponse,
+    /// The same capture scaled to fit the requested square
+    pub thumbnail: ScreenshotResponse,
+}
+
+/// Capture the viewport once and return it with a thumbnail that fits in
+/// `thumb_max_dim` by `thumb_max_dim` pixels, for previews that load the
+/// full image on demand.
+///
+/// Fonts, paint, highlights and hidden elements are handled as for
+/// [`take_viewport_screenshot`]; `viewport`, elements and the capture cache
+/// are not supported. Falls back to rendering the DOM when the platform has
+/// no native capture.
+pub async fn capture_with_thumbnail<R: Runtime>(
+    window: &WebviewWindow<R>,
+    thumb_max_dim: u32,
+    options: &ScreenshotOptions,
+) -> Result<ThumbnailResponse, CommandError> {
+    let fonts_ready = wait_for_fonts(window, options).await;
+    let highlights_hidden = !options.keep_highlights
+        && hide_highlights(window).await.unwrap_or_else(|e| {
+            mcp_log_error("SCREENSHOT", &format!("Failed to hide highlights: {e}"));
+            false
+        });
+    let elements_hidden = hide_page_elements(window, options).await;
+    let paint_options = ScreenshotOptions {
+        wait_for_paint: options.wait_for_paint || highlights_hidden || elements_hidden,
+        ..options.clone()
+    };
+    wait_for_paint(window, &paint_options).await;
+
+    let result = match screenshot::capture_with_thumbnail(window, thumb_max_dim, options).await {
+        Err(ScreenshotError::PlatformUnsupported(_)) => capture_dom_screenshot(window, options)
+            .await
+            .and_then(|full| {
+                let thumbnail = screenshot::encode_thumbnail(&full, thumb_max_dim, options)?;
+                Ok(ThumbnailCapture { full, thumbnail })
+            }),
+        result => result,
+    };
+    if highlights_hidden {
+        restore_highlights(window).await;
+    }
+    if elements_hidden {
+        restore_page_elements(window).await;
+    }
+
+    let capture = result?;
+    let mut full = ScreenshotResponse::from_capture(Ok(capture.full))?;
+    let mut thumbnail = ScreenshotResponse::from_capture(Ok(capture.thumbnail))?;
+    full.fonts_ready = fonts_ready;
+    thumbnail.fonts_ready = fonts_ready;
+    Ok(ThumbnailResponse { full, thumbnail })
+}
+
+/// Result of capturing one window in [`capture_all_windows`].
+#[derive(Debug, Serialize)]
+#[cfg_attr(feature = "ts-bindings", derive(ts_rs::TS))]
+#[serde(rename_all = "camelCase")]
+pub struct WindowCapture {
+    /// Label of the captured window
+    pub window_label: String,
+    /// The capture, or `None` if the window was skipped or the capture failed
+    pub screenshot: Option<ScreenshotResponse>,
+    /// Whether the window was skipped because it is hidden or minimized
+    pub skipped: bool,
+    /// Why the window was skipped or the capture failed
+    pub error: Option<String>,
+}
+
+/// Capture the viewport of every window.
+///
+/// Hidden and minimized windows are skipped, since they have nothing to
+/// paint. Failures are reported per window instead of failing the batch.
+/// Results are ordered by label, with "main" first.
+pub async fn capture_all_windows<R: Runtime>(
+    app: &AppHandle<R>,
+    options: &ScreenshotOptions,
+) -> Vec<WindowCapture> {
+    let mut windows: Vec<_> = app.webview_windows().into_iter().collect();
+    windows.sort_by(|(a, _), (b, _)| (a != "main", a).cmp(&(b != "main", b)));
+
+    let mut captures = Vec::with_capacity(windows.len());
+    for (label, window) in windows {
+        let skip_reason = if !window.is_visible().unwrap_or(false) {
+            Some("Window is hidden")
+        } else if window.is_minimized().unwrap_or(false) {
+            Some("Window is minimized")
+        } else {
+            None
+        };
+
+        let capture = match skip_reason {
+            Some(reason) => WindowCapture {
+                window_label: label,
+                screenshot: None,
+                skipped: true,
+                error: Some(reason.to_string()),
+            },
+            None => {
+                let result = take_viewport_screenshot(&window, options).await;
+                WindowCapture {
+                    window_label: label,
+                    skipped: false,
+                    error: result.as_ref().err().map(ToString::to_string),
+                    screenshot: result.ok(),
+                }
+            }
+        };
+        captures.push(capture);
+    }
+
+    captures
+}
+
+/// Build capture options from the command's optional arguments.
+pub(crate) fn options_from_args<R: Runtime>(
+    window: &WebviewWindow<R>,
+    format: Option<String>,
+    quality: Option<u8>,
+    max_width: Option<u32>,
+) -> ScreenshotOptions {
+    let defaults = ScreenshotOptions::default();
+    let config = window.try_state::<Config>();
+
+    ScreenshotOptions {
+        format: format
+            .as_deref()
+            .map(ImageFormat::from_name)
+            .unwrap_or(defaults.format),
+        quality: quality.unwrap_or(defaults.quality),
+        max_width,
+        auto_format_threshold: config
+            .as_ref()
+            .map(|config| config.auto_format_threshold)
+            .unwrap_or(defaults.auto_format_threshold),
+        capture: CaptureOptions {
+            timeout: config
+                .map(|config| config.capture_timeout)
+                .unwrap_or(DEFAULT_CAPTURE_TIMEOUT),
+            ..Default::default()
+        },
+        ..defaults
+    }
+}
 
 /// Native screenshot command using platform-specific APIs.
 ///
@@ -17,7 +694,7 @@ use tauri::{command, Runtime, WebviewWindow};
 /// # Arguments
 ///
 /// * `window` - The window to capture
-/// * `format` - Image format ("png" or "jpeg")
+/// * `format` - Image format ("png", "jpeg" or "auto")
 /// * `quality` - JPEG quality (0-100), only used for JPEG format
 ///
 /// # Returns
@@ -31,14 +708,98 @@ pub async fn capture_native_screenshot<R: Runtime>(
     quality: Option<u8>,
     max_width: Option<u32>,
 ) -> Result<String, String> {
-    let format = format.unwrap_or_else(|| "png".to_string());
-    let quality = quality.unwrap_or(90);
+    let options = options_from_args(&window, format, quality, max_width);
+
+    take_viewport_screenshot(&window, &options)
+        .await
+        .map(|response| response.data)
+        .map_err(|e| e.to_string())
+}
+
+/// Full screen screenshot command.
+///
+/// Unlike [`capture_native_screenshot`], this includes native UI outside the
+/// webview:
+/// - macOS: Grabs the main display (requires Screen Recording permission)
+/// - Windows: Grabs the primary monitor
+/// - Android: Draws the activity's root decor view (toolbars, native views).
+///   This is the app's window only, without the system bars or other apps;
+///   capturing the whole display would need MediaProjection consent
+/// - Linux, iOS: Not supported, returns an error
+///
+/// # Arguments
+///
+/// * `window` - The window whose screen should be captured
+/// * `format` - Image format ("png", "jpeg" or "auto")
+/// * `quality` - JPEG quality (0-100), only used for JPEG format
+/// * `max_width` - Optional maximum width, larger images are downscaled
+///
+/// # Returns
+///
+/// * `Ok(String)` - Base64-encoded image data URL
+/// * `Err(String)` - Error message if capture fails or permission is denied
+#[command]
+pub async fn capture_screen<R: Runtime>(
+    window: WebviewWindow<R>,
+    format: Option<String>,
+    quality: Option<u8>,
+    max_width: Option<u32>,
+) -> Result<String, String> {
+    let options = options_from_args(&window, format, quality, max_width);
+
+    take_screen_screenshot(&window, &options)
+        .await
+        .map(|response| response.data)
+        .map_err(|e| e.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_hide_elements_script_embeds_selectors() {
+        let script = hide_elements_script(true, &[r#"[data-banner="it's"]"#.to_string()]);
+        assert!(script.contains(r#"for (const selector of ["[data-banner=\"it's\"]"])"#));
+        assert!(script.contains("const rules = true"));
+        assert!(script.contains(r#"style.id = "__mcp_capture_hide__";"#));
+    }
+
+    #[test]
+    fn test_cache_key_ignores_timeouts() {
+        let options = ScreenshotOptions::default();
+        let slower = ScreenshotOptions {
+            font_timeout: options.font_timeout * 2,
+            capture: CaptureOptions {
+                timeout: options.capture.timeout * 2,
+                ..Default::default()
+            },
+            ..options.clone()
+        };
+        assert_eq!(cache_key(&options), cache_key(&slower));
+
+        let jpeg = ScreenshotOptions {
+            format: ImageFormat::Jpeg,
+            ..options.clone()
+        };
+        assert_ne!(cache_key(&options), cache_key(&jpeg));
 
-    // Use the screenshot module for viewport capture
-    use crate::screenshot;
+        let hidden = ScreenshotOptions {
+            capture: CaptureOptions {
+                hide_selectors: vec![".banner".to_string()],
+                ..Default::default()
+            },
+            ..options.clone()
+        };
+        assert_ne!(cache_key(&options), cache_key(&hidden));
 
-    match screenshot::capture_viewport_screenshot(&window, &format, quality, max_width).await {
-        Ok(data_url) => Ok(data_url),
-        Err(e) => Err(e.to_string()),
+        let pixel_copy = ScreenshotOptions {
+            capture: CaptureOptions {
+                strategy: serde_json::from_value(serde_json::json!("pixelCopy")).unwrap(),
+                ..Default::default()
+            },
+            ..options.clone()
+        };
+        assert_ne!(cache_key(&options), cache_key(&pixel_copy));
     }
 }
@@ -0,0 +1,400 @@
+//! Streaming screenshots over a Tauri channel or as events.
+//!
+//! A stream captures the viewport at a fixed cadence and pushes each frame
+//! to the caller's [`Channel`], or emits it as a [`CAPTURE_FRAME_EVENT`],
+//! until it is stopped or the window closes.
+
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+use serde::Serialize;
+use tauri::ipc::Channel;
+use tauri::{command, Emitter, Listener, Manager, Runtime, State, WebviewWindow};
+use tokio::sync::mpsc::{self, error::TrySendError};
+use tokio::time::MissedTickBehavior;
+use uuid::Uuid;
+
+use super::screenshot::{options_from_args, take_viewport_screenshot};
+use crate::logging::mcp_log_error;
+use crate::monitor::current_timestamp;
+use crate::screenshot::{ContentEncoding, ScreenshotOptions};
+
+/// Event emitted to a window once it has been destroyed.
+const WINDOW_DESTROYED_EVENT: &str = "tauri://destroyed";
+
+/// Frames waiting for delivery. When full, new frames are dropped so a slow
+/// consumer can't grow memory.
+const FRAME_QUEUE_CAPACITY: usize = 2;
+
+/// Shortest allowed interval between frames.
+const MIN_INTERVAL_MS: u64 = 16;
+
+/// Event carrying each frame of a capture stream, as a [`CaptureStreamFrame`].
+pub const CAPTURE_FRAME_EVENT: &str = "mcp://frame";
+
+/// A single frame pushed to a screenshot stream.
+#[derive(Debug, Clone, Serialize)]
+#[cfg_attr(feature = "ts-bindings", derive(ts_rs::TS))]
+#[serde(rename_all = "camelCase")]
+pub struct ScreenshotFrame {
+    /// Capture sequence number. Gaps mean frames were dropped.
+    #[cfg_attr(feature = "ts-bindings", ts(type = "number"))]
+    pub sequence: u64,
+    /// When the frame was captured (epoch millis)
+    #[cfg_attr(feature = "ts-bindings", ts(type = "number"))]
+    pub timestamp: u64,
+    /// Base64-encoded image data URL
+    pub data: String,
+    /// Set when `data` holds compressed bytes the client must decode first
+    pub content_encoding: Option<ContentEncoding>,
+}
+
+/// A frame emitted by a capture stream as a [`CAPTURE_FRAME_EVENT`].
+#[derive(Debug, Clone, Serialize)]
+#[cfg_attr(feature = "ts-bindings", derive(ts_rs::TS))]
+#[serde(rename_all = "camelCase")]
+pub struct CaptureStreamFrame {
+    /// The stream that produced the frame
+    pub stream_id: String,
+    /// Label of the captured window
+    pub window_label: String,
+    #[serde(flatten)]
+    pub frame: ScreenshotFrame,
+}
+
+/// Flags shared between a running stream and its controllers.
+#[derive(Debug, Default)]
+struct StreamHandle {
+    stopped: AtomicBool,
+    /// Set while an emitted frame awaits [`ack_capture_frame`]
+    awaiting_ack: AtomicBool,
+}
+
+/// Running screenshot streams, keyed by stream ID.
+#[derive(Debug, Default)]
+pub struct ScreenshotStreams {
+    streams: Mutex<HashMap<String, Arc<StreamHandle>>>,
+}
+
+impl ScreenshotStreams {
+    fn register(&self, id: &str) -> Arc<StreamHandle> {
+        let handle = Arc::new(StreamHandle::default());
+        self.streams
+            .lock()
+            .unwrap()
+            .insert(id.to_string(), handle.clone());
+        handle
+    }
+
+    /// Signals a stream to stop. Returns false if no such stream is running.
+    pub fn stop(&self, id: &str) -> bool {
+        match self.streams.lock().unwrap().remove(id) {
+            Some(handle) => {
+                handle.stopped.store(true, Ordering::Relaxed);
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// Marks the last emitted frame of a stream as consumed, so the next one
+    /// can be captured. Returns false if no such stream is running.
+    pub fn ack(&self, id: &str) -> bool {
+        match self.streams.lock().unwrap().get(id) {
+            Some(handle) => {
+                handle.awaiting_ack.store(false, Ordering::Relaxed);
+                true
+            }
+            None => false,
+        }
+    }
+}
+
+/// Checks the interval and registers a new stream, returning its ID.
+fn register_stream(
+    streams: &ScreenshotStreams,
+    interval_ms: u64,
+) -> Result<(String, Arc<StreamHandle>), String> {
+    if interval_ms < MIN_INTERVAL_MS {
+        return Err(format!(
+            "Stream interval must be at least {MIN_INTERVAL_MS}ms, got {interval_ms}ms"
+        ));
+    }
+
+    let stream_id = Uuid::new_v4().to_string();
+    let handle = streams.register(&stream_id);
+    Ok((stream_id, handle))
+}
+
+/// Captures frames every `interval_ms` and hands them to `deliver` until the
+/// stream is stopped, the window closes, or `deliver` returns false.
+///
+/// Ticks are skipped while the stream is awaiting an ack.
+fn spawn_capture_loop<R: Runtime>(
+    window: WebviewWindow<R>,
+    options: ScreenshotOptions,
+    interval_ms: u64,
+    stream_id: String,
+    handle: Arc<StreamHandle>,
+    mut deliver: impl FnMut(ScreenshotFrame) -> bool + Send + 'static,
+) {
+    let destroyed = handle.clone();
+    let destroyed_listener = window.once(WINDOW_DESTROYED_EVENT, move |_| {
+        destroyed.stopped.store(true, Ordering::Relaxed);
+    });
+
+    tauri::async_runtime::spawn(async move {
+        let mut ticker = tokio::time::interval(Duration::from_millis(interval_ms));
+        ticker.set_missed_tick_behavior(MissedTickBehavior::Delay);
+        let mut sequence = 0;
+
+        while !handle.stopped.load(Ordering::Relaxed) {
+            ticker.tick().await;
+            if handle.stopped.load(Ordering::Relaxed) {
+                break;
+            }
+            if handle.awaiting_ack.load(Ordering::Relaxed) {
+                sequence += 1;
+                continue;
+            }
+
+            let response = match take_viewport_screenshot(&window, &options).await {
+                Ok(response) => response,
+                Err(e) => {
+                    mcp_log_error("SCREENSHOT", &format!("Stream {stream_id} stopped: {e}"));
+                    break;
+                }
+            };
+
+            let frame = ScreenshotFrame {
+                sequence,
+                timestamp: current_timestamp(),
+                data: response.data,
+                content_encoding: response.content_encoding,
+            };
+            sequence += 1;
+
+            if !deliver(frame) {
+                break;
+            }
+        }
+
+        window.unlisten(destroyed_listener);
+        window.state::<ScreenshotStreams>().stop(&stream_id);
+    });
+}
+
+/// Starts pushing viewport screenshots to `channel` every `interval_ms`.
+///
+/// Frames are captured at a fixed cadence; a capture that takes longer than
+/// the interval delays the next one rather than queueing extra captures.
+/// The stream ends when [`stop_screenshot_stream`] is called, the window
+/// closes, or the channel can no longer deliver frames.
+///
+/// # Arguments
+///
+/// * `window` - The window to capture
+/// * `interval_ms` - Time between captures, at least 16ms
+/// * `channel` - Channel receiving [`ScreenshotFrame`]s
+/// * `format` - Image format ("png", "jpeg" or "auto")
+/// * `quality` - JPEG quality (0-100), only used for JPEG format
+/// * `max_width` - Optional maximum width, larger images are downscaled
+///
+/// # Returns
+///
+/// * `Ok(String)` - The stream ID, used to stop the stream
+/// * `Err(String)` - Error message if the interval is too short
+#[command]
+pub async fn start_screenshot_stream<R: Runtime>(
+    window: WebviewWindow<R>,
+    streams: State<'_, ScreenshotStreams>,
+    interval_ms: u64,
+    channel: Channel<ScreenshotFrame>,
+    format: Option<String>,
+    quality: Option<u8>,
+    max_width: Option<u32>,
+) -> Result<String, String> {
+    let (stream_id, handle) = register_stream(&streams, interval_ms)?;
+    let options = options_from_args(&window, format, quality, max_width);
+
+    let (frame_tx, mut frame_rx) = mpsc::channel::<ScreenshotFrame>(FRAME_QUEUE_CAPACITY);
+
+    // Deliver queued frames to the channel
+    let delivery_handle = handle.clone();
+    tauri::async_runtime::spawn(async move {
+        while let Some(frame) = frame_rx.recv().await {
+            if let Err(e) = channel.send(frame) {
+                mcp_log_error(
+                    "SCREENSHOT",
+                    &format!("Failed to deliver stream frame: {e}"),
+                );
+                delivery_handle.stopped.store(true, Ordering::Relaxed);
+                break;
+            }
+        }
+    });
+
+    spawn_capture_loop(
+        window,
+        options,
+        interval_ms,
+        stream_id.clone(),
+        handle,
+        move |frame| match frame_tx.try_send(frame) {
+            Ok(()) | Err(TrySendError::Full(_)) => true,
+            Err(TrySendError::Closed(_)) => false,
+        },
+    );
+
+    Ok(stream_id)
+}
+
+/// Stops a screenshot stream started with [`start_screenshot_stream`].
+///
+/// # Returns
+///
+/// * `Ok(bool)` - Whether a running stream with this ID was found
+#[command]
+pub async fn stop_screenshot_stream(
+    streams: State<'_, ScreenshotStreams>,
+    stream_id: String,
+) -> Result<bool, String> {
+    Ok(streams.stop(&stream_id))
+}
+
+/// Starts emitting viewport screenshots as [`CAPTURE_FRAME_EVENT`] events
+/// every `interval_ms`, for live previews that listen rather than poll.
+///
+/// After each frame the stream waits for [`ack_capture_frame`] and skips
+/// ticks until it arrives, so a slow consumer never falls behind. Sequence
+/// numbers keep counting skipped ticks. The stream ends when
+/// [`stop_capture_stream`] is called or the window closes.
+///
+/// # Arguments
+///
+/// * `window` - The window to capture
+/// * `interval_ms` - Time between captures, at least 16ms
+/// * `format` - Image format ("png", "jpeg" or "auto")
+/// * `quality` - JPEG quality (0-100), only used for JPEG format
+/// * `max_width` - Optional maximum width, larger images are downscaled
+///
+/// # Returns
+///
+/// * `Ok(String)` - The stream ID, used to ack frames and stop the stream
+/// * `Err(String)` - Error message if the interval is too short
+#[command]
+pub async fn start_capture_stream<R: Runtime>(
+    window: WebviewWindow<R>,
+    streams: State<'_, ScreenshotStreams>,
+    interval_ms: u64,
+    format: Option<String>,
+    quality: Option<u8>,
+    max_width: Option<u32>,
+) -> Result<String, String> {
+    let (stream_id, handle) = register_stream(&streams, interval_ms)?;
+    let options = options_from_args(&window, format, quality, max_width);
+
+    let emitter = window.clone();
+    let id = stream_id.clone();
+    let ack = handle.clone();
+    spawn_capture_loop(
+        window,
+        options,
+        interval_ms,
+        stream_id.clone(),
+        handle,
+        move |frame| {
+            ack.awaiting_ack.store(true, Ordering::Relaxed);
+            let payload = CaptureStreamFrame {
+                stream_id: id.clone(),
+                window_label: emitter.label().to_string(),
+                frame,
+            };
+            match emitter.emit(CAPTURE_FRAME_EVENT, payload) {
+                Ok(()) => true,
+                Err(e) => {
+                    mcp_log_error("SCREENSHOT", &format!("Failed to emit stream frame: {e}"));
+                    false
+                }
+            }
+        },
+    );
+
+    Ok(stream_id)
+}
+
+/// Acknowledges the last frame of a capture stream, allowing the next one.
+///
+/// # Returns
+///
+/// * `Ok(bool)` - Whether a running stream with this ID was found
+#[command]
+pub async fn ack_capture_frame(
+    streams: State<'_, ScreenshotStreams>,
+    stream_id: String,
+) -> Result<bool, String> {
+    Ok(streams.ack(&stream_id))
+}
+
+/// Stops a capture stream started with [`start_capture_stream`].
+///
+/// # Returns
+///
+/// * `Ok(bool)` - Whether a running stream with this ID was found
+#[command]
+pub async fn stop_capture_stream(
+    streams: State<'_, ScreenshotStreams>,
+    stream_id: String,
+) -> Result<bool, String> {
+    Ok(streams.stop(&stream_id))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_stop_signals_stream_once() {
+        let streams = ScreenshotStreams::default();
+        let handle = streams.register("stream-1");
+
+        assert!(streams.stop("stream-1"));
+        assert!(handle.stopped.load(Ordering::Relaxed));
+        assert!(!streams.stop("stream-1"));
+        assert!(!streams.stop("unknown"));
+    }
+
+    #[test]
+    fn test_ack_clears_pending_frame() {
+        let streams = ScreenshotStreams::default();
+        let handle = streams.register("stream-1");
+        handle.awaiting_ack.store(true, Ordering::Relaxed);
+
+        assert!(streams.ack("stream-1"));
+        assert!(!handle.awaiting_ack.load(Ordering::Relaxed));
+
+        streams.stop("stream-1");
+        assert!(!streams.ack("stream-1"));
+    }
+
+    #[test]
+    fn test_capture_frame_flattens_into_event_payload() {
+        let payload = CaptureStreamFrame {
+            stream_id: "s".to_string(),
+            window_label: "main".to_string(),
+            frame: ScreenshotFrame {
+                sequence: 3,
+                timestamp: 10,
+                data: "data:image/png;base64,".to_string(),
+                content_encoding: None,
+            },
+        };
+
+        let value = serde_json::to_value(payload).unwrap();
+        assert_eq!(value["streamId"], "s");
+        assert_eq!(value["windowLabel"], "main");
+        assert_eq!(value["sequence"], 3);
+    }
+}
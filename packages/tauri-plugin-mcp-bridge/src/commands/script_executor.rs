@@ -30,7 +30,8 @@ impl Default for ScriptExecutor {
 
 /// Command to receive script execution results from JavaScript.
 ///
-/// This is called by JavaScript after script execution completes.
+/// This is called by JavaScript after script execution completes. `stack`
+/// carries the JavaScript stack trace of a thrown error or rejection.
 #[command]
 pub async fn script_result<R: Runtime>(
     app: AppHandle<R>,
@@ -38,6 +39,7 @@ pub async fn script_result<R: Runtime>(
     success: bool,
     data: Option<Value>,
     error: Option<String>,
+    stack: Option<String>,
 ) -> Result<(), String> {
     // Get the script executor from app state
     if let Some(executor) = app.try_state::<ScriptExecutor>() {
@@ -53,7 +55,8 @@ pub async fn script_result<R: Runtime>(
             } else {
                 serde_json::json!({
                     "success": false,
-                    "error": error.unwrap_or_else(|| "Unknown error".to_string())
+                    "error": error.unwrap_or_else(|| "Unknown error".to_string()),
+                    "stack": stack
                 })
             };
 
@@ -1,30 +1,629 @@
 //! Script injection command for re-injecting registered scripts on page load.
 
+use super::execute_js::run_script;
+use crate::logging::{mcp_log_error, mcp_log_info};
+use crate::monitor::current_timestamp;
 use crate::script_registry::{ScriptEntry, ScriptType, SharedScriptRegistry};
-use tauri::{command, Runtime, State, WebviewWindow};
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, HashSet, VecDeque};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+use tauri::{command, Manager, Runtime, State, WebviewWindow};
+
+/// How a script payload was delivered to the webview.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[cfg_attr(feature = "ts-bindings", derive(ts_rs::TS))]
+#[serde(rename_all = "lowercase")]
+pub enum InjectionStrategy {
+    /// Evaluated in a single `eval` call.
+    Direct,
+    /// Split into several `eval` calls that append to a buffer, then executed.
+    Chunked,
+}
+
+/// Whether [`inject_scripts`] injected a requested script.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[cfg_attr(feature = "ts-bindings", derive(ts_rs::TS))]
+#[serde(rename_all = "snake_case")]
+pub enum ScriptInjectionStatus {
+    Injected,
+    /// No script with the ID is registered for the window
+    NotFound,
+}
+
+/// Outcome of [`inject_scripts`] for one requested ID.
+#[derive(Debug, Clone, Serialize)]
+#[cfg_attr(feature = "ts-bindings", derive(ts_rs::TS))]
+pub struct ScriptInjectionOutcome {
+    pub id: String,
+    pub status: ScriptInjectionStatus,
+}
+
+/// Outcome of [`inject_scripts`].
+#[derive(Debug, Clone, Serialize)]
+#[cfg_attr(feature = "ts-bindings", derive(ts_rs::TS))]
+pub struct InjectScriptsResult {
+    /// One outcome per requested ID, in the order requested
+    pub scripts: Vec<ScriptInjectionOutcome>,
+    /// How the payload was delivered; `None` when no ID was found
+    pub strategy: Option<InjectionStrategy>,
+    /// Whether the injected code was confirmed to have run in the page;
+    /// `None` when verification wasn't requested or no ID was found
+    pub verified: Option<bool>,
+    /// Cost of each script the page ran
+    pub metrics: Vec<ScriptInjectionMetric>,
+}
+
+/// Outcome of [`request_script_injection`], in the same shape whether or
+/// not anything was injected.
+#[derive(Debug, Clone, Default, Serialize)]
+#[cfg_attr(feature = "ts-bindings", derive(ts_rs::TS))]
+#[serde(rename_all = "camelCase")]
+pub struct InjectionResult {
+    /// Number of scripts injected
+    pub injected: usize,
+    /// IDs of the injected scripts, in injection order
+    pub script_ids: Vec<String>,
+    /// IDs of the scripts that would have been injected, when injection is
+    /// paused or a re-application was rate-limited
+    pub skipped: Vec<String>,
+    /// Why nothing was injected
+    pub message: Option<String>,
+    /// How the payload was delivered; `None` when nothing was injected
+    pub strategy: Option<InjectionStrategy>,
+    /// Cost of each script the page ran
+    pub metrics: Vec<ScriptInjectionMetric>,
+}
+
+impl InjectionResult {
+    /// A result for a request that injected nothing.
+    fn not_injected(skipped: &[ScriptEntry], message: &str) -> Self {
+        Self {
+            skipped: skipped.iter().map(|entry| entry.id.clone()).collect(),
+            message: Some(message.to_string()),
+            ..Self::default()
+        }
+    }
+}
+
+/// Cost of injecting one script, as reported by the page.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[cfg_attr(feature = "ts-bindings", derive(ts_rs::TS))]
+#[serde(rename_all = "camelCase")]
+pub struct ScriptInjectionMetric {
+    pub id: String,
+    /// How long the script took to run, in milliseconds; `None` for URL and
+    /// module scripts, which load after injection returns
+    pub duration_ms: Option<f64>,
+    /// Size of the script's content (code or URL) in bytes
+    pub bytes: usize,
+}
+
+/// Typical run time of a script, from [`InjectionMetrics`].
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[cfg_attr(feature = "ts-bindings", derive(ts_rs::TS))]
+#[serde(rename_all = "camelCase")]
+pub struct ScriptCost {
+    /// Mean run time over the recent injections, in milliseconds
+    pub average_duration_ms: f64,
+    /// Number of injections the average covers
+    pub samples: usize,
+}
+
+/// Injections averaged per script by [`InjectionMetrics`].
+const METRICS_WINDOW: usize = 20;
+
+/// Rolling average of each script's run time over its last
+/// [`METRICS_WINDOW`] injections, shown by `list_scripts`.
+#[derive(Debug, Default)]
+pub struct InjectionMetrics {
+    durations: Mutex<HashMap<String, VecDeque<f64>>>,
+}
+
+impl InjectionMetrics {
+    /// Adds the measured run times from one injection.
+    fn record(&self, metrics: &[ScriptInjectionMetric]) {
+        let mut durations = self.durations.lock().unwrap();
+        for metric in metrics {
+            let Some(duration_ms) = metric.duration_ms else {
+                continue;
+            };
+            let samples = durations.entry(metric.id.clone()).or_default();
+            if samples.len() == METRICS_WINDOW {
+                samples.pop_front();
+            }
+            samples.push_back(duration_ms);
+        }
+    }
+
+    /// Typical run time of a script, if it has been measured.
+    pub fn cost(&self, id: &str) -> Option<ScriptCost> {
+        let durations = self.durations.lock().unwrap();
+        let samples = durations.get(id).filter(|samples| !samples.is_empty())?;
+        Some(ScriptCost {
+            average_duration_ms: samples.iter().sum::<f64>() / samples.len() as f64,
+            samples: samples.len(),
+        })
+    }
+}
+
+/// Script injection errors
+#[derive(Debug, thiserror::Error)]
+pub enum InjectionError {
+    #[error(
+        "The page's Content Security Policy blocked script injection: {0}. \
+         Allow 'unsafe-inline' and 'unsafe-eval' in script-src, or register the \
+         script with Builder::initialization_script, which is not subject to the page's CSP"
+    )]
+    CspBlocked(String),
+
+    #[error("Failed to inject scripts: {0}")]
+    EvalFailed(String),
+
+    #[error("Failed to serialize scripts for injection: {0}")]
+    Serialization(String),
+}
+
+impl InjectionError {
+    /// Classifies an error reported while evaluating injected code.
+    pub fn from_eval_message(message: impl Into<String>) -> Self {
+        let message = message.into();
+        let lower = message.to_lowercase();
+
+        // Chromium/WebKit: "Refused to evaluate a string as JavaScript because
+        // 'unsafe-eval' is not an allowed source...", Firefox: "call to eval()
+        // blocked by CSP"
+        let is_csp = lower.contains("content security policy")
+            || lower.contains("unsafe-eval")
+            || lower.contains("unsafe-inline")
+            || lower.contains("blocked by csp");
+
+        if is_csp {
+            InjectionError::CspBlocked(message)
+        } else {
+            InjectionError::EvalFailed(message)
+        }
+    }
+
+    /// Machine-readable error code for clients.
+    pub fn code(&self) -> &'static str {
+        match self {
+            InjectionError::CspBlocked(_) => "CSP_BLOCKED",
+            InjectionError::EvalFailed(_) => "INJECTION_FAILED",
+            InjectionError::Serialization(_) => "SERIALIZATION_FAILED",
+        }
+    }
+}
+
+/// Reads and clears what bridge.js recorded while injecting: the error, if
+/// any, and the run time of each script.
+const TAKE_INJECTION_ACK_SCRIPT: &str = r#"return (() => {
+    const ack = {
+        error: window.__MCP_INJECTION_ERROR__ || null,
+        metrics: window.__MCP_INJECTION_METRICS__ || []
+    };
+    delete window.__MCP_INJECTION_ERROR__;
+    delete window.__MCP_INJECTION_METRICS__;
+    return ack;
+})();"#;
+
+/// Result of [`TAKE_INJECTION_ACK_SCRIPT`].
+#[derive(Debug, Default, Deserialize)]
+struct InjectionAck {
+    error: Option<String>,
+    #[serde(default)]
+    metrics: Vec<AckMetric>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct AckMetric {
+    id: String,
+    duration_ms: Option<f64>,
+}
+
+/// Room left in each chunk for the buffer assignment around the string literal.
+const CHUNK_OVERHEAD: usize = 64;
+
+/// Smallest chunk body, so tiny limits still make progress.
+const MIN_CHUNK_BYTES: usize = 256;
+
+static NEXT_BUFFER_ID: AtomicU64 = AtomicU64::new(0);
+
+static NEXT_SENTINEL: AtomicU64 = AtomicU64::new(0);
+
+/// Shortest time between two re-applications in the same window. Requests
+/// arriving sooner are acknowledged without evaluating anything.
+const MIN_REAPPLY_INTERVAL: Duration = Duration::from_millis(250);
+
+/// Rate-limits re-application requests from the DOM replacement observer,
+/// so bursts of route changes cost one injection.
+#[derive(Debug, Default)]
+pub struct ReapplyGuard {
+    last_reapply: Mutex<HashMap<String, Instant>>,
+}
+
+impl ReapplyGuard {
+    /// Records a re-application in the window, returning false if the
+    /// previous one was less than [`MIN_REAPPLY_INTERVAL`] ago.
+    fn try_begin(&self, window_label: &str, now: Instant) -> bool {
+        let mut last_reapply = self.last_reapply.lock().unwrap();
+        match last_reapply.get(window_label) {
+            Some(last) if now.duration_since(*last) < MIN_REAPPLY_INTERVAL => false,
+            _ => {
+                last_reapply.insert(window_label.to_string(), now);
+                true
+            }
+        }
+    }
+}
+
+/// How long the navigation watchdog waits after a page finishes loading for
+/// bridge.js to request injection itself.
+const WATCHDOG_GRACE: Duration = Duration::from_millis(500);
+
+/// Page loads seen by the navigation watchdog in one window.
+#[derive(Debug, Default)]
+struct PageLoads {
+    /// Page loads started so far
+    count: u64,
+    /// Whether scripts were injected since the last load started
+    injected: bool,
+}
+
+/// Injects registered scripts from Rust after each page load that bridge.js
+/// didn't report, when enabled with
+/// [`crate::Builder::navigation_watchdog`].
+///
+/// bridge.js normally requests injection itself, but it can't when the
+/// page has no IPC access, such as remote origins without a capability.
+/// The watchdog tracks each window's page loads; when one finishes and no
+/// request arrives within a short grace period, it injects as bridge.js
+/// would have. Requests that arrive after the watchdog injected are
+/// harmless: the page skips scripts it already has.
+#[derive(Debug, Default)]
+pub struct NavigationWatchdog {
+    pages: Mutex<HashMap<String, PageLoads>>,
+}
+
+impl NavigationWatchdog {
+    /// Records that a page started loading in the window, returning the
+    /// load's number.
+    pub fn page_started(&self, window_label: &str) -> u64 {
+        let mut pages = self.pages.lock().unwrap();
+        let page = pages.entry(window_label.to_string()).or_default();
+        page.count += 1;
+        page.injected = false;
+        page.count
+    }
+
+    /// Number of the window's most recent page load, zero before the first.
+    pub fn current_load(&self, window_label: &str) -> u64 {
+        self.pages
+            .lock()
+            .unwrap()
+            .get(window_label)
+            .map_or(0, |page| page.count)
+    }
+
+    /// Records that scripts were injected into the window's current page.
+    fn mark_injected(&self, window_label: &str) {
+        if let Some(page) = self.pages.lock().unwrap().get_mut(window_label) {
+            page.injected = true;
+        }
+    }
+
+    /// Claims injection of page load `load` for the watchdog, returning
+    /// false if another load has started since or scripts were already
+    /// injected.
+    fn claim(&self, window_label: &str, load: u64) -> bool {
+        let mut pages = self.pages.lock().unwrap();
+        match pages.get_mut(window_label) {
+            Some(page) if page.count == load && !page.injected => {
+                page.injected = true;
+                true
+            }
+            _ => false,
+        }
+    }
+}
+
+/// Injects registered scripts into the window once page load `load` has
+/// finished, unless bridge.js requests injection within the grace period.
+pub(crate) async fn watchdog_inject<R: Runtime>(window: WebviewWindow<R>, load: u64) {
+    tokio::time::sleep(WATCHDOG_GRACE).await;
+    if !window
+        .state::<NavigationWatchdog>()
+        .claim(window.label(), load)
+    {
+        return;
+    }
+
+    let registry = window.state::<SharedScriptRegistry>();
+    // Without bridge.js's document ID, each page load counts as a new document
+    let document_id = format!("watchdog-{load}");
+    match inject_registered(&window, &registry, Some(&document_id), false).await {
+        Ok(result) => mcp_log_info(
+            "SCRIPTS",
+            &format!(
+                "Watchdog injected {} scripts into '{}' after navigation",
+                result.injected,
+                window.label()
+            ),
+        ),
+        Err(e) => mcp_log_error(
+            "SCRIPTS",
+            &format!(
+                "Watchdog failed to inject scripts into '{}': {e}",
+                window.label()
+            ),
+        ),
+    }
+}
+
+/// Evaluates `script` in the webview, switching to chunked evaluation when it
+/// is larger than the configured `max_inline_eval_bytes`.
+pub(crate) fn eval_with_limit<R: Runtime>(
+    window: &WebviewWindow<R>,
+    script: &str,
+) -> tauri::Result<InjectionStrategy> {
+    let max_bytes = window.state::<crate::Config>().max_inline_eval_bytes;
+    if script.len() <= max_bytes {
+        window.eval(script)?;
+        return Ok(InjectionStrategy::Direct);
+    }
+
+    let key = format!("c{}", NEXT_BUFFER_ID.fetch_add(1, Ordering::Relaxed));
+    window.eval(format!(
+        "(window.__MCP_EVAL_BUFFER__ = window.__MCP_EVAL_BUFFER__ || {{}})['{key}'] = '';"
+    ))?;
+
+    // Evals on one webview run in order, so the buffer is complete by the last call
+    for chunk in split_string_literal(script, max_bytes.saturating_sub(CHUNK_OVERHEAD)) {
+        window.eval(format!("window.__MCP_EVAL_BUFFER__['{key}'] += {chunk};"))?;
+    }
+
+    window.eval(format!(
+        "(function() {{ var src = window.__MCP_EVAL_BUFFER__['{key}']; delete window.__MCP_EVAL_BUFFER__['{key}']; try {{ (0, eval)(src); }} catch (e) {{ window.__MCP_INJECTION_ERROR__ = String(e && e.message || e); }} }})();"
+    ))?;
+
+    Ok(InjectionStrategy::Chunked)
+}
+
+/// Splits `script` into JavaScript string literals of at most `max_bytes`
+/// each (including quotes), never breaking an escape sequence or character.
+fn split_string_literal(script: &str, max_bytes: usize) -> Vec<String> {
+    let escaped = serde_json::Value::String(script.to_owned()).to_string();
+    let body = &escaped[1..escaped.len() - 1];
+    let budget = max_bytes.saturating_sub(2).max(MIN_CHUNK_BYTES);
+
+    let mut chunks = Vec::new();
+    let mut start = 0;
+    let mut end = 0;
+    let bytes = body.as_bytes();
+
+    while end < bytes.len() {
+        let token_len = match bytes[end] {
+            b'\\' if bytes.get(end + 1) == Some(&b'u') => 6,
+            b'\\' => 2,
+            b if b < 0x80 => 1,
+            b if b >= 0xF0 => 4,
+            b if b >= 0xE0 => 3,
+            _ => 2,
+        };
+
+        if end + token_len - start > budget && end > start {
+            chunks.push(format!("\"{}\"", &body[start..end]));
+            start = end;
+        }
+        end += token_len;
+    }
+
+    if end > start {
+        chunks.push(format!("\"{}\"", &body[start..end]));
+    }
+    chunks
+}
 
 /// Request script injection - called by bridge.js when a page loads.
-/// This command retrieves all registered scripts and injects them into the webview.
+/// This command retrieves the global scripts merged with the calling window's
+/// own scope and injects the ones whose match patterns fit the page's URL.
+///
+/// `document_id` identifies the page's JavaScript context, so scripts
+/// registered with `run_once` are skipped on client-side navigations and run
+/// again after a reload; see
+/// [`ScopedScriptRegistry::scripts_for_document`](crate::script_registry::ScopedScriptRegistry::scripts_for_document).
+/// Without it, as from older bridge scripts, every request is a new document.
+///
+/// With `reapply`, scripts already on the page are removed and injected
+/// again. bridge.js requests this when the configured root's content is
+/// replaced; such requests are cheap to repeat, as ones arriving within
+/// 250ms of the last are skipped, listing the scripts in
+/// [`InjectionResult::skipped`].
+///
+/// When injection is paused via `set_injection_enabled`, nothing is
+/// evaluated and the response lists the scripts as skipped, with a message
+/// saying injection is paused.
+///
+/// Pages whose Content Security Policy forbids inline scripts or `eval` block
+/// the injected code. bridge.js records the failure and the command returns
+/// [`InjectionError::CspBlocked`] with guidance; scripts that must run on
+/// such pages belong in [`crate::Builder::initialization_script`].
 #[command]
 pub async fn request_script_injection<R: Runtime>(
     window: WebviewWindow<R>,
     registry: State<'_, SharedScriptRegistry>,
-) -> Result<serde_json::Value, String> {
+    reapply: Option<bool>,
+    document_id: Option<String>,
+) -> Result<InjectionResult, String> {
+    let reapply = reapply.unwrap_or(false);
+    if reapply
+        && !window
+            .state::<ReapplyGuard>()
+            .try_begin(window.label(), Instant::now())
+    {
+        let url = window.url().ok().map(|url| url.to_string());
+        let scripts = registry
+            .lock()
+            .map_err(|e| format!("Failed to lock registry: {e}"))?
+            .scripts_for_window(window.label(), url.as_deref());
+        return Ok(InjectionResult::not_injected(
+            &scripts,
+            "Re-application skipped: requested within 250ms of the last",
+        ));
+    }
+
+    if let Some(watchdog) = window.try_state::<NavigationWatchdog>() {
+        watchdog.mark_injected(window.label());
+    }
+
+    inject_registered(&window, &registry, document_id.as_deref(), reapply).await
+}
+
+/// Injects the registered scripts for the window's document, as
+/// [`request_script_injection`] describes.
+async fn inject_registered<R: Runtime>(
+    window: &WebviewWindow<R>,
+    registry: &SharedScriptRegistry,
+    document_id: Option<&str>,
+    reapply: bool,
+) -> Result<InjectionResult, String> {
+    // Watch for DOM replacement on every page load, even with nothing to
+    // inject yet, since scripts may be registered later. Idempotent in JS.
+    if let Some(root) = &window.state::<crate::Config>().reinject_root {
+        let watch_script = format!(
+            "if (window.__MCP_WATCH_DOM_REPLACEMENT__) {{ window.__MCP_WATCH_DOM_REPLACEMENT__({}); }}",
+            serde_json::Value::String(root.clone())
+        );
+        if let Err(e) = window.eval(watch_script) {
+            mcp_log_error(
+                "SCRIPTS",
+                &format!("Failed to watch for DOM replacement: {e}"),
+            );
+        }
+    }
+
+    let url = window.url().ok().map(|url| url.to_string());
+    let url = url.as_deref();
     let scripts: Vec<ScriptEntry> = {
-        let reg = registry
+        let mut reg = registry
             .lock()
             .map_err(|e| format!("Failed to lock registry: {e}"))?;
-        reg.get_all().iter().map(|e| (*e).clone()).collect()
+
+        if !reg.is_injection_enabled() {
+            return Ok(InjectionResult::not_injected(
+                &reg.scripts_for_window(window.label(), url),
+                "Script injection is paused",
+            ));
+        }
+
+        match document_id {
+            Some(document_id) => reg.scripts_for_document(window.label(), url, document_id),
+            None => reg.scripts_for_window(window.label(), url),
+        }
     };
 
     if scripts.is_empty() {
-        return Ok(serde_json::json!({
-            "injected": 0,
-            "message": "No scripts registered"
-        }));
+        return Ok(InjectionResult::not_injected(&[], "No scripts registered"));
     }
 
-    // Build the injection script
+    let injection = inject_entries(window, registry, &scripts, reapply, false).await?;
+
+    Ok(InjectionResult {
+        injected: scripts.len(),
+        script_ids: scripts.iter().map(|s| s.id.clone()).collect(),
+        skipped: Vec::new(),
+        message: None,
+        strategy: Some(injection.strategy),
+        metrics: injection.metrics,
+    })
+}
+
+/// Injects the registered scripts with the given IDs into a window now,
+/// whether or not injection is paused.
+///
+/// Scripts are injected in registry order, the order page loads use,
+/// regardless of the order of `ids`. Match patterns are ignored, since
+/// the scripts are named explicitly. Scripts already on the page are
+/// removed and run again. IDs not registered for the window are reported
+/// as [`ScriptInjectionStatus::NotFound`] and don't fail the call.
+///
+/// Some WebView2 and WKWebView versions return from `eval` without error
+/// even when the code never ran in the page. With `verify`, the injected
+/// code also sets a sentinel that is then read back through the script
+/// callback channel, and the result reports whether it was found. This
+/// costs one more round trip, so it is opt-in.
+///
+/// # Arguments
+///
+/// * `window` - The webview window to inject into
+/// * `registry` - The script registry to look the IDs up in
+/// * `ids` - IDs of the scripts to inject
+/// * `verify` - Confirm that the injected code ran
+///
+/// # Returns
+///
+/// * `Ok(InjectScriptsResult)` - Per-ID status
+/// * `Err(String)` - Error message if evaluating the scripts failed, such
+///   as when the page's CSP blocks them
+pub async fn inject_scripts<R: Runtime>(
+    window: &WebviewWindow<R>,
+    registry: &SharedScriptRegistry,
+    ids: &[String],
+    verify: bool,
+) -> Result<InjectScriptsResult, String> {
+    let requested: HashSet<&str> = ids.iter().map(|id| id.trim()).collect();
+    let scripts: Vec<ScriptEntry> = registry
+        .lock()
+        .map_err(|e| format!("Failed to lock registry: {e}"))?
+        .scripts_for_window(window.label(), None)
+        .into_iter()
+        .filter(|entry| requested.contains(entry.id.as_str()))
+        .collect();
+
+    let injection = if scripts.is_empty() {
+        None
+    } else {
+        Some(inject_entries(window, registry, &scripts, true, verify).await?)
+    };
+
+    let found: HashSet<&str> = scripts.iter().map(|entry| entry.id.as_str()).collect();
+    let scripts = ids
+        .iter()
+        .map(|id| ScriptInjectionOutcome {
+            id: id.clone(),
+            status: if found.contains(id.trim()) {
+                ScriptInjectionStatus::Injected
+            } else {
+                ScriptInjectionStatus::NotFound
+            },
+        })
+        .collect();
+
+    Ok(match injection {
+        Some(injection) => InjectScriptsResult {
+            scripts,
+            strategy: Some(injection.strategy),
+            verified: injection.verified,
+            metrics: injection.metrics,
+        },
+        None => InjectScriptsResult {
+            scripts,
+            strategy: None,
+            verified: None,
+            metrics: Vec::new(),
+        },
+    })
+}
+
+/// Builds the script that hands `scripts` to bridge.js, then sets
+/// `window.__MCP_INJECTION_SENTINEL__` to `sentinel` when one is given.
+fn injection_script(
+    scripts: &[ScriptEntry],
+    reapply: bool,
+    sentinel: Option<&str>,
+) -> Result<String, InjectionError> {
     let scripts_json: Vec<serde_json::Value> = scripts
         .iter()
         .map(|entry| {
@@ -33,23 +632,286 @@ pub async fn request_script_injection<R: Runtime>(
                 "type": match entry.script_type {
                     ScriptType::Inline => "inline",
                     ScriptType::Url => "url",
+                    ScriptType::Module => "module",
                 },
-                "content": entry.content
+                "content": entry.content,
+                "external": entry.script_type == ScriptType::Module && entry.is_module_url(),
+                "hash": entry.content_hash(),
+                "cache": entry.cache
             })
         })
         .collect();
 
-    let inject_script = format!(
-        "if (window.__MCP_INJECT_SCRIPTS__) {{ window.__MCP_INJECT_SCRIPTS__({}); }}",
-        serde_json::to_string(&scripts_json).unwrap_or_else(|_| "[]".to_string())
+    let payload = serde_json::to_string(&scripts_json)
+        .map_err(|e| InjectionError::Serialization(e.to_string()))?;
+    let sentinel = sentinel
+        .map(|token| {
+            format!(
+                " window.__MCP_INJECTION_SENTINEL__ = {};",
+                serde_json::Value::String(token.to_string())
+            )
+        })
+        .unwrap_or_default();
+    Ok(format!(
+        "if (window.__MCP_INJECT_SCRIPTS__) {{ window.__MCP_INJECT_SCRIPTS__({payload}, {{ reapply: {reapply} }});{sentinel} }}"
+    ))
+}
+
+/// Reads back the sentinel set by the injection script. A failed read
+/// counts as unverified, since it goes through `eval` as well.
+async fn verify_sentinel<R: Runtime>(window: &WebviewWindow<R>, sentinel: &str) -> bool {
+    let script = format!(
+        "return window.__MCP_INJECTION_SENTINEL__ === {};",
+        serde_json::Value::String(sentinel.to_string())
     );
+    match run_script(window, &script).await {
+        Ok(found) => found.as_bool().unwrap_or(false),
+        Err(e) => {
+            mcp_log_error("SCRIPTS", &format!("Failed to verify injection: {e}"));
+            false
+        }
+    }
+}
+
+/// Joins the run times bridge.js reported with the size of each script.
+fn injection_metrics(scripts: &[ScriptEntry], ack: Vec<AckMetric>) -> Vec<ScriptInjectionMetric> {
+    let sizes: HashMap<&str, usize> = scripts
+        .iter()
+        .map(|entry| (entry.id.as_str(), entry.content.len()))
+        .collect();
+    ack.into_iter()
+        .filter_map(|metric| {
+            Some(ScriptInjectionMetric {
+                bytes: *sizes.get(metric.id.as_str())?,
+                id: metric.id,
+                duration_ms: metric.duration_ms,
+            })
+        })
+        .collect()
+}
+
+/// Outcome of [`inject_entries`].
+struct Injection {
+    strategy: InjectionStrategy,
+    verified: Option<bool>,
+    metrics: Vec<ScriptInjectionMetric>,
+}
+
+/// Evaluates `scripts` in the webview through bridge.js and records them
+/// as injected, along with each script's run time. With `verify`, also
+/// reports whether the code ran.
+async fn inject_entries<R: Runtime>(
+    window: &WebviewWindow<R>,
+    registry: &SharedScriptRegistry,
+    scripts: &[ScriptEntry],
+    reapply: bool,
+    verify: bool,
+) -> Result<Injection, String> {
+    let sentinel = verify.then(|| {
+        format!(
+            "{}-{}",
+            current_timestamp(),
+            NEXT_SENTINEL.fetch_add(1, Ordering::Relaxed)
+        )
+    });
+    let inject_script =
+        injection_script(scripts, reapply, sentinel.as_deref()).map_err(|e| e.to_string())?;
 
-    window
-        .eval(&inject_script)
-        .map_err(|e| format!("Failed to inject scripts: {e}"))?;
+    let strategy = eval_with_limit(window, &inject_script)
+        .map_err(|e| InjectionError::from_eval_message(e.to_string()).to_string())?;
 
-    Ok(serde_json::json!({
-        "injected": scripts.len(),
-        "scriptIds": scripts.iter().map(|s| s.id.clone()).collect::<Vec<_>>()
-    }))
+    // Evals on one webview run in order, so the injection has finished
+    let ack = match run_script(window, TAKE_INJECTION_ACK_SCRIPT).await {
+        Ok(ack) => serde_json::from_value::<InjectionAck>(ack).unwrap_or_default(),
+        Err(e) => {
+            mcp_log_error(
+                "SCRIPTS",
+                &format!("Failed to check for injection errors: {e}"),
+            );
+            InjectionAck::default()
+        }
+    };
+    if let Some(message) = ack.error {
+        return Err(InjectionError::from_eval_message(message).to_string());
+    }
+    let metrics = injection_metrics(scripts, ack.metrics);
+    window.state::<InjectionMetrics>().record(&metrics);
+
+    if let Ok(mut reg) = registry.lock() {
+        reg.mark_injected(
+            window.label(),
+            scripts.iter().map(|s| s.id.as_str()),
+            current_timestamp(),
+        );
+    }
+
+    let verified = match &sentinel {
+        Some(sentinel) => Some(verify_sentinel(window, sentinel).await),
+        None => None,
+    };
+    Ok(Injection {
+        strategy,
+        verified,
+        metrics,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_injection_result_shape_is_stable() {
+        let scripts = [ScriptEntry::new("a", ScriptType::Inline, "1")];
+        let paused = InjectionResult::not_injected(&scripts, "Script injection is paused");
+        assert_eq!(
+            serde_json::to_value(paused).unwrap(),
+            serde_json::json!({
+                "injected": 0,
+                "scriptIds": [],
+                "skipped": ["a"],
+                "message": "Script injection is paused",
+                "strategy": null,
+                "metrics": []
+            })
+        );
+    }
+
+    #[test]
+    fn test_injection_metrics_roll_over_recent_injections() {
+        let scripts = [ScriptEntry::new("slow", ScriptType::Inline, "while (0) {}")];
+        let ack = |duration_ms: f64| {
+            vec![
+                AckMetric {
+                    id: "slow".to_string(),
+                    duration_ms: Some(duration_ms),
+                },
+                AckMetric {
+                    id: "unknown".to_string(),
+                    duration_ms: Some(1.0),
+                },
+            ]
+        };
+
+        let first = injection_metrics(&scripts, ack(100.0));
+        assert_eq!(first.len(), 1);
+        assert_eq!(first[0].bytes, 12);
+
+        let metrics = InjectionMetrics::default();
+        metrics.record(&first);
+        for _ in 0..METRICS_WINDOW {
+            metrics.record(&injection_metrics(&scripts, ack(2.0)));
+        }
+        let cost = metrics.cost("slow").unwrap();
+        assert_eq!(cost.samples, METRICS_WINDOW);
+        assert_eq!(cost.average_duration_ms, 2.0);
+        assert!(metrics.cost("unknown").is_none());
+    }
+
+    #[test]
+    fn test_reapply_guard_skips_bursts_per_window() {
+        let guard = ReapplyGuard::default();
+        let start = Instant::now();
+
+        assert!(guard.try_begin("main", start));
+        assert!(!guard.try_begin("main", start + Duration::from_millis(100)));
+        assert!(guard.try_begin("other", start + Duration::from_millis(100)));
+        assert!(guard.try_begin("main", start + MIN_REAPPLY_INTERVAL));
+    }
+
+    #[test]
+    fn test_watchdog_claims_only_unreported_current_load() {
+        let watchdog = NavigationWatchdog::default();
+        assert!(!watchdog.claim("main", 1));
+
+        let first = watchdog.page_started("main");
+        assert!(watchdog.claim("main", first));
+        assert!(!watchdog.claim("main", first));
+
+        // bridge.js reported the page itself
+        let second = watchdog.page_started("main");
+        watchdog.mark_injected("main");
+        assert!(!watchdog.claim("main", second));
+
+        // A newer load supersedes a pending one
+        let third = watchdog.page_started("main");
+        let fourth = watchdog.page_started("main");
+        assert!(!watchdog.claim("main", third));
+        assert!(watchdog.claim("main", fourth));
+    }
+
+    #[test]
+    fn test_csp_eval_errors_are_classified() {
+        let webkit = "EvalError: Refused to evaluate a string as JavaScript because \
+                      'unsafe-eval' is not an allowed source of script in the following \
+                      Content Security Policy directive: \"script-src 'self'\".";
+        let error = InjectionError::from_eval_message(webkit);
+        assert!(matches!(error, InjectionError::CspBlocked(_)));
+        assert_eq!(error.code(), "CSP_BLOCKED");
+        assert!(error.to_string().contains("Builder::initialization_script"));
+
+        let firefox = "call to eval() blocked by CSP";
+        assert!(matches!(
+            InjectionError::from_eval_message(firefox),
+            InjectionError::CspBlocked(_)
+        ));
+
+        let other = InjectionError::from_eval_message("SyntaxError: Unexpected token");
+        assert_eq!(other.code(), "INJECTION_FAILED");
+    }
+
+    #[test]
+    fn test_split_reassembles_original() {
+        let script = "console.log(\"héllo\\n\u{1F600}\");\n".repeat(200);
+        let chunks = split_string_literal(&script, 300);
+
+        assert!(chunks.len() > 1);
+        let mut rebuilt = String::new();
+        for chunk in &chunks {
+            assert!(chunk.len() <= 300);
+            rebuilt.push_str(&serde_json::from_str::<String>(chunk).unwrap());
+        }
+        assert_eq!(rebuilt, script);
+    }
+
+    #[test]
+    fn test_injection_status_serializes_snake_case() {
+        let outcome = ScriptInjectionOutcome {
+            id: "missing".to_string(),
+            status: ScriptInjectionStatus::NotFound,
+        };
+        assert_eq!(
+            serde_json::to_value(&outcome).unwrap(),
+            serde_json::json!({ "id": "missing", "status": "not_found" })
+        );
+    }
+
+    #[test]
+    fn test_injection_payload_round_trips_escapes() {
+        let content = "const s = \"it's \\\"quoted\\\"\\\\path\";\n\
+                       document.title = '</script>\u{2028}héllo \u{1F600}\0';";
+        let entry = ScriptEntry::new("stress \"id\"", ScriptType::Inline, content);
+        let script = injection_script(&[entry], false, None).unwrap();
+
+        let prefix = "window.__MCP_INJECT_SCRIPTS__(";
+        let start = script.find(prefix).unwrap() + prefix.len();
+        let end = script.rfind(", { reapply: false }").unwrap();
+        let payload: serde_json::Value = serde_json::from_str(&script[start..end]).unwrap();
+        assert_eq!(payload[0]["id"], "stress \"id\"");
+        assert_eq!(payload[0]["content"], content);
+    }
+
+    #[test]
+    fn test_injection_script_sets_sentinel_after_injecting() {
+        let entry = ScriptEntry::new("a", ScriptType::Inline, "1");
+        let script = injection_script(&[entry], true, Some("42-0")).unwrap();
+        assert!(script
+            .ends_with(r#"{ reapply: true }); window.__MCP_INJECTION_SENTINEL__ = "42-0"; }"#));
+    }
+
+    #[test]
+    fn test_split_small_script_is_single_chunk() {
+        let chunks = split_string_literal("let a = 1;", 1024);
+        assert_eq!(chunks, ["\"let a = 1;\""]);
+    }
 }
@@ -0,0 +1,326 @@
+//! Registering scripts listed in a remote manifest.
+//!
+//! A manifest is a JSON array of [`ScriptEntry`] objects, served from one of
+//! the hosts allowed with [`crate::Builder::allow_manifest_host`]. Entries
+//! are checked one by one, so a bad entry is reported without dropping the
+//! rest. Valid entries are added to the global scope and injected on the
+//! next page load whose URL fits the entry's `matches` patterns, like any
+//! other registered script. Entries whose IDs are already registered are
+//! rejected rather than replacing the existing script. Downloading requires
+//! the `remote-manifests` feature.
+
+use serde::{Deserialize, Serialize};
+use std::collections::HashSet;
+use std::net::IpAddr;
+use tauri::Url;
+
+use crate::script_registry::{
+    normalize_script_id, ScopedScriptRegistry, ScriptEntry, ScriptScope, SharedScriptRegistry,
+};
+use crate::Config;
+
+/// A manifest entry that wasn't registered.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "ts-bindings", derive(ts_rs::TS))]
+#[serde(rename_all = "camelCase")]
+pub struct RejectedScript {
+    /// Position of the entry in the manifest
+    pub index: usize,
+    /// The entry's ID, when it has one
+    pub id: Option<String>,
+    pub reason: String,
+}
+
+/// Outcome of [`load_script_manifest`].
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "ts-bindings", derive(ts_rs::TS))]
+#[serde(rename_all = "camelCase")]
+pub struct ScriptManifestResult {
+    pub url: String,
+    /// IDs of the scripts added to the registry
+    pub registered: Vec<String>,
+    pub rejected: Vec<RejectedScript>,
+}
+
+/// Checks that `url` points at an allowed manifest host, over HTTPS unless
+/// the host is a loopback address.
+fn check_manifest_url(url: &str, allowed_hosts: &[String]) -> Result<Url, String> {
+    let url = Url::parse(url).map_err(|e| format!("Invalid manifest URL '{url}': {e}"))?;
+    let host = url
+        .host_str()
+        .ok_or_else(|| format!("Manifest URL '{url}' has no host"))?;
+
+    if !allowed_hosts
+        .iter()
+        .any(|allowed| allowed.eq_ignore_ascii_case(host))
+    {
+        return Err(format!(
+            "Manifest host '{host}' is not allowed; add it with Builder::allow_manifest_host"
+        ));
+    }
+
+    let loopback = host.eq_ignore_ascii_case("localhost")
+        || host
+            .trim_matches(|c| c == '[' || c == ']')
+            .parse::<IpAddr>()
+            .is_ok_and(|ip| ip.is_loopback());
+    match url.scheme() {
+        "https" => Ok(url),
+        "http" if loopback => Ok(url),
+        scheme => Err(format!(
+            "Manifest URL must use https (got '{scheme}'), except on loopback hosts"
+        )),
+    }
+}
+
+/// A parsed manifest, split into the entries that can be registered and
+/// the ones that can't.
+struct ParsedManifest {
+    /// Valid entries with their positions in the manifest
+    entries: Vec<(usize, ScriptEntry)>,
+    rejected: Vec<RejectedScript>,
+}
+
+fn parse_manifest(body: &[u8]) -> Result<ParsedManifest, String> {
+    let items: Vec<serde_json::Value> = serde_json::from_slice(body)
+        .map_err(|e| format!("Manifest is not a JSON array of scripts: {e}"))?;
+
+    let mut entries = Vec::new();
+    let mut rejected = Vec::new();
+    let mut ids = HashSet::new();
+
+    for (index, item) in items.into_iter().enumerate() {
+        let id = item.get("id").and_then(|v| v.as_str()).map(str::to_string);
+        let reject = |reason: String| RejectedScript {
+            index,
+            id: id.clone(),
+            reason,
+        };
+
+        let mut entry = match serde_json::from_value::<ScriptEntry>(item) {
+            Ok(entry) => entry,
+            Err(e) => {
+                rejected.push(reject(e.to_string()));
+                continue;
+            }
+        };
+        // Entries carry the trimmed ID the registry stores, so duplicates
+        // here and in the registry are found by it
+        match entry
+            .validate()
+            .and_then(|()| normalize_script_id(&entry.id))
+        {
+            Err(e) => rejected.push(reject(e.to_string())),
+            Ok(id) if ids.contains(&id) => {
+                rejected.push(reject("id is used more than once".to_string()))
+            }
+            Ok(id) => {
+                ids.insert(id.clone());
+                entry.id = id;
+                entries.push((index, entry));
+            }
+        }
+    }
+
+    Ok(ParsedManifest { entries, rejected })
+}
+
+/// Adds parsed entries, with trimmed IDs, to the global scope and returns
+/// their IDs, moving entries that can't be added to `rejected`.
+///
+/// An ID that is already registered globally is rejected instead of
+/// replacing the existing script.
+fn register_entries(
+    registry: &mut ScopedScriptRegistry,
+    entries: Vec<(usize, ScriptEntry)>,
+    rejected: &mut Vec<RejectedScript>,
+) -> Vec<String> {
+    let mut registered = Vec::new();
+    for (index, entry) in entries {
+        let id = entry.id.clone();
+        let reject = |reason: String| RejectedScript {
+            index,
+            id: Some(id.clone()),
+            reason,
+        };
+
+        let taken = registry
+            .scope(&ScriptScope::Global)
+            .is_some_and(|global| global.contains(&id));
+        if taken {
+            rejected.push(reject("id already registered".to_string()));
+            continue;
+        }
+        match registry.add(ScriptScope::Global, entry) {
+            Ok(id) => registered.push(id),
+            Err(e) => rejected.push(reject(e.to_string())),
+        }
+    }
+    registered
+}
+
+/// Downloads a manifest, failing once it grows past `max_bytes`.
+///
+/// Redirects aren't followed, since they could lead off the allowed hosts.
+#[cfg(feature = "remote-manifests")]
+async fn fetch_manifest(url: &Url, max_bytes: usize) -> Result<Vec<u8>, String> {
+    let too_large = || format!("Manifest is larger than {max_bytes} bytes");
+
+    let client = reqwest::Client::builder()
+        .redirect(reqwest::redirect::Policy::none())
+        .timeout(std::time::Duration::from_secs(30))
+        .build()
+        .map_err(|e| format!("Failed to create HTTP client: {e}"))?;
+    let mut response = client
+        .get(url.as_str())
+        .send()
+        .await
+        .map_err(|e| format!("Failed to download manifest: {e}"))?;
+
+    if !response.status().is_success() {
+        return Err(format!(
+            "Failed to download manifest: HTTP {}",
+            response.status()
+        ));
+    }
+    if response
+        .content_length()
+        .is_some_and(|length| length > max_bytes as u64)
+    {
+        return Err(too_large());
+    }
+
+    let mut body = Vec::new();
+    while let Some(chunk) = response
+        .chunk()
+        .await
+        .map_err(|e| format!("Failed to download manifest: {e}"))?
+    {
+        if body.len() + chunk.len() > max_bytes {
+            return Err(too_large());
+        }
+        body.extend_from_slice(&chunk);
+    }
+    Ok(body)
+}
+
+#[cfg(not(feature = "remote-manifests"))]
+async fn fetch_manifest(_url: &Url, _max_bytes: usize) -> Result<Vec<u8>, String> {
+    Err(
+        "Script manifests are unavailable: the plugin was built without the `remote-manifests` feature"
+            .to_string(),
+    )
+}
+
+/// Downloads a script manifest and registers its valid entries globally.
+///
+/// # Arguments
+///
+/// * `registry` - The registry to add the scripts to
+/// * `config` - Plugin config holding the allowed hosts and size limit
+/// * `url` - URL of the manifest
+///
+/// # Returns
+///
+/// * `Ok(ScriptManifestResult)` - Registered script IDs and rejected entries
+/// * `Err(String)` - Error message if the URL isn't allowed, or the manifest
+///   can't be downloaded or isn't a JSON array
+pub async fn load_script_manifest(
+    registry: &SharedScriptRegistry,
+    config: &Config,
+    url: &str,
+) -> Result<ScriptManifestResult, String> {
+    let url = check_manifest_url(url, &config.manifest_hosts)?;
+    let body = fetch_manifest(&url, config.max_manifest_bytes).await?;
+    let ParsedManifest {
+        entries,
+        mut rejected,
+    } = parse_manifest(&body)?;
+
+    let registered = {
+        let mut registry = registry
+            .lock()
+            .map_err(|e| format!("Failed to lock registry: {e}"))?;
+        register_entries(&mut registry, entries, &mut rejected)
+    };
+
+    rejected.sort_by_key(|rejection| rejection.index);
+    Ok(ScriptManifestResult {
+        url: url.to_string(),
+        registered,
+        rejected,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::script_registry::ScriptType;
+
+    #[test]
+    fn test_check_manifest_url() {
+        let hosts = vec!["scripts.example.com".to_string(), "localhost".to_string()];
+
+        assert!(check_manifest_url("https://scripts.example.com/m.json", &hosts).is_ok());
+        assert!(check_manifest_url("http://localhost:8080/m.json", &hosts).is_ok());
+        assert!(check_manifest_url("http://scripts.example.com/m.json", &hosts).is_err());
+        assert!(check_manifest_url("https://evil.example.com/m.json", &hosts).is_err());
+        assert!(check_manifest_url("not a url", &hosts).is_err());
+    }
+
+    #[test]
+    fn test_parse_manifest_rejects_bad_entries() {
+        let body = br#"[
+            { "id": "a", "script_type": "inline", "content": "1" },
+            { "id": "b", "script_type": "inline", "content": " " },
+            { "id": "a", "script_type": "inline", "content": "2" },
+            { "id": "c" },
+            { "id": " a", "script_type": "inline", "content": "3" },
+            { "id": "d e", "script_type": "inline", "content": "4" },
+            { "id": "f", "script_type": "inline", "content": "5", "matches": ["*://*/*"] },
+            { "id": "g", "script_type": "inline", "content": "6", "matches": [""] }
+        ]"#;
+
+        let manifest = parse_manifest(body).unwrap();
+        let ids: Vec<&str> = manifest
+            .entries
+            .iter()
+            .map(|(_, e)| e.id.as_str())
+            .collect();
+        assert_eq!(ids, ["a", "f"]);
+        assert_eq!(manifest.entries[1].1.matches, ["*://*/*"]);
+        let indices: Vec<usize> = manifest.rejected.iter().map(|r| r.index).collect();
+        assert_eq!(indices, [1, 2, 3, 4, 5, 7]);
+        assert!(parse_manifest(br#"{ "scripts": [] }"#).is_err());
+    }
+
+    #[test]
+    fn test_register_entries_keeps_existing_scripts() {
+        let mut registry = ScopedScriptRegistry::new();
+        registry
+            .add(
+                ScriptScope::Global,
+                ScriptEntry::new("app", ScriptType::Inline, "original()"),
+            )
+            .unwrap();
+
+        let entries = vec![
+            (0, ScriptEntry::new("app", ScriptType::Inline, "replaced()")),
+            (1, ScriptEntry::new("extra", ScriptType::Inline, "1")),
+        ];
+        let mut rejected = Vec::new();
+        let registered = register_entries(&mut registry, entries, &mut rejected);
+
+        assert_eq!(registered, ["extra"]);
+        assert_eq!(
+            rejected,
+            [RejectedScript {
+                index: 0,
+                id: Some("app".to_string()),
+                reason: "id already registered".to_string(),
+            }]
+        );
+        let global = registry.scope(&ScriptScope::Global).unwrap();
+        assert_eq!(global.get("app").unwrap().content, "original()");
+    }
+}
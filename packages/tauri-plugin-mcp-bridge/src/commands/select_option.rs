@@ -0,0 +1,114 @@
+//! Choosing options in `<select>` elements, whose native popups can't be
+//! driven by clicks.
+
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use tauri::{Runtime, WebviewWindow};
+
+use super::execute_js::run_script;
+
+/// Which option [`select_option`] picks.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "ts-bindings", derive(ts_rs::TS))]
+#[serde(rename_all = "camelCase")]
+pub enum OptionMatch {
+    /// The option whose `value` attribute equals the string
+    Value(String),
+    /// The option whose visible text, trimmed, equals the string
+    Label(String),
+    /// The option at this position, counting from zero
+    Index(u32),
+}
+
+/// Outcome of [`select_option`].
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "ts-bindings", derive(ts_rs::TS))]
+#[serde(rename_all = "camelCase")]
+pub struct SelectOptionResult {
+    /// The select's `value` after the change
+    pub value: String,
+    /// Visible text of the chosen option
+    pub label: String,
+    /// Position of the chosen option
+    pub index: u32,
+    /// Whether the option wasn't already selected; `change` is only
+    /// dispatched when it wasn't
+    pub changed: bool,
+}
+
+fn select_script(selector: &str, by: &OptionMatch) -> String {
+    format!(
+        r#"return (() => {{
+    const by = {by};
+    const select = document.querySelector({selector});
+    if (!select) throw new Error('No element matches selector: ' + {selector});
+    if (!(select instanceof HTMLSelectElement)) {{
+        throw new Error('Element is a <' + select.tagName.toLowerCase() + '>, not a <select>');
+    }}
+
+    const options = Array.from(select.options);
+    const option = 'index' in by
+        ? options[by.index]
+        : options.find((o) => 'value' in by ? o.value === by.value : o.text.trim() === by.label);
+    if (!option) throw new Error('No option matches ' + JSON.stringify(by));
+    if (option.disabled) throw new Error('Option ' + JSON.stringify(option.text.trim()) + ' is disabled');
+
+    const changed = !option.selected;
+    if (changed) {{
+        option.selected = true;
+        select.dispatchEvent(new Event('input', {{ bubbles: true, composed: true }}));
+        select.dispatchEvent(new Event('change', {{ bubbles: true }}));
+    }}
+    return {{ value: select.value, label: option.text.trim(), index: option.index, changed }};
+}})();"#,
+        by = serde_json::to_string(by).unwrap_or_else(|_| "{}".to_string()),
+        selector = Value::String(selector.to_string()),
+    )
+}
+
+/// Selects an option of the `<select>` matching `selector` and dispatches
+/// `input` and `change`, as choosing it from the popup would.
+///
+/// In a single-choice select the previous option is deselected; in a
+/// `multiple` select the option is added to the selection.
+///
+/// # Arguments
+///
+/// * `window` - The webview window containing the select
+/// * `selector` - CSS selector of the `<select>` element
+/// * `by` - The option to choose, by value, label or index
+///
+/// # Returns
+///
+/// * `Ok(SelectOptionResult)` - The select's resulting value
+/// * `Err(String)` - Error message if the selector matches nothing or a
+///   non-select element, no enabled option matches, or the script could
+///   not be evaluated
+pub async fn select_option<R: Runtime>(
+    window: &WebviewWindow<R>,
+    selector: &str,
+    by: &OptionMatch,
+) -> Result<SelectOptionResult, String> {
+    let result = run_script(window, &select_script(selector, by)).await?;
+    serde_json::from_value(result).map_err(|e| format!("Unexpected select result: {e}"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_option_match_shapes() {
+        let by: OptionMatch =
+            serde_json::from_value(serde_json::json!({ "label": "Red" })).unwrap();
+        assert_eq!(by, OptionMatch::Label("Red".to_string()));
+        let by: OptionMatch = serde_json::from_value(serde_json::json!({ "index": 2 })).unwrap();
+        assert_eq!(by, OptionMatch::Index(2));
+        assert!(
+            serde_json::from_value::<OptionMatch>(serde_json::json!({ "text": "Red" })).is_err()
+        );
+
+        let script = select_script("#color", &OptionMatch::Value("r".to_string()));
+        assert!(script.contains(r#"const by = {"value":"r"};"#));
+    }
+}
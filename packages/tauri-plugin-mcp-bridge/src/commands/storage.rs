@@ -0,0 +1,166 @@
+//! Web storage access for seeding and inspecting test state.
+
+use serde::Deserialize;
+use serde_json::Value;
+use tauri::{Runtime, WebviewWindow};
+
+use super::execute_js::run_script;
+
+/// Which web storage area to access.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[cfg_attr(feature = "ts-bindings", derive(ts_rs::TS))]
+#[serde(rename_all = "lowercase")]
+pub enum StorageArea {
+    /// `window.localStorage`
+    Local,
+    /// `window.sessionStorage`
+    Session,
+}
+
+impl StorageArea {
+    /// Name of the storage object on `window`.
+    fn js_object(self) -> &'static str {
+        match self {
+            StorageArea::Local => "localStorage",
+            StorageArea::Session => "sessionStorage",
+        }
+    }
+}
+
+/// Storage command error types
+#[derive(Debug, thiserror::Error)]
+pub enum StorageError {
+    #[error("Storage quota exceeded: {0}")]
+    QuotaExceeded(String),
+
+    #[error("Storage access failed: {0}")]
+    ScriptFailed(String),
+}
+
+impl StorageError {
+    /// Machine-readable error code for clients.
+    pub fn code(&self) -> &'static str {
+        match self {
+            StorageError::QuotaExceeded(_) => "QUOTA_EXCEEDED",
+            StorageError::ScriptFailed(_) => "SCRIPT_FAILED",
+        }
+    }
+}
+
+/// Sets a storage key and returns its previous value.
+///
+/// # Arguments
+///
+/// * `window` - The webview window whose storage should be written
+/// * `area` - `local` or `session` storage
+/// * `key` - Storage key
+/// * `value` - New value
+///
+/// # Returns
+///
+/// * `Ok(Option<String>)` - The previous value, or `None` if the key was unset
+/// * `Err(StorageError::QuotaExceeded)` - The browser rejected the write
+/// * `Err(StorageError::ScriptFailed)` - Storage could not be accessed
+pub async fn storage_set<R: Runtime>(
+    window: &WebviewWindow<R>,
+    area: StorageArea,
+    key: &str,
+    value: &str,
+) -> Result<Option<String>, StorageError> {
+    let result = run_script(window, &set_script(area, key, value))
+        .await
+        .map_err(StorageError::ScriptFailed)?;
+
+    if result
+        .get("quotaExceeded")
+        .and_then(|v| v.as_bool())
+        .unwrap_or(false)
+    {
+        let message = result
+            .get("message")
+            .and_then(|v| v.as_str())
+            .unwrap_or("quota exceeded");
+        return Err(StorageError::QuotaExceeded(message.to_string()));
+    }
+
+    Ok(string_or_none(result.get("previous")))
+}
+
+/// Reads a storage key.
+///
+/// # Returns
+///
+/// * `Ok(Option<String>)` - The stored value, or `None` if the key is missing
+/// * `Err(StorageError::ScriptFailed)` - Storage could not be accessed
+pub async fn storage_get<R: Runtime>(
+    window: &WebviewWindow<R>,
+    area: StorageArea,
+    key: &str,
+) -> Result<Option<String>, StorageError> {
+    let result = run_script(window, &get_script(area, key))
+        .await
+        .map_err(StorageError::ScriptFailed)?;
+
+    Ok(string_or_none(Some(&result)))
+}
+
+fn string_or_none(value: Option<&Value>) -> Option<String> {
+    value.and_then(|v| v.as_str()).map(|s| s.to_string())
+}
+
+/// Quote a Rust string as a JavaScript string literal.
+fn js_string(value: &str) -> String {
+    Value::String(value.to_string()).to_string()
+}
+
+fn set_script(area: StorageArea, key: &str, value: &str) -> String {
+    format!(
+        r#"const __storage = window.{storage};
+const __previous = __storage.getItem({key});
+try {{
+    __storage.setItem({key}, {value});
+}} catch (e) {{
+    if (e && (e.name === 'QuotaExceededError' || e.name === 'NS_ERROR_DOM_QUOTA_REACHED' || e.code === 22)) {{
+        return {{ quotaExceeded: true, message: e.message || String(e) }};
+    }}
+    throw e;
+}}
+return {{ previous: __previous }};"#,
+        storage = area.js_object(),
+        key = js_string(key),
+        value = js_string(value),
+    )
+}
+
+fn get_script(area: StorageArea, key: &str) -> String {
+    format!(
+        "return window.{}.getItem({});",
+        area.js_object(),
+        js_string(key)
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_storage_area_parses_lowercase() {
+        let area: StorageArea = serde_json::from_value(serde_json::json!("session")).unwrap();
+        assert_eq!(area, StorageArea::Session);
+        assert!(serde_json::from_value::<StorageArea>(serde_json::json!("cookie")).is_err());
+    }
+
+    #[test]
+    fn test_scripts_quote_keys_and_values() {
+        let script = set_script(StorageArea::Local, "it's", "a \"b\"\n");
+        assert!(script.contains("window.localStorage"));
+        assert!(script.contains(r#"setItem("it's", "a \"b\"\n")"#));
+
+        let script = get_script(StorageArea::Session, "</script>");
+        assert_eq!(
+            script,
+            r#"return window.sessionStorage.getItem("</script>");"#
+        );
+    }
+}
@@ -0,0 +1,238 @@
+//! Reading and overriding the webview's User-Agent string.
+//!
+//! The override goes through the webview itself, so it also changes the
+//! `User-Agent` header sent with requests: WebView2's settings on Windows,
+//! `customUserAgent` on macOS and iOS, and `WebSettings` on Android.
+//! WebKitGTK isn't reachable from the plugin, so Linux returns an error;
+//! `emulate` can still override `navigator.userAgent` there. A page keeps
+//! the User-Agent it loaded with until it reloads.
+
+use serde::{Deserialize, Serialize};
+use tauri::{Runtime, WebviewWindow};
+
+use super::execute_js::run_script;
+
+/// How long to wait for the webview to apply an override.
+#[cfg(any(target_os = "windows", target_vendor = "apple", target_os = "android"))]
+const OVERRIDE_TIMEOUT: std::time::Duration = std::time::Duration::from_secs(5);
+
+/// Whether this platform supports [`set_user_agent`].
+pub const USER_AGENT_OVERRIDE_SUPPORTED: bool = cfg!(any(
+    target_os = "windows",
+    target_vendor = "apple",
+    target_os = "android"
+));
+
+const RELOAD_NOTE: &str = "The new User-Agent applies to requests made from now on. \
+     navigator.userAgent and the loaded page keep the old value until the page reloads.";
+
+/// The User-Agent the page currently reports.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "ts-bindings", derive(ts_rs::TS))]
+#[serde(rename_all = "camelCase")]
+pub struct UserAgentInfo {
+    /// `navigator.userAgent` as seen by the page
+    pub user_agent: String,
+    /// Whether [`set_user_agent`] works on this platform
+    pub override_supported: bool,
+}
+
+/// Outcome of [`set_user_agent`].
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "ts-bindings", derive(ts_rs::TS))]
+#[serde(rename_all = "camelCase")]
+pub struct SetUserAgentResult {
+    /// The User-Agent the webview was given
+    pub user_agent: String,
+    /// Always `true`: the page must reload to see the new value
+    pub requires_reload: bool,
+    pub note: String,
+}
+
+/// Reads `navigator.userAgent` from a window's page.
+///
+/// # Returns
+///
+/// * `Ok(UserAgentInfo)` - The page's User-Agent
+/// * `Err(String)` - Error message if the page couldn't be evaluated
+pub async fn get_user_agent<R: Runtime>(
+    window: &WebviewWindow<R>,
+) -> Result<UserAgentInfo, String> {
+    let user_agent = run_script(window, "return navigator.userAgent;").await?;
+    let user_agent = user_agent
+        .as_str()
+        .ok_or("Unexpected navigator.userAgent value")?
+        .to_string();
+
+    Ok(UserAgentInfo {
+        user_agent,
+        override_supported: USER_AGENT_OVERRIDE_SUPPORTED,
+    })
+}
+
+/// Overrides the User-Agent a window's webview sends and reports.
+///
+/// # Arguments
+///
+/// * `window` - The webview window to change
+/// * `user_agent` - The new User-Agent string
+///
+/// # Returns
+///
+/// * `Ok(SetUserAgentResult)` - The override was applied; reload to see it
+/// * `Err(String)` - Error message if the string is invalid, the platform
+///   has no override support, or the webview rejected it
+pub async fn set_user_agent<R: Runtime>(
+    window: &WebviewWindow<R>,
+    user_agent: &str,
+) -> Result<SetUserAgentResult, String> {
+    validate_user_agent(user_agent)?;
+    override_user_agent(window, user_agent).await?;
+
+    Ok(SetUserAgentResult {
+        user_agent: user_agent.to_string(),
+        requires_reload: true,
+        note: RELOAD_NOTE.to_string(),
+    })
+}
+
+/// Rejects strings that can't be sent as a header value.
+fn validate_user_agent(user_agent: &str) -> Result<(), String> {
+    if user_agent.trim().is_empty() {
+        return Err("User-Agent must not be empty".to_string());
+    }
+    if user_agent.chars().any(char::is_control) {
+        return Err("User-Agent must not contain control characters".to_string());
+    }
+    Ok(())
+}
+
+#[cfg(any(target_os = "windows", target_vendor = "apple", target_os = "android"))]
+type OverrideSender = tokio::sync::oneshot::Sender<Result<(), String>>;
+
+#[cfg(any(target_os = "windows", target_vendor = "apple", target_os = "android"))]
+async fn override_user_agent<R: Runtime>(
+    window: &WebviewWindow<R>,
+    user_agent: &str,
+) -> Result<(), String> {
+    let (tx, rx) = tokio::sync::oneshot::channel();
+    let user_agent = user_agent.to_string();
+
+    window
+        .with_webview(move |webview| apply_user_agent(webview, user_agent, tx))
+        .map_err(|e| format!("Failed to access webview: {e}"))?;
+
+    match tokio::time::timeout(OVERRIDE_TIMEOUT, rx).await {
+        Ok(Ok(result)) => result,
+        Ok(Err(_)) => Err("Webview dropped the User-Agent override".to_string()),
+        Err(_) => Err("Timed out waiting for the webview to apply the User-Agent".to_string()),
+    }
+}
+
+#[cfg(not(any(target_os = "windows", target_vendor = "apple", target_os = "android")))]
+async fn override_user_agent<R: Runtime>(
+    _window: &WebviewWindow<R>,
+    _user_agent: &str,
+) -> Result<(), String> {
+    Err("User-Agent override is not supported on this platform; \
+         use emulate to override navigator.userAgent instead"
+        .to_string())
+}
+
+#[cfg(target_os = "windows")]
+fn apply_user_agent(
+    webview: tauri::webview::PlatformWebview,
+    user_agent: String,
+    tx: OverrideSender,
+) {
+    use webview2_com::Microsoft::Web::WebView2::Win32::ICoreWebView2Settings2;
+    use windows::core::{Interface, HSTRING};
+
+    let result = unsafe {
+        webview
+            .controller()
+            .CoreWebView2()
+            .and_then(|core| core.Settings())
+            .and_then(|settings| settings.cast::<ICoreWebView2Settings2>())
+            .and_then(|settings| settings.SetUserAgent(&HSTRING::from(user_agent)))
+    };
+    let _ = tx.send(result.map_err(|e| format!("WebView2 rejected the User-Agent: {e}")));
+}
+
+#[cfg(target_vendor = "apple")]
+fn apply_user_agent(
+    webview: tauri::webview::PlatformWebview,
+    user_agent: String,
+    tx: OverrideSender,
+) {
+    use objc2::runtime::AnyObject;
+    use objc2_foundation::NSString;
+
+    unsafe {
+        // The typed WKWebView binding is macOS-only, so message it directly
+        let wkwebview: *mut AnyObject = webview.inner().cast();
+        let user_agent = NSString::from_str(&user_agent);
+        let _: () = objc2::msg_send![wkwebview, setCustomUserAgent: &*user_agent];
+    }
+    let _ = tx.send(Ok(()));
+}
+
+#[cfg(target_os = "android")]
+fn apply_user_agent(
+    webview: tauri::webview::PlatformWebview,
+    user_agent: String,
+    tx: OverrideSender,
+) {
+    use jni::objects::JValue;
+
+    webview.jni_handle().exec(move |env, _activity, webview| {
+        let result = (|| -> jni::errors::Result<()> {
+            let settings = env
+                .call_method(
+                    webview,
+                    "getSettings",
+                    "()Landroid/webkit/WebSettings;",
+                    &[],
+                )?
+                .l()?;
+            let user_agent = env.new_string(&user_agent)?;
+            env.call_method(
+                &settings,
+                "setUserAgentString",
+                "(Ljava/lang/String;)V",
+                &[JValue::Object(&user_agent)],
+            )?;
+            Ok(())
+        })();
+
+        if result.is_err() && env.exception_check().unwrap_or(false) {
+            let _ = env.exception_clear();
+        }
+        let _ = tx.send(result.map_err(|e| format!("WebView rejected the User-Agent: {e}")));
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_validate_user_agent() {
+        assert!(validate_user_agent("Mozilla/5.0 (X11; Linux x86_64)").is_ok());
+        assert!(validate_user_agent("  ").is_err());
+        assert!(validate_user_agent("Mozilla/5.0\r\nX-Injected: 1").is_err());
+    }
+
+    #[test]
+    fn test_set_result_serialization() {
+        let result = SetUserAgentResult {
+            user_agent: "Test/1.0".to_string(),
+            requires_reload: true,
+            note: RELOAD_NOTE.to_string(),
+        };
+        let value = serde_json::to_value(result).unwrap();
+        assert_eq!(value["userAgent"], json!("Test/1.0"));
+        assert_eq!(value["requiresReload"], json!(true));
+    }
+}
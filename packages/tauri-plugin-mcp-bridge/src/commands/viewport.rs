@@ -0,0 +1,39 @@
+//! Viewport and layout metrics for planning captures and scrolling.
+
+use serde_json::Value;
+use tauri::{Runtime, WebviewWindow};
+
+use super::execute_js::run_script;
+
+/// Gathers every metric in a single evaluation. Sizes and offsets are
+/// rounded to integer CSS pixels; the device pixel ratio is left as-is.
+const METRICS_SCRIPT: &str = r#"return (() => {
+    const root = document.documentElement;
+    const body = document.body;
+    return {
+        innerWidth: Math.round(window.innerWidth),
+        innerHeight: Math.round(window.innerHeight),
+        scrollWidth: Math.round(Math.max(root.scrollWidth, body ? body.scrollWidth : 0)),
+        scrollHeight: Math.round(Math.max(root.scrollHeight, body ? body.scrollHeight : 0)),
+        scrollX: Math.round(window.scrollX),
+        scrollY: Math.round(window.scrollY),
+        devicePixelRatio: window.devicePixelRatio || 1
+    };
+})();"#;
+
+/// Returns the viewport size, document scroll size, scroll offsets and
+/// device pixel ratio of a window.
+///
+/// # Arguments
+///
+/// * `window` - The webview window to measure
+///
+/// # Returns
+///
+/// * `Ok(Value)` - An object with `innerWidth`, `innerHeight`, `scrollWidth`,
+///   `scrollHeight`, `scrollX` and `scrollY` in integer CSS pixels, plus
+///   `devicePixelRatio`
+/// * `Err(String)` - Error message if the script could not be evaluated
+pub async fn get_viewport_metrics<R: Runtime>(window: &WebviewWindow<R>) -> Result<Value, String> {
+    run_script(window, METRICS_SCRIPT).await
+}
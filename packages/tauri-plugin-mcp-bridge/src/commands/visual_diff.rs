@@ -0,0 +1,138 @@
+//! Visual regression checks against a baseline image.
+
+use std::io::Cursor;
+
+use base64::Engine as _;
+use image::ImageFormat;
+use serde::{Deserialize, Serialize};
+use tauri::{Manager, Runtime, WebviewWindow};
+
+use super::resize_window::WindowSize;
+use crate::config::Config;
+use crate::error::CommandError;
+use crate::screenshot::{self, diff, CaptureOptions, ScreenshotOptions};
+
+/// Options for [`visual_diff`].
+#[derive(Debug, Clone, Deserialize)]
+#[cfg_attr(feature = "ts-bindings", derive(ts_rs::TS))]
+#[serde(rename_all = "camelCase", default)]
+pub struct VisualDiffOptions {
+    /// Maximum per-channel difference (0-255) before a pixel counts as changed
+    pub tolerance: u8,
+    /// Highest percentage of changed pixels that still counts as a match
+    pub threshold: f64,
+    /// Whether to return the diff image
+    pub include_diff_image: bool,
+}
+
+impl Default for VisualDiffOptions {
+    fn default() -> Self {
+        Self {
+            tolerance: 0,
+            threshold: 0.0,
+            include_diff_image: true,
+        }
+    }
+}
+
+/// Sizes of the two images when they can't be compared.
+#[derive(Debug, Clone, Serialize)]
+#[cfg_attr(feature = "ts-bindings", derive(ts_rs::TS))]
+pub struct SizeMismatch {
+    /// Size of the baseline image in pixels
+    pub baseline: WindowSize,
+    /// Size of the fresh capture in pixels
+    pub actual: WindowSize,
+}
+
+/// Result of comparing a fresh capture against a baseline.
+#[derive(Debug, Clone, Serialize)]
+#[cfg_attr(feature = "ts-bindings", derive(ts_rs::TS))]
+#[serde(rename_all = "camelCase")]
+pub struct VisualDiffResult {
+    /// Whether the capture matches the baseline within the threshold
+    #[serde(rename = "match")]
+    pub matches: bool,
+    /// Percentage of changed pixels (100 when the sizes differ)
+    pub diff_percent: f64,
+    /// Base64-encoded PNG highlighting changed pixels in red
+    pub diff_image_base64: Option<String>,
+    /// Set when the images have different dimensions and weren't compared
+    pub size_mismatch: Option<SizeMismatch>,
+}
+
+/// Captures the viewport and compares it against a baseline image on disk.
+///
+/// The capture is taken as a PNG at full resolution (subject to the max
+/// width environment variable), so the baseline should be captured the
+/// same way on the same display scale.
+///
+/// # Arguments
+///
+/// * `window` - The window to capture
+/// * `baseline_path` - Path to a PNG or JPEG baseline image
+/// * `options` - Tolerance, match threshold and diff image options
+///
+/// # Returns
+///
+/// * `Ok(VisualDiffResult)` - The comparison, including size mismatches
+/// * `Err(CommandError)` - Error if the baseline can't be read or the
+///   capture fails
+pub async fn visual_diff<R: Runtime>(
+    window: &WebviewWindow<R>,
+    baseline_path: &str,
+    options: &VisualDiffOptions,
+) -> Result<VisualDiffResult, CommandError> {
+    let baseline = image::open(baseline_path)
+        .map_err(|e| format!("Failed to read baseline '{baseline_path}': {e}"))?
+        .to_rgba8();
+
+    let capture_options = ScreenshotOptions {
+        capture: CaptureOptions {
+            timeout: window.state::<Config>().capture_timeout,
+            ..Default::default()
+        },
+        ..Default::default()
+    };
+    let capture = screenshot::capture_viewport_screenshot(window, &capture_options).await?;
+    let current = capture
+        .decode()
+        .map_err(|e| format!("Failed to decode capture: {e}"))?;
+
+    let Some(pixel_diff) = diff::compare(&baseline, &current, options.tolerance) else {
+        return Ok(VisualDiffResult {
+            matches: false,
+            diff_percent: 100.0,
+            diff_image_base64: None,
+            size_mismatch: Some(SizeMismatch {
+                baseline: size_of(&baseline),
+                actual: size_of(&current),
+            }),
+        });
+    };
+
+    let diff_image_base64 = if options.include_diff_image {
+        let mut buffer = Cursor::new(Vec::new());
+        pixel_diff
+            .diff_image
+            .write_to(&mut buffer, ImageFormat::Png)
+            .map_err(|e| format!("Failed to encode diff image: {e}"))?;
+        Some(base64::engine::general_purpose::STANDARD.encode(buffer.into_inner()))
+    } else {
+        None
+    };
+
+    Ok(VisualDiffResult {
+        matches: pixel_diff.diff_percent <= options.threshold,
+        diff_percent: pixel_diff.diff_percent,
+        diff_image_base64,
+        size_mismatch: None,
+    })
+}
+
+fn size_of(image: &image::RgbaImage) -> WindowSize {
+    WindowSize {
+        width: image.width(),
+        height: image.height(),
+    }
+}
@@ -0,0 +1,179 @@
+//! Waiting for an arbitrary page condition, expressed as a JavaScript
+//! expression.
+//!
+//! The expression is re-evaluated on a timer, or on DOM mutations with
+//! [`ConditionWait::Observe`], until it is truthy or the timeout fires. The
+//! expression runs in the page's global scope; an expression that throws
+//! ends the wait with its error instead of being retried.
+
+use std::time::Duration;
+
+use serde::{Deserialize, Serialize};
+use tauri::{Runtime, WebviewWindow};
+
+use super::execute_js::run_script_with_timeout;
+
+/// Polling interval used when none is given.
+pub const DEFAULT_POLL_INTERVAL: Duration = Duration::from_millis(100);
+
+/// Extra time given to the script on top of the wait timeout, so the page's
+/// own timeout fires first and reports the last result.
+const SCRIPT_TIMEOUT_MARGIN: Duration = Duration::from_secs(2);
+
+/// When [`wait_for_condition`] re-evaluates the condition.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ConditionWait {
+    /// On a fixed interval; sees any state, including JavaScript variables
+    Poll(Duration),
+    /// Whenever the DOM changes; only sees changes that touch the DOM
+    Observe,
+}
+
+/// Outcome of [`wait_for_condition`].
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "ts-bindings", derive(ts_rs::TS))]
+#[serde(rename_all = "camelCase")]
+pub struct ConditionResult {
+    /// Whether the condition was truthy when the wait ended
+    pub satisfied: bool,
+    /// How long the wait took, in milliseconds
+    #[cfg_attr(feature = "ts-bindings", ts(type = "number"))]
+    pub elapsed_ms: u64,
+    /// How many times the condition was evaluated
+    pub checks: u32,
+}
+
+fn condition_script(predicate: &str, timeout_ms: u128, wait: ConditionWait) -> String {
+    let (observe, interval_ms) = match wait {
+        ConditionWait::Poll(interval) => (false, interval.as_millis().max(1)),
+        ConditionWait::Observe => (true, 0),
+    };
+
+    // The newlines keep a trailing `//` comment in the predicate from
+    // swallowing the closing parenthesis
+    format!(
+        r#"return await (async () => {{
+    const timeoutMs = {timeout_ms};
+    const observe = {observe};
+    const intervalMs = {interval_ms};
+    const predicate = () => (
+{predicate}
+    );
+
+    const start = Date.now();
+    let checks = 0;
+
+    return await new Promise((resolve, reject) => {{
+        let observer = null;
+        let timer = null;
+        let timeout = null;
+        let done = false;
+
+        const stop = () => {{
+            done = true;
+            if (observer) observer.disconnect();
+            clearTimeout(timer);
+            clearTimeout(timeout);
+        }};
+
+        // Returns the condition's value, or null after ending the wait
+        // because it threw
+        const check = () => {{
+            checks++;
+            try {{
+                return !!predicate();
+            }} catch (error) {{
+                stop();
+                reject(new Error('Condition threw: ' + (error && error.message || String(error))));
+                return null;
+            }}
+        }};
+
+        const finish = (satisfied) => {{
+            stop();
+            resolve({{ satisfied, elapsedMs: Date.now() - start, checks }});
+        }};
+
+        const recheck = () => {{
+            if (done) return;
+            const result = check();
+            if (result === true) finish(true);
+            else if (result === false && !observe) timer = setTimeout(recheck, intervalMs);
+        }};
+
+        timeout = setTimeout(() => {{
+            if (done) return;
+            // Report the condition as it stands when time runs out
+            const result = check();
+            if (result !== null) finish(result);
+        }}, timeoutMs);
+
+        recheck();
+        if (observe && !done) {{
+            observer = new MutationObserver(recheck);
+            observer.observe(document.documentElement, {{
+                subtree: true,
+                childList: true,
+                attributes: true,
+                characterData: true
+            }});
+        }}
+    }});
+}})();"#
+    )
+}
+
+/// Waits until `predicate` evaluates to a truthy value.
+///
+/// `predicate` must be a single JavaScript expression. One that fails to
+/// parse isn't detected and runs into the timeout.
+///
+/// # Arguments
+///
+/// * `window` - The webview window to evaluate the condition in
+/// * `predicate` - JavaScript expression, such as
+///   `document.querySelectorAll('.row').length > 10`
+/// * `timeout` - Maximum time to wait
+/// * `wait` - Whether to poll or re-check on DOM mutations
+///
+/// # Returns
+///
+/// * `Ok(ConditionResult)` - `satisfied: true` once the condition held, or
+///   `satisfied: false` if the timeout fired first
+/// * `Err(String)` - Error message if the condition threw, or the script
+///   could not be evaluated
+pub async fn wait_for_condition<R: Runtime>(
+    window: &WebviewWindow<R>,
+    predicate: &str,
+    timeout: Duration,
+    wait: ConditionWait,
+) -> Result<ConditionResult, String> {
+    if predicate.trim().is_empty() {
+        return Err("Condition is empty".to_string());
+    }
+
+    let script = condition_script(predicate, timeout.as_millis(), wait);
+    let result = run_script_with_timeout(window, &script, timeout + SCRIPT_TIMEOUT_MARGIN).await?;
+
+    serde_json::from_value(result).map_err(|e| format!("Unexpected condition result: {e}"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_script_embeds_predicate_and_mode() {
+        let script = condition_script(
+            "window.ready // set by the app",
+            5000,
+            ConditionWait::Poll(Duration::from_millis(250)),
+        );
+        assert!(script.contains("const observe = false;"));
+        assert!(script.contains("const intervalMs = 250;"));
+        assert!(script.contains("\nwindow.ready // set by the app\n    );"));
+
+        let script = condition_script("true", 5000, ConditionWait::Observe);
+        assert!(script.contains("const observe = true;"));
+    }
+}
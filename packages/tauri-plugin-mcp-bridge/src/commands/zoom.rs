@@ -0,0 +1,135 @@
+//! Webview zoom, for accessibility testing and fitting more into a capture.
+//!
+//! The webview's native zoom is used where Tauri supports it. Android has no
+//! native zoom, and older macOS and iOS versions reject it; there the page
+//! is scaled with CSS `zoom`, or a `transform` if the engine lacks `zoom`.
+//! CSS scaling only lasts until the page navigates.
+
+use serde::{Deserialize, Serialize};
+use tauri::{Runtime, WebviewWindow};
+
+use super::execute_js::run_script;
+
+/// Smallest zoom factor accepted by [`set_zoom`].
+pub const MIN_ZOOM: f64 = 0.25;
+
+/// Largest zoom factor accepted by [`set_zoom`].
+pub const MAX_ZOOM: f64 = 5.0;
+
+/// How [`set_zoom`] applied the zoom.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "ts-bindings", derive(ts_rs::TS))]
+#[serde(rename_all = "camelCase")]
+pub enum ZoomMethod {
+    /// The webview's own zoom, as with the browser's zoom keys
+    Native,
+    /// CSS `zoom` on the root element
+    CssZoom,
+    /// CSS `transform: scale()` on the root element
+    CssTransform,
+}
+
+/// Outcome of [`set_zoom`].
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "ts-bindings", derive(ts_rs::TS))]
+#[serde(rename_all = "camelCase")]
+pub struct ZoomResult {
+    /// Zoom factor in effect
+    pub zoom: f64,
+    pub method: ZoomMethod,
+    /// Why native zoom wasn't used, when CSS scaling was applied instead
+    #[serde(default)]
+    pub fallback_reason: Option<String>,
+}
+
+fn css_zoom_script(factor: f64) -> String {
+    format!(
+        r#"return (() => {{
+    const factor = {factor};
+    const style = document.documentElement.style;
+    for (const property of ['zoom', 'transform', 'transform-origin', 'width']) {{
+        style.removeProperty(property);
+    }}
+
+    if (CSS.supports('zoom', String(factor))) {{
+        if (factor !== 1) style.setProperty('zoom', String(factor));
+        const zoom = parseFloat(getComputedStyle(document.documentElement).zoom);
+        return {{ zoom: zoom || factor, method: 'cssZoom' }};
+    }}
+
+    if (factor !== 1) {{
+        // Widen the root so the scaled page still fills the viewport
+        style.setProperty('transform', 'scale(' + factor + ')');
+        style.setProperty('transform-origin', '0 0');
+        style.setProperty('width', (100 / factor) + '%');
+    }}
+    return {{ zoom: factor, method: 'cssTransform' }};
+}})();"#
+    )
+}
+
+/// Sets the zoom factor of a window's webview.
+///
+/// # Arguments
+///
+/// * `window` - The webview window to zoom
+/// * `factor` - Zoom factor from [`MIN_ZOOM`] to [`MAX_ZOOM`]; `1.0` resets
+///
+/// # Returns
+///
+/// * `Ok(ZoomResult)` - The zoom in effect and how it was applied
+/// * `Err(String)` - Error message if the factor is out of range, or neither
+///   native zoom nor CSS scaling could be applied
+pub async fn set_zoom<R: Runtime>(
+    window: &WebviewWindow<R>,
+    factor: f64,
+) -> Result<ZoomResult, String> {
+    if !(MIN_ZOOM..=MAX_ZOOM).contains(&factor) {
+        return Err(format!(
+            "Zoom factor must be between {MIN_ZOOM} and {MAX_ZOOM}, got {factor}"
+        ));
+    }
+
+    let native_error = if cfg!(target_os = "android") {
+        "native zoom is not supported on Android".to_string()
+    } else {
+        match window.set_zoom(factor) {
+            Ok(()) => {
+                return Ok(ZoomResult {
+                    zoom: factor,
+                    method: ZoomMethod::Native,
+                    fallback_reason: None,
+                })
+            }
+            // macOS before 11 and iOS before 14
+            Err(e) => format!("native zoom failed: {e}"),
+        }
+    };
+
+    let result = run_script(window, &css_zoom_script(factor))
+        .await
+        .map_err(|e| format!("Zoom is unavailable: {native_error}, and CSS scaling failed: {e}"))?;
+    let mut result: ZoomResult =
+        serde_json::from_value(result).map_err(|e| format!("Unexpected zoom result: {e}"))?;
+    result.fallback_reason = Some(native_error);
+    Ok(result)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parses_css_fallback_result() {
+        let result: ZoomResult = serde_json::from_value(serde_json::json!({
+            "zoom": 1.5,
+            "method": "cssTransform"
+        }))
+        .unwrap();
+
+        assert_eq!(result.method, ZoomMethod::CssTransform);
+        assert_eq!(result.zoom, 1.5);
+        assert!(result.fallback_reason.is_none());
+        assert!(css_zoom_script(1.5).contains("const factor = 1.5;"));
+    }
+}
@@ -1,7 +1,38 @@
 //! Configuration for the MCP Bridge plugin.
 //!
 //! This module provides configuration options for customizing the plugin behavior,
-//! including the WebSocket server bind address.
+//! including the WebSocket server bind address, timeouts, and limits. The
+//! configuration is stored in managed state so commands can read it.
+
+use std::collections::HashMap;
+use std::path::PathBuf;
+use std::time::Duration;
+
+use crate::policy::PermissionSet;
+use crate::rate_limit::{default_rate_limits, RateLimit};
+use crate::screenshot::cache::DEFAULT_CAPTURE_CACHE_TTL;
+use crate::screenshot::limiter::DEFAULT_MAX_CONCURRENT_CAPTURES;
+use crate::screenshot::{DEFAULT_AUTO_FORMAT_THRESHOLD, DEFAULT_CAPTURE_TIMEOUT};
+use crate::script_registry::ScriptEntry;
+
+/// How long `execute_js` waits for a script result by default.
+pub const DEFAULT_SCRIPT_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// Largest script evaluated in a single `eval` call by default.
+pub const DEFAULT_MAX_INLINE_EVAL_BYTES: usize = 512 * 1024;
+
+/// Largest request or response body kept in the network log by default.
+pub const DEFAULT_MAX_NETWORK_BODY_BYTES: usize = 64 * 1024;
+
+/// How long bridge.js collects console calls before forwarding them by
+/// default.
+pub const DEFAULT_CONSOLE_BATCH_INTERVAL: Duration = Duration::from_millis(250);
+
+/// Console calls forwarded in one batch by default.
+pub const DEFAULT_CONSOLE_BATCH_SIZE: usize = 50;
+
+/// Largest script manifest `load_script_manifest` downloads by default.
+pub const DEFAULT_MAX_MANIFEST_BYTES: usize = 1024 * 1024;
 
 /// Configuration for the MCP Bridge plugin.
 #[derive(Clone, Debug)]
@@ -13,6 +44,77 @@ pub struct Config {
     /// The base port for the WebSocket server.
     /// Default: 9223. The plugin will scan up to 100 ports from this base.
     pub base_port: u16,
+    /// Whether agents may close windows through the `close_window` command.
+    /// Default: false, so the main window can't be closed by accident.
+    pub allow_close: bool,
+    /// How long to wait for a native screenshot before timing out.
+    /// Default: 10 seconds.
+    pub capture_timeout: Duration,
+    /// How many native captures may run at once across all windows.
+    /// Captures of the same window always run one at a time.
+    /// Default: 2.
+    pub max_concurrent_captures: usize,
+    /// How long a viewport capture may be reused for an identical request
+    /// while the page's DOM is unchanged.
+    /// Default: zero (disabled).
+    pub capture_cache_ttl: Duration,
+    /// Share of distinct colors above which `"auto"` captures are encoded
+    /// as JPEG instead of PNG. Higher values prefer PNG.
+    /// Default: 0.5.
+    pub auto_format_threshold: f64,
+    /// How long to wait for a JavaScript execution result.
+    /// Default: 5 seconds.
+    pub script_timeout: Duration,
+    /// CSS selector of the element whose content swaps on client-side
+    /// routing. Replacing a large part of it re-applies the registered
+    /// scripts.
+    /// Default: None (only page loads and `popstate` inject scripts).
+    pub reinject_root: Option<String>,
+    /// Whether to inject registered scripts from Rust after page loads
+    /// that bridge.js didn't report.
+    /// Default: false.
+    pub navigation_watchdog: bool,
+    /// Maximum number of scripts in the script registry.
+    /// Default: None (unlimited).
+    pub max_scripts: Option<usize>,
+    /// Largest script injection payload evaluated in a single `eval` call.
+    /// Larger payloads are sent in chunks.
+    /// Default: 512 KiB.
+    pub max_inline_eval_bytes: usize,
+    /// Largest request or response body recorded in the network log.
+    /// Longer bodies are truncated in the page before being sent.
+    /// Default: 64 KiB.
+    pub max_network_body_bytes: usize,
+    /// How long the page collects console calls before forwarding them.
+    /// Default: 250 milliseconds.
+    pub console_batch_interval: Duration,
+    /// Console calls that fill a batch, which is then forwarded without
+    /// waiting for the interval.
+    /// Default: 50.
+    pub console_batch_size: usize,
+    /// Hosts `load_script_manifest` may download manifests from.
+    /// Default: none, so manifests can't be loaded.
+    pub manifest_hosts: Vec<String>,
+    /// Largest script manifest `load_script_manifest` downloads.
+    /// Default: 1 MiB.
+    pub max_manifest_bytes: usize,
+    /// Which commands MCP clients may call over the WebSocket.
+    /// Default: all commands.
+    pub permissions: PermissionSet,
+    /// Per-command rate limits, keyed by command name.
+    /// Default: generous limits on captures and script evaluation, see
+    /// [`default_rate_limits`].
+    pub rate_limits: HashMap<String, RateLimit>,
+    /// Scripts run at the start of every page, after bridge.js.
+    /// Default: none.
+    pub initialization_scripts: Vec<String>,
+    /// Scripts added to the global script registry at startup.
+    /// Default: none.
+    pub scripts: Vec<ScriptEntry>,
+    /// JSON or TOML files listing more startup scripts, read when the
+    /// plugin is built.
+    /// Default: none.
+    pub scripts_files: Vec<PathBuf>,
 }
 
 impl Default for Config {
@@ -20,6 +122,26 @@ impl Default for Config {
         Self {
             bind_address: "0.0.0.0".to_string(),
             base_port: 9223,
+            allow_close: false,
+            capture_timeout: DEFAULT_CAPTURE_TIMEOUT,
+            max_concurrent_captures: DEFAULT_MAX_CONCURRENT_CAPTURES,
+            capture_cache_ttl: DEFAULT_CAPTURE_CACHE_TTL,
+            auto_format_threshold: DEFAULT_AUTO_FORMAT_THRESHOLD,
+            script_timeout: DEFAULT_SCRIPT_TIMEOUT,
+            reinject_root: None,
+            navigation_watchdog: false,
+            max_scripts: None,
+            max_inline_eval_bytes: DEFAULT_MAX_INLINE_EVAL_BYTES,
+            max_network_body_bytes: DEFAULT_MAX_NETWORK_BODY_BYTES,
+            console_batch_interval: DEFAULT_CONSOLE_BATCH_INTERVAL,
+            console_batch_size: DEFAULT_CONSOLE_BATCH_SIZE,
+            manifest_hosts: Vec::new(),
+            max_manifest_bytes: DEFAULT_MAX_MANIFEST_BYTES,
+            permissions: PermissionSet::full(),
+            rate_limits: default_rate_limits(),
+            initialization_scripts: Vec::new(),
+            scripts: Vec::new(),
+            scripts_files: Vec::new(),
         }
     }
 }
@@ -30,6 +152,26 @@ impl Config {
         Self {
             bind_address: bind_address.to_string(),
             base_port: 9223,
+            allow_close: false,
+            capture_timeout: DEFAULT_CAPTURE_TIMEOUT,
+            max_concurrent_captures: DEFAULT_MAX_CONCURRENT_CAPTURES,
+            capture_cache_ttl: DEFAULT_CAPTURE_CACHE_TTL,
+            auto_format_threshold: DEFAULT_AUTO_FORMAT_THRESHOLD,
+            script_timeout: DEFAULT_SCRIPT_TIMEOUT,
+            reinject_root: None,
+            navigation_watchdog: false,
+            max_scripts: None,
+            max_inline_eval_bytes: DEFAULT_MAX_INLINE_EVAL_BYTES,
+            max_network_body_bytes: DEFAULT_MAX_NETWORK_BODY_BYTES,
+            console_batch_interval: DEFAULT_CONSOLE_BATCH_INTERVAL,
+            console_batch_size: DEFAULT_CONSOLE_BATCH_SIZE,
+            manifest_hosts: Vec::new(),
+            max_manifest_bytes: DEFAULT_MAX_MANIFEST_BYTES,
+            permissions: PermissionSet::full(),
+            rate_limits: default_rate_limits(),
+            initialization_scripts: Vec::new(),
+            scripts: Vec::new(),
+            scripts_files: Vec::new(),
         }
     }
 
@@ -38,6 +180,26 @@ impl Config {
         Self {
             bind_address: "127.0.0.1".to_string(),
             base_port: 9223,
+            allow_close: false,
+            capture_timeout: DEFAULT_CAPTURE_TIMEOUT,
+            max_concurrent_captures: DEFAULT_MAX_CONCURRENT_CAPTURES,
+            capture_cache_ttl: DEFAULT_CAPTURE_CACHE_TTL,
+            auto_format_threshold: DEFAULT_AUTO_FORMAT_THRESHOLD,
+            script_timeout: DEFAULT_SCRIPT_TIMEOUT,
+            reinject_root: None,
+            navigation_watchdog: false,
+            max_scripts: None,
+            max_inline_eval_bytes: DEFAULT_MAX_INLINE_EVAL_BYTES,
+            max_network_body_bytes: DEFAULT_MAX_NETWORK_BODY_BYTES,
+            console_batch_interval: DEFAULT_CONSOLE_BATCH_INTERVAL,
+            console_batch_size: DEFAULT_CONSOLE_BATCH_SIZE,
+            manifest_hosts: Vec::new(),
+            max_manifest_bytes: DEFAULT_MAX_MANIFEST_BYTES,
+            permissions: PermissionSet::full(),
+            rate_limits: default_rate_limits(),
+            initialization_scripts: Vec::new(),
+            scripts: Vec::new(),
+            scripts_files: Vec::new(),
         }
     }
 }
@@ -56,6 +218,12 @@ impl Config {
 /// let plugin: tauri::plugin::TauriPlugin<tauri::Wry> = Builder::new()
 ///     .bind_address("127.0.0.1")
 ///     .build();
+///
+/// // Custom timeouts and limits:
+/// let plugin: tauri::plugin::TauriPlugin<tauri::Wry> = Builder::new()
+///     .capture_timeout(std::time::Duration::from_secs(20))
+///     .max_scripts(50)
+///     .build();
 /// ```
 pub struct Builder {
     config: Config,
@@ -113,6 +281,411 @@ impl Builder {
         self
     }
 
+    /// Allows agents to close windows with the `close_window` command.
+    ///
+    /// Disabled by default.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use tauri_plugin_mcp_bridge::Builder;
+    ///
+    /// let builder = Builder::new().allow_close(true);
+    /// ```
+    pub fn allow_close(mut self, allow: bool) -> Self {
+        self.config.allow_close = allow;
+        self
+    }
+
+    /// Sets how long to wait for a native screenshot before timing out.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use std::time::Duration;
+    /// use tauri_plugin_mcp_bridge::Builder;
+    ///
+    /// let builder = Builder::new().capture_timeout(Duration::from_secs(20));
+    /// ```
+    pub fn capture_timeout(mut self, timeout: Duration) -> Self {
+        self.config.capture_timeout = timeout;
+        self
+    }
+
+    /// Sets how many native captures may run at once across all windows.
+    ///
+    /// Captures of the same window are always serialized. A capture that
+    /// can't start within the capture timeout fails with `Timeout`.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use tauri_plugin_mcp_bridge::Builder;
+    ///
+    /// let builder = Builder::new().max_concurrent_captures(1);
+    /// ```
+    pub fn max_concurrent_captures(mut self, max: usize) -> Self {
+        self.config.max_concurrent_captures = max;
+        self
+    }
+
+    /// Re-applies registered scripts when the content of `root_selector` is
+    /// replaced, as client-side routers do when swapping `#app`.
+    ///
+    /// bridge.js watches the first matching element, or `body` if none
+    /// matches, and requests re-injection 100ms after a mutation removes at
+    /// least 50 elements. Scripts already on the page are removed and run
+    /// again, so they should tolerate running more than once.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use tauri_plugin_mcp_bridge::Builder;
+    ///
+    /// let builder = Builder::new().reinject_on_dom_replace("#app");
+    /// ```
+    pub fn reinject_on_dom_replace(mut self, root_selector: impl Into<String>) -> Self {
+        self.config.reinject_root = Some(root_selector.into());
+        self
+    }
+
+    /// Injects registered scripts from Rust when a page finishes loading and
+    /// bridge.js hasn't requested injection within 500ms.
+    ///
+    /// bridge.js requests injection on every page load, but it needs Tauri
+    /// IPC to do so, which remote pages without a matching capability lack.
+    /// Injection itself is evaluated from Rust and works there too. Loads
+    /// bridge.js does report aren't injected twice.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use tauri_plugin_mcp_bridge::Builder;
+    ///
+    /// let builder = Builder::new().navigation_watchdog(true);
+    /// ```
+    pub fn navigation_watchdog(mut self, enabled: bool) -> Self {
+        self.config.navigation_watchdog = enabled;
+        self
+    }
+
+    /// Sets how long a viewport capture may be reused.
+    ///
+    /// A repeated capture of the same window with the same options returns
+    /// the cached image while it is younger than `ttl` and no navigation or
+    /// DOM mutation happened since. Changes outside the DOM, such as CSS
+    /// animations or video, aren't detected, so keep this short.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use std::time::Duration;
+    /// use tauri_plugin_mcp_bridge::Builder;
+    ///
+    /// let builder = Builder::new().capture_cache_ttl(Duration::from_millis(250));
+    /// ```
+    pub fn capture_cache_ttl(mut self, ttl: Duration) -> Self {
+        self.config.capture_cache_ttl = ttl;
+        self
+    }
+
+    /// Sets the distinct color share above which `"auto"` captures are
+    /// encoded as JPEG.
+    ///
+    /// Values are clamped to `0.0..=1.0`; `1.0` always picks PNG.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use tauri_plugin_mcp_bridge::Builder;
+    ///
+    /// let builder = Builder::new().auto_format_threshold(0.7);
+    /// ```
+    pub fn auto_format_threshold(mut self, threshold: f64) -> Self {
+        self.config.auto_format_threshold = threshold.clamp(0.0, 1.0);
+        self
+    }
+
+    /// Sets how long to wait for a JavaScript execution result.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use std::time::Duration;
+    /// use tauri_plugin_mcp_bridge::Builder;
+    ///
+    /// let builder = Builder::new().script_timeout(Duration::from_secs(10));
+    /// ```
+    pub fn script_timeout(mut self, timeout: Duration) -> Self {
+        self.config.script_timeout = timeout;
+        self
+    }
+
+    /// Limits how many scripts the script registry can hold.
+    ///
+    /// Registering a new script beyond the limit fails. Replacing an
+    /// existing script is always allowed.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use tauri_plugin_mcp_bridge::Builder;
+    ///
+    /// let builder = Builder::new().max_scripts(50);
+    /// ```
+    pub fn max_scripts(mut self, max: usize) -> Self {
+        self.config.max_scripts = Some(max);
+        self
+    }
+
+    /// Sets the largest script injection payload evaluated in one `eval` call.
+    ///
+    /// Some WebView engines truncate or reject very large `eval` strings.
+    /// Payloads above this size are split into chunks that are buffered in
+    /// the page and executed once complete.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use tauri_plugin_mcp_bridge::Builder;
+    ///
+    /// let builder = Builder::new().max_inline_eval_bytes(256 * 1024);
+    /// ```
+    pub fn max_inline_eval_bytes(mut self, bytes: usize) -> Self {
+        self.config.max_inline_eval_bytes = bytes;
+        self
+    }
+
+    /// Sets the largest request or response body kept in the network log.
+    ///
+    /// Bodies are only recorded when the log is started with bodies enabled,
+    /// and longer bodies are truncated to this size.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use tauri_plugin_mcp_bridge::Builder;
+    ///
+    /// let builder = Builder::new().max_network_body_bytes(16 * 1024);
+    /// ```
+    pub fn max_network_body_bytes(mut self, bytes: usize) -> Self {
+        self.config.max_network_body_bytes = bytes;
+        self
+    }
+
+    /// Sets how long the page collects console calls before forwarding
+    /// them to the console log.
+    ///
+    /// Longer intervals mean fewer IPC calls from chatty pages, but
+    /// `get_console_logs` flushes the pending batch first either way.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use std::time::Duration;
+    /// use tauri_plugin_mcp_bridge::Builder;
+    ///
+    /// let builder = Builder::new().console_batch_interval(Duration::from_secs(1));
+    /// ```
+    pub fn console_batch_interval(mut self, interval: Duration) -> Self {
+        self.config.console_batch_interval = interval;
+        self
+    }
+
+    /// Sets how many console calls fill a batch, which is then forwarded
+    /// without waiting for the batch interval.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use tauri_plugin_mcp_bridge::Builder;
+    ///
+    /// let builder = Builder::new().console_batch_size(200);
+    /// ```
+    pub fn console_batch_size(mut self, size: usize) -> Self {
+        self.config.console_batch_size = size;
+        self
+    }
+
+    /// Allows `load_script_manifest` to download manifests from a host.
+    ///
+    /// The host must match the manifest URL's host exactly. Manifests are
+    /// fetched over HTTPS, except from loopback addresses. Requires the
+    /// `remote-manifests` feature.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use tauri_plugin_mcp_bridge::Builder;
+    ///
+    /// let builder = Builder::new().allow_manifest_host("scripts.example.com");
+    /// ```
+    pub fn allow_manifest_host(mut self, host: impl Into<String>) -> Self {
+        self.config.manifest_hosts.push(host.into());
+        self
+    }
+
+    /// Sets the largest script manifest `load_script_manifest` downloads.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use tauri_plugin_mcp_bridge::Builder;
+    ///
+    /// let builder = Builder::new().max_manifest_bytes(256 * 1024);
+    /// ```
+    pub fn max_manifest_bytes(mut self, bytes: usize) -> Self {
+        self.config.max_manifest_bytes = bytes;
+        self
+    }
+
+    /// Restricts which commands MCP clients may call.
+    ///
+    /// Denied commands return a `PermissionDenied` error without running.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use tauri_plugin_mcp_bridge::{Builder, PermissionSet};
+    ///
+    /// // Query and capture only
+    /// let builder = Builder::new().permissions(PermissionSet::read_only());
+    /// ```
+    pub fn permissions(mut self, permissions: PermissionSet) -> Self {
+        self.config.permissions = permissions;
+        self
+    }
+
+    /// Limits how often MCP clients may call a command.
+    ///
+    /// Calls over the limit return a `RateLimited` error with the time to
+    /// wait before retrying.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use tauri_plugin_mcp_bridge::{Builder, RateLimit};
+    ///
+    /// let builder = Builder::new()
+    ///     .rate_limit("capture_native_screenshot", RateLimit::per_second(5));
+    /// ```
+    pub fn rate_limit(mut self, command: &str, limit: RateLimit) -> Self {
+        self.config.rate_limits.insert(command.to_string(), limit);
+        self
+    }
+
+    /// Removes all rate limits, including the defaults. Limits added with
+    /// [`rate_limit`](Self::rate_limit) afterwards still apply.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use tauri_plugin_mcp_bridge::Builder;
+    ///
+    /// let builder = Builder::new().disable_rate_limits();
+    /// ```
+    pub fn disable_rate_limits(mut self) -> Self {
+        self.config.rate_limits.clear();
+        self
+    }
+
+    /// Adds a script that runs at the start of every page in every window.
+    ///
+    /// Unlike scripts registered at runtime, initialization scripts are
+    /// installed by the webview itself, so they run on pages whose Content
+    /// Security Policy blocks inline scripts and `eval`. The tradeoffs:
+    /// they are fixed when the plugin is built, can't be removed or
+    /// scoped to a window, run again only on full page loads (not
+    /// client-side navigations), and only take effect in windows created
+    /// after the plugin is registered.
+    ///
+    /// Each script runs inside its own function, so assign to `window` to
+    /// share values with the page. Scripts are appended to bridge.js, so a
+    /// syntax error in one stops the bridge from loading.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use tauri_plugin_mcp_bridge::Builder;
+    ///
+    /// let builder = Builder::new().initialization_script("window.__APP_UNDER_TEST__ = true;");
+    /// ```
+    pub fn initialization_script(mut self, script: impl Into<String>) -> Self {
+        self.config.initialization_scripts.push(script.into());
+        self
+    }
+
+    /// Registers a script in the global scope at startup, as if an MCP
+    /// client had called `register_script`.
+    ///
+    /// Inline entries with [`RunAt::DocumentStart`] also become webview
+    /// initialization scripts, so they run before the page's own scripts
+    /// on every navigation, in time to hook globals. The page skips them
+    /// when registered scripts are injected after load. Initialization
+    /// scripts can't be changed once the plugin is built: removing such an
+    /// entry through `remove_script` stops late injection, but it keeps
+    /// running at document start until the app restarts. Like
+    /// [`Self::initialization_script`], each runs inside its own function.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use tauri_plugin_mcp_bridge::script_registry::{RunAt, ScriptEntry, ScriptType};
+    /// use tauri_plugin_mcp_bridge::Builder;
+    ///
+    /// let mut hook = ScriptEntry::new(
+    ///     "fetch-hook",
+    ///     ScriptType::Inline,
+    ///     "window.__originalFetch = window.fetch;",
+    /// );
+    /// hook.run_at = RunAt::DocumentStart;
+    ///
+    /// let builder = Builder::new().script(hook);
+    /// ```
+    ///
+    /// [`RunAt::DocumentStart`]: crate::script_registry::RunAt::DocumentStart
+    pub fn script(mut self, entry: ScriptEntry) -> Self {
+        self.config.scripts.push(entry);
+        self
+    }
+
+    /// Registers several scripts in the global scope at startup; see
+    /// [`Self::script`].
+    pub fn scripts(mut self, entries: impl IntoIterator<Item = ScriptEntry>) -> Self {
+        self.config.scripts.extend(entries);
+        self
+    }
+
+    /// Registers the scripts listed in a JSON or TOML file at startup; see
+    /// [`Self::script`].
+    ///
+    /// The file is read when the plugin is built, relative to the working
+    /// directory. Files ending in `.toml` are parsed as TOML, others as
+    /// JSON. Each entry is validated, and a missing or malformed file, an
+    /// invalid entry, or an ID used twice across all startup scripts makes
+    /// the plugin's setup fail, so the app doesn't start with part of its
+    /// scripts missing.
+    ///
+    /// ```toml
+    /// [[scripts]]
+    /// id = "fetch-hook"
+    /// script_type = "inline"
+    /// content = "window.__originalFetch = window.fetch;"
+    /// run_at = "documentStart"
+    /// ```
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use tauri_plugin_mcp_bridge::Builder;
+    ///
+    /// let builder = Builder::new().scripts_file("mcp-scripts.toml");
+    /// ```
+    pub fn scripts_file(mut self, path: impl Into<PathBuf>) -> Self {
+        self.config.scripts_files.push(path.into());
+        self
+    }
+
     /// Builds the plugin with the configured options.
     pub fn build<R: tauri::Runtime>(self) -> tauri::plugin::TauriPlugin<R> {
         crate::init_with_config(self.config)
@@ -0,0 +1,99 @@
+//! Console output forwarded from webview pages.
+//!
+//! bridge.js collects console calls into batches and forwards each batch
+//! through the `record_console_entries` command once the batch interval
+//! passes or the batch fills, so a chatty page costs one IPC call per batch
+//! rather than one per message. Entries are kept in a bounded ring buffer
+//! per window, which `get_console_logs` reads.
+
+use std::collections::{HashMap, VecDeque};
+use std::sync::{Arc, Mutex};
+
+use crate::commands::ConsoleEntry;
+
+/// Entries kept per window before the oldest are dropped.
+pub const DEFAULT_CONSOLE_LOG_CAPACITY: usize = 1000;
+
+/// Per-window ring buffers of console calls.
+pub struct ConsoleLog {
+    capacity: usize,
+    windows: HashMap<String, VecDeque<ConsoleEntry>>,
+}
+
+impl Default for ConsoleLog {
+    fn default() -> Self {
+        Self::new(DEFAULT_CONSOLE_LOG_CAPACITY)
+    }
+}
+
+impl ConsoleLog {
+    /// Creates a log keeping up to `capacity` entries per window.
+    pub fn new(capacity: usize) -> Self {
+        Self {
+            capacity,
+            windows: HashMap::new(),
+        }
+    }
+
+    /// Appends a batch of entries for a window, dropping the oldest once
+    /// full.
+    pub fn record(&mut self, window_label: &str, batch: Vec<ConsoleEntry>) {
+        if self.capacity == 0 || batch.is_empty() {
+            return;
+        }
+
+        let entries = self.windows.entry(window_label.to_string()).or_default();
+        entries.extend(batch);
+        let excess = entries.len().saturating_sub(self.capacity);
+        entries.drain(..excess);
+    }
+
+    /// Returns the entries recorded for a window, oldest first, and drops
+    /// them when `clear` is set.
+    pub fn entries(&mut self, window_label: &str, clear: bool) -> Vec<ConsoleEntry> {
+        if clear {
+            return self
+                .windows
+                .remove(window_label)
+                .map(Vec::from)
+                .unwrap_or_default();
+        }
+        self.windows
+            .get(window_label)
+            .map(|entries| entries.iter().cloned().collect())
+            .unwrap_or_default()
+    }
+}
+
+/// Type alias for thread-safe console log state.
+pub type ConsoleLogState = Arc<Mutex<ConsoleLog>>;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn entry(message: &str) -> ConsoleEntry {
+        ConsoleEntry {
+            level: "log".to_string(),
+            message: message.to_string(),
+            args: vec![message.to_string()],
+            timestamp: 0,
+        }
+    }
+
+    #[test]
+    fn test_batches_fill_ring_buffer_and_drain() {
+        let mut log = ConsoleLog::new(3);
+        log.record("main", vec![entry("a"), entry("b")]);
+        log.record("main", vec![entry("c"), entry("d")]);
+        log.record("other", vec![entry("x")]);
+
+        let messages = |entries: Vec<ConsoleEntry>| -> Vec<String> {
+            entries.into_iter().map(|e| e.message).collect()
+        };
+        assert_eq!(messages(log.entries("main", false)), ["b", "c", "d"]);
+        assert_eq!(messages(log.entries("main", true)), ["b", "c", "d"]);
+        assert!(log.entries("main", false).is_empty());
+        assert_eq!(messages(log.entries("other", false)), ["x"]);
+    }
+}
@@ -0,0 +1,186 @@
+/**
+ * Drag-and-drop simulation for the MCP Bridge plugin.
+ *
+ * Run by `drag_element`. Presses the pointer on the source element's
+ * center, moves it to the drop point in `options.steps` moves, and
+ * releases it there. Pointer events are followed by their compatibility
+ * mouse events unless `pointerdown` was canceled, as browsers do. Sources
+ * with `draggable="true"` use the HTML drag-and-drop events instead, which
+ * pages can't receive from synthetic pointer events. `options` is
+ * substituted by Rust.
+ *
+ * Coordinates are CSS pixels relative to the viewport.
+ */
+(async function(options) {
+   'use strict';
+
+   // One frame between moves, so pages that throttle moves see each one
+   var MOVE_INTERVAL_MS = 16;
+
+   var source = document.querySelector(options.from),
+       start, end, target;
+
+   if (!source) {
+      throw new Error('No element matches selector: ' + options.from);
+   }
+
+   function center(el) {
+      var rect = el.getBoundingClientRect();
+
+      return { x: rect.left + rect.width / 2, y: rect.top + rect.height / 2 };
+   }
+
+   function pause() {
+      return new Promise(function(resolve) {
+         setTimeout(resolve, MOVE_INTERVAL_MS);
+      });
+   }
+
+   function describe(el) {
+      if (!el) {
+         return null;
+      }
+      return el.tagName.toLowerCase() + (el.id ? '#' + el.id : '');
+   }
+
+   source.scrollIntoView({ block: 'center', inline: 'center' });
+
+   if (typeof options.to === 'string') {
+      target = document.querySelector(options.to);
+      if (!target) {
+         throw new Error('No element matches selector: ' + options.to);
+      }
+      end = center(target);
+   } else {
+      end = { x: options.to.x, y: options.to.y };
+   }
+   start = center(source);
+
+   if (end.x < 0 || end.y < 0 || end.x >= window.innerWidth || end.y >= window.innerHeight) {
+      throw new Error('Drop point (' + end.x + ', ' + end.y + ') is outside the viewport');
+   }
+
+   function pointAt(step) {
+      var progress = step / options.steps;
+
+      return {
+         x: start.x + (end.x - start.x) * progress,
+         y: start.y + (end.y - start.y) * progress,
+      };
+   }
+
+   function elementAt(point) {
+      return document.elementFromPoint(point.x, point.y) || document.body;
+   }
+
+   function pointerInit(point, buttons) {
+      return {
+         bubbles: true,
+         cancelable: true,
+         composed: true,
+         view: window,
+         clientX: point.x,
+         clientY: point.y,
+         screenX: window.screenX + point.x,
+         screenY: window.screenY + point.y,
+         button: 0,
+         buttons: buttons,
+         pointerId: 1,
+         pointerType: 'mouse',
+         isPrimary: true,
+      };
+   }
+
+   async function pointerDrag() {
+      var init = pointerInit(start, 1),
+          mouseEvents = source.dispatchEvent(new PointerEvent('pointerdown', init)),
+          point, el, i;
+
+      if (mouseEvents) {
+         source.dispatchEvent(new MouseEvent('mousedown', init));
+      }
+
+      for (i = 1; i <= options.steps; i++) {
+         await pause();
+         point = pointAt(i);
+         el = elementAt(point);
+         init = pointerInit(point, 1);
+         el.dispatchEvent(new PointerEvent('pointermove', init));
+         if (mouseEvents) {
+            el.dispatchEvent(new MouseEvent('mousemove', init));
+         }
+      }
+
+      init = pointerInit(end, 0);
+      el = elementAt(end);
+      el.dispatchEvent(new PointerEvent('pointerup', init));
+      if (mouseEvents) {
+         el.dispatchEvent(new MouseEvent('mouseup', init));
+      }
+
+      return { completed: true, dropTarget: describe(el) };
+   }
+
+   async function nativeDrag() {
+      var dataTransfer = new DataTransfer(),
+          accepted = false,
+          previous = null,
+          point, el, i;
+
+      function fire(target, type, at) {
+         return target.dispatchEvent(new DragEvent(type, {
+            bubbles: true,
+            cancelable: true,
+            composed: true,
+            view: window,
+            clientX: at.x,
+            clientY: at.y,
+            dataTransfer: dataTransfer,
+         }));
+      }
+
+      if (!fire(source, 'dragstart', start)) {
+         // The page refused to start the drag
+         return { completed: false, dropTarget: null };
+      }
+
+      for (i = 1; i <= options.steps; i++) {
+         await pause();
+         point = pointAt(i);
+         el = elementAt(point);
+         if (el !== previous) {
+            if (previous) {
+               fire(previous, 'dragleave', point);
+            }
+            fire(el, 'dragenter', point);
+            previous = el;
+         }
+         fire(source, 'drag', point);
+         // Drop zones accept a drop by canceling dragover
+         accepted = !fire(el, 'dragover', point);
+      }
+
+      el = elementAt(end);
+      if (accepted) {
+         fire(el, 'drop', end);
+      } else {
+         fire(el, 'dragleave', end);
+      }
+      dataTransfer.dropEffect = accepted ? 'move' : 'none';
+      fire(source, 'dragend', end);
+
+      return { completed: accepted, dropTarget: describe(el) };
+   }
+
+   // Not `source.draggable`, which is also true for plain images and links
+   var native = source.getAttribute('draggable') === 'true',
+       outcome = native ? await nativeDrag() : await pointerDrag();
+
+   return {
+      completed: outcome.completed,
+      native: native,
+      from: start,
+      to: end,
+      dropTarget: outcome.dropTarget,
+   };
+})(__MCP_DRAG_OPTIONS__);
@@ -0,0 +1,62 @@
+/**
+ * Device emulation shims for the MCP Bridge plugin.
+ *
+ * Registered as a bridge script by `emulate`. Overrides what page scripts
+ * read from `navigator.userAgent` and `window.devicePixelRatio`, keeping
+ * the original property descriptors so `reset_emulation` can restore them.
+ * Properties the webview won't let us redefine are listed in `unsupported`.
+ * `options` is substituted by Rust when the script is registered.
+ *
+ * Only JavaScript sees the overrides: requests keep the webview's real
+ * User-Agent header, and resolution media queries match the real ratio.
+ */
+(function(options) {
+   'use strict';
+
+   var state;
+
+   if (window.__MCP_EMULATION__) {
+      // Re-registered on the same page; start over from the real values
+      window.__MCP_EMULATION__.restore();
+   }
+
+   state = { originals: [], unsupported: [] };
+
+   function override(target, name, value) {
+      if (value === null || value === undefined) {
+         return;
+      }
+
+      var original = Object.getOwnPropertyDescriptor(target, name);
+
+      try {
+         Object.defineProperty(target, name, {
+            configurable: true,
+            enumerable: true,
+            get: function() {
+               return value;
+            },
+         });
+         state.originals.push({ target: target, name: name, descriptor: original });
+      } catch(e) {
+         state.unsupported.push(name);
+      }
+   }
+
+   state.restore = function() {
+      state.originals.forEach(function(original) {
+         if (original.descriptor) {
+            Object.defineProperty(original.target, original.name, original.descriptor);
+         } else {
+            // Own override over a prototype getter
+            delete original.target[original.name];
+         }
+      });
+      delete window.__MCP_EMULATION__;
+   };
+
+   override(navigator, 'userAgent', options.userAgent);
+   override(window, 'devicePixelRatio', options.devicePixelRatio);
+
+   window.__MCP_EMULATION__ = state;
+}(__MCP_EMULATION_OPTIONS__));
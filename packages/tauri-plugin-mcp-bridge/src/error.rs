@@ -0,0 +1,107 @@
+//! Errors returned by commands to MCP clients.
+//!
+//! A [`CommandError`] carries a machine-readable code and whether retrying
+//! the same request may succeed, so clients make the same retry decision
+//! for the same failure whichever command surfaced it. Module errors such
+//! as [`ScreenshotError`] convert into it, which lets commands use `?`.
+
+use serde_json::Value;
+
+use crate::screenshot::ScreenshotError;
+
+/// Code of errors that carry only a message.
+pub const COMMAND_FAILED: &str = "COMMAND_FAILED";
+
+/// Code of errors for a selector that matches no element.
+pub const ELEMENT_NOT_FOUND: &str = "ELEMENT_NOT_FOUND";
+
+/// A command failure as reported to MCP clients.
+#[derive(Debug, Clone, PartialEq, Eq, thiserror::Error)]
+#[error("{message}")]
+pub struct CommandError {
+    code: &'static str,
+    message: String,
+    retryable: bool,
+}
+
+impl CommandError {
+    /// Creates a non-retryable error.
+    pub fn new(code: &'static str, message: impl Into<String>) -> Self {
+        Self {
+            code,
+            message: message.into(),
+            retryable: false,
+        }
+    }
+
+    /// Marks the error as transient, so the same request may succeed later.
+    pub fn retryable(mut self) -> Self {
+        self.retryable = true;
+        self
+    }
+
+    /// Machine-readable error code for clients.
+    pub fn code(&self) -> &'static str {
+        self.code
+    }
+
+    /// Whether retrying the same request may succeed.
+    pub fn is_retryable(&self) -> bool {
+        self.retryable
+    }
+
+    /// The error in MCP's shape: `{ code, message, retryable }`.
+    pub fn to_mcp_error(&self) -> Value {
+        serde_json::json!({
+            "code": self.code,
+            "message": self.message,
+            "retryable": self.retryable,
+        })
+    }
+}
+
+impl From<String> for CommandError {
+    fn from(message: String) -> Self {
+        Self::new(COMMAND_FAILED, message)
+    }
+}
+
+impl From<ScreenshotError> for CommandError {
+    fn from(error: ScreenshotError) -> Self {
+        let command_error = Self::new(error.code(), error.to_string());
+        if error.is_retryable() {
+            command_error.retryable()
+        } else {
+            command_error
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_screenshot_errors_keep_retry_semantics() {
+        let timeout = CommandError::from(ScreenshotError::Timeout);
+        assert_eq!(timeout.code(), "CAPTURE_TIMEOUT");
+        assert!(timeout.is_retryable());
+
+        let failed = CommandError::from(ScreenshotError::CaptureFailed("busy".into()));
+        assert!(failed.is_retryable());
+
+        let unsupported = CommandError::from(ScreenshotError::PlatformUnsupported("web".into()));
+        assert_eq!(
+            unsupported.to_mcp_error(),
+            serde_json::json!({
+                "code": "PLATFORM_UNSUPPORTED",
+                "message": "Platform not supported: web",
+                "retryable": false,
+            })
+        );
+
+        let other = CommandError::from("Missing selector".to_string());
+        assert_eq!(other.code(), COMMAND_FAILED);
+        assert!(!other.is_retryable());
+    }
+}
@@ -69,24 +69,57 @@
 //! - `start_ipc_monitor`
 //! - `stop_ipc_monitor`
 //! - `get_ipc_events`
+//!
+//! ## Tracing
+//!
+//! With the `tracing` feature, every WebSocket command is recorded in an
+//! `mcp_command` span with `command`, `window`, `success` and `elapsed_ms`
+//! fields, native captures get their own spans as children of the command's,
+//! and the Android capture logs each JNI step at trace level. Install a
+//! subscriber in the host app to see them, for example with
+//! `tracing-subscriber`:
+//!
+//! ```rust,ignore
+//! use tracing_subscriber::fmt::format::FmtSpan;
+//!
+//! tracing_subscriber::fmt()
+//!     .with_env_filter("tauri_plugin_mcp_bridge=trace")
+//!     .with_span_events(FmtSpan::CLOSE)
+//!     .init();
+//! ```
+//!
+//! Without the feature the instrumentation is compiled out.
 
+#[cfg(feature = "ts-bindings")]
+pub mod bindings;
 pub mod commands;
 pub mod config;
+pub mod console_log;
 pub mod discovery;
+pub mod error;
 mod logging;
 pub mod monitor;
+pub mod network_log;
+pub mod policy;
+pub mod rate_limit;
 pub mod screenshot;
 pub mod script_registry;
+mod trace;
+pub mod validation;
 pub mod websocket;
 
 pub use config::{Builder, Config};
+pub use error::CommandError;
+pub use policy::PermissionSet;
+pub use rate_limit::RateLimit;
 
 use commands::ScriptExecutor;
 use discovery::find_available_port;
 use logging::{mcp_log_error, mcp_log_info};
 use monitor::IPCMonitor;
-use script_registry::create_shared_registry;
+use script_registry::{create_shared_registry, RegistryError, ScriptEntry, ScriptScope};
 use std::sync::{Arc, Mutex};
+use tauri::webview::PageLoadEvent;
 use tauri::{plugin::Builder as PluginBuilder, plugin::TauriPlugin, Manager, Runtime};
 
 /// Initializes the MCP Bridge plugin.
@@ -126,6 +159,61 @@ pub fn init<R: Runtime>() -> TauriPlugin<R> {
     init_with_config(Config::default())
 }
 
+/// bridge.js, with the console batching options substituted, followed by
+/// the configured initialization scripts and the document-start registry
+/// entries, each in its own function so a script that throws doesn't stop
+/// the rest.
+///
+/// Document-start entries record their IDs in
+/// `window.__MCP_DOCUMENT_START_IDS__`, so bridge.js doesn't inject them a
+/// second time after load.
+fn init_script(config: &Config, scripts: &[ScriptEntry]) -> String {
+    let console_batch = serde_json::json!({
+        "intervalMs": config.console_batch_interval.as_millis() as u64,
+        "size": config.console_batch_size.max(1),
+    });
+    let mut script = include_str!("bridge.js")
+        .replace("__MCP_CONSOLE_BATCH_OPTIONS__", &console_batch.to_string());
+    for user_script in &config.initialization_scripts {
+        script.push_str(&format!(
+            "\n;(function() {{\ntry {{\n{user_script}\n}} catch (e) {{ console.error('[MCP] Initialization script failed:', e); }}\n}})();\n"
+        ));
+    }
+    for entry in scripts
+        .iter()
+        .filter(|entry| entry.runs_at_document_start())
+    {
+        script.push_str(&format!(
+            "\n;(function() {{\n(window.__MCP_DOCUMENT_START_IDS__ = window.__MCP_DOCUMENT_START_IDS__ || []).push({id});\ntry {{\n{content}\n}} catch (e) {{ console.error('[MCP] Document-start script failed:', {id}, e); }}\n}})();\n",
+            id = serde_json::Value::String(entry.id.clone()),
+            content = entry.content,
+        ));
+    }
+    script
+}
+
+/// The configured startup scripts followed by those from the scripts
+/// files, validated together against the registry's rules and size limit.
+///
+/// IDs are trimmed, so document-start scripts are recorded under the same
+/// IDs the registry holds them by.
+fn startup_scripts(config: &Config) -> Result<Vec<ScriptEntry>, RegistryError> {
+    let mut scripts = config.scripts.clone();
+    for path in &config.scripts_files {
+        scripts.extend(script_registry::load_scripts_file(path)?);
+    }
+    script_registry::validate_entries(&scripts)?;
+    if let Some(max) = config.max_scripts {
+        if scripts.len() > max {
+            return Err(RegistryError::LimitReached { max });
+        }
+    }
+    for entry in &mut scripts {
+        entry.id = script_registry::normalize_script_id(&entry.id)?;
+    }
+    Ok(scripts)
+}
+
 /// Initializes the MCP Bridge plugin with custom configuration.
 ///
 /// # Arguments
@@ -147,6 +235,10 @@ pub fn init<R: Runtime>() -> TauriPlugin<R> {
 pub fn init_with_config<R: Runtime>(config: Config) -> TauriPlugin<R> {
     let bind_address = config.bind_address.clone();
     let base_port = config.base_port;
+    let max_scripts = config.max_scripts;
+    let navigation_watchdog = config.navigation_watchdog;
+    let startup_scripts = startup_scripts(&config);
+    let rate_limiter = rate_limit::RateLimiter::new(config.rate_limits.clone());
 
     PluginBuilder::<R>::new("mcp-bridge")
         .invoke_handler(tauri::generate_handler![
@@ -160,11 +252,88 @@ pub fn init_with_config<R: Runtime>(config: Config) -> TauriPlugin<R> {
             commands::execute_js::execute_js,
             commands::script_executor::script_result,
             commands::screenshot::capture_native_screenshot,
+            commands::screenshot::capture_screen,
+            commands::screenshot_stream::start_screenshot_stream,
+            commands::screenshot_stream::stop_screenshot_stream,
+            commands::screenshot_stream::start_capture_stream,
+            commands::screenshot_stream::ack_capture_frame,
+            commands::screenshot_stream::stop_capture_stream,
             commands::list_windows::list_windows,
             commands::script_injection::request_script_injection,
+            commands::network_log::record_network_entry,
+            commands::console_logs::record_console_entries,
         ])
-        .js_init_script(include_str!("bridge.js").to_string())
+        .js_init_script(init_script(
+            &config,
+            startup_scripts.as_deref().unwrap_or_default(),
+        ))
+        .on_page_load(move |webview, payload| {
+            if !navigation_watchdog {
+                return;
+            }
+            let Some(window) = webview.get_webview_window(webview.label()) else {
+                return;
+            };
+
+            let watchdog = window.state::<commands::NavigationWatchdog>();
+            match payload.event() {
+                PageLoadEvent::Started => {
+                    watchdog.page_started(window.label());
+                }
+                PageLoadEvent::Finished => {
+                    let load = watchdog.current_load(window.label());
+                    tauri::async_runtime::spawn(commands::script_injection::watchdog_inject(
+                        window, load,
+                    ));
+                }
+            }
+        })
         .setup(move |app, _api| {
+            // Refuse to start with startup scripts missing
+            let startup_scripts = match startup_scripts {
+                Ok(scripts) => scripts,
+                Err(e) => {
+                    mcp_log_error("SCRIPTS", &format!("Failed to load startup scripts: {e}"));
+                    return Err(e.into());
+                }
+            };
+
+            // Initialize console and network log state before the configuration moves
+            app.manage(Arc::new(Mutex::new(console_log::ConsoleLog::default())));
+            app.manage(Arc::new(Mutex::new(network_log::NetworkLog::new(
+                network_log::DEFAULT_NETWORK_LOG_CAPACITY,
+                config.max_network_body_bytes,
+            ))));
+
+            // Reuse captures of unchanged pages for a short while
+            app.manage(screenshot::cache::CaptureCache::new(
+                config.capture_cache_ttl,
+            ));
+
+            // Serialize captures per window, limited across windows
+            app.manage(screenshot::limiter::CaptureLimiter::new(
+                config.max_concurrent_captures,
+            ));
+
+            // Make the configuration available to commands
+            app.manage(config);
+            app.manage(rate_limiter);
+
+            // Rate-limit script re-application after DOM replacement
+            app.manage(commands::ReapplyGuard::default());
+
+            // Average script run times across injections
+            app.manage(commands::InjectionMetrics::default());
+
+            // Track page loads for the navigation watchdog
+            app.manage(commands::NavigationWatchdog::default());
+
+            // Remember window sizes from before device emulation
+            app.manage(commands::EmulationState::default());
+
+            // Initialize screenshot stream state
+            app.manage(commands::ScreenshotStreams::default());
+
             // Initialize script executor state
             app.manage(ScriptExecutor::new());
 
@@ -174,6 +343,16 @@ pub fn init_with_config<R: Runtime>(config: Config) -> TauriPlugin<R> {
 
             // Initialize script registry for persistent script injection
             let script_registry = create_shared_registry();
+            {
+                let mut registry = script_registry.lock().unwrap();
+                registry.set_max_scripts(max_scripts);
+                for entry in startup_scripts {
+                    if let Err(e) = registry.add(ScriptScope::Global, entry) {
+                        mcp_log_error("SCRIPTS", &format!("Failed to register startup script: {e}"));
+                        return Err(e.into());
+                    }
+                }
+            }
             app.manage(script_registry);
 
             // Find an available port for WebSocket server
@@ -0,0 +1,185 @@
+/**
+ * Network log hooks for the MCP Bridge plugin.
+ *
+ * Registered as a bridge script by `start_network_log`. Wraps `fetch` and
+ * `XMLHttpRequest` to record each request and forwards the entries to Rust
+ * through the `record_network_entry` command. Also counts in-flight
+ * requests for `wait_for_network_idle`, which then sees requests started
+ * before it was called. `options` is substituted by Rust when the script is
+ * registered.
+ */
+(function(options) {
+   'use strict';
+
+   if (window.__MCP_NETWORK_LOG__) {
+      // Re-registered on the same page; pick up the new options
+      window.__MCP_NETWORK_LOG__.options = options;
+      return;
+   }
+
+   var state = { options: options, inflight: 0, lastActivity: Date.now() },
+       originalFetch = window.fetch,
+       originalOpen = XMLHttpRequest.prototype.open,
+       originalSend = XMLHttpRequest.prototype.send;
+
+   // Tauri IPC goes through fetch itself, so recording it would loop forever
+   function isIpcUrl(url) {
+      return /^ipc:/.test(url) || /^https?:\/\/ipc\.localhost\//.test(url);
+   }
+
+   function resolveUrl(url) {
+      try {
+         return new URL(String(url), location.href).href;
+      } catch(e) {
+         return String(url);
+      }
+   }
+
+   function isTextual(contentType) {
+      return !contentType || /^text\/|json|xml|javascript|x-www-form-urlencoded/.test(contentType);
+   }
+
+   function setBody(entry, field, body) {
+      var max = state.options.maxBodyBytes;
+
+      if (!state.options.captureBodies || body === null || body === undefined) {
+         return;
+      }
+      if (body instanceof URLSearchParams) {
+         body = body.toString();
+      }
+      if (typeof body !== 'string') {
+         return;
+      }
+      if (body.length > max) {
+         body = body.slice(0, max);
+         entry.bodyTruncated = true;
+      }
+      entry[field] = body;
+   }
+
+   function begin() {
+      state.inflight++;
+      state.lastActivity = Date.now();
+   }
+
+   function settle() {
+      state.inflight = Math.max(0, state.inflight - 1);
+      state.lastActivity = Date.now();
+   }
+
+   function record(entry) {
+      try {
+         window.__TAURI__.core.invoke('plugin:mcp-bridge|record_network_entry', { entry: entry })
+            .catch(function() {
+               // The log is best effort; never surface errors to the page
+            });
+      } catch(e) {
+         // Tauri API not available yet
+      }
+   }
+
+   function wrappedFetch(input, init) {
+      var promise = originalFetch.apply(this, arguments),
+          request = input instanceof Request ? input : null,
+          url = resolveUrl(request ? request.url : input),
+          entry, start;
+
+      if (isIpcUrl(url)) {
+         return promise;
+      }
+
+      begin();
+      start = performance.now();
+      entry = {
+         kind: 'fetch',
+         method: String((init && init.method) || (request && request.method) || 'GET').toUpperCase(),
+         url: url,
+         startedAt: Date.now(),
+      };
+      setBody(entry, 'requestBody', init && init.body);
+
+      promise.then(function(response) {
+         settle();
+         entry.status = response.status;
+         entry.durationMs = performance.now() - start;
+
+         if (!state.options.captureBodies || !isTextual(response.headers.get('content-type'))) {
+            record(entry);
+            return;
+         }
+         response.clone().text().then(function(text) {
+            setBody(entry, 'responseBody', text);
+            record(entry);
+         }, function() {
+            record(entry);
+         });
+      }, function(error) {
+         settle();
+         entry.durationMs = performance.now() - start;
+         entry.error = (error && error.message) || String(error);
+         record(entry);
+      });
+
+      return promise;
+   }
+
+   function wrappedOpen(method, url) {
+      this.__mcpNetworkEntry = {
+         kind: 'xhr',
+         method: String(method || 'GET').toUpperCase(),
+         url: resolveUrl(url),
+      };
+      return originalOpen.apply(this, arguments);
+   }
+
+   function wrappedSend(body) {
+      var xhr = this,
+          entry = xhr.__mcpNetworkEntry,
+          start = performance.now();
+
+      if (entry && !isIpcUrl(entry.url)) {
+         begin();
+         entry.startedAt = Date.now();
+         setBody(entry, 'requestBody', body);
+
+         xhr.addEventListener('loadend', function() {
+            settle();
+            entry.durationMs = performance.now() - start;
+            if (xhr.status) {
+               entry.status = xhr.status;
+            } else {
+               entry.error = 'Network error';
+            }
+            if (xhr.responseType === '' || xhr.responseType === 'text') {
+               setBody(entry, 'responseBody', xhr.responseText);
+            } else if (xhr.responseType === 'json' && xhr.response !== null) {
+               setBody(entry, 'responseBody', JSON.stringify(xhr.response));
+            }
+            record(entry);
+         }, { once: true });
+      }
+
+      return originalSend.apply(this, arguments);
+   }
+
+   window.fetch = wrappedFetch;
+   XMLHttpRequest.prototype.open = wrappedOpen;
+   XMLHttpRequest.prototype.send = wrappedSend;
+
+   // Only restore what we replaced, in case the page wrapped them again
+   state.stop = function() {
+      if (window.fetch === wrappedFetch) {
+         window.fetch = originalFetch;
+      }
+      if (XMLHttpRequest.prototype.open === wrappedOpen) {
+         XMLHttpRequest.prototype.open = originalOpen;
+      }
+      if (XMLHttpRequest.prototype.send === wrappedSend) {
+         XMLHttpRequest.prototype.send = originalSend;
+      }
+      delete window.__MCP_NETWORK_LOG__;
+   };
+
+   window.__MCP_NETWORK_LOG__ = state;
+}(__MCP_NETWORK_LOG_OPTIONS__));
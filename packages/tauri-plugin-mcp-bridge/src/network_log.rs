@@ -0,0 +1,268 @@
+//! Network request logging for webview pages.
+//!
+//! A bridge script wraps `fetch` and `XMLHttpRequest` in the page and
+//! forwards one [`NetworkEntry`] per finished request. Entries are kept in a
+//! bounded ring buffer per window, so long-running pages don't grow the log
+//! without limit.
+
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, VecDeque};
+use std::sync::{Arc, Mutex};
+
+use crate::config::DEFAULT_MAX_NETWORK_BODY_BYTES;
+
+/// Entries kept per window before the oldest are dropped.
+pub const DEFAULT_NETWORK_LOG_CAPACITY: usize = 500;
+
+/// A finished request recorded in the page.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[cfg_attr(feature = "ts-bindings", derive(ts_rs::TS))]
+#[serde(rename_all = "camelCase")]
+pub struct NetworkEntry {
+    /// `fetch` or `xhr`
+    pub kind: String,
+    /// Uppercase HTTP method
+    pub method: String,
+    /// Absolute request URL
+    pub url: String,
+    /// HTTP status, `None` when the request failed before a response
+    #[serde(default)]
+    pub status: Option<u16>,
+    /// When the request started, as Unix milliseconds
+    #[cfg_attr(feature = "ts-bindings", ts(type = "number"))]
+    pub started_at: u64,
+    /// Time from start to response (or failure), in milliseconds
+    #[serde(default)]
+    pub duration_ms: f64,
+    /// Request body, when bodies are captured and the body is text
+    #[serde(default)]
+    pub request_body: Option<String>,
+    /// Response body, when bodies are captured and the body is text
+    #[serde(default)]
+    pub response_body: Option<String>,
+    /// Whether a body was cut to the configured maximum size
+    #[serde(default)]
+    pub body_truncated: bool,
+    /// Network error message, when no response arrived
+    #[serde(default)]
+    pub error: Option<String>,
+}
+
+/// Criteria for selecting entries from the log. Unset fields match anything.
+#[derive(Debug, Clone, Default, Deserialize)]
+#[cfg_attr(feature = "ts-bindings", derive(ts_rs::TS))]
+#[serde(rename_all = "camelCase", default)]
+pub struct NetworkLogFilter {
+    /// Substring the URL must contain
+    pub url: Option<String>,
+    /// HTTP method, compared case-insensitively
+    pub method: Option<String>,
+    /// Exact HTTP status
+    pub status: Option<u16>,
+    /// Only failed requests: network errors and 4xx/5xx responses
+    pub failed_only: bool,
+}
+
+impl NetworkLogFilter {
+    /// Whether `entry` satisfies every set criterion.
+    pub fn matches(&self, entry: &NetworkEntry) -> bool {
+        if let Some(url) = &self.url {
+            if !entry.url.contains(url.as_str()) {
+                return false;
+            }
+        }
+        if let Some(method) = &self.method {
+            if !entry.method.eq_ignore_ascii_case(method) {
+                return false;
+            }
+        }
+        if self.status.is_some() && entry.status != self.status {
+            return false;
+        }
+        if self.failed_only && entry.error.is_none() && entry.status.unwrap_or(0) < 400 {
+            return false;
+        }
+        true
+    }
+}
+
+/// Per-window ring buffers of recorded requests.
+pub struct NetworkLog {
+    capacity: usize,
+    max_body_bytes: usize,
+    windows: HashMap<String, VecDeque<NetworkEntry>>,
+}
+
+impl Default for NetworkLog {
+    fn default() -> Self {
+        Self::new(DEFAULT_NETWORK_LOG_CAPACITY, DEFAULT_MAX_NETWORK_BODY_BYTES)
+    }
+}
+
+impl NetworkLog {
+    /// Creates a log keeping up to `capacity` entries per window, with bodies
+    /// cut to `max_body_bytes`.
+    pub fn new(capacity: usize, max_body_bytes: usize) -> Self {
+        Self {
+            capacity,
+            max_body_bytes,
+            windows: HashMap::new(),
+        }
+    }
+
+    /// Largest body kept per entry.
+    pub fn max_body_bytes(&self) -> usize {
+        self.max_body_bytes
+    }
+
+    /// Appends an entry for a window, dropping the oldest once full.
+    ///
+    /// Bodies over the size limit are truncated here too, in case the page
+    /// script was tampered with or counts characters rather than bytes.
+    pub fn record(&mut self, window_label: &str, mut entry: NetworkEntry) {
+        if self.capacity == 0 {
+            return;
+        }
+
+        for body in [&mut entry.request_body, &mut entry.response_body]
+            .into_iter()
+            .flatten()
+        {
+            if truncate_to_bytes(body, self.max_body_bytes) {
+                entry.body_truncated = true;
+            }
+        }
+
+        let entries = self.windows.entry(window_label.to_string()).or_default();
+        if entries.len() == self.capacity {
+            entries.pop_front();
+        }
+        entries.push_back(entry);
+    }
+
+    /// Returns the most recent `limit` matching entries for a window, oldest
+    /// first.
+    pub fn entries(
+        &self,
+        window_label: &str,
+        filter: &NetworkLogFilter,
+        limit: Option<usize>,
+    ) -> Vec<NetworkEntry> {
+        let Some(entries) = self.windows.get(window_label) else {
+            return Vec::new();
+        };
+
+        let mut matching: Vec<NetworkEntry> = entries
+            .iter()
+            .rev()
+            .filter(|entry| filter.matches(entry))
+            .take(limit.unwrap_or(usize::MAX))
+            .cloned()
+            .collect();
+        matching.reverse();
+        matching
+    }
+
+    /// Drops all entries recorded for a window.
+    pub fn clear(&mut self, window_label: &str) {
+        self.windows.remove(window_label);
+    }
+}
+
+/// Type alias for thread-safe network log state.
+pub type NetworkLogState = Arc<Mutex<NetworkLog>>;
+
+/// Cuts `body` to at most `max_bytes` on a character boundary. Returns
+/// whether anything was removed.
+fn truncate_to_bytes(body: &mut String, max_bytes: usize) -> bool {
+    if body.len() <= max_bytes {
+        return false;
+    }
+
+    let mut end = max_bytes;
+    while !body.is_char_boundary(end) {
+        end -= 1;
+    }
+    body.truncate(end);
+    true
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn entry(method: &str, url: &str, status: Option<u16>) -> NetworkEntry {
+        NetworkEntry {
+            kind: "fetch".to_string(),
+            method: method.to_string(),
+            url: url.to_string(),
+            status,
+            started_at: 0,
+            duration_ms: 1.0,
+            request_body: None,
+            response_body: None,
+            body_truncated: false,
+            error: None,
+        }
+    }
+
+    #[test]
+    fn test_ring_buffer_drops_oldest() {
+        let mut log = NetworkLog::new(2, 1024);
+        log.record("main", entry("GET", "https://a.test/1", Some(200)));
+        log.record("main", entry("GET", "https://a.test/2", Some(200)));
+        log.record("main", entry("GET", "https://a.test/3", Some(200)));
+        log.record("other", entry("GET", "https://b.test/", Some(200)));
+
+        let urls: Vec<_> = log
+            .entries("main", &NetworkLogFilter::default(), None)
+            .into_iter()
+            .map(|e| e.url)
+            .collect();
+        assert_eq!(urls, ["https://a.test/2", "https://a.test/3"]);
+        assert_eq!(log.entries("other", &Default::default(), None).len(), 1);
+    }
+
+    #[test]
+    fn test_filter_and_limit() {
+        let mut log = NetworkLog::default();
+        log.record("main", entry("GET", "https://a.test/api/users", Some(200)));
+        log.record("main", entry("post", "https://a.test/api/users", Some(500)));
+        log.record("main", entry("GET", "https://a.test/style.css", Some(404)));
+        log.record("main", entry("GET", "https://a.test/api/items", Some(200)));
+
+        let filter = NetworkLogFilter {
+            url: Some("/api/".to_string()),
+            ..Default::default()
+        };
+        let latest = log.entries("main", &filter, Some(2));
+        assert_eq!(latest.len(), 2);
+        assert_eq!(latest[1].url, "https://a.test/api/items");
+
+        let filter = NetworkLogFilter {
+            method: Some("POST".to_string()),
+            ..Default::default()
+        };
+        assert_eq!(log.entries("main", &filter, None).len(), 1);
+
+        let filter = NetworkLogFilter {
+            failed_only: true,
+            ..Default::default()
+        };
+        assert_eq!(log.entries("main", &filter, None).len(), 2);
+    }
+
+    #[test]
+    fn test_bodies_are_truncated_to_byte_limit() {
+        let mut log = NetworkLog::new(10, 4);
+        let mut large = entry("POST", "https://a.test/", Some(200));
+        large.request_body = Some("abc".to_string());
+        large.response_body = Some("aé€bc".to_string());
+        log.record("main", large);
+
+        let recorded = &log.entries("main", &Default::default(), None)[0];
+        assert_eq!(recorded.request_body.as_deref(), Some("abc"));
+        assert_eq!(recorded.response_body.as_deref(), Some("aé"));
+        assert!(recorded.body_truncated);
+    }
+}
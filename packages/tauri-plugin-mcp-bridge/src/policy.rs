@@ -0,0 +1,189 @@
+//! Command permissions for MCP clients.
+//!
+//! Every WebSocket command is checked against the [`PermissionSet`] from the
+//! plugin [`Config`](crate::Config) before it runs. Commands invoked from the
+//! app's own frontend through Tauri IPC are governed by Tauri capabilities
+//! instead.
+
+use std::collections::HashSet;
+
+/// Commands allowed by the [`PermissionSet::read_only`] preset.
+///
+/// These only query state or capture screenshots and never change the app.
+/// Queries that reach past the page are left out: `wait_for_network_idle`
+/// injects instrumentation into the page, `visual_diff` reads a baseline
+/// from any path on disk, and `get_cookies` returns HttpOnly cookies.
+pub const READ_ONLY_COMMANDS: &[&str] = &[
+    "list_windows",
+    "get_window_info",
+    "get_backend_state",
+    "get_ipc_events",
+    "capture_native_screenshot",
+    "capture_screen",
+    "capture_with_thumbnail",
+    "capture_at_width",
+    "capture_all_windows",
+    "diff_screenshots",
+    "capture_text",
+    "get_viewport_metrics",
+    "get_performance_timing",
+    "get_color_scheme",
+    "get_user_agent",
+    "get_bounding_rects",
+    "get_computed_style",
+    "get_accessibility_tree",
+    "get_interactive_elements",
+    "get_network_log",
+    "get_console_logs",
+    "get_scripts",
+    "list_scripts",
+    "export_registry",
+    "storage_get",
+];
+
+/// Permission error types
+#[derive(Debug, thiserror::Error)]
+pub enum PermissionError {
+    #[error("Permission denied: command '{0}' is not allowed for MCP clients")]
+    PermissionDenied(String),
+}
+
+impl PermissionError {
+    /// Machine-readable error code for clients.
+    pub fn code(&self) -> &'static str {
+        match self {
+            PermissionError::PermissionDenied(_) => "PERMISSION_DENIED",
+        }
+    }
+}
+
+/// Which commands an MCP client may call, keyed by command name.
+///
+/// # Examples
+///
+/// ```rust
+/// use tauri_plugin_mcp_bridge::policy::PermissionSet;
+///
+/// // Query and capture only, plus script evaluation
+/// let permissions = PermissionSet::read_only().allow("execute_js");
+/// assert!(permissions.is_allowed("execute_js"));
+/// assert!(!permissions.is_allowed("close_window"));
+///
+/// // Everything except closing windows
+/// let permissions = PermissionSet::full().deny("close_window");
+/// assert!(!permissions.is_allowed("close_window"));
+/// ```
+#[derive(Debug, Clone)]
+pub struct PermissionSet {
+    /// Allowed commands, or `None` to allow every command
+    allowed: Option<HashSet<String>>,
+    /// Commands denied even if otherwise allowed
+    denied: HashSet<String>,
+}
+
+impl Default for PermissionSet {
+    fn default() -> Self {
+        Self::full()
+    }
+}
+
+impl PermissionSet {
+    /// Allows every command. This is the default.
+    pub fn full() -> Self {
+        Self {
+            allowed: None,
+            denied: HashSet::new(),
+        }
+    }
+
+    /// Allows only the query and capture commands in [`READ_ONLY_COMMANDS`].
+    pub fn read_only() -> Self {
+        Self {
+            allowed: Some(READ_ONLY_COMMANDS.iter().map(|c| c.to_string()).collect()),
+            denied: HashSet::new(),
+        }
+    }
+
+    /// Allows no commands until they are added with [`allow`](Self::allow).
+    pub fn none() -> Self {
+        Self {
+            allowed: Some(HashSet::new()),
+            denied: HashSet::new(),
+        }
+    }
+
+    /// Allows an additional command.
+    pub fn allow(mut self, command: &str) -> Self {
+        self.denied.remove(command);
+        if let Some(allowed) = self.allowed.as_mut() {
+            allowed.insert(command.to_string());
+        }
+        self
+    }
+
+    /// Denies a command.
+    pub fn deny(mut self, command: &str) -> Self {
+        self.denied.insert(command.to_string());
+        self
+    }
+
+    /// Returns true if the command may be called.
+    pub fn is_allowed(&self, command: &str) -> bool {
+        if self.denied.contains(command) {
+            return false;
+        }
+
+        match &self.allowed {
+            Some(allowed) => allowed.contains(command),
+            None => true,
+        }
+    }
+
+    /// Returns `PermissionDenied` if the command may not be called.
+    pub fn check(&self, command: &str) -> Result<(), PermissionError> {
+        if self.is_allowed(command) {
+            Ok(())
+        } else {
+            Err(PermissionError::PermissionDenied(command.to_string()))
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_full_allows_everything() {
+        let permissions = PermissionSet::full();
+        assert!(permissions.is_allowed("execute_js"));
+        assert!(permissions.check("close_window").is_ok());
+    }
+
+    #[test]
+    fn test_read_only_blocks_mutations() {
+        let permissions = PermissionSet::read_only();
+        assert!(permissions.is_allowed("capture_native_screenshot"));
+        assert!(permissions.is_allowed("list_windows"));
+        assert!(!permissions.is_allowed("execute_js"));
+        assert!(!permissions.is_allowed("set_cookie"));
+        assert!(!permissions.is_allowed("get_cookies"));
+        assert!(!permissions.is_allowed("visual_diff"));
+
+        let err = permissions.check("execute_js").unwrap_err();
+        assert_eq!(err.code(), "PERMISSION_DENIED");
+    }
+
+    #[test]
+    fn test_allow_and_deny_overrides() {
+        let permissions = PermissionSet::none().allow("list_windows");
+        assert!(permissions.is_allowed("list_windows"));
+        assert!(!permissions.is_allowed("get_window_info"));
+
+        let permissions = PermissionSet::full().deny("execute_js");
+        assert!(!permissions.is_allowed("execute_js"));
+
+        let permissions = permissions.allow("execute_js");
+        assert!(permissions.is_allowed("execute_js"));
+    }
+}
@@ -0,0 +1,291 @@
+//! Token-bucket rate limiting for expensive commands.
+//!
+//! Limits are configured per command name through the plugin
+//! [`Builder`](crate::Builder), optionally with a separate bucket for each
+//! window. Captures and script evaluation have generous limits by default,
+//! so a runaway client can't starve the webview's UI thread. Commands
+//! without a limit skip the limiter entirely, so it costs a single map
+//! lookup when unused.
+
+use std::collections::HashMap;
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+/// Maximum request rate for a command.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct RateLimit {
+    /// Number of calls allowed in a burst
+    pub burst: u32,
+    /// Tokens refilled per second
+    pub per_second: f64,
+    /// Whether each window gets its own bucket, instead of one shared by
+    /// all windows
+    pub per_window: bool,
+}
+
+impl RateLimit {
+    /// Allows `count` calls per second, with bursts of up to `count` calls.
+    pub fn per_second(count: u32) -> Self {
+        Self {
+            burst: count,
+            per_second: count as f64,
+            per_window: false,
+        }
+    }
+
+    /// Allows `count` calls per minute, with bursts of up to `count` calls.
+    pub fn per_minute(count: u32) -> Self {
+        Self {
+            burst: count,
+            per_second: count as f64 / 60.0,
+            per_window: false,
+        }
+    }
+
+    /// Gives each window its own bucket, so one busy window doesn't use up
+    /// the others' calls.
+    pub fn per_window(mut self) -> Self {
+        self.per_window = true;
+        self
+    }
+}
+
+/// Limits applied unless configured otherwise: far above what an agent
+/// needs, low enough to keep a runaway loop from starving the UI thread.
+pub fn default_rate_limits() -> HashMap<String, RateLimit> {
+    let captures = [
+        "capture_native_screenshot",
+        "capture_screen",
+        "capture_text",
+    ]
+    .map(|command| (command, RateLimit::per_second(20).per_window()));
+    let scripts = ["execute_js", "evaluate_script", "eval_async"]
+        .map(|command| (command, RateLimit::per_second(50).per_window()));
+
+    captures
+        .into_iter()
+        .chain(scripts)
+        .chain([("capture_all_windows", RateLimit::per_second(5))])
+        .map(|(command, limit)| (command.to_string(), limit))
+        .collect()
+}
+
+/// Rate limit error types
+#[derive(Debug, thiserror::Error)]
+pub enum RateLimitError {
+    #[error("Rate limit exceeded for '{command}', retry after {}ms", retry_after.as_millis())]
+    RateLimited {
+        command: String,
+        retry_after: Duration,
+    },
+}
+
+impl RateLimitError {
+    /// Machine-readable error code for clients.
+    pub fn code(&self) -> &'static str {
+        match self {
+            RateLimitError::RateLimited { .. } => "RATE_LIMITED",
+        }
+    }
+}
+
+#[derive(Debug)]
+struct TokenBucket {
+    tokens: f64,
+    updated_at: Instant,
+}
+
+/// Per-command token-bucket rate limiter, stored in managed state.
+#[derive(Debug, Default)]
+pub struct RateLimiter {
+    limits: HashMap<String, RateLimit>,
+    /// Keyed by command, and window label for per-window limits
+    buckets: Mutex<HashMap<(String, Option<String>), TokenBucket>>,
+}
+
+impl RateLimiter {
+    /// Creates a limiter with the given per-command limits.
+    pub fn new(limits: HashMap<String, RateLimit>) -> Self {
+        Self {
+            limits,
+            buckets: Mutex::new(HashMap::new()),
+        }
+    }
+
+    /// Takes a token for `command` in `window_label`, or returns
+    /// `RateLimited` with the time until the next token is available.
+    ///
+    /// The window label only matters for per-window limits, and should be
+    /// the label of an existing window; calls without one share a bucket.
+    /// Buckets that have refilled to their burst size are dropped whenever a
+    /// new one is created, so closed windows don't keep theirs forever.
+    pub fn check(&self, command: &str, window_label: Option<&str>) -> Result<(), RateLimitError> {
+        self.check_at(command, window_label, Instant::now())
+    }
+
+    fn check_at(
+        &self,
+        command: &str,
+        window_label: Option<&str>,
+        now: Instant,
+    ) -> Result<(), RateLimitError> {
+        let Some(limit) = self.limits.get(command) else {
+            return Ok(());
+        };
+        let window_label = window_label
+            .filter(|_| limit.per_window)
+            .map(|label| label.to_string());
+
+        let mut buckets = self.buckets.lock().unwrap_or_else(|e| e.into_inner());
+        let key = (command.to_string(), window_label);
+        if !buckets.contains_key(&key) {
+            // A full bucket behaves exactly like a missing one
+            buckets.retain(|(command, _), bucket| {
+                self.limits.get(command).is_some_and(|limit| {
+                    let elapsed = now.saturating_duration_since(bucket.updated_at);
+                    bucket.tokens + elapsed.as_secs_f64() * limit.per_second < limit.burst as f64
+                })
+            });
+        }
+        let bucket = buckets.entry(key).or_insert_with(|| TokenBucket {
+            tokens: limit.burst as f64,
+            updated_at: now,
+        });
+
+        // Refill for the time elapsed since the last call
+        let elapsed = now.saturating_duration_since(bucket.updated_at);
+        bucket.tokens =
+            (bucket.tokens + elapsed.as_secs_f64() * limit.per_second).min(limit.burst as f64);
+        bucket.updated_at = now;
+
+        if bucket.tokens >= 1.0 {
+            bucket.tokens -= 1.0;
+            return Ok(());
+        }
+
+        let retry_after = if limit.per_second > 0.0 {
+            Duration::from_secs_f64((1.0 - bucket.tokens) / limit.per_second)
+        } else {
+            Duration::MAX
+        };
+
+        Err(RateLimitError::RateLimited {
+            command: command.to_string(),
+            retry_after,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn limiter(command: &str, limit: RateLimit) -> RateLimiter {
+        RateLimiter::new(HashMap::from([(command.to_string(), limit)]))
+    }
+
+    #[test]
+    fn test_unlimited_commands_pass() {
+        let limiter = limiter("capture_screen", RateLimit::per_second(1));
+        for _ in 0..100 {
+            assert!(limiter.check("list_windows", None).is_ok());
+        }
+    }
+
+    #[test]
+    fn test_limit_exceeded_after_burst() {
+        let limiter = limiter("capture_screen", RateLimit::per_second(5));
+        let now = Instant::now();
+
+        for _ in 0..5 {
+            assert!(limiter.check_at("capture_screen", None, now).is_ok());
+        }
+
+        let err = limiter.check_at("capture_screen", None, now).unwrap_err();
+        assert_eq!(err.code(), "RATE_LIMITED");
+        let RateLimitError::RateLimited { retry_after, .. } = err;
+        assert_eq!(retry_after, Duration::from_millis(200));
+    }
+
+    #[test]
+    fn test_tokens_refill_over_time() {
+        let limiter = limiter("capture_screen", RateLimit::per_second(2));
+        let start = Instant::now();
+
+        assert!(limiter.check_at("capture_screen", None, start).is_ok());
+        assert!(limiter.check_at("capture_screen", None, start).is_ok());
+        assert!(limiter.check_at("capture_screen", None, start).is_err());
+
+        // Half a second refills one token at 2/sec
+        let later = start + Duration::from_millis(500);
+        assert!(limiter.check_at("capture_screen", None, later).is_ok());
+        assert!(limiter.check_at("capture_screen", None, later).is_err());
+
+        // Refill is capped at the burst size
+        let much_later = later + Duration::from_secs(60);
+        assert!(limiter.check_at("capture_screen", None, much_later).is_ok());
+        assert!(limiter.check_at("capture_screen", None, much_later).is_ok());
+        assert!(limiter
+            .check_at("capture_screen", None, much_later)
+            .is_err());
+    }
+
+    #[test]
+    fn test_burst_traffic_is_limited_per_window() {
+        let limiter = limiter("execute_js", RateLimit::per_second(10).per_window());
+        let start = Instant::now();
+        let allowed = |window: &str, at: Instant| limiter.check_at("execute_js", Some(window), at);
+
+        // A burst of 100 calls in the same instant gets the burst size through
+        let passed = (0..100).filter(|_| allowed("main", start).is_ok()).count();
+        assert_eq!(passed, 10);
+
+        // Other windows keep their own budget
+        assert!(allowed("settings", start).is_ok());
+
+        // Hammering every millisecond for a second lets through the refill rate
+        let passed = (1..=1000)
+            .filter(|ms| allowed("main", start + Duration::from_millis(*ms)).is_ok())
+            .count();
+        assert_eq!(passed, 10);
+
+        let err = allowed("main", start + Duration::from_millis(1000)).unwrap_err();
+        let RateLimitError::RateLimited { retry_after, .. } = err;
+        assert!(retry_after <= Duration::from_millis(100));
+    }
+
+    #[test]
+    fn test_refilled_buckets_are_pruned() {
+        let limiter = limiter("execute_js", RateLimit::per_second(10).per_window());
+        let start = Instant::now();
+
+        for window in ["main", "closed-1", "closed-2"] {
+            assert!(limiter.check_at("execute_js", Some(window), start).is_ok());
+        }
+        assert_eq!(limiter.buckets.lock().unwrap().len(), 3);
+
+        // A new bucket after every other one has refilled drops them all
+        let later = start + Duration::from_secs(1);
+        assert!(limiter
+            .check_at("execute_js", Some("settings"), later)
+            .is_ok());
+        assert_eq!(limiter.buckets.lock().unwrap().len(), 1);
+
+        // Buckets still short of their burst are kept
+        assert!(limiter.check_at("execute_js", Some("main"), later).is_ok());
+        assert_eq!(limiter.buckets.lock().unwrap().len(), 2);
+    }
+
+    #[test]
+    fn test_shared_limits_ignore_window() {
+        let limiter = limiter("capture_all_windows", RateLimit::per_second(1));
+        let now = Instant::now();
+        assert!(limiter
+            .check_at("capture_all_windows", Some("main"), now)
+            .is_ok());
+        assert!(limiter
+            .check_at("capture_all_windows", Some("settings"), now)
+            .is_err());
+        assert!(default_rate_limits()["capture_native_screenshot"].per_window);
+    }
+}
@@ -1,265 +1,557 @@
-use super::{Screenshot, ScreenshotError};
+use super::{
+    AndroidCaptureStrategy, CaptureOptions, CaptureOrientation, Screenshot, ScreenshotError,
+};
+#[cfg(target_os = "android")]
+use crate::trace::trace_step;
 use tauri::{Runtime, WebviewWindow};
 
-/// Android-specific screenshot implementation using WebView.draw()
+/// Android-specific screenshot implementation
 ///
-/// This implementation captures the visible viewport by:
+/// With the default [`AndroidCaptureStrategy::Draw`] strategy the visible
+/// viewport is captured by:
 /// 1. Getting the WebView dimensions via JNI
 /// 2. Creating a Bitmap with those dimensions
 /// 3. Creating a Canvas from the Bitmap
 /// 4. Drawing the WebView to the Canvas
 /// 5. Compressing the Bitmap to PNG bytes
+///
+/// A [`CaptureOrientation`] other than the natural one rotates the Canvas
+/// before step 4, so the current layout is drawn turned by 90 degrees into a
+/// Bitmap with swapped dimensions.
+///
+/// `View.draw()` renders into a software Canvas, so hardware-accelerated and
+/// cross-process content (video, WebGL) comes out blank.
+/// [`AndroidCaptureStrategy::PixelCopy`] copies the rendered window surface
+/// instead, through `PixelCopyCapture` in the plugin's Android library.
 pub fn capture_viewport<R: Runtime>(
     window: &WebviewWindow<R>,
+    options: &CaptureOptions,
 ) -> Result<Screenshot, ScreenshotError> {
     #[cfg(target_os = "android")]
     {
-        use jni::objects::{JByteArray, JValue};
-        use std::sync::mpsc;
-
-        let (tx, rx) = mpsc::channel::<Result<Screenshot, ScreenshotError>>();
-
-        // Use Tauri's with_webview to access the Android WebView via JNI
-        window
-            .with_webview(move |webview| {
-                webview
-                    .jni_handle()
-                    .exec(move |env, _activity, webview_obj| {
-                        let result: Result<Screenshot, ScreenshotError> = (|| {
-                            // Get WebView dimensions
-                            let width = env
-                                .call_method(webview_obj, "getWidth", "()I", &[])
-                                .map_err(|e| {
-                                    ScreenshotError::CaptureFailed(format!(
-                                        "Failed to get width: {e}"
-                                    ))
-                                })?
-                                .i()
-                                .map_err(|e| {
-                                    ScreenshotError::CaptureFailed(format!("Invalid width: {e}"))
-                                })?;
-
-                            let height = env
-                                .call_method(webview_obj, "getHeight", "()I", &[])
-                                .map_err(|e| {
-                                    ScreenshotError::CaptureFailed(format!(
-                                        "Failed to get height: {e}"
-                                    ))
-                                })?
-                                .i()
-                                .map_err(|e| {
-                                    ScreenshotError::CaptureFailed(format!("Invalid height: {e}"))
-                                })?;
-
-                            if width <= 0 || height <= 0 {
-                                return Err(ScreenshotError::CaptureFailed(format!(
-                                    "Invalid WebView dimensions: {width}x{height}"
-                                )));
-                            }
-
-                            // Create Bitmap with ARGB_8888 config
-                            let bitmap_class =
-                                env.find_class("android/graphics/Bitmap").map_err(|e| {
-                                    ScreenshotError::CaptureFailed(format!(
-                                        "Failed to find Bitmap class: {e}"
-                                    ))
-                                })?;
-
-                            let config_class = env
-                                .find_class("android/graphics/Bitmap$Config")
-                                .map_err(|e| {
-                                    ScreenshotError::CaptureFailed(format!(
-                                        "Failed to find Bitmap.Config class: {e}"
-                                    ))
-                                })?;
-
-                            let argb_8888 = env
-                                .get_static_field(
-                                    &config_class,
-                                    "ARGB_8888",
-                                    "Landroid/graphics/Bitmap$Config;",
-                                )
-                                .map_err(|e| {
-                                    ScreenshotError::CaptureFailed(format!(
-                                        "Failed to get ARGB_8888: {e}"
-                                    ))
-                                })?
-                                .l()
-                                .map_err(|e| {
-                                    ScreenshotError::CaptureFailed(format!(
-                                        "Invalid ARGB_8888: {e}"
-                                    ))
-                                })?;
-
-                            let bitmap = env
-                                .call_static_method(
-                                    &bitmap_class,
-                                    "createBitmap",
-                                    "(IILandroid/graphics/Bitmap$Config;)Landroid/graphics/Bitmap;",
-                                    &[
-                                        JValue::Int(width),
-                                        JValue::Int(height),
-                                        JValue::Object(&argb_8888),
-                                    ],
-                                )
-                                .map_err(|e| {
-                                    ScreenshotError::CaptureFailed(format!(
-                                        "Failed to create Bitmap: {e}"
-                                    ))
-                                })?
-                                .l()
-                                .map_err(|e| {
-                                    ScreenshotError::CaptureFailed(format!("Invalid Bitmap: {e}"))
-                                })?;
-
-                            // Create Canvas from Bitmap
-                            let canvas_class =
-                                env.find_class("android/graphics/Canvas").map_err(|e| {
-                                    ScreenshotError::CaptureFailed(format!(
-                                        "Failed to find Canvas class: {e}"
-                                    ))
-                                })?;
-
-                            let canvas = env
-                                .new_object(
-                                    &canvas_class,
-                                    "(Landroid/graphics/Bitmap;)V",
-                                    &[JValue::Object(&bitmap)],
-                                )
-                                .map_err(|e| {
-                                    ScreenshotError::CaptureFailed(format!(
-                                        "Failed to create Canvas: {e}"
-                                    ))
-                                })?;
-
-                            // Draw WebView to Canvas
-                            env.call_method(
-                                webview_obj,
-                                "draw",
-                                "(Landroid/graphics/Canvas;)V",
-                                &[JValue::Object(&canvas)],
-                            )
-                            .map_err(|e| {
-                                ScreenshotError::CaptureFailed(format!(
-                                    "Failed to draw WebView: {e}"
-                                ))
-                            })?;
-
-                            // Compress Bitmap to PNG bytes
-                            let baos_class = env
-                                .find_class("java/io/ByteArrayOutputStream")
-                                .map_err(|e| {
-                                    ScreenshotError::CaptureFailed(format!(
-                                        "Failed to find ByteArrayOutputStream class: {e}"
-                                    ))
-                                })?;
-
-                            let baos =
-                                env.new_object(&baos_class, "()V", &[]).map_err(|e| {
-                                    ScreenshotError::CaptureFailed(format!(
-                                        "Failed to create ByteArrayOutputStream: {e}"
-                                    ))
-                                })?;
-
-                            let compress_format_class = env
-                                .find_class("android/graphics/Bitmap$CompressFormat")
-                                .map_err(|e| {
-                                    ScreenshotError::CaptureFailed(format!(
-                                        "Failed to find CompressFormat class: {e}"
-                                    ))
-                                })?;
-
-                            let png_format = env
-                                .get_static_field(
-                                    &compress_format_class,
-                                    "PNG",
-                                    "Landroid/graphics/Bitmap$CompressFormat;",
-                                )
-                                .map_err(|e| {
-                                    ScreenshotError::CaptureFailed(format!(
-                                        "Failed to get PNG format: {e}"
-                                    ))
-                                })?
-                                .l()
-                                .map_err(|e| {
-                                    ScreenshotError::CaptureFailed(format!(
-                                        "Invalid PNG format: {e}"
-                                    ))
-                                })?;
-
-                            env.call_method(
-                                &bitmap,
-                                "compress",
-                                "(Landroid/graphics/Bitmap$CompressFormat;ILjava/io/OutputStream;)Z",
-                                &[
-                                    JValue::Object(&png_format),
-                                    JValue::Int(100),
-                                    JValue::Object(&baos),
-                                ],
-                            )
-                            .map_err(|e| {
-                                ScreenshotError::CaptureFailed(format!(
-                                    "Failed to compress Bitmap: {e}"
-                                ))
-                            })?;
-
-                            // Get byte array from ByteArrayOutputStream
-                            let byte_array = env
-                                .call_method(&baos, "toByteArray", "()[B", &[])
-                                .map_err(|e| {
-                                    ScreenshotError::CaptureFailed(format!(
-                                        "Failed to get byte array: {e}"
-                                    ))
-                                })?
-                                .l()
-                                .map_err(|e| {
-                                    ScreenshotError::CaptureFailed(format!(
-                                        "Invalid byte array: {e}"
-                                    ))
-                                })?;
-
-                            // Convert Java byte array to Rust Vec<u8>
-                            let byte_array = JByteArray::from(byte_array);
-                            let len = env.get_array_length(&byte_array).map_err(|e| {
-                                ScreenshotError::CaptureFailed(format!(
-                                    "Failed to get array length: {e}"
-                                ))
-                            })? as usize;
-
-                            let mut data = vec![0i8; len];
-                            env.get_byte_array_region(&byte_array, 0, &mut data)
-                                .map_err(|e| {
-                                    ScreenshotError::CaptureFailed(format!(
-                                        "Failed to copy byte array: {e}"
-                                    ))
-                                })?;
-
-                            // Convert i8 to u8 (safe reinterpret)
-                            let data: Vec<u8> = data.into_iter().map(|b| b as u8).collect();
-
-                            // Clean up: recycle the bitmap to free memory
-                            let _ = env.call_method(&bitmap, "recycle", "()V", &[]);
-
-                            Ok(Screenshot { data })
-                        })();
-
-                        let _ = tx.send(result);
-                    });
-            })
-            .map_err(|e| {
-                ScreenshotError::CaptureFailed(format!("Failed to access webview: {e}"))
-            })?;
-
-        // Wait for result with timeout
-        match rx.recv_timeout(std::time::Duration::from_secs(10)) {
-            Ok(result) => result,
-            Err(_) => Err(ScreenshotError::Timeout),
+        let orientation = options.orientation;
+        match options.strategy {
+            AndroidCaptureStrategy::Draw => {
+                capture_with(window, options, move |env, _activity, webview_obj| {
+                    draw_view_to_png(env, webview_obj, orientation)
+                })
+            }
+            AndroidCaptureStrategy::PixelCopy => capture_with_pixel_copy(window, options, false),
         }
     }
 
     #[cfg(not(target_os = "android"))]
     {
-        let _ = window;
-        Err(ScreenshotError::PlatformUnsupported)
+        let _ = (window, options);
+        Err(ScreenshotError::PlatformUnsupported(
+            "this capture requires Android".to_string(),
+        ))
+    }
+}
+
+/// Android capture of the app's whole window, standing in for a full screen
+/// capture.
+///
+/// Draws, or with [`AndroidCaptureStrategy::PixelCopy`] copies, the activity
+/// window's root decor view instead of the WebView, so native toolbars and
+/// other views hosted by the activity are included. System UI (status bar,
+/// navigation bar) and other apps are owned by other processes and are not
+/// part of the capture; that would need a MediaProjection session with the
+/// user's consent.
+pub fn capture_screen<R: Runtime>(
+    window: &WebviewWindow<R>,
+    options: &CaptureOptions,
+) -> Result<Screenshot, ScreenshotError> {
+    #[cfg(target_os = "android")]
+    {
+        if options.strategy == AndroidCaptureStrategy::PixelCopy {
+            return capture_with_pixel_copy(window, options, true);
+        }
+
+        let orientation = options.orientation;
+        capture_with(window, options, move |env, activity, _webview_obj| {
+            let root_view = match activity_root_view(env, activity) {
+                Ok(view) => view,
+                Err(e) => {
+                    return Err(java_error(
+                        env,
+                        format!("Failed to get activity root view: {e}"),
+                    ))
+                }
+            };
+
+            draw_view_to_png(env, &root_view, orientation)
+        })
+    }
+
+    #[cfg(not(target_os = "android"))]
+    {
+        let _ = (window, options);
+        Err(ScreenshotError::PlatformUnsupported(
+            "this capture requires Android".to_string(),
+        ))
+    }
+}
+
+/// Runs a capture closure on the Android UI thread with access to the JNI
+/// environment, the activity and the WebView, waiting for its result.
+#[cfg(target_os = "android")]
+fn capture_with<R, F>(
+    window: &WebviewWindow<R>,
+    options: &CaptureOptions,
+    capture: F,
+) -> Result<Screenshot, ScreenshotError>
+where
+    R: Runtime,
+    F: FnOnce(
+            &mut jni::JNIEnv,
+            &jni::objects::JObject,
+            &jni::objects::JObject,
+        ) -> Result<Vec<u8>, ScreenshotError>
+        + Send
+        + 'static,
+{
+    use std::sync::mpsc;
+
+    let (tx, rx) = mpsc::channel::<Result<Screenshot, ScreenshotError>>();
+
+    // Use Tauri's with_webview to access the Android WebView via JNI
+    window
+        .with_webview(move |webview| {
+            webview
+                .jni_handle()
+                .exec(move |env, activity, webview_obj| {
+                    trace_step!("running capture on the UI thread");
+                    let result = capture(env, activity, webview_obj).map(Screenshot::new);
+                    trace_step!(ok = result.is_ok(), "capture finished on the UI thread");
+                    let _ = tx.send(result);
+                });
+        })
+        .map_err(|e| ScreenshotError::CaptureFailed(format!("Failed to access webview: {e}")))?;
+
+    // Wait for result with timeout
+    super::recv_webview_capture(&rx, options.timeout)
+}
+
+/// Class shipped in the plugin's Android library that issues
+/// `PixelCopy.request()`, since its result listener can't be implemented over
+/// JNI.
+#[cfg(target_os = "android")]
+const PIXEL_COPY_CLASS: &str = "app.tauri.mcpbridge.PixelCopyCapture";
+
+/// Result channels of PixelCopy requests waiting for their callback.
+#[cfg(target_os = "android")]
+type PendingCaptures =
+    std::collections::HashMap<i64, std::sync::mpsc::Sender<Result<Screenshot, ScreenshotError>>>;
+
+#[cfg(target_os = "android")]
+fn pending_pixel_copies() -> &'static std::sync::Mutex<PendingCaptures> {
+    static PENDING: std::sync::OnceLock<std::sync::Mutex<PendingCaptures>> =
+        std::sync::OnceLock::new();
+    PENDING.get_or_init(Default::default)
+}
+
+/// Copies the WebView, or the activity's whole window with `root_view`, from
+/// the rendered window surface with `PixelCopy.request()`.
+///
+/// The request is issued on the UI thread through `PixelCopyCapture` in the
+/// plugin's Android library, which reports back through
+/// `on_pixel_copy_finished` on its own handler thread. The result arrives
+/// on the same channel as the draw-based captures, so it shares their
+/// timeout.
+#[cfg(target_os = "android")]
+fn capture_with_pixel_copy<R: Runtime>(
+    window: &WebviewWindow<R>,
+    options: &CaptureOptions,
+    root_view: bool,
+) -> Result<Screenshot, ScreenshotError> {
+    use std::sync::atomic::{AtomicI64, Ordering};
+    use std::sync::mpsc;
+
+    static NEXT_REQUEST_ID: AtomicI64 = AtomicI64::new(1);
+
+    let (tx, rx) = mpsc::channel::<Result<Screenshot, ScreenshotError>>();
+    let request_id = NEXT_REQUEST_ID.fetch_add(1, Ordering::Relaxed);
+    let orientation = options.orientation;
+
+    window
+        .with_webview(move |webview| {
+            webview
+                .jni_handle()
+                .exec(move |env, activity, webview_obj| {
+                    // Registered only once the closure runs, so a runtime that
+                    // drops it still disconnects the channel
+                    pending_pixel_copies()
+                        .lock()
+                        .unwrap_or_else(|e| e.into_inner())
+                        .insert(request_id, tx);
+
+                    let requested = if root_view {
+                        activity_root_view(env, activity)
+                            .map_err(|e| {
+                                java_error(env, format!("Failed to get activity root view: {e}"))
+                            })
+                            .and_then(|view| {
+                                request_pixel_copy(env, activity, &view, orientation, request_id)
+                            })
+                    } else {
+                        request_pixel_copy(env, activity, webview_obj, orientation, request_id)
+                    };
+                    trace_step!(ok = requested.is_ok(), "pixel copy: issued request");
+
+                    if let Err(e) = requested {
+                        finish_pixel_copy(request_id, Err(e));
+                    }
+                });
+        })
+        .map_err(|e| ScreenshotError::CaptureFailed(format!("Failed to access webview: {e}")))?;
+
+    let result = super::recv_webview_capture(&rx, options.timeout);
+    pending_pixel_copies()
+        .lock()
+        .unwrap_or_else(|e| e.into_inner())
+        .remove(&request_id);
+    result
+}
+
+/// Issues the PixelCopy request for `view`, rotated to `orientation`.
+#[cfg(target_os = "android")]
+fn request_pixel_copy(
+    env: &mut jni::JNIEnv,
+    activity: &jni::objects::JObject,
+    view: &jni::objects::JObject,
+    orientation: CaptureOrientation,
+    request_id: i64,
+) -> Result<(), ScreenshotError> {
+    use jni::objects::{JClass, JValue};
+
+    let width = env
+        .call_method(view, "getWidth", "()I", &[])
+        .and_then(|v| v.i())
+        .map_err(|e| java_error(env, format!("Failed to get width: {e}")))?;
+    let height = env
+        .call_method(view, "getHeight", "()I", &[])
+        .and_then(|v| v.i())
+        .map_err(|e| java_error(env, format!("Failed to get height: {e}")))?;
+    trace_step!(width, height, "pixel copy: resolved view size");
+
+    if width <= 0 || height <= 0 {
+        return Err(ScreenshotError::CaptureFailed(format!(
+            "Invalid view dimensions: {width}x{height}"
+        )));
     }
+
+    // JNI's FindClass uses the system class loader on this thread, which
+    // doesn't see the app's classes
+    let class = env
+        .call_method(activity, "getClassLoader", "()Ljava/lang/ClassLoader;", &[])
+        .and_then(|loader| loader.l())
+        .and_then(|loader| {
+            let name = env.new_string(PIXEL_COPY_CLASS)?;
+            env.call_method(
+                &loader,
+                "loadClass",
+                "(Ljava/lang/String;)Ljava/lang/Class;",
+                &[JValue::Object(&name)],
+            )?
+            .l()
+        })
+        .map(JClass::from)
+        .map_err(|e| java_error(env, format!("Failed to load {PIXEL_COPY_CLASS}: {e}")))?;
+
+    // Bound explicitly rather than by symbol name, which the linker may not
+    // export from this crate
+    env.register_native_methods(
+        &class,
+        &[jni::NativeMethod {
+            name: "onCaptureFinished".into(),
+            sig: "(J[BLjava/lang/String;)V".into(),
+            fn_ptr: on_pixel_copy_finished as *mut std::ffi::c_void,
+        }],
+    )
+    .map_err(|e| java_error(env, format!("Failed to register PixelCopy callback: {e}")))?;
+
+    let requested = env
+        .call_static_method(
+            &class,
+            "request",
+            "(Landroid/app/Activity;Landroid/view/View;ZJ)Z",
+            &[
+                JValue::Object(activity),
+                JValue::Object(view),
+                JValue::Bool(orientation.needs_rotation(width, height).into()),
+                JValue::Long(request_id),
+            ],
+        )
+        .and_then(|v| v.z())
+        .map_err(|e| java_error(env, format!("Failed to request PixelCopy: {e}")))?;
+
+    if !requested {
+        return Err(ScreenshotError::PlatformUnsupported(
+            "PixelCopy capture requires Android 8.0 (API 26) or later".to_string(),
+        ));
+    }
+    Ok(())
+}
+
+/// Sends the result of a PixelCopy request to its waiting capture, if it
+/// hasn't timed out.
+#[cfg(target_os = "android")]
+fn finish_pixel_copy(request_id: i64, result: Result<Vec<u8>, ScreenshotError>) {
+    let sender = pending_pixel_copies()
+        .lock()
+        .unwrap_or_else(|e| e.into_inner())
+        .remove(&request_id);
+    if let Some(sender) = sender {
+        let _ = sender.send(result.map(Screenshot::new));
+    }
+}
+
+/// `PixelCopyCapture.onCaptureFinished(requestId, png, error)`, called on the
+/// library's handler thread once the copy is encoded or has failed.
+#[cfg(target_os = "android")]
+extern "system" fn on_pixel_copy_finished<'local>(
+    mut env: jni::JNIEnv<'local>,
+    _class: jni::objects::JClass<'local>,
+    request_id: jni::sys::jlong,
+    png: jni::objects::JByteArray<'local>,
+    error: jni::objects::JString<'local>,
+) {
+    let result = if !error.is_null() {
+        let message = env
+            .get_string(&error)
+            .map(String::from)
+            .unwrap_or_else(|_| "PixelCopy failed".to_string());
+        Err(ScreenshotError::CaptureFailed(message))
+    } else {
+        env.convert_byte_array(&png)
+            .map_err(|e| ScreenshotError::CaptureFailed(format!("Failed to copy PNG bytes: {e}")))
+    };
+    trace_step!(request_id, ok = result.is_ok(), "pixel copy: finished");
+    finish_pixel_copy(request_id, result);
+}
+
+/// Resolves `activity.getWindow().getDecorView().getRootView()`.
+#[cfg(target_os = "android")]
+fn activity_root_view<'local>(
+    env: &mut jni::JNIEnv<'local>,
+    activity: &jni::objects::JObject,
+) -> jni::errors::Result<jni::objects::JObject<'local>> {
+    let activity_window = env
+        .call_method(activity, "getWindow", "()Landroid/view/Window;", &[])?
+        .l()?;
+    let decor_view = env
+        .call_method(
+            &activity_window,
+            "getDecorView",
+            "()Landroid/view/View;",
+            &[],
+        )?
+        .l()?;
+    env.call_method(&decor_view, "getRootView", "()Landroid/view/View;", &[])?
+        .l()
+}
+
+/// Converts a failed JNI call into a screenshot error, clearing any pending
+/// Java exception. A `SecurityException` is reported as `PermissionDenied`.
+#[cfg(target_os = "android")]
+fn java_error(env: &mut jni::JNIEnv, message: String) -> ScreenshotError {
+    if env.exception_check().unwrap_or(false) {
+        let throwable = env.exception_occurred();
+        let _ = env.exception_clear();
+
+        if let Ok(throwable) = throwable {
+            if env
+                .is_instance_of(&throwable, "java/lang/SecurityException")
+                .unwrap_or(false)
+            {
+                return ScreenshotError::PermissionDenied(message);
+            }
+        }
+    }
+
+    ScreenshotError::CaptureFailed(message)
+}
+
+/// Draws an Android view into an ARGB_8888 Bitmap and compresses it to PNG bytes,
+/// rotated to `orientation` when the view is laid out the other way.
+#[cfg(target_os = "android")]
+fn draw_view_to_png(
+    env: &mut jni::JNIEnv,
+    view: &jni::objects::JObject,
+    orientation: CaptureOrientation,
+) -> Result<Vec<u8>, ScreenshotError> {
+    use jni::objects::{JByteArray, JValue};
+
+    // Get view dimensions
+    let width = env
+        .call_method(view, "getWidth", "()I", &[])
+        .map_err(|e| ScreenshotError::CaptureFailed(format!("Failed to get width: {e}")))?
+        .i()
+        .map_err(|e| ScreenshotError::CaptureFailed(format!("Invalid width: {e}")))?;
+
+    let height = env
+        .call_method(view, "getHeight", "()I", &[])
+        .map_err(|e| ScreenshotError::CaptureFailed(format!("Failed to get height: {e}")))?
+        .i()
+        .map_err(|e| ScreenshotError::CaptureFailed(format!("Invalid height: {e}")))?;
+    trace_step!(width, height, "draw: resolved view size");
+
+    if width <= 0 || height <= 0 {
+        return Err(ScreenshotError::CaptureFailed(format!(
+            "Invalid view dimensions: {width}x{height}"
+        )));
+    }
+
+    let rotate = orientation.needs_rotation(width, height);
+    let (bitmap_width, bitmap_height) = if rotate {
+        (height, width)
+    } else {
+        (width, height)
+    };
+
+    // Create Bitmap with ARGB_8888 config
+    let bitmap_class = env
+        .find_class("android/graphics/Bitmap")
+        .map_err(|e| ScreenshotError::CaptureFailed(format!("Failed to find Bitmap class: {e}")))?;
+
+    let config_class = env
+        .find_class("android/graphics/Bitmap$Config")
+        .map_err(|e| {
+            ScreenshotError::CaptureFailed(format!("Failed to find Bitmap.Config class: {e}"))
+        })?;
+
+    let argb_8888 = env
+        .get_static_field(
+            &config_class,
+            "ARGB_8888",
+            "Landroid/graphics/Bitmap$Config;",
+        )
+        .map_err(|e| ScreenshotError::CaptureFailed(format!("Failed to get ARGB_8888: {e}")))?
+        .l()
+        .map_err(|e| ScreenshotError::CaptureFailed(format!("Invalid ARGB_8888: {e}")))?;
+
+    let bitmap = env
+        .call_static_method(
+            &bitmap_class,
+            "createBitmap",
+            "(IILandroid/graphics/Bitmap$Config;)Landroid/graphics/Bitmap;",
+            &[
+                JValue::Int(bitmap_width),
+                JValue::Int(bitmap_height),
+                JValue::Object(&argb_8888),
+            ],
+        )
+        .map_err(|e| ScreenshotError::CaptureFailed(format!("Failed to create Bitmap: {e}")))?
+        .l()
+        .map_err(|e| ScreenshotError::CaptureFailed(format!("Invalid Bitmap: {e}")))?;
+    trace_step!("draw: created ARGB_8888 bitmap");
+
+    // Create Canvas from Bitmap
+    let canvas_class = env
+        .find_class("android/graphics/Canvas")
+        .map_err(|e| ScreenshotError::CaptureFailed(format!("Failed to find Canvas class: {e}")))?;
+
+    let canvas = env
+        .new_object(
+            &canvas_class,
+            "(Landroid/graphics/Bitmap;)V",
+            &[JValue::Object(&bitmap)],
+        )
+        .map_err(|e| ScreenshotError::CaptureFailed(format!("Failed to create Canvas: {e}")))?;
+    trace_step!("draw: created canvas");
+
+    if rotate {
+        // Turn clockwise about the origin, then shift the view back into
+        // the bitmap: (x, y) lands at (height - y, x)
+        env.call_method(
+            &canvas,
+            "translate",
+            "(FF)V",
+            &[JValue::Float(height as f32), JValue::Float(0.0)],
+        )
+        .map_err(|e| ScreenshotError::CaptureFailed(format!("Failed to translate Canvas: {e}")))?;
+        env.call_method(&canvas, "rotate", "(F)V", &[JValue::Float(90.0)])
+            .map_err(|e| ScreenshotError::CaptureFailed(format!("Failed to rotate Canvas: {e}")))?;
+        trace_step!(?orientation, "draw: rotated canvas");
+    }
+
+    // Draw the view to the Canvas
+    env.call_method(
+        view,
+        "draw",
+        "(Landroid/graphics/Canvas;)V",
+        &[JValue::Object(&canvas)],
+    )
+    .map_err(|e| ScreenshotError::CaptureFailed(format!("Failed to draw view: {e}")))?;
+    trace_step!("draw: drew view to canvas");
+
+    // Compress Bitmap to PNG bytes
+    let baos_class = env
+        .find_class("java/io/ByteArrayOutputStream")
+        .map_err(|e| {
+            ScreenshotError::CaptureFailed(format!(
+                "Failed to find ByteArrayOutputStream class: {e}"
+            ))
+        })?;
+
+    let baos = env.new_object(&baos_class, "()V", &[]).map_err(|e| {
+        ScreenshotError::CaptureFailed(format!("Failed to create ByteArrayOutputStream: {e}"))
+    })?;
+
+    let compress_format_class = env
+        .find_class("android/graphics/Bitmap$CompressFormat")
+        .map_err(|e| {
+            ScreenshotError::CaptureFailed(format!("Failed to find CompressFormat class: {e}"))
+        })?;
+
+    let png_format = env
+        .get_static_field(
+            &compress_format_class,
+            "PNG",
+            "Landroid/graphics/Bitmap$CompressFormat;",
+        )
+        .map_err(|e| ScreenshotError::CaptureFailed(format!("Failed to get PNG format: {e}")))?
+        .l()
+        .map_err(|e| ScreenshotError::CaptureFailed(format!("Invalid PNG format: {e}")))?;
+
+    env.call_method(
+        &bitmap,
+        "compress",
+        "(Landroid/graphics/Bitmap$CompressFormat;ILjava/io/OutputStream;)Z",
+        &[
+            JValue::Object(&png_format),
+            JValue::Int(100),
+            JValue::Object(&baos),
+        ],
+    )
+    .map_err(|e| ScreenshotError::CaptureFailed(format!("Failed to compress Bitmap: {e}")))?;
+    trace_step!("draw: compressed bitmap to PNG");
+
+    // Get byte array from ByteArrayOutputStream
+    let byte_array = env
+        .call_method(&baos, "toByteArray", "()[B", &[])
+        .map_err(|e| ScreenshotError::CaptureFailed(format!("Failed to get byte array: {e}")))?
+        .l()
+        .map_err(|e| ScreenshotError::CaptureFailed(format!("Invalid byte array: {e}")))?;
+
+    // Convert Java byte array to Rust Vec<u8>
+    let byte_array = JByteArray::from(byte_array);
+    let len = env
+        .get_array_length(&byte_array)
+        .map_err(|e| ScreenshotError::CaptureFailed(format!("Failed to get array length: {e}")))?
+        as usize;
+
+    let mut data = vec![0i8; len];
+    env.get_byte_array_region(&byte_array, 0, &mut data)
+        .map_err(|e| ScreenshotError::CaptureFailed(format!("Failed to copy byte array: {e}")))?;
+
+    // Convert i8 to u8 (safe reinterpret)
+    let data: Vec<u8> = data.into_iter().map(|b| b as u8).collect();
+    trace_step!(bytes = data.len(), "draw: copied PNG bytes from Java");
+
+    // Clean up: recycle the bitmap to free memory
+    let _ = env.call_method(&bitmap, "recycle", "()V", &[]);
+
+    Ok(data)
 }
@@ -0,0 +1,125 @@
+//! Assembling screenshots into one animated PNG (APNG).
+//!
+//! APNG files are valid PNGs, so viewers without animation support show
+//! the first frame. Frames of differing sizes are padded with transparent
+//! pixels to the largest width and height, anchored at the top left.
+
+use std::time::Duration;
+
+use image::RgbaImage;
+
+use super::diff::pad;
+use super::{CaptureSource, ImageFormat, Screenshot, ScreenshotError};
+
+/// Frame delay used when none is given.
+pub const DEFAULT_FRAME_DELAY: Duration = Duration::from_millis(200);
+
+/// Encodes `frames` as an APNG that loops forever, showing each frame for
+/// `frame_delay`.
+///
+/// # Arguments
+///
+/// * `frames` - Screenshots in display order, in any format or transport
+///   encoding
+/// * `frame_delay` - How long each frame is shown, rounded to milliseconds
+///   and capped at about 65 seconds
+///
+/// # Returns
+///
+/// * `Ok(Screenshot)` - The animation, as a PNG without transport encoding
+/// * `Err(ScreenshotError)` - If there are no frames, or a frame can't be
+///   decoded or the animation encoded
+pub fn encode_apng(
+    frames: &[Screenshot],
+    frame_delay: Duration,
+) -> Result<Screenshot, ScreenshotError> {
+    let Some(first) = frames.first() else {
+        return Err(ScreenshotError::EncodeFailed(
+            "An animation needs at least one frame".to_string(),
+        ));
+    };
+
+    let images = frames
+        .iter()
+        .map(Screenshot::decode)
+        .collect::<Result<Vec<_>, _>>()?;
+    let width = images.iter().map(RgbaImage::width).max().unwrap_or(0);
+    let height = images.iter().map(RgbaImage::height).max().unwrap_or(0);
+
+    let encode_error = |e: png::EncodingError| {
+        ScreenshotError::EncodeFailed(format!("Failed to encode animation: {e}"))
+    };
+    let delay_ms = frame_delay.as_millis().min(u128::from(u16::MAX)) as u16;
+
+    let mut data = Vec::new();
+    let mut encoder = png::Encoder::new(&mut data, width, height);
+    encoder.set_color(png::ColorType::Rgba);
+    encoder.set_depth(png::BitDepth::Eight);
+    encoder
+        .set_animated(images.len() as u32, 0)
+        .map_err(encode_error)?;
+    encoder
+        .set_frame_delay(delay_ms, 1000)
+        .map_err(encode_error)?;
+    // Each frame replaces the previous one rather than drawing over it
+    encoder
+        .set_blend_op(png::BlendOp::Source)
+        .map_err(encode_error)?;
+
+    let mut writer = encoder.write_header().map_err(encode_error)?;
+    for image in images {
+        writer
+            .write_image_data(pad(image, width, height).as_raw())
+            .map_err(encode_error)?;
+    }
+    writer.finish().map_err(encode_error)?;
+
+    let source = if frames.iter().all(|frame| frame.source == first.source) {
+        first.source
+    } else {
+        // Mixed sources; report the lower-fidelity one
+        CaptureSource::Dom
+    };
+    Ok(Screenshot {
+        format: ImageFormat::Png,
+        source,
+        bounds: first.bounds,
+        ..Screenshot::new(data)
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn frame(width: u32, height: u32, color: [u8; 4]) -> Screenshot {
+        let mut data = Vec::new();
+        RgbaImage::from_pixel(width, height, image::Rgba(color))
+            .write_to(
+                &mut std::io::Cursor::new(&mut data),
+                image::ImageFormat::Png,
+            )
+            .unwrap();
+        Screenshot::new(data)
+    }
+
+    #[test]
+    fn test_encodes_padded_frames() {
+        let frames = [frame(4, 2, [255, 0, 0, 255]), frame(2, 3, [0, 0, 255, 255])];
+        let animation = encode_apng(&frames, Duration::from_millis(100)).unwrap();
+
+        let decoder = png::Decoder::new(std::io::Cursor::new(animation.data.as_slice()));
+        let reader = decoder.read_info().unwrap();
+        let info = reader.info();
+        assert_eq!((info.width, info.height), (4, 3));
+        let control = info.animation_control.unwrap();
+        assert_eq!((control.num_frames, control.num_plays), (2, 0));
+
+        // The first frame doubles as the still image
+        let still = animation.decode().unwrap();
+        assert_eq!(still.get_pixel(0, 0).0, [255, 0, 0, 255]);
+        assert_eq!(still.get_pixel(3, 2).0, [0, 0, 0, 0]);
+
+        assert!(encode_apng(&[], DEFAULT_FRAME_DELAY).is_err());
+    }
+}
@@ -0,0 +1,149 @@
+//! Short-lived cache of viewport captures, so rapid repeated requests for an
+//! unchanged page skip the native capture.
+//!
+//! Each window keeps its last capture together with the options it was
+//! encoded with and the page's DOM version, a token that bridge.js changes
+//! on every navigation and DOM mutation. A cached capture is reused only
+//! while it is younger than the TTL and both still match. Changes that
+//! don't touch the DOM (CSS animations, canvas, video) are bounded by the
+//! TTL alone, so keep it short.
+
+use std::collections::HashMap;
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+use super::Screenshot;
+
+/// Cache TTL by default: disabled.
+pub const DEFAULT_CAPTURE_CACHE_TTL: Duration = Duration::ZERO;
+
+struct CachedCapture {
+    key: String,
+    dom_version: String,
+    captured_at: Instant,
+    screenshot: Screenshot,
+}
+
+/// Last capture of each window, keyed by window label.
+pub struct CaptureCache {
+    ttl: Duration,
+    windows: Mutex<HashMap<String, CachedCapture>>,
+}
+
+impl Default for CaptureCache {
+    fn default() -> Self {
+        Self::new(DEFAULT_CAPTURE_CACHE_TTL)
+    }
+}
+
+impl CaptureCache {
+    /// Creates a cache keeping captures for `ttl`. A zero TTL disables it.
+    pub fn new(ttl: Duration) -> Self {
+        Self {
+            ttl,
+            windows: Mutex::new(HashMap::new()),
+        }
+    }
+
+    /// Whether captures are cached at all.
+    pub fn is_enabled(&self) -> bool {
+        !self.ttl.is_zero()
+    }
+
+    /// Returns the window's cached capture if it was taken with the same
+    /// options key, on the same DOM version, within the TTL.
+    pub fn get(&self, window_label: &str, key: &str, dom_version: &str) -> Option<Screenshot> {
+        self.get_at(window_label, key, dom_version, Instant::now())
+    }
+
+    /// Stores the window's latest capture, replacing any previous one.
+    pub fn insert(
+        &self,
+        window_label: &str,
+        key: String,
+        dom_version: String,
+        screenshot: Screenshot,
+    ) {
+        self.insert_at(window_label, key, dom_version, screenshot, Instant::now());
+    }
+
+    fn get_at(
+        &self,
+        window_label: &str,
+        key: &str,
+        dom_version: &str,
+        now: Instant,
+    ) -> Option<Screenshot> {
+        let mut windows = self.windows.lock().unwrap();
+        let cached = windows.get(window_label)?;
+
+        if now.duration_since(cached.captured_at) >= self.ttl {
+            windows.remove(window_label);
+            return None;
+        }
+
+        (cached.key == key && cached.dom_version == dom_version).then(|| cached.screenshot.clone())
+    }
+
+    fn insert_at(
+        &self,
+        window_label: &str,
+        key: String,
+        dom_version: String,
+        screenshot: Screenshot,
+        now: Instant,
+    ) {
+        if !self.is_enabled() {
+            return;
+        }
+
+        self.windows.lock().unwrap().insert(
+            window_label.to_string(),
+            CachedCapture {
+                key,
+                dom_version,
+                captured_at: now,
+                screenshot,
+            },
+        );
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_hits_only_while_fresh_and_unchanged() {
+        let cache = CaptureCache::new(Duration::from_millis(100));
+        let start = Instant::now();
+        cache.insert_at(
+            "main",
+            "png".into(),
+            "a:1".into(),
+            Screenshot::new(vec![1]),
+            start,
+        );
+
+        let soon = start + Duration::from_millis(10);
+        assert_eq!(
+            cache.get_at("main", "png", "a:1", soon).unwrap().data,
+            vec![1]
+        );
+        assert!(cache.get_at("main", "jpeg", "a:1", soon).is_none());
+        assert!(cache.get_at("main", "png", "a:2", soon).is_none());
+        assert!(cache.get_at("other", "png", "a:1", soon).is_none());
+
+        let later = start + Duration::from_millis(100);
+        assert!(cache.get_at("main", "png", "a:1", later).is_none());
+    }
+
+    #[test]
+    fn test_zero_ttl_disables_cache() {
+        let cache = CaptureCache::default();
+        assert!(!cache.is_enabled());
+
+        cache.insert("main", "png".into(), "a:1".into(), Screenshot::new(vec![1]));
+        assert!(cache.get("main", "png", "a:1").is_none());
+    }
+}
@@ -0,0 +1,307 @@
+//! Pixel comparison of screenshots against a baseline image, or against
+//! each other.
+
+use base64::Engine as _;
+use image::{Rgba, RgbaImage};
+use serde::Serialize;
+
+use super::{ImageFormat, Screenshot};
+
+/// Color used to mark differing pixels in the diff image.
+const DIFF_COLOR: Rgba<u8> = Rgba([255, 0, 0, 255]);
+
+/// Result of comparing two images of the same size.
+#[derive(Debug)]
+pub struct PixelDiff {
+    /// Number of pixels that differ by more than the tolerance
+    pub differing_pixels: u64,
+    /// Percentage of differing pixels (0-100)
+    pub diff_percent: f64,
+    /// The current image, dimmed, with differing pixels marked in red
+    pub diff_image: RgbaImage,
+}
+
+/// Per-channel difference [`diff_screenshots`] callers should use when
+/// they have no better value; it absorbs JPEG and scaling noise.
+pub const DEFAULT_NOISE_TOLERANCE: u8 = 8;
+
+/// Smallest rectangle containing every changed pixel, in pixels.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[cfg_attr(feature = "ts-bindings", derive(ts_rs::TS))]
+pub struct ChangedRegion {
+    pub x: u32,
+    pub y: u32,
+    pub width: u32,
+    pub height: u32,
+}
+
+/// Result of comparing two screenshots with [`diff_screenshots`].
+#[derive(Debug, Clone, Serialize)]
+#[cfg_attr(feature = "ts-bindings", derive(ts_rs::TS))]
+#[serde(rename_all = "camelCase")]
+pub struct DiffResult {
+    /// Whether any pixel changed
+    pub changed: bool,
+    /// Number of pixels that differ by more than the tolerance
+    #[cfg_attr(feature = "ts-bindings", ts(type = "number"))]
+    pub changed_pixels: u64,
+    /// Percentage of changed pixels (0-100) of the compared area
+    pub diff_percent: f64,
+    /// Where the changes are, when there are any
+    pub changed_region: Option<ChangedRegion>,
+    /// Whether the screenshots had different dimensions
+    pub size_changed: bool,
+}
+
+/// Whether any channel of two pixels differs by more than `tolerance`.
+fn differs(a: &Rgba<u8>, b: &Rgba<u8>, tolerance: u8) -> bool {
+    a.0.iter()
+        .zip(b.0.iter())
+        .any(|(a, b)| a.abs_diff(*b) > tolerance)
+}
+
+/// `image` on a transparent canvas of `width` by `height`, anchored at the
+/// top left.
+pub(crate) fn pad(image: RgbaImage, width: u32, height: u32) -> RgbaImage {
+    if image.dimensions() == (width, height) {
+        return image;
+    }
+    let mut canvas = RgbaImage::new(width, height);
+    image::imageops::replace(&mut canvas, &image, 0, 0);
+    canvas
+}
+
+/// Compares two screenshots, for telling whether a page changed after an
+/// action.
+///
+/// Both are decoded, whatever their format and transport encoding, and
+/// padded with transparent pixels to the larger width and height, so an
+/// area only one of them covers counts as changed.
+///
+/// # Arguments
+///
+/// * `a` - The earlier screenshot
+/// * `b` - The later screenshot
+/// * `tolerance` - Largest per-channel difference (0-255) still counted as
+///   unchanged; see [`DEFAULT_NOISE_TOLERANCE`]
+///
+/// # Returns
+///
+/// * `Ok(DiffResult)` - How much changed and where
+/// * `Err(String)` - Error message if either screenshot can't be decoded
+pub fn diff_screenshots(
+    a: &Screenshot,
+    b: &Screenshot,
+    tolerance: u8,
+) -> Result<DiffResult, String> {
+    let a = a.decode().map_err(|e| format!("First screenshot: {e}"))?;
+    let b = b.decode().map_err(|e| format!("Second screenshot: {e}"))?;
+    let size_changed = a.dimensions() != b.dimensions();
+    let width = a.width().max(b.width());
+    let height = a.height().max(b.height());
+    let (a, b) = (pad(a, width, height), pad(b, width, height));
+
+    let mut changed_pixels = 0u64;
+    let (mut min_x, mut min_y, mut max_x, mut max_y) = (u32::MAX, u32::MAX, 0, 0);
+    for ((x, y, before), after) in a.enumerate_pixels().zip(b.pixels()) {
+        if differs(before, after, tolerance) {
+            changed_pixels += 1;
+            min_x = min_x.min(x);
+            min_y = min_y.min(y);
+            max_x = max_x.max(x);
+            max_y = max_y.max(y);
+        }
+    }
+
+    let total = u64::from(width) * u64::from(height);
+    let diff_percent = if total == 0 {
+        0.0
+    } else {
+        changed_pixels as f64 * 100.0 / total as f64
+    };
+
+    Ok(DiffResult {
+        changed: changed_pixels > 0,
+        changed_pixels,
+        diff_percent,
+        changed_region: (changed_pixels > 0).then(|| ChangedRegion {
+            x: min_x,
+            y: min_y,
+            width: max_x - min_x + 1,
+            height: max_y - min_y + 1,
+        }),
+        size_changed,
+    })
+}
+
+/// Reads a PNG or JPEG screenshot given as base64, with or without a
+/// `data:` URL prefix, as returned by the capture commands.
+pub fn screenshot_from_base64(encoded: &str) -> Result<Screenshot, String> {
+    let encoded = encoded
+        .split_once(";base64,")
+        .filter(|(header, _)| header.starts_with("data:"))
+        .map_or(encoded, |(_, data)| data);
+    let data = base64::engine::general_purpose::STANDARD
+        .decode(encoded.trim())
+        .map_err(|e| format!("Invalid base64 image: {e}"))?;
+
+    let format = match image::guess_format(&data) {
+        Ok(image::ImageFormat::Png) => ImageFormat::Png,
+        Ok(image::ImageFormat::Jpeg) => ImageFormat::Jpeg,
+        _ => return Err("Image is neither a PNG nor a JPEG".to_string()),
+    };
+    Ok(Screenshot {
+        format,
+        ..Screenshot::new(data)
+    })
+}
+
+/// Compares two images pixel by pixel.
+///
+/// A pixel differs when any channel differs by more than `tolerance`.
+/// Returns `None` if the images have different dimensions.
+pub fn compare(baseline: &RgbaImage, current: &RgbaImage, tolerance: u8) -> Option<PixelDiff> {
+    if baseline.dimensions() != current.dimensions() {
+        return None;
+    }
+
+    let (width, height) = current.dimensions();
+    let mut diff_image = RgbaImage::new(width, height);
+    let mut differing_pixels = 0u64;
+
+    for ((expected, actual), out) in baseline
+        .pixels()
+        .zip(current.pixels())
+        .zip(diff_image.pixels_mut())
+    {
+        *out = if differs(expected, actual, tolerance) {
+            differing_pixels += 1;
+            DIFF_COLOR
+        } else {
+            dim(actual)
+        };
+    }
+
+    let total = u64::from(width) * u64::from(height);
+    let diff_percent = if total == 0 {
+        0.0
+    } else {
+        differing_pixels as f64 * 100.0 / total as f64
+    };
+
+    Some(PixelDiff {
+        differing_pixels,
+        diff_percent,
+        diff_image,
+    })
+}
+
+/// Fades a pixel to light gray so marked differences stand out.
+fn dim(pixel: &Rgba<u8>) -> Rgba<u8> {
+    let [r, g, b, _] = pixel.0;
+    let luma = (u16::from(r) * 3 + u16::from(g) * 6 + u16::from(b)) / 10;
+    let faded = 192 + (luma / 4) as u8;
+    Rgba([faded, faded, faded, 255])
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn solid(width: u32, height: u32, value: u8) -> RgbaImage {
+        RgbaImage::from_pixel(width, height, Rgba([value, value, value, 255]))
+    }
+
+    #[test]
+    fn test_identical_images_match() {
+        let diff = compare(&solid(4, 4, 10), &solid(4, 4, 10), 0).unwrap();
+        assert_eq!(diff.differing_pixels, 0);
+        assert_eq!(diff.diff_percent, 0.0);
+    }
+
+    #[test]
+    fn test_tolerance_and_diff_percent() {
+        let baseline = solid(2, 2, 100);
+        let mut current = solid(2, 2, 100);
+        current.put_pixel(0, 0, Rgba([105, 100, 100, 255]));
+        current.put_pixel(1, 1, Rgba([100, 100, 140, 255]));
+
+        let strict = compare(&baseline, &current, 0).unwrap();
+        assert_eq!(strict.differing_pixels, 2);
+        assert_eq!(strict.diff_percent, 50.0);
+        assert_eq!(*strict.diff_image.get_pixel(1, 1), DIFF_COLOR);
+
+        let tolerant = compare(&baseline, &current, 10).unwrap();
+        assert_eq!(tolerant.differing_pixels, 1);
+        assert_eq!(tolerant.diff_percent, 25.0);
+    }
+
+    #[test]
+    fn test_size_mismatch_is_reported() {
+        assert!(compare(&solid(2, 2, 0), &solid(3, 2, 0), 0).is_none());
+    }
+
+    fn screenshot(image: &RgbaImage) -> Screenshot {
+        let mut data = Vec::new();
+        image
+            .write_to(
+                &mut std::io::Cursor::new(&mut data),
+                image::ImageFormat::Png,
+            )
+            .unwrap();
+        Screenshot::new(data)
+    }
+
+    #[test]
+    fn test_diff_screenshots_bounds_changes() {
+        let before = solid(8, 6, 100);
+        let mut after = solid(8, 6, 100);
+        // Noise below the tolerance, and a real change in a 3x2 block
+        after.put_pixel(0, 0, Rgba([104, 100, 100, 255]));
+        for (x, y) in [(2, 1), (4, 2)] {
+            after.put_pixel(x, y, Rgba([0, 0, 0, 255]));
+        }
+
+        let diff = diff_screenshots(
+            &screenshot(&before),
+            &screenshot(&after),
+            DEFAULT_NOISE_TOLERANCE,
+        )
+        .unwrap();
+        assert!(diff.changed && !diff.size_changed);
+        assert_eq!(diff.changed_pixels, 2);
+        assert_eq!(
+            diff.changed_region,
+            Some(ChangedRegion {
+                x: 2,
+                y: 1,
+                width: 3,
+                height: 2
+            })
+        );
+
+        let same = diff_screenshots(&screenshot(&before), &screenshot(&before), 0).unwrap();
+        assert!(!same.changed && same.changed_region.is_none());
+
+        // The extra column only one image covers counts as changed
+        let wider =
+            diff_screenshots(&screenshot(&before), &screenshot(&solid(9, 6, 100)), 0).unwrap();
+        assert!(wider.size_changed);
+        assert_eq!(wider.changed_pixels, 6);
+        assert_eq!(wider.changed_region.unwrap().x, 8);
+    }
+
+    #[test]
+    fn test_screenshot_from_base64() {
+        let png = screenshot(&solid(1, 1, 0)).data;
+        let encoded = base64::engine::general_purpose::STANDARD.encode(&png);
+
+        let parsed = screenshot_from_base64(&format!("data:image/png;base64,{encoded}")).unwrap();
+        assert_eq!(parsed.data, png);
+        assert_eq!(
+            screenshot_from_base64(&encoded).unwrap().format,
+            ImageFormat::Png
+        );
+        assert!(screenshot_from_base64("aGVsbG8=").is_err());
+    }
+}
@@ -1,4 +1,4 @@
-use super::{Screenshot, ScreenshotError};
+use super::{CaptureOptions, Screenshot, ScreenshotError};
 use tauri::{Runtime, WebviewWindow};
 
 /// iOS-specific screenshot implementation using WKWebView's takeSnapshot
@@ -12,6 +12,7 @@ use tauri::{Runtime, WebviewWindow};
 /// The takeSnapshotWithConfiguration:completionHandler: method returns UIImage on iOS.
 pub fn capture_viewport<R: Runtime>(
     window: &WebviewWindow<R>,
+    options: &CaptureOptions,
 ) -> Result<Screenshot, ScreenshotError> {
     #[cfg(target_os = "ios")]
     {
@@ -59,7 +60,7 @@ pub fn capture_viewport<R: Runtime>(
                                 let img = &*image;
                                 match convert_uiimage_to_png(img) {
                                     Ok(data) => {
-                                        let _ = tx.send(Ok(Screenshot { data }));
+                                        let _ = tx.send(Ok(Screenshot::new(data)));
                                     }
                                     Err(e) => {
                                         let _ = tx.send(Err(e));
@@ -88,12 +89,15 @@ pub fn capture_viewport<R: Runtime>(
 
         // Wait for result while running the event loop
         // This is necessary because the completion handler is called asynchronously
-        unsafe { wait_for_blocking_operation(rx) }
+        unsafe { wait_for_blocking_operation(rx, options.timeout) }
     }
 
     #[cfg(not(target_os = "ios"))]
     {
-        Err(ScreenshotError::PlatformUnsupported)
+        let _ = (window, options);
+        Err(ScreenshotError::PlatformUnsupported(
+            "this capture requires iOS".to_string(),
+        ))
     }
 }
 
@@ -102,12 +106,13 @@ pub fn capture_viewport<R: Runtime>(
 #[cfg(target_os = "ios")]
 unsafe fn wait_for_blocking_operation(
     rx: std::sync::mpsc::Receiver<Result<Screenshot, ScreenshotError>>,
+    timeout: std::time::Duration,
 ) -> Result<Screenshot, ScreenshotError> {
     use objc2_foundation::{NSDate, NSRunLoop, NSString};
 
     let interval = std::time::Duration::from_millis(10);
     let interval_as_secs = interval.as_secs_f64();
-    let limit = 10.0; // 10 second timeout
+    let limit = timeout.as_secs_f64();
     let mut elapsed = 0.0;
 
     loop {
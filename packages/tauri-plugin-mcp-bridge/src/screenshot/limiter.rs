@@ -0,0 +1,150 @@
+//! Serializes native captures so concurrent requests don't contend for the
+//! platform capture machinery.
+//!
+//! Captures of the same window run one at a time, since each schedules work
+//! on the WebView (and on Android allocates a full-size bitmap through JNI).
+//! Captures of different windows may overlap, up to a configurable limit.
+
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+use tokio::sync::{Mutex as AsyncMutex, OwnedMutexGuard, OwnedSemaphorePermit, Semaphore};
+
+use super::ScreenshotError;
+
+/// Captures allowed to run at once across all windows by default.
+pub const DEFAULT_MAX_CONCURRENT_CAPTURES: usize = 2;
+
+/// Held for the duration of a capture. Dropping it lets the next one start.
+pub struct CapturePermit {
+    _window: OwnedMutexGuard<()>,
+    _global: OwnedSemaphorePermit,
+}
+
+/// Per-window capture locks plus a global concurrency limit.
+pub struct CaptureLimiter {
+    windows: Mutex<HashMap<String, Arc<AsyncMutex<()>>>>,
+    global: Arc<Semaphore>,
+}
+
+impl Default for CaptureLimiter {
+    fn default() -> Self {
+        Self::new(DEFAULT_MAX_CONCURRENT_CAPTURES)
+    }
+}
+
+impl CaptureLimiter {
+    /// Creates a limiter allowing `max_concurrent` captures at once. A limit
+    /// of zero is treated as one.
+    pub fn new(max_concurrent: usize) -> Self {
+        Self {
+            windows: Mutex::new(HashMap::new()),
+            global: Arc::new(Semaphore::new(max_concurrent.max(1))),
+        }
+    }
+
+    /// Waits for the window's capture lock and a global slot.
+    ///
+    /// Returns `ScreenshotError::Timeout` if both can't be acquired within
+    /// `timeout`.
+    pub async fn acquire(
+        &self,
+        window_label: &str,
+        timeout: Duration,
+    ) -> Result<CapturePermit, ScreenshotError> {
+        let window_lock = self
+            .windows
+            .lock()
+            .unwrap()
+            .entry(window_label.to_string())
+            .or_default()
+            .clone();
+        let global = self.global.clone();
+
+        // Take the window lock first so queued captures of one window don't
+        // hold global slots other windows could use
+        let acquire = async move {
+            let window = window_lock.lock_owned().await;
+            let global = global
+                .acquire_owned()
+                .await
+                .expect("capture semaphore is never closed");
+            CapturePermit {
+                _window: window,
+                _global: global,
+            }
+        };
+
+        tokio::time::timeout(timeout, acquire)
+            .await
+            .map_err(|_| ScreenshotError::Timeout)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    /// Fires many concurrent "captures" and records the peak number running
+    /// at once.
+    async fn peak_concurrency(limiter: Arc<CaptureLimiter>, labels: &[&str]) -> usize {
+        let running = Arc::new(AtomicUsize::new(0));
+        let peak = Arc::new(AtomicUsize::new(0));
+
+        let tasks: Vec<_> = (0..50)
+            .map(|i| {
+                let limiter = limiter.clone();
+                let running = running.clone();
+                let peak = peak.clone();
+                let label = labels[i % labels.len()].to_string();
+
+                tokio::spawn(async move {
+                    let _permit = limiter
+                        .acquire(&label, Duration::from_secs(10))
+                        .await
+                        .unwrap();
+                    let now = running.fetch_add(1, Ordering::SeqCst) + 1;
+                    peak.fetch_max(now, Ordering::SeqCst);
+                    tokio::time::sleep(Duration::from_millis(2)).await;
+                    running.fetch_sub(1, Ordering::SeqCst);
+                })
+            })
+            .collect();
+
+        for task in tasks {
+            task.await.unwrap();
+        }
+        peak.load(Ordering::SeqCst)
+    }
+
+    #[tokio::test(flavor = "multi_thread", worker_threads = 4)]
+    async fn test_same_window_captures_are_serialized() {
+        let limiter = Arc::new(CaptureLimiter::new(8));
+        assert_eq!(peak_concurrency(limiter, &["main"]).await, 1);
+    }
+
+    #[tokio::test(flavor = "multi_thread", worker_threads = 4)]
+    async fn test_global_limit_caps_captures_across_windows() {
+        let limiter = Arc::new(CaptureLimiter::new(2));
+        let peak = peak_concurrency(limiter, &["a", "b", "c", "d"]).await;
+        assert!((1..=2).contains(&peak), "peak was {peak}");
+    }
+
+    #[tokio::test]
+    async fn test_acquire_times_out_while_window_is_busy() {
+        let limiter = CaptureLimiter::new(2);
+        let _held = limiter
+            .acquire("main", Duration::from_secs(1))
+            .await
+            .unwrap();
+
+        let result = limiter.acquire("main", Duration::from_millis(20)).await;
+        assert!(matches!(result, Err(ScreenshotError::Timeout)));
+        assert!(limiter
+            .acquire("other", Duration::from_millis(20))
+            .await
+            .is_ok());
+    }
+}
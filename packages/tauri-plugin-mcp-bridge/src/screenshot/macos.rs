@@ -1,4 +1,4 @@
-use super::{Screenshot, ScreenshotError};
+use super::{bgra_to_png, CaptureOptions, Screenshot, ScreenshotError};
 use tauri::{Runtime, WebviewWindow};
 
 /// macOS-specific screenshot implementation using WKWebView's takeSnapshot
@@ -7,6 +7,7 @@ use tauri::{Runtime, WebviewWindow};
 /// It uses the native WKWebView takeSnapshot API to get a high-quality screenshot.
 pub fn capture_viewport<R: Runtime>(
     window: &WebviewWindow<R>,
+    options: &CaptureOptions,
 ) -> Result<Screenshot, ScreenshotError> {
     #[cfg(target_os = "macos")]
     {
@@ -44,7 +45,7 @@ pub fn capture_viewport<R: Runtime>(
                                 // Convert NSImage to PNG data
                                 match convert_nsimage_to_png(img) {
                                     Ok(data) => {
-                                        let _ = tx.send(Ok(Screenshot { data }));
+                                        let _ = tx.send(Ok(Screenshot::new(data)));
                                     }
                                     Err(e) => {
                                         let _ = tx.send(Err(e));
@@ -68,7 +69,7 @@ pub fn capture_viewport<R: Runtime>(
             })?;
 
         // Wait for result
-        match rx.recv_timeout(std::time::Duration::from_secs(10)) {
+        match rx.recv_timeout(options.timeout) {
             Ok(result) => result,
             Err(_) => Err(ScreenshotError::Timeout),
         }
@@ -76,7 +77,181 @@ pub fn capture_viewport<R: Runtime>(
 
     #[cfg(not(target_os = "macos"))]
     {
-        Err(ScreenshotError::PlatformUnsupported)
+        let _ = (window, options);
+        Err(ScreenshotError::PlatformUnsupported(
+            "this capture requires macOS".to_string(),
+        ))
+    }
+}
+
+/// Captures the whole window, including the title bar, from the window server.
+///
+/// Requires the Screen Recording permission. Without it the window server
+/// returns no image, which is reported as `PermissionDenied`.
+pub fn capture_window<R: Runtime>(
+    window: &WebviewWindow<R>,
+    _options: &CaptureOptions,
+) -> Result<Screenshot, ScreenshotError> {
+    use objc2::msg_send;
+    use objc2::runtime::AnyObject;
+
+    let ns_window = window
+        .ns_window()
+        .map_err(|e| ScreenshotError::CaptureFailed(format!("Failed to access window: {e}")))?;
+
+    unsafe {
+        let ns_window: &AnyObject = &*(ns_window as *const AnyObject);
+        let window_number: isize = msg_send![ns_window, windowNumber];
+
+        let image = core_graphics::CGWindowListCreateImage(
+            core_graphics::CG_RECT_NULL,
+            core_graphics::WINDOW_LIST_OPTION_INCLUDING_WINDOW,
+            window_number as u32,
+            core_graphics::WINDOW_IMAGE_BOUNDS_IGNORE_FRAMING
+                | core_graphics::WINDOW_IMAGE_BEST_RESOLUTION,
+        );
+        if image.is_null() {
+            return Err(ScreenshotError::PermissionDenied(
+                "Screen Recording permission is required to capture window decorations".to_string(),
+            ));
+        }
+
+        let result = cgimage_to_png(image);
+        core_graphics::CGImageRelease(image);
+        result.map(Screenshot::new)
+    }
+}
+
+/// Captures the main display, including other apps' windows and the menu bar.
+///
+/// Requires the Screen Recording permission. Without it the window server
+/// would still return an image of just the desktop and this app's own
+/// windows, so the permission is checked up front and its absence reported
+/// as `PermissionDenied`.
+pub fn capture_screen<R: Runtime>(
+    _window: &WebviewWindow<R>,
+    _options: &CaptureOptions,
+) -> Result<Screenshot, ScreenshotError> {
+    unsafe {
+        if !core_graphics::CGPreflightScreenCaptureAccess() {
+            return Err(ScreenshotError::PermissionDenied(
+                "Screen Recording permission is required to capture the screen".to_string(),
+            ));
+        }
+
+        let image = core_graphics::CGDisplayCreateImage(core_graphics::CGMainDisplayID());
+        if image.is_null() {
+            return Err(ScreenshotError::CaptureFailed(
+                "The window server returned no image of the main display".to_string(),
+            ));
+        }
+
+        let result = cgimage_to_png(image);
+        core_graphics::CGImageRelease(image);
+        result.map(Screenshot::new)
+    }
+}
+
+/// Encodes a window server image, which uses 32-bit little-endian BGRA.
+unsafe fn cgimage_to_png(image: core_graphics::CGImageRef) -> Result<Vec<u8>, ScreenshotError> {
+    use core_graphics::*;
+
+    let width = CGImageGetWidth(image);
+    let height = CGImageGetHeight(image);
+    let stride = CGImageGetBytesPerRow(image);
+    let bitmap_info = CGImageGetBitmapInfo(image);
+
+    if CGImageGetBitsPerPixel(image) != 32
+        || bitmap_info & BITMAP_BYTE_ORDER_MASK != BITMAP_BYTE_ORDER_32_LITTLE
+    {
+        return Err(ScreenshotError::EncodeFailed(format!(
+            "Unsupported window server image pixel layout ({bitmap_info:#x})"
+        )));
+    }
+
+    let data = CGDataProviderCopyData(CGImageGetDataProvider(image));
+    if data.is_null() {
+        return Err(ScreenshotError::CaptureFailed(
+            "Window server image has no pixel data".to_string(),
+        ));
+    }
+
+    let bytes = std::slice::from_raw_parts(CFDataGetBytePtr(data), CFDataGetLength(data) as usize);
+    let result = bgra_to_png(width as u32, height as u32, stride, bytes);
+    CFRelease(data);
+    result
+}
+
+/// The few CoreGraphics window server calls used for window and screen capture.
+mod core_graphics {
+    use std::ffi::c_void;
+
+    pub type CGImageRef = *mut c_void;
+    pub type CFDataRef = *const c_void;
+
+    #[repr(C)]
+    pub struct CGPoint {
+        pub x: f64,
+        pub y: f64,
+    }
+
+    #[repr(C)]
+    pub struct CGSize {
+        pub width: f64,
+        pub height: f64,
+    }
+
+    #[repr(C)]
+    pub struct CGRect {
+        pub origin: CGPoint,
+        pub size: CGSize,
+    }
+
+    /// `CGRectNull`, which asks for the window's own bounds.
+    pub const CG_RECT_NULL: CGRect = CGRect {
+        origin: CGPoint {
+            x: f64::INFINITY,
+            y: f64::INFINITY,
+        },
+        size: CGSize {
+            width: 0.0,
+            height: 0.0,
+        },
+    };
+
+    pub const WINDOW_LIST_OPTION_INCLUDING_WINDOW: u32 = 1 << 3;
+    /// Leaves out the drop shadow; the title bar is part of the window.
+    pub const WINDOW_IMAGE_BOUNDS_IGNORE_FRAMING: u32 = 1 << 0;
+    pub const WINDOW_IMAGE_BEST_RESOLUTION: u32 = 1 << 3;
+    pub const BITMAP_BYTE_ORDER_MASK: u32 = 0x7000;
+    pub const BITMAP_BYTE_ORDER_32_LITTLE: u32 = 2 << 12;
+
+    #[link(name = "CoreGraphics", kind = "framework")]
+    extern "C" {
+        pub fn CGWindowListCreateImage(
+            bounds: CGRect,
+            list_option: u32,
+            window_id: u32,
+            image_option: u32,
+        ) -> CGImageRef;
+        pub fn CGPreflightScreenCaptureAccess() -> bool;
+        pub fn CGMainDisplayID() -> u32;
+        pub fn CGDisplayCreateImage(display_id: u32) -> CGImageRef;
+        pub fn CGImageGetWidth(image: CGImageRef) -> usize;
+        pub fn CGImageGetHeight(image: CGImageRef) -> usize;
+        pub fn CGImageGetBytesPerRow(image: CGImageRef) -> usize;
+        pub fn CGImageGetBitsPerPixel(image: CGImageRef) -> usize;
+        pub fn CGImageGetBitmapInfo(image: CGImageRef) -> u32;
+        pub fn CGImageGetDataProvider(image: CGImageRef) -> *mut c_void;
+        pub fn CGDataProviderCopyData(provider: *mut c_void) -> CFDataRef;
+        pub fn CGImageRelease(image: CGImageRef);
+    }
+
+    #[link(name = "CoreFoundation", kind = "framework")]
+    extern "C" {
+        pub fn CFDataGetBytePtr(data: CFDataRef) -> *const u8;
+        pub fn CFDataGetLength(data: CFDataRef) -> isize;
+        pub fn CFRelease(object: *const c_void);
     }
 }
 
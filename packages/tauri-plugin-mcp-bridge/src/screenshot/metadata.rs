@@ -0,0 +1,172 @@
+//! Removal of metadata from encoded screenshots.
+//!
+//! PNG and JPEG files are filtered chunk by chunk without decoding the
+//! pixels. Only images carrying an EXIF orientation are decoded, rotated
+//! and re-encoded, so the orientation survives without the tag.
+
+use std::io::Cursor;
+
+use image::metadata::Orientation;
+use image::{DynamicImage, ImageDecoder, ImageReader};
+
+use super::{encode_image, ImageFormat, PngCompression, ScreenshotError};
+
+/// PNG file signature
+const PNG_SIGNATURE: &[u8] = b"\x89PNG\r\n\x1a\n";
+
+/// PNG chunks that only carry metadata: text, EXIF and modification time.
+const PNG_METADATA_CHUNKS: [&[u8; 4]; 5] = [b"tEXt", b"zTXt", b"iTXt", b"eXIf", b"tIME"];
+
+/// JPEG start of scan; entropy-coded data follows until the end of the file.
+const JPEG_SOS: u8 = 0xDA;
+
+/// Removes metadata from encoded image bytes and bakes in any EXIF
+/// orientation.
+///
+/// Natively captured images rarely carry more than a few hundred bytes of
+/// metadata, so the size saving is small; the point is not leaking it.
+pub fn strip_metadata(
+    data: Vec<u8>,
+    format: ImageFormat,
+    quality: u8,
+    png_compression: Option<PngCompression>,
+) -> Result<Vec<u8>, ScreenshotError> {
+    if let Some(image) = decode_rotated(&data)? {
+        // Our encoders write no metadata
+        return encode_image(&image, format, quality, png_compression);
+    }
+
+    match format {
+        ImageFormat::Jpeg => strip_jpeg(&data),
+        ImageFormat::Png | ImageFormat::Auto => strip_png(&data),
+    }
+}
+
+/// Decodes the image with its orientation applied, or returns `None` when
+/// it needs no rotation.
+fn decode_rotated(data: &[u8]) -> Result<Option<DynamicImage>, ScreenshotError> {
+    let decode_error = |e: image::ImageError| {
+        ScreenshotError::EncodeFailed(format!("Failed to decode image: {e}"))
+    };
+
+    let mut decoder = ImageReader::new(Cursor::new(data))
+        .with_guessed_format()
+        .map_err(|e| ScreenshotError::EncodeFailed(format!("Failed to read image: {e}")))?
+        .into_decoder()
+        .map_err(decode_error)?;
+
+    let orientation = decoder.orientation().unwrap_or(Orientation::NoTransforms);
+    if orientation == Orientation::NoTransforms {
+        return Ok(None);
+    }
+
+    let mut image = DynamicImage::from_decoder(decoder).map_err(decode_error)?;
+    image.apply_orientation(orientation);
+    Ok(Some(image))
+}
+
+/// Copies a PNG without its metadata chunks.
+fn strip_png(data: &[u8]) -> Result<Vec<u8>, ScreenshotError> {
+    let malformed = || ScreenshotError::EncodeFailed("Malformed PNG".to_string());
+
+    let mut chunks = data.strip_prefix(PNG_SIGNATURE).ok_or_else(malformed)?;
+    let mut stripped = Vec::with_capacity(data.len());
+    stripped.extend_from_slice(PNG_SIGNATURE);
+
+    while !chunks.is_empty() {
+        let header = chunks.get(..8).ok_or_else(malformed)?;
+        let length = u32::from_be_bytes([header[0], header[1], header[2], header[3]]) as usize;
+        // Length, type, data and CRC
+        let chunk = chunks.get(..length + 12).ok_or_else(malformed)?;
+
+        if !PNG_METADATA_CHUNKS
+            .iter()
+            .any(|kind| &header[4..8] == *kind)
+        {
+            stripped.extend_from_slice(chunk);
+        }
+        chunks = &chunks[chunk.len()..];
+    }
+
+    Ok(stripped)
+}
+
+/// Copies a JPEG without APP1-APP15 and comment segments. APP0 (JFIF) is
+/// kept since some decoders rely on it.
+fn strip_jpeg(data: &[u8]) -> Result<Vec<u8>, ScreenshotError> {
+    let malformed = || ScreenshotError::EncodeFailed("Malformed JPEG".to_string());
+
+    let mut rest = data.strip_prefix(&[0xFF, 0xD8]).ok_or_else(malformed)?;
+    let mut stripped = Vec::with_capacity(data.len());
+    stripped.extend_from_slice(&[0xFF, 0xD8]);
+
+    loop {
+        // Markers may be preceded by fill bytes
+        while rest.len() > 1 && rest[0] == 0xFF && rest[1] == 0xFF {
+            rest = &rest[1..];
+        }
+
+        let header = rest.get(..4).ok_or_else(malformed)?;
+        if header[0] != 0xFF {
+            return Err(malformed());
+        }
+
+        let marker = header[1];
+        if marker == JPEG_SOS {
+            stripped.extend_from_slice(rest);
+            return Ok(stripped);
+        }
+
+        let length = u16::from_be_bytes([header[2], header[3]]) as usize;
+        let segment = rest.get(..length + 2).ok_or_else(malformed)?;
+
+        let is_metadata = (0xE1..=0xEF).contains(&marker) || marker == 0xFE;
+        if !is_metadata {
+            stripped.extend_from_slice(segment);
+        }
+        rest = &rest[segment.len()..];
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn png_chunk(kind: &[u8; 4], data: &[u8]) -> Vec<u8> {
+        let mut chunk = (data.len() as u32).to_be_bytes().to_vec();
+        chunk.extend_from_slice(kind);
+        chunk.extend_from_slice(data);
+        // CRC is not checked by the filter
+        chunk.extend_from_slice(&[0; 4]);
+        chunk
+    }
+
+    #[test]
+    fn test_strip_png_drops_metadata_chunks() {
+        let image =
+            encode_image(&DynamicImage::new_rgb8(2, 2), ImageFormat::Png, 90, None).unwrap();
+
+        // Insert a text chunk right after IHDR (8 signature + 25 IHDR bytes)
+        let mut tagged = image[..33].to_vec();
+        tagged.extend(png_chunk(b"tEXt", b"Author\0someone"));
+        tagged.extend_from_slice(&image[33..]);
+
+        let stripped = strip_metadata(tagged, ImageFormat::Png, 90, None).unwrap();
+        assert_eq!(stripped, image);
+        assert!(strip_png(b"not a png").is_err());
+    }
+
+    #[test]
+    fn test_strip_jpeg_drops_app_segments() {
+        let image =
+            encode_image(&DynamicImage::new_rgb8(2, 2), ImageFormat::Jpeg, 90, None).unwrap();
+
+        // Insert a comment segment right after SOI
+        let mut tagged = vec![0xFF, 0xD8, 0xFF, 0xFE, 0x00, 0x06];
+        tagged.extend_from_slice(b"note");
+        tagged.extend_from_slice(&image[2..]);
+
+        let stripped = strip_metadata(tagged, ImageFormat::Jpeg, 90, None).unwrap();
+        assert_eq!(stripped, image);
+    }
+}
@@ -1,9 +1,24 @@
+use std::collections::HashSet;
 use std::env;
-use std::io::Cursor;
+use std::io::{Cursor, Read, Write};
+use std::sync::mpsc::{Receiver, RecvTimeoutError};
+use std::time::Duration;
 
+use flate2::read::GzDecoder;
+use flate2::write::GzEncoder;
+use flate2::Compression;
 use image::imageops::FilterType;
-use image::ImageFormat;
-use tauri::{Runtime, WebviewWindow};
+use image::{DynamicImage, RgbaImage};
+use tauri::{Manager, Runtime, WebviewWindow};
+
+use crate::trace::trace_step;
+
+#[cfg(feature = "animation")]
+pub mod animation;
+pub mod cache;
+pub mod diff;
+pub mod limiter;
+pub mod metadata;
 
 // Platform-specific modules
 #[cfg(target_os = "macos")]
@@ -24,18 +39,400 @@ mod android;
 /// Environment variable name for default max width
 const ENV_MAX_WIDTH: &str = "TAURI_MCP_SCREENSHOT_MAX_WIDTH";
 
+/// How long to wait for the native capture callback by default
+pub const DEFAULT_CAPTURE_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// How long captures wait for web fonts by default
+pub const DEFAULT_FONT_TIMEOUT: Duration = Duration::from_secs(3);
+
+/// Share of distinct colors among sampled pixels above which
+/// [`ImageFormat::Auto`] picks JPEG. UI screenshots sit well below this,
+/// photos well above.
+pub const DEFAULT_AUTO_FORMAT_THRESHOLD: f64 = 0.5;
+
+/// Pixels sampled when choosing a format for [`ImageFormat::Auto`]
+const AUTO_FORMAT_SAMPLES: usize = 10_000;
+
+/// Lowest JPEG quality tried when fitting a capture into `max_bytes`
+const MIN_FIT_QUALITY: u8 = 10;
+
+/// Narrowest width PNG captures are downscaled to when fitting `max_bytes`
+const MIN_FIT_WIDTH: u32 = 64;
+
+/// Downscaling rounds tried when fitting a PNG capture into `max_bytes`
+const MAX_FIT_ATTEMPTS: usize = 6;
+
 /// Screenshot result containing the image data
-#[derive(Debug)]
+#[derive(Debug, Clone)]
 pub struct Screenshot {
-    /// The raw PNG bytes
+    /// The image bytes, possibly wrapped in a transport encoding
     pub data: Vec<u8>,
+    /// Transport encoding applied to `data`, if any
+    pub content_encoding: Option<ContentEncoding>,
+    /// Image format of `data`, never `Auto`
+    pub format: ImageFormat,
+    /// How the pixels were obtained
+    pub source: CaptureSource,
+    /// Screen area the capture covers, when the window geometry is known
+    pub bounds: Option<CaptureBounds>,
+    /// How the image was shrunk to fit `max_bytes`, if it had to be
+    pub budget_fit: Option<BudgetFit>,
+}
+
+impl Screenshot {
+    /// Create a natively captured screenshot from raw PNG bytes with no
+    /// transport encoding.
+    pub fn new(data: Vec<u8>) -> Self {
+        Self {
+            data,
+            content_encoding: None,
+            format: ImageFormat::Png,
+            source: CaptureSource::Native,
+            bounds: None,
+            budget_fit: None,
+        }
+    }
+
+    /// MIME type of `data`, from the recorded format.
+    pub fn mime_type(&self) -> &'static str {
+        self.format.mime_type()
+    }
+
+    /// Convert the screenshot to a base64 data URL with the given MIME type.
+    ///
+    /// When `content_encoding` is set the payload holds compressed bytes, so
+    /// clients must decode it before treating it as an image.
+    pub fn to_data_url(&self, mime_type: &str) -> String {
+        use base64::Engine as _;
+        let base64_data = base64::engine::general_purpose::STANDARD.encode(&self.data);
+        format!("data:{mime_type};base64,{base64_data}")
+    }
+
+    /// Decode the screenshot to RGBA pixels, undoing any transport encoding.
+    ///
+    /// The image's `width()` and `height()` are the capture's pixel
+    /// dimensions.
+    pub fn decode(&self) -> Result<RgbaImage, ScreenshotError> {
+        let gunzipped;
+        let data = match self.content_encoding {
+            Some(ContentEncoding::Gzip) => {
+                let mut bytes = Vec::new();
+                GzDecoder::new(self.data.as_slice())
+                    .read_to_end(&mut bytes)
+                    .map_err(|e| {
+                        ScreenshotError::EncodeFailed(format!("Failed to decompress image: {e}"))
+                    })?;
+                gunzipped = bytes;
+                &gunzipped
+            }
+            None => &self.data,
+        };
+
+        let format = match self.format {
+            ImageFormat::Jpeg => image::ImageFormat::Jpeg,
+            ImageFormat::Png | ImageFormat::Auto => image::ImageFormat::Png,
+        };
+        image::load_from_memory_with_format(data, format)
+            .map(|img| img.to_rgba8())
+            .map_err(|e| ScreenshotError::EncodeFailed(format!("Failed to decode image: {e}")))
+    }
+}
+
+/// Image format of a screenshot
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+#[cfg_attr(feature = "ts-bindings", derive(ts_rs::TS))]
+#[serde(rename_all = "lowercase")]
+pub enum ImageFormat {
+    #[default]
+    Png,
+    Jpeg,
+    /// Pick PNG or JPEG from the captured pixels: JPEG for photographic
+    /// content, PNG otherwise
+    Auto,
+}
+
+impl ImageFormat {
+    /// Parse a format name, treating unknown names as PNG.
+    pub fn from_name(name: &str) -> Self {
+        match name {
+            "jpeg" | "jpg" => ImageFormat::Jpeg,
+            "auto" => ImageFormat::Auto,
+            _ => ImageFormat::Png,
+        }
+    }
+
+    /// MIME type of the format. `Auto` reports PNG, its fallback.
+    pub fn mime_type(self) -> &'static str {
+        match self {
+            ImageFormat::Jpeg => "image/jpeg",
+            ImageFormat::Png | ImageFormat::Auto => "image/png",
+        }
+    }
+
+    /// Format for a MIME type, if captures can be encoded in it.
+    pub fn from_mime_type(mime_type: &str) -> Option<Self> {
+        match mime_type {
+            "image/png" => Some(ImageFormat::Png),
+            "image/jpeg" => Some(ImageFormat::Jpeg),
+            _ => None,
+        }
+    }
+}
+
+/// Detects the MIME type of encoded image bytes from their signature.
+///
+/// Recognizes PNG, JPEG and WebP.
+pub fn detect_mime_type(data: &[u8]) -> Option<&'static str> {
+    if data.starts_with(b"\x89PNG\r\n\x1a\n") {
+        Some("image/png")
+    } else if data.starts_with(&[0xFF, 0xD8, 0xFF]) {
+        Some("image/jpeg")
+    } else if data.len() >= 12 && data.starts_with(b"RIFF") && &data[8..12] == b"WEBP" {
+        Some("image/webp")
+    } else {
+        None
+    }
+}
+
+/// zlib effort spent on PNG output
+#[derive(Debug, Clone, Copy, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+#[cfg_attr(feature = "ts-bindings", derive(ts_rs::TS))]
+#[serde(rename_all = "lowercase")]
+pub enum PngCompression {
+    /// Fastest encoding, largest output
+    Fast,
+    /// zlib's default level
+    Default,
+    /// Smallest output at the highest CPU cost
+    Best,
+}
+
+impl From<PngCompression> for image::codecs::png::CompressionType {
+    fn from(compression: PngCompression) -> Self {
+        match compression {
+            PngCompression::Fast => Self::Fast,
+            PngCompression::Default => Self::Default,
+            PngCompression::Best => Self::Best,
+        }
+    }
+}
+
+/// Transport encoding applied on top of the image format
+#[derive(Debug, Clone, Copy, PartialEq, Eq, serde::Serialize)]
+#[cfg_attr(feature = "ts-bindings", derive(ts_rs::TS))]
+#[serde(rename_all = "lowercase")]
+pub enum ContentEncoding {
+    Gzip,
+}
+
+/// Where a screenshot's pixels came from
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, serde::Serialize)]
+#[cfg_attr(feature = "ts-bindings", derive(ts_rs::TS))]
+#[serde(rename_all = "lowercase")]
+pub enum CaptureSource {
+    /// Rendered by the platform's native webview snapshot API
+    #[default]
+    Native,
+    /// Re-rendered from the DOM in JavaScript. Lower fidelity: cross-origin
+    /// images, video and some CSS features may be missing.
+    Dom,
+}
+
+/// Screen area covered by a capture, in physical pixels
+#[derive(Debug, Clone, Copy, PartialEq, Eq, serde::Serialize)]
+#[cfg_attr(feature = "ts-bindings", derive(ts_rs::TS))]
+#[serde(rename_all = "camelCase")]
+pub struct CaptureBounds {
+    pub x: i32,
+    pub y: i32,
+    pub width: u32,
+    pub height: u32,
+    /// Whether the area includes the window's title bar and frame
+    pub includes_chrome: bool,
+}
+
+/// How a capture was shrunk to fit [`ScreenshotOptions::max_bytes`]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, serde::Serialize)]
+#[cfg_attr(feature = "ts-bindings", derive(ts_rs::TS))]
+#[serde(rename_all = "camelCase")]
+pub struct BudgetFit {
+    /// JPEG quality the image was re-encoded at
+    pub quality: Option<u8>,
+    /// Width PNG images were downscaled to
+    pub width: Option<u32>,
+    /// Whether even the smallest attempt is larger than `max_bytes`; the
+    /// smallest attempt is returned
+    pub over_budget: bool,
+}
+
+/// How the Android capture obtains the WebView pixels
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, serde::Deserialize)]
+#[cfg_attr(feature = "ts-bindings", derive(ts_rs::TS))]
+#[serde(rename_all = "camelCase")]
+pub enum AndroidCaptureStrategy {
+    /// Draw the view into a software Canvas with `View.draw()`.
+    ///
+    /// Hardware-accelerated and cross-process content (video, WebGL) renders blank.
+    #[default]
+    Draw,
+    /// Copy the rendered window surface with `PixelCopy.request()`, including
+    /// GPU content. Requires Android 8.0 (API 26) or later.
+    PixelCopy,
+}
+
+/// Orientation to render an Android capture in
+///
+/// A rotated capture draws the current layout turned by 90 degrees into a
+/// bitmap with swapped dimensions. The device isn't rotated and the page
+/// doesn't lay out again, so this is not what a real orientation change
+/// would show.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, serde::Deserialize)]
+#[cfg_attr(feature = "ts-bindings", derive(ts_rs::TS))]
+#[serde(rename_all = "camelCase")]
+pub enum CaptureOrientation {
+    /// As the view is currently laid out.
+    #[default]
+    Natural,
+    /// Taller than wide, rotating a landscape view.
+    Portrait,
+    /// Wider than tall, rotating a portrait view.
+    Landscape,
+}
+
+impl CaptureOrientation {
+    /// Whether a view of `width` by `height` pixels must be rotated to
+    /// render in this orientation. Square views are never rotated.
+    pub fn needs_rotation(self, width: i32, height: i32) -> bool {
+        match self {
+            CaptureOrientation::Natural => false,
+            CaptureOrientation::Portrait => width > height,
+            CaptureOrientation::Landscape => height > width,
+        }
+    }
+}
+
+/// Options controlling how the native capture is performed
+#[derive(Debug, Clone)]
+pub struct CaptureOptions {
+    /// Capture strategy used on Android, ignored on other platforms
+    pub strategy: AndroidCaptureStrategy,
+    /// Orientation of Android captures, ignored on other platforms; see
+    /// [`CaptureOrientation`]
+    pub orientation: CaptureOrientation,
+    /// How long to wait for the platform capture before returning `Timeout`
+    pub timeout: Duration,
+    /// Capture the whole window including its title bar and frame, not just
+    /// the WebView. Desktop only: on Android and iOS the WebView has no
+    /// separate chrome, so this is a no-op.
+    pub include_chrome: bool,
+    /// Hide the page's scrollbars for the capture
+    pub hide_scrollbars: bool,
+    /// CSS selectors of elements to hide for the capture, such as sticky
+    /// headers and cookie banners
+    pub hide_selectors: Vec<String>,
+}
+
+impl Default for CaptureOptions {
+    fn default() -> Self {
+        Self {
+            strategy: AndroidCaptureStrategy::default(),
+            orientation: CaptureOrientation::default(),
+            timeout: DEFAULT_CAPTURE_TIMEOUT,
+            include_chrome: false,
+            hide_scrollbars: false,
+            hide_selectors: Vec::new(),
+        }
+    }
+}
+
+/// Options controlling how a captured screenshot is encoded
+#[derive(Debug, Clone)]
+pub struct ScreenshotOptions {
+    /// Image format to encode the capture as
+    pub format: ImageFormat,
+    /// JPEG quality (0-100), only used for JPEG format
+    pub quality: u8,
+    /// Re-encode PNG output at this compression level. `None` keeps
+    /// natively encoded PNGs as they are and encodes converted ones fast.
+    pub png_compression: Option<PngCompression>,
+    /// Distinct color share above which `Auto` picks JPEG
+    pub auto_format_threshold: f64,
+    /// Maximum width, larger images are downscaled (falls back to env var)
+    pub max_width: Option<u32>,
+    /// Maximum size of the encoded image, before transport compression and
+    /// base64. Larger JPEGs are re-encoded at a lower quality and larger
+    /// PNGs downscaled until they fit.
+    pub max_bytes: Option<usize>,
+    /// Remove EXIF, text and other metadata from the encoded image, baking
+    /// in any EXIF orientation. Saves little space; meant for sharing
+    /// captures without stray metadata.
+    pub strip_metadata: bool,
+    /// Gzip the encoded image bytes for constrained transports
+    pub compress_transport: bool,
+    /// Gzip even formats that are already compressed, such as JPEG
+    pub force_compression: bool,
+    /// Wait for the webview to paint pending DOM changes before capturing
+    pub wait_for_paint: bool,
+    /// Wait for web fonts to load before capturing, so text isn't captured
+    /// in a fallback font
+    pub wait_for_fonts: bool,
+    /// Font families to wait for; every font the page uses when empty
+    pub font_families: Vec<String>,
+    /// Capture anyway once fonts have been loading for this long
+    pub font_timeout: Duration,
+    /// Temporarily resize the window to this logical inner size for the
+    /// capture, restoring the original size afterwards
+    pub viewport: Option<crate::commands::WindowSize>,
+    /// Return the visible interactive elements alongside the image
+    pub include_elements: bool,
+    /// Draw numbered overlays over interactive elements during the capture
+    pub annotate_elements: bool,
+    /// Leave debug highlights drawn by `highlight` in the capture; they are
+    /// hidden for the capture otherwise
+    pub keep_highlights: bool,
+    /// Native capture options
+    pub capture: CaptureOptions,
+}
+
+impl Default for ScreenshotOptions {
+    fn default() -> Self {
+        Self {
+            format: ImageFormat::Png,
+            quality: 90,
+            png_compression: None,
+            auto_format_threshold: DEFAULT_AUTO_FORMAT_THRESHOLD,
+            max_width: None,
+            max_bytes: None,
+            strip_metadata: false,
+            compress_transport: false,
+            force_compression: false,
+            wait_for_paint: false,
+            wait_for_fonts: false,
+            font_families: Vec::new(),
+            font_timeout: DEFAULT_FONT_TIMEOUT,
+            viewport: None,
+            include_elements: false,
+            annotate_elements: false,
+            keep_highlights: false,
+            capture: CaptureOptions::default(),
+        }
+    }
+}
+
+impl ScreenshotOptions {
+    /// Whether bytes encoded as `format` should be gzipped for transport.
+    ///
+    /// JPEG is already compressed, so it is skipped unless forced.
+    fn should_compress(&self, format: ImageFormat) -> bool {
+        self.compress_transport && (format != ImageFormat::Jpeg || self.force_compression)
+    }
 }
 
 /// Screenshot error types
 #[derive(Debug, thiserror::Error)]
 pub enum ScreenshotError {
-    #[error("Platform not supported")]
-    PlatformUnsupported,
+    #[error("Platform not supported: {0}")]
+    PlatformUnsupported(String),
 
     #[error("Webview capture failed: {0}")]
     CaptureFailed(String),
@@ -48,6 +445,39 @@ pub enum ScreenshotError {
 
     #[error("Timeout exceeded")]
     Timeout,
+
+    #[error("Permission denied: {0}")]
+    PermissionDenied(String),
+
+    #[error("Window '{0}' is closed or being destroyed")]
+    WindowUnavailable(String),
+}
+
+impl ScreenshotError {
+    /// Machine-readable error code for clients.
+    pub fn code(&self) -> &'static str {
+        match self {
+            ScreenshotError::PlatformUnsupported(_) => "PLATFORM_UNSUPPORTED",
+            ScreenshotError::CaptureFailed(_) => "CAPTURE_FAILED",
+            ScreenshotError::EncodeFailed(_) => "ENCODE_FAILED",
+            ScreenshotError::ResizeFailed(_) => "RESIZE_FAILED",
+            ScreenshotError::Timeout => "CAPTURE_TIMEOUT",
+            ScreenshotError::PermissionDenied(_) => "PERMISSION_DENIED",
+            ScreenshotError::WindowUnavailable(_) => "WINDOW_UNAVAILABLE",
+        }
+    }
+
+    /// Whether the same capture may succeed if retried.
+    ///
+    /// Native captures fail transiently while the webview is navigating or
+    /// its surface isn't ready yet; the other errors won't go away on
+    /// their own.
+    pub fn is_retryable(&self) -> bool {
+        matches!(
+            self,
+            ScreenshotError::Timeout | ScreenshotError::CaptureFailed(_)
+        )
+    }
 }
 
 /// Get the effective max_width value.
@@ -62,50 +492,112 @@ fn get_effective_max_width(param: Option<u32>) -> Option<u32> {
         .and_then(|s| s.parse::<u32>().ok())
 }
 
-/// Resize image data if it exceeds max_width, preserving aspect ratio.
-/// Returns the original data if no resizing is needed.
-fn resize_if_needed(
+/// Resize to `max_width` and re-encode in the requested format.
+///
+/// Returns the original bytes when neither a resize, a format change nor a
+/// PNG compression level is needed, avoiding a decode/encode round trip.
+fn convert(
     data: Vec<u8>,
-    max_width: u32,
-    format: &str,
-    quality: u8,
-) -> Result<Vec<u8>, ScreenshotError> {
-    let img = image::load_from_memory(&data)
-        .map_err(|e| ScreenshotError::ResizeFailed(format!("Failed to decode image: {e}")))?;
+    source_format: ImageFormat,
+    max_width: Option<u32>,
+    options: &ScreenshotOptions,
+) -> Result<(Vec<u8>, ImageFormat), ScreenshotError> {
+    let recompress =
+        |format: ImageFormat| format == ImageFormat::Png && options.png_compression.is_some();
+    if options.format == source_format && max_width.is_none() && !recompress(source_format) {
+        return Ok((data, source_format));
+    }
 
-    let current_width = img.width();
+    let img = image::load_from_memory(&data)
+        .map_err(|e| ScreenshotError::EncodeFailed(format!("Failed to decode image: {e}")))?;
 
     // Only resize if image is wider than max_width (never upscale)
-    if current_width <= max_width {
-        return Ok(data);
-    }
+    let (img, resized) = match max_width {
+        Some(max_width) if img.width() > max_width => {
+            // Calculate new dimensions preserving aspect ratio
+            let scale = max_width as f64 / img.width() as f64;
+            let new_height = (img.height() as f64 * scale).round() as u32;
 
-    // Calculate new dimensions preserving aspect ratio
-    let current_height = img.height();
-    let scale = max_width as f64 / current_width as f64;
-    let new_height = (current_height as f64 * scale).round() as u32;
+            // Resize using Lanczos3 for high quality
+            (
+                img.resize(max_width, new_height, FilterType::Lanczos3),
+                true,
+            )
+        }
+        _ => (img, false),
+    };
 
-    // Resize using Lanczos3 for high quality
-    let resized = img.resize(max_width, new_height, FilterType::Lanczos3);
+    let format = match options.format {
+        ImageFormat::Auto => choose_format(&img, options.auto_format_threshold),
+        format => format,
+    };
+    if format == source_format && !resized && !recompress(format) {
+        return Ok((data, format));
+    }
 
-    // Encode back to the requested format
+    // Encode back to the chosen format
+    let data = encode_image(&img, format, options.quality, options.png_compression)?;
+    Ok((data, format))
+}
+
+/// Encode an image as PNG with the given compression level, or as JPEG
+/// with the given quality.
+fn encode_image(
+    img: &DynamicImage,
+    format: ImageFormat,
+    quality: u8,
+    png_compression: Option<PngCompression>,
+) -> Result<Vec<u8>, ScreenshotError> {
     let mut buffer = Cursor::new(Vec::new());
 
-    if format == "jpeg" {
+    if format == ImageFormat::Jpeg {
         let encoder = image::codecs::jpeg::JpegEncoder::new_with_quality(&mut buffer, quality);
 
-        resized
+        // JPEG has no alpha channel
+        DynamicImage::ImageRgb8(img.to_rgb8())
             .write_with_encoder(encoder)
-            .map_err(|e| ScreenshotError::ResizeFailed(format!("Failed to encode JPEG: {e}")))?;
+            .map_err(|e| ScreenshotError::EncodeFailed(format!("Failed to encode JPEG: {e}")))?;
     } else {
-        resized
-            .write_to(&mut buffer, ImageFormat::Png)
-            .map_err(|e| ScreenshotError::ResizeFailed(format!("Failed to encode PNG: {e}")))?;
+        let encoder = image::codecs::png::PngEncoder::new_with_quality(
+            &mut buffer,
+            png_compression.map(Into::into).unwrap_or_default(),
+            image::codecs::png::FilterType::Adaptive,
+        );
+        img.write_with_encoder(encoder)
+            .map_err(|e| ScreenshotError::EncodeFailed(format!("Failed to encode PNG: {e}")))?;
     }
 
     Ok(buffer.into_inner())
 }
 
+/// Pick JPEG for photographic content and PNG for everything else.
+///
+/// Samples pixels evenly and measures the share of distinct colors (at 6
+/// bits per channel). Flat UI and text repeat a few colors; photos rarely
+/// repeat any.
+fn choose_format(img: &DynamicImage, threshold: f64) -> ImageFormat {
+    let rgb = img.to_rgb8();
+    let pixels = rgb.as_raw().chunks_exact(3);
+    let step = (pixels.len() / AUTO_FORMAT_SAMPLES).max(1);
+
+    let mut samples = 0usize;
+    let mut colors = HashSet::new();
+    for pixel in pixels.step_by(step) {
+        samples += 1;
+        colors.insert(
+            (u32::from(pixel[0] >> 2) << 12)
+                | (u32::from(pixel[1] >> 2) << 6)
+                | u32::from(pixel[2] >> 2),
+        );
+    }
+
+    if samples > 0 && colors.len() as f64 / samples as f64 > threshold {
+        ImageFormat::Jpeg
+    } else {
+        ImageFormat::Png
+    }
+}
+
 /// Platform-specific screenshot implementation trait
 pub trait PlatformScreenshot {
     /// Capture a screenshot of the current viewport
@@ -115,27 +607,86 @@ pub trait PlatformScreenshot {
 }
 
 /// Capture a screenshot of the current viewport using platform-specific APIs
+#[cfg_attr(
+    feature = "tracing",
+    tracing::instrument(level = "debug", skip_all, fields(window = window.label()))
+)]
 pub async fn capture_viewport_screenshot<R: Runtime>(
     window: &WebviewWindow<R>,
-    format: &str,
-    quality: u8,
-    max_width: Option<u32>,
-) -> Result<String, ScreenshotError> {
+    options: &ScreenshotOptions,
+) -> Result<Screenshot, ScreenshotError> {
+    let screenshot = capture_native_viewport(window, &options.capture).await?;
+    encode(screenshot, options)
+}
+
+/// A capture and a downscaled copy of it, from [`capture_with_thumbnail`].
+#[derive(Debug, Clone)]
+pub struct ThumbnailCapture {
+    /// The capture, encoded like [`capture_viewport_screenshot`] does
+    pub full: Screenshot,
+    /// The capture scaled to fit the requested square
+    pub thumbnail: Screenshot,
+}
+
+/// Capture the viewport once and return it alongside a thumbnail that fits
+/// in `thumb_max_dim` by `thumb_max_dim` pixels.
+///
+/// Both images are encoded with `options`. The thumbnail is downscaled from
+/// the decoded native capture rather than captured again, and ignores
+/// `max_width`, `max_bytes` and `strip_metadata`.
+#[cfg_attr(
+    feature = "tracing",
+    tracing::instrument(level = "debug", skip_all, fields(window = window.label()))
+)]
+pub async fn capture_with_thumbnail<R: Runtime>(
+    window: &WebviewWindow<R>,
+    thumb_max_dim: u32,
+    options: &ScreenshotOptions,
+) -> Result<ThumbnailCapture, ScreenshotError> {
+    let native = capture_native_viewport(window, &options.capture).await?;
+    let thumbnail = encode_thumbnail(&native, thumb_max_dim, options)?;
+
+    Ok(ThumbnailCapture {
+        full: encode(native, options)?,
+        thumbnail,
+    })
+}
+
+/// Capture the viewport natively, as a PNG with its screen bounds.
+async fn capture_native_viewport<R: Runtime>(
+    window: &WebviewWindow<R>,
+    options: &CaptureOptions,
+) -> Result<Screenshot, ScreenshotError> {
+    ensure_window_available(window)?;
+    let _permit = acquire_capture_permit(window, options).await?;
+    trace_step!(
+        include_chrome = options.include_chrome,
+        "acquired capture permit"
+    );
+
     // Dispatch to platform-specific implementation
     #[cfg(target_os = "macos")]
-    let screenshot = macos::capture_viewport(window)?;
+    let mut screenshot = if options.include_chrome {
+        macos::capture_window(window, options)?
+    } else {
+        macos::capture_viewport(window, options)?
+    };
 
     #[cfg(target_os = "windows")]
-    let screenshot = windows::capture_viewport(window)?;
+    let mut screenshot = if options.include_chrome {
+        windows::capture_window(window, options)?
+    } else {
+        windows::capture_viewport(window, options)?
+    };
 
     #[cfg(target_os = "linux")]
-    let screenshot = linux::capture_viewport(window)?;
+    let mut screenshot = linux::capture_viewport(window, options)?;
 
     #[cfg(target_os = "ios")]
-    let screenshot = ios::capture_viewport(window)?;
+    let mut screenshot = ios::capture_viewport(window, options)?;
 
     #[cfg(target_os = "android")]
-    let screenshot = android::capture_viewport(window)?;
+    let mut screenshot = android::capture_viewport(window, options)?;
 
     #[cfg(not(any(
         target_os = "macos",
@@ -144,25 +695,685 @@ pub async fn capture_viewport_screenshot<R: Runtime>(
         target_os = "ios",
         target_os = "android"
     )))]
-    return Err(ScreenshotError::PlatformUnsupported);
+    return Err(ScreenshotError::PlatformUnsupported(
+        "no native viewport capture on this platform".to_string(),
+    ));
+
+    // Mobile WebViews have no chrome, so the flag only applies on desktop
+    let chrome = options.include_chrome && cfg!(any(target_os = "macos", target_os = "windows"));
+    screenshot.bounds = capture_bounds(window, chrome);
+
+    Ok(screenshot)
+}
+
+/// Screen area of the window's WebView, or of the whole window including its
+/// title bar and frame when `include_chrome` is set.
+pub(crate) fn capture_bounds<R: Runtime>(
+    window: &WebviewWindow<R>,
+    include_chrome: bool,
+) -> Option<CaptureBounds> {
+    let (position, size) = if include_chrome {
+        (window.outer_position().ok()?, window.outer_size().ok()?)
+    } else {
+        (window.inner_position().ok()?, window.inner_size().ok()?)
+    };
+
+    Some(CaptureBounds {
+        x: position.x,
+        y: position.y,
+        width: size.width,
+        height: size.height,
+        includes_chrome: include_chrome,
+    })
+}
+
+/// Capture the whole screen, including native UI outside the WebView.
+///
+/// On macOS and Windows this grabs the primary monitor, which on macOS needs
+/// the Screen Recording permission. On Android it only captures the app's
+/// own window through the activity's root view, without the system bars or
+/// other apps. Linux has no native capture at all and iOS does not allow capturing
+/// outside the app, so both return `PlatformUnsupported`; use
+/// [`capture_viewport_screenshot`] there instead.
+#[cfg_attr(
+    feature = "tracing",
+    tracing::instrument(level = "debug", skip_all, fields(window = window.label()))
+)]
+pub async fn capture_screen_screenshot<R: Runtime>(
+    window: &WebviewWindow<R>,
+    options: &ScreenshotOptions,
+) -> Result<Screenshot, ScreenshotError> {
+    ensure_window_available(window)?;
+    let _permit = acquire_capture_permit(window, &options.capture).await?;
+    trace_step!("acquired capture permit");
+    let screenshot = capture_screen(window, &options.capture)?;
+
+    encode(screenshot, options)
+}
+
+/// Fail fast on closing windows instead of waiting for a capture callback
+/// that will never fire.
+fn ensure_window_available<R: Runtime>(window: &WebviewWindow<R>) -> Result<(), ScreenshotError> {
+    if crate::commands::is_window_available(window) {
+        Ok(())
+    } else {
+        Err(ScreenshotError::WindowUnavailable(
+            window.label().to_string(),
+        ))
+    }
+}
+
+/// Wait for this window's turn to capture, bounded by the capture timeout.
+///
+/// Without a managed [`limiter::CaptureLimiter`] captures are not limited.
+async fn acquire_capture_permit<R: Runtime>(
+    window: &WebviewWindow<R>,
+    options: &CaptureOptions,
+) -> Result<Option<limiter::CapturePermit>, ScreenshotError> {
+    match window.try_state::<limiter::CaptureLimiter>() {
+        Some(limiter) => limiter
+            .acquire(window.label(), options.timeout)
+            .await
+            .map(Some),
+        None => Ok(None),
+    }
+}
+
+/// Dispatch a full screen capture to the platform implementation.
+fn capture_screen<R: Runtime>(
+    window: &WebviewWindow<R>,
+    options: &CaptureOptions,
+) -> Result<Screenshot, ScreenshotError> {
+    #[cfg(target_os = "macos")]
+    {
+        macos::capture_screen(window, options)
+    }
 
+    #[cfg(target_os = "windows")]
+    {
+        windows::capture_screen(window, options)
+    }
+
+    #[cfg(target_os = "android")]
+    {
+        android::capture_screen(window, options)
+    }
+
+    #[cfg(not(any(target_os = "macos", target_os = "windows", target_os = "android")))]
+    {
+        let _ = (window, options);
+        Err(ScreenshotError::PlatformUnsupported(
+            "native full screen capture is not available on this platform".to_string(),
+        ))
+    }
+}
+
+/// Apply the max_width constraint and optional transport compression.
+pub(crate) fn encode(
+    screenshot: Screenshot,
+    options: &ScreenshotOptions,
+) -> Result<Screenshot, ScreenshotError> {
     // Apply max_width constraint if specified (param or env var)
-    let effective_max_width = get_effective_max_width(max_width);
-    let final_data = match effective_max_width {
-        Some(max_w) => resize_if_needed(screenshot.data, max_w, format, quality)?,
-        None => screenshot.data,
+    let effective_max_width = get_effective_max_width(options.max_width);
+    let (data, format) = convert(
+        screenshot.data,
+        screenshot.format,
+        effective_max_width,
+        options,
+    )?;
+    let data = if options.strip_metadata {
+        metadata::strip_metadata(data, format, options.quality, options.png_compression)?
+    } else {
+        data
+    };
+    let (data, budget_fit) = match options.max_bytes {
+        Some(max_bytes) if data.len() > max_bytes => {
+            let (data, fit) = fit_to_budget(&data, format, max_bytes, options)?;
+            (data, Some(fit))
+        }
+        _ => (data, None),
+    };
+
+    if options.should_compress(format) {
+        return Ok(Screenshot {
+            data: gzip(&data)?,
+            content_encoding: Some(ContentEncoding::Gzip),
+            format,
+            source: screenshot.source,
+            bounds: screenshot.bounds,
+            budget_fit,
+        });
+    }
+
+    Ok(Screenshot {
+        data,
+        content_encoding: None,
+        format,
+        source: screenshot.source,
+        bounds: screenshot.bounds,
+        budget_fit,
+    })
+}
+
+/// Downscale a capture to fit in `max_dim` by `max_dim` pixels, keeping its
+/// aspect ratio, and encode it in `options`' format, quality and transport
+/// compression. Smaller captures are re-encoded without scaling.
+pub(crate) fn encode_thumbnail(
+    screenshot: &Screenshot,
+    max_dim: u32,
+    options: &ScreenshotOptions,
+) -> Result<Screenshot, ScreenshotError> {
+    let img = DynamicImage::ImageRgba8(screenshot.decode()?);
+    let img = if img.width() > max_dim || img.height() > max_dim {
+        img.thumbnail(max_dim, max_dim)
+    } else {
+        img
     };
 
-    // Convert to base64 data URL
-    let mime_type = if format == "jpeg" {
-        "image/jpeg"
+    let format = match options.format {
+        ImageFormat::Auto => choose_format(&img, options.auto_format_threshold),
+        format => format,
+    };
+    let data = encode_image(&img, format, options.quality, options.png_compression)?;
+    let (data, content_encoding) = if options.should_compress(format) {
+        (gzip(&data)?, Some(ContentEncoding::Gzip))
     } else {
-        "image/png"
+        (data, None)
     };
 
-    use base64::Engine as _;
-    let base64_data = base64::engine::general_purpose::STANDARD.encode(&final_data);
-    let data_url = format!("data:{mime_type};base64,{base64_data}");
+    Ok(Screenshot {
+        data,
+        content_encoding,
+        format,
+        source: screenshot.source,
+        bounds: screenshot.bounds,
+        budget_fit: None,
+    })
+}
+
+/// Shrink an encoded image to at most `max_bytes`.
+///
+/// Images that already fit are returned as they are. JPEGs are re-encoded
+/// at the highest quality below the requested one that fits, found by
+/// binary search down to `MIN_FIT_QUALITY`, or lower when the requested
+/// quality is already below it. PNGs are lossless, so they are downscaled
+/// instead, by the square root of the size overshoot each round. When
+/// nothing fits, the smallest attempt is returned with `over_budget` set.
+fn fit_to_budget(
+    data: &[u8],
+    format: ImageFormat,
+    max_bytes: usize,
+    options: &ScreenshotOptions,
+) -> Result<(Vec<u8>, BudgetFit), ScreenshotError> {
+    if data.len() <= max_bytes {
+        return Ok((
+            data.to_vec(),
+            BudgetFit {
+                quality: None,
+                width: None,
+                over_budget: false,
+            },
+        ));
+    }
+
+    let img = image::load_from_memory(data)
+        .map_err(|e| ScreenshotError::EncodeFailed(format!("Failed to decode image: {e}")))?;
+
+    if format == ImageFormat::Jpeg {
+        // Search below the requested quality only, even when that is already
+        // under the usual floor
+        let mut high = options.quality.saturating_sub(1);
+        let floor = MIN_FIT_QUALITY.min(high).max(1);
+        let mut low = floor;
+        let (mut best, mut smallest) = (None, None);
+        while low <= high {
+            let quality = low + (high - low) / 2;
+            let encoded = encode_image(&img, format, quality, None)?;
+            if encoded.len() <= max_bytes {
+                best = Some((encoded, quality));
+                low = quality + 1;
+            } else if quality == floor {
+                smallest = Some((encoded, quality));
+                break;
+            } else {
+                high = quality - 1;
+            }
+        }
+
+        let (data, quality) = match best.or(smallest) {
+            Some((data, quality)) => (data, Some(quality)),
+            // No quality left below the requested one
+            None => (data.to_vec(), None),
+        };
+        let over_budget = data.len() > max_bytes;
+        return Ok((
+            data,
+            BudgetFit {
+                quality,
+                width: None,
+                over_budget,
+            },
+        ));
+    }
+
+    let mut size = data.len();
+    let mut width = img.width();
+    let mut smallest = None;
+    for _ in 0..MAX_FIT_ATTEMPTS {
+        if width <= MIN_FIT_WIDTH {
+            break;
+        }
+        // Encoded size shrinks roughly with the pixel count; aim a little low
+        let scale = (max_bytes as f64 / size as f64).sqrt() * 0.95;
+        width = ((width as f64 * scale) as u32).clamp(MIN_FIT_WIDTH, width - 1);
+        let height =
+            ((img.height() as f64 * width as f64 / img.width() as f64).round() as u32).max(1);
+
+        let encoded = encode_image(
+            &img.resize_exact(width, height, FilterType::Lanczos3),
+            format,
+            options.quality,
+            options.png_compression,
+        )?;
+        size = encoded.len();
+        smallest = Some((encoded, width));
+        if size <= max_bytes {
+            break;
+        }
+    }
+
+    match smallest {
+        Some((data, width)) => Ok((
+            data,
+            BudgetFit {
+                quality: None,
+                width: Some(width),
+                over_budget: size > max_bytes,
+            },
+        )),
+        // Already at the minimum width
+        None => Ok((
+            data.to_vec(),
+            BudgetFit {
+                quality: None,
+                width: None,
+                over_budget: true,
+            },
+        )),
+    }
+}
+
+/// Waits for the result a `with_webview` capture callback sends on `rx`.
+///
+/// Runtimes without native webview access, such as Tauri's mock runtime,
+/// drop the callback without calling it. The channel then disconnects
+/// before the timeout, which is reported as `PlatformUnsupported` rather
+/// than `Timeout` so clients don't retry.
+#[cfg_attr(not(target_os = "android"), allow(dead_code))]
+pub(crate) fn recv_webview_capture(
+    rx: &Receiver<Result<Screenshot, ScreenshotError>>,
+    timeout: Duration,
+) -> Result<Screenshot, ScreenshotError> {
+    match rx.recv_timeout(timeout) {
+        Ok(result) => result,
+        Err(RecvTimeoutError::Timeout) => Err(ScreenshotError::Timeout),
+        Err(RecvTimeoutError::Disconnected) => Err(ScreenshotError::PlatformUnsupported(
+            "the webview runtime doesn't support `with_webview`, so native capture is unavailable"
+                .to_string(),
+        )),
+    }
+}
+
+/// Encode top-down 32-bit BGRA rows, as returned by the desktop window
+/// capture APIs, as PNG. `stride` is the byte length of one row.
+#[cfg_attr(not(any(target_os = "macos", target_os = "windows")), allow(dead_code))]
+fn bgra_to_png(
+    width: u32,
+    height: u32,
+    stride: usize,
+    data: &[u8],
+) -> Result<Vec<u8>, ScreenshotError> {
+    let row_len = width as usize * 4;
+    if stride < row_len || data.len() < stride * height as usize {
+        return Err(ScreenshotError::EncodeFailed(
+            "Window capture returned a truncated bitmap".to_string(),
+        ));
+    }
+
+    let mut rgba = Vec::with_capacity(row_len * height as usize);
+    for row in data.chunks(stride).take(height as usize) {
+        for pixel in row[..row_len].chunks_exact(4) {
+            rgba.extend_from_slice(&[pixel[2], pixel[1], pixel[0], pixel[3]]);
+        }
+    }
+
+    let image = image::RgbaImage::from_raw(width, height, rgba)
+        .ok_or_else(|| ScreenshotError::EncodeFailed("Window capture size mismatch".to_string()))?;
+    let mut buffer = Cursor::new(Vec::new());
+    image
+        .write_to(&mut buffer, image::ImageFormat::Png)
+        .map_err(|e| ScreenshotError::EncodeFailed(format!("Failed to encode PNG: {e}")))?;
+
+    Ok(buffer.into_inner())
+}
 
-    Ok(data_url)
+/// Gzip the encoded image bytes.
+fn gzip(data: &[u8]) -> Result<Vec<u8>, ScreenshotError> {
+    let mut encoder = GzEncoder::new(Vec::new(), Compression::default());
+    encoder
+        .write_all(data)
+        .and_then(|_| encoder.finish())
+        .map_err(|e| ScreenshotError::EncodeFailed(format!("Failed to gzip screenshot: {e}")))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use flate2::read::GzDecoder;
+    use std::io::Read;
+
+    #[test]
+    fn test_dropped_webview_callback_is_unsupported() {
+        let (tx, rx) = std::sync::mpsc::channel();
+        // A runtime without webview access drops the callback holding `tx`
+        drop(tx);
+
+        let result = recv_webview_capture(&rx, Duration::from_secs(5));
+        assert!(matches!(
+            result,
+            Err(ScreenshotError::PlatformUnsupported(message)) if message.contains("with_webview")
+        ));
+    }
+
+    #[test]
+    fn test_webview_capture_without_result_times_out() {
+        let (_tx, rx) = std::sync::mpsc::channel();
+        let result = recv_webview_capture(&rx, Duration::from_millis(10));
+        assert!(matches!(result, Err(ScreenshotError::Timeout)));
+    }
+
+    fn options(format: &str, compress: bool, force: bool) -> ScreenshotOptions {
+        ScreenshotOptions {
+            format: ImageFormat::from_name(format),
+            compress_transport: compress,
+            force_compression: force,
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_compression_skips_jpeg_unless_forced() {
+        let compresses = |o: ScreenshotOptions| o.should_compress(o.format);
+        assert!(!compresses(options("png", false, false)));
+        assert!(compresses(options("png", true, false)));
+        assert!(!compresses(options("jpeg", true, false)));
+        assert!(compresses(options("jpeg", true, true)));
+    }
+
+    #[test]
+    fn test_mime_types() {
+        let png = encode_png(&image::RgbImage::new(1, 1));
+        let jpeg = convert(
+            png.clone(),
+            ImageFormat::Png,
+            None,
+            &options("jpeg", false, false),
+        )
+        .unwrap()
+        .0;
+        let webp = b"RIFF\x1a\x00\x00\x00WEBPVP8L";
+
+        assert_eq!(detect_mime_type(&png), Some("image/png"));
+        assert_eq!(detect_mime_type(&jpeg), Some("image/jpeg"));
+        assert_eq!(detect_mime_type(webp), Some("image/webp"));
+        assert_eq!(detect_mime_type(b"GIF89a"), None);
+        assert_eq!(detect_mime_type(b"RIFF"), None);
+
+        assert_eq!(Screenshot::new(png).mime_type(), "image/png");
+        let screenshot = Screenshot {
+            format: ImageFormat::Jpeg,
+            ..Screenshot::new(jpeg)
+        };
+        assert_eq!(screenshot.mime_type(), "image/jpeg");
+
+        assert_eq!(
+            ImageFormat::from_mime_type("image/jpeg"),
+            Some(ImageFormat::Jpeg)
+        );
+        assert_eq!(ImageFormat::from_mime_type("image/webp"), None);
+    }
+
+    fn encode_png(img: &image::RgbImage) -> Vec<u8> {
+        let mut buffer = Cursor::new(Vec::new());
+        img.write_to(&mut buffer, image::ImageFormat::Png).unwrap();
+        buffer.into_inner()
+    }
+
+    /// A flat UI-like image and a noisy photo-like one.
+    fn sample_images() -> (image::RgbImage, image::RgbImage) {
+        let ui = image::RgbImage::from_fn(200, 100, |x, _| {
+            if x < 40 {
+                image::Rgb([30, 30, 30])
+            } else {
+                image::Rgb([250, 250, 250])
+            }
+        });
+
+        let mut state = 12345u32;
+        let photo = image::RgbImage::from_fn(200, 100, |_, _| {
+            state = state.wrapping_mul(1_103_515_245).wrapping_add(12345);
+            let [r, g, b, _] = state.to_be_bytes();
+            image::Rgb([r, g, b])
+        });
+
+        (ui, photo)
+    }
+
+    #[test]
+    fn test_auto_format_picks_png_for_ui_and_jpeg_for_photos() {
+        let (ui, photo) = sample_images();
+        let ui = DynamicImage::ImageRgb8(ui);
+        let photo = DynamicImage::ImageRgb8(photo);
+
+        assert_eq!(choose_format(&ui, 0.5), ImageFormat::Png);
+        assert_eq!(choose_format(&photo, 0.5), ImageFormat::Jpeg);
+        // A threshold of 1.0 never picks JPEG
+        assert_eq!(choose_format(&photo, 1.0), ImageFormat::Png);
+    }
+
+    #[test]
+    fn test_convert_records_chosen_format() {
+        let (ui, photo) = sample_images();
+        let auto = options("auto", false, false);
+
+        let ui_png = encode_png(&ui);
+        let (data, format) = convert(ui_png.clone(), ImageFormat::Png, None, &auto).unwrap();
+        assert_eq!(format, ImageFormat::Png);
+        assert_eq!(data, ui_png);
+
+        let (data, format) = convert(encode_png(&photo), ImageFormat::Png, None, &auto).unwrap();
+        assert_eq!(format, ImageFormat::Jpeg);
+        assert_eq!(&data[..2], &[0xFF, 0xD8]);
+
+        // Explicit JPEG works for images with an alpha channel
+        let mut buffer = Cursor::new(Vec::new());
+        image::RgbaImage::new(4, 4)
+            .write_to(&mut buffer, image::ImageFormat::Png)
+            .unwrap();
+        let (_, format) = convert(
+            buffer.into_inner(),
+            ImageFormat::Png,
+            None,
+            &options("jpeg", false, false),
+        )
+        .unwrap();
+        assert_eq!(format, ImageFormat::Jpeg);
+    }
+
+    #[test]
+    fn test_convert_recompresses_png_only_when_asked() {
+        let (ui, _) = sample_images();
+        let native = encode_png(&ui);
+
+        let (data, _) = convert(
+            native.clone(),
+            ImageFormat::Png,
+            None,
+            &options("png", false, false),
+        )
+        .unwrap();
+        assert_eq!(data, native);
+
+        let best = ScreenshotOptions {
+            png_compression: Some(PngCompression::Best),
+            ..options("png", false, false)
+        };
+        let (data, format) = convert(native.clone(), ImageFormat::Png, None, &best).unwrap();
+        assert_eq!(format, ImageFormat::Png);
+        assert!(data.len() < native.len());
+        assert_eq!(image::load_from_memory(&data).unwrap().to_rgb8(), ui);
+    }
+
+    #[test]
+    fn test_fit_to_budget_lowers_quality_or_downscales() {
+        let (_, photo) = sample_images();
+        let jpeg = options("jpeg", false, false);
+        let (full, _) = convert(encode_png(&photo), ImageFormat::Png, None, &jpeg).unwrap();
+
+        let budget = full.len() * 2 / 3;
+        let (data, fit) = fit_to_budget(&full, ImageFormat::Jpeg, budget, &jpeg).unwrap();
+        assert!(data.len() <= budget);
+        assert!(!fit.over_budget);
+        assert!(fit.quality.is_some_and(|q| q < jpeg.quality));
+
+        let (data, fit) = fit_to_budget(&full, ImageFormat::Jpeg, 100, &jpeg).unwrap();
+        assert!(fit.over_budget);
+        assert_eq!(fit.quality, Some(MIN_FIT_QUALITY));
+        assert!(data.len() < budget);
+
+        let (data, fit) = fit_to_budget(&full, ImageFormat::Jpeg, full.len(), &jpeg).unwrap();
+        assert_eq!(data, full);
+        assert_eq!(fit.quality, None);
+        assert!(!fit.over_budget);
+
+        // Already below the usual floor: never re-encode at a higher quality
+        let low = ScreenshotOptions {
+            quality: 5,
+            ..jpeg.clone()
+        };
+        let (low_data, _) = convert(encode_png(&photo), ImageFormat::Png, None, &low).unwrap();
+        let (data, fit) = fit_to_budget(&low_data, ImageFormat::Jpeg, 100, &low).unwrap();
+        assert!(fit.quality.is_some_and(|q| q < 5));
+        assert_eq!(fit.over_budget, data.len() > 100);
+        assert!(data.len() <= low_data.len());
+
+        let png = encode_png(&photo);
+        let budget = png.len() / 4;
+        let (data, fit) = fit_to_budget(
+            &png,
+            ImageFormat::Png,
+            budget,
+            &options("png", false, false),
+        )
+        .unwrap();
+        assert!(data.len() <= budget);
+        assert!(!fit.over_budget);
+        assert_eq!(
+            image::load_from_memory(&data).unwrap().width(),
+            fit.width.unwrap()
+        );
+    }
+
+    #[test]
+    fn test_bgra_to_png_swaps_channels_and_skips_padding() {
+        // 1x2 image with 4 bytes of row padding
+        let data = [
+            1, 2, 3, 255, 0, 0, 0, 0, //
+            10, 20, 30, 128, 0, 0, 0, 0,
+        ];
+        let png = bgra_to_png(1, 2, 8, &data).unwrap();
+
+        let decoded = image::load_from_memory(&png).unwrap().to_rgba8();
+        assert_eq!(decoded.get_pixel(0, 0).0, [3, 2, 1, 255]);
+        assert_eq!(decoded.get_pixel(0, 1).0, [30, 20, 10, 128]);
+        assert!(bgra_to_png(2, 2, 8, &data[..12]).is_err());
+    }
+
+    #[test]
+    fn test_decode_known_png_and_gzip() {
+        // 2x1 RGBA PNG: opaque red, then half-transparent blue
+        const PNG: &[u8] = &[
+            0x89, 0x50, 0x4e, 0x47, 0x0d, 0x0a, 0x1a, 0x0a, 0x00, 0x00, 0x00, 0x0d, 0x49, 0x48,
+            0x44, 0x52, 0x00, 0x00, 0x00, 0x02, 0x00, 0x00, 0x00, 0x01, 0x08, 0x06, 0x00, 0x00,
+            0x00, 0xf4, 0x22, 0x7f, 0x8a, 0x00, 0x00, 0x00, 0x0e, 0x49, 0x44, 0x41, 0x54, 0x78,
+            0x9c, 0x63, 0xf8, 0xcf, 0xc0, 0x00, 0x42, 0x0d, 0x00, 0x0f, 0x7a, 0x03, 0x7e, 0x77,
+            0xe9, 0x7f, 0x97, 0x00, 0x00, 0x00, 0x00, 0x49, 0x45, 0x4e, 0x44, 0xae, 0x42, 0x60,
+            0x82,
+        ];
+
+        let screenshot = Screenshot::new(PNG.to_vec());
+        let pixels = screenshot.decode().unwrap();
+        assert_eq!(pixels.dimensions(), (2, 1));
+        assert_eq!(pixels.get_pixel(0, 0).0, [255, 0, 0, 255]);
+        assert_eq!(pixels.get_pixel(1, 0).0, [0, 0, 255, 128]);
+
+        let gzipped = Screenshot {
+            data: gzip(PNG).unwrap(),
+            content_encoding: Some(ContentEncoding::Gzip),
+            ..Screenshot::new(Vec::new())
+        };
+        assert_eq!(gzipped.decode().unwrap(), pixels);
+
+        let jpeg = Screenshot {
+            format: ImageFormat::Jpeg,
+            ..Screenshot::new(PNG.to_vec())
+        };
+        assert!(matches!(
+            jpeg.decode(),
+            Err(ScreenshotError::EncodeFailed(_))
+        ));
+    }
+
+    #[test]
+    fn test_orientation_rotates_only_mismatched_views() {
+        assert!(!CaptureOrientation::Natural.needs_rotation(1080, 2400));
+        assert!(!CaptureOrientation::Portrait.needs_rotation(1080, 2400));
+        assert!(CaptureOrientation::Landscape.needs_rotation(1080, 2400));
+        assert!(CaptureOrientation::Portrait.needs_rotation(2400, 1080));
+        assert!(!CaptureOrientation::Landscape.needs_rotation(1000, 1000));
+
+        let orientation: CaptureOrientation =
+            serde_json::from_value(serde_json::json!("landscape")).unwrap();
+        assert_eq!(orientation, CaptureOrientation::Landscape);
+    }
+
+    #[test]
+    fn test_thumbnail_fits_max_dim_and_keeps_aspect() {
+        let (ui, _) = sample_images();
+        let native = Screenshot::new(encode_png(&ui));
+
+        let thumbnail = encode_thumbnail(&native, 50, &options("jpeg", false, false)).unwrap();
+        assert_eq!(thumbnail.format, ImageFormat::Jpeg);
+        let pixels = thumbnail.decode().unwrap();
+        assert_eq!((pixels.width(), pixels.height()), (50, 25));
+
+        // Never upscaled, and gzipped like the full image would be
+        let thumbnail = encode_thumbnail(&native, 500, &options("png", true, false)).unwrap();
+        assert_eq!(thumbnail.content_encoding, Some(ContentEncoding::Gzip));
+        let pixels = thumbnail.decode().unwrap();
+        assert_eq!((pixels.width(), pixels.height()), (200, 100));
+    }
+
+    #[test]
+    fn test_gzip_round_trip() {
+        let data = vec![7u8; 4096];
+        let compressed = gzip(&data).unwrap();
+        assert!(compressed.len() < data.len());
+
+        let mut decoded = Vec::new();
+        GzDecoder::new(compressed.as_slice())
+            .read_to_end(&mut decoded)
+            .unwrap();
+        assert_eq!(decoded, data);
+    }
 }
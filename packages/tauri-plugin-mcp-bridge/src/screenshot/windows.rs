@@ -1,4 +1,4 @@
-use super::{Screenshot, ScreenshotError};
+use super::{bgra_to_png, CaptureOptions, Screenshot, ScreenshotError};
 use tauri::{Runtime, WebviewWindow};
 
 /// Windows-specific screenshot implementation using WebView2's CapturePreview
@@ -7,6 +7,7 @@ use tauri::{Runtime, WebviewWindow};
 /// WebView2's CapturePreview API naturally captures just the viewport.
 pub fn capture_viewport<R: Runtime>(
     window: &WebviewWindow<R>,
+    options: &CaptureOptions,
 ) -> Result<Screenshot, ScreenshotError> {
     #[cfg(target_os = "windows")]
     {
@@ -39,7 +40,7 @@ pub fn capture_viewport<R: Runtime>(
                                 // SAFETY: read_stream_to_vec requires the stream to be valid,
                                 // which is guaranteed by the CapturePreview completion handler
                                 match read_stream_to_vec(&stream_clone) {
-                                    Ok(data) => Ok(Screenshot { data }),
+                                    Ok(data) => Ok(Screenshot::new(data)),
                                     Err(e) => Err(e),
                                 }
                             }
@@ -65,7 +66,7 @@ pub fn capture_viewport<R: Runtime>(
             })?;
 
         // Wait for result
-        match rx.recv_timeout(std::time::Duration::from_secs(10)) {
+        match rx.recv_timeout(options.timeout) {
             Ok(result) => result,
             Err(_) => Err(ScreenshotError::Timeout),
         }
@@ -73,10 +74,166 @@ pub fn capture_viewport<R: Runtime>(
 
     #[cfg(not(target_os = "windows"))]
     {
-        Err(ScreenshotError::PlatformUnsupported)
+        let _ = (window, options);
+        Err(ScreenshotError::PlatformUnsupported(
+            "this capture requires Windows".to_string(),
+        ))
     }
 }
 
+/// Captures the whole window, including the title bar and frame.
+///
+/// Uses `PrintWindow` with `PW_RENDERFULLCONTENT`, which also renders the
+/// WebView2's composited content into the bitmap.
+pub fn capture_window<R: Runtime>(
+    window: &WebviewWindow<R>,
+    _options: &CaptureOptions,
+) -> Result<Screenshot, ScreenshotError> {
+    use windows::Win32::Foundation::{HWND, RECT};
+    use windows::Win32::Storage::Xps::{PrintWindow, PRINT_WINDOW_FLAGS};
+    use windows::Win32::UI::WindowsAndMessaging::GetWindowRect;
+
+    const PW_RENDERFULLCONTENT: u32 = 0x2;
+
+    let hwnd = window
+        .hwnd()
+        .map_err(|e| ScreenshotError::CaptureFailed(format!("Failed to access window: {e}")))?;
+    let hwnd = HWND(hwnd.0 as _);
+
+    unsafe {
+        let mut rect = RECT::default();
+        GetWindowRect(hwnd, &mut rect).map_err(|e| {
+            ScreenshotError::CaptureFailed(format!("Failed to read window rect: {e}"))
+        })?;
+        let width = rect.right - rect.left;
+        let height = rect.bottom - rect.top;
+        if width <= 0 || height <= 0 {
+            return Err(ScreenshotError::CaptureFailed(
+                "Window has no visible area".to_string(),
+            ));
+        }
+
+        render_to_png(
+            width,
+            height,
+            "PrintWindow failed to render the window",
+            |_, memory_dc| {
+                PrintWindow(hwnd, memory_dc, PRINT_WINDOW_FLAGS(PW_RENDERFULLCONTENT)).as_bool()
+            },
+        )
+    }
+}
+
+/// Captures the primary monitor, including other apps' windows and the taskbar.
+///
+/// Copies the screen DC with `BitBlt`. `CAPTUREBLT` includes layered windows,
+/// so WebView2 content and tooltips show up as they do on screen.
+pub fn capture_screen<R: Runtime>(
+    _window: &WebviewWindow<R>,
+    _options: &CaptureOptions,
+) -> Result<Screenshot, ScreenshotError> {
+    use windows::Win32::Graphics::Gdi::{BitBlt, CAPTUREBLT, ROP_CODE, SRCCOPY};
+    use windows::Win32::UI::WindowsAndMessaging::{GetSystemMetrics, SM_CXSCREEN, SM_CYSCREEN};
+
+    unsafe {
+        // The primary monitor's top-left corner is the virtual screen origin
+        let width = GetSystemMetrics(SM_CXSCREEN);
+        let height = GetSystemMetrics(SM_CYSCREEN);
+        if width <= 0 || height <= 0 {
+            return Err(ScreenshotError::CaptureFailed(
+                "Primary monitor has no visible area".to_string(),
+            ));
+        }
+
+        render_to_png(
+            width,
+            height,
+            "BitBlt failed to copy the screen",
+            |screen_dc, memory_dc| {
+                BitBlt(
+                    memory_dc,
+                    0,
+                    0,
+                    width,
+                    height,
+                    Some(screen_dc),
+                    0,
+                    0,
+                    ROP_CODE(SRCCOPY.0 | CAPTUREBLT.0),
+                )
+                .is_ok()
+            },
+        )
+    }
+}
+
+/// Lets `render` draw `width` by `height` pixels into a memory DC compatible
+/// with the screen, and encodes them as PNG.
+///
+/// `render` receives the screen DC and the memory DC and reports whether it
+/// succeeded; on failure the capture fails with `failure`.
+///
+/// # Safety
+/// `render` must only draw into the memory DC it is given.
+unsafe fn render_to_png(
+    width: i32,
+    height: i32,
+    failure: &str,
+    render: impl FnOnce(windows::Win32::Graphics::Gdi::HDC, windows::Win32::Graphics::Gdi::HDC) -> bool,
+) -> Result<Screenshot, ScreenshotError> {
+    use windows::Win32::Graphics::Gdi::{
+        CreateCompatibleBitmap, CreateCompatibleDC, DeleteDC, DeleteObject, GetDC, GetDIBits,
+        ReleaseDC, SelectObject, BITMAPINFO, BITMAPINFOHEADER, BI_RGB, DIB_RGB_COLORS,
+    };
+
+    let screen_dc = GetDC(None);
+    let memory_dc = CreateCompatibleDC(Some(screen_dc));
+    let bitmap = CreateCompatibleBitmap(screen_dc, width, height);
+    let previous = SelectObject(memory_dc, bitmap.into());
+
+    let rendered = render(screen_dc, memory_dc);
+
+    // A negative height asks for top-down rows
+    let mut info = BITMAPINFO {
+        bmiHeader: BITMAPINFOHEADER {
+            biSize: std::mem::size_of::<BITMAPINFOHEADER>() as u32,
+            biWidth: width,
+            biHeight: -height,
+            biPlanes: 1,
+            biBitCount: 32,
+            biCompression: BI_RGB.0,
+            ..Default::default()
+        },
+        ..Default::default()
+    };
+    let mut pixels = vec![0u8; width as usize * height as usize * 4];
+    let lines = GetDIBits(
+        memory_dc,
+        bitmap,
+        0,
+        height as u32,
+        Some(pixels.as_mut_ptr() as *mut _),
+        &mut info,
+        DIB_RGB_COLORS,
+    );
+
+    SelectObject(memory_dc, previous);
+    let _ = DeleteObject(bitmap.into());
+    let _ = DeleteDC(memory_dc);
+    ReleaseDC(None, screen_dc);
+
+    if !rendered || lines != height {
+        return Err(ScreenshotError::CaptureFailed(failure.to_string()));
+    }
+
+    // GDI leaves alpha at zero for opaque windows
+    for alpha in pixels.iter_mut().skip(3).step_by(4) {
+        *alpha = 255;
+    }
+
+    bgra_to_png(width as u32, height as u32, width as usize * 4, &pixels).map(Screenshot::new)
+}
+
 /// Read all bytes from an IStream
 ///
 /// # Safety
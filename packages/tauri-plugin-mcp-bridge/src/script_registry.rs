@@ -3,22 +3,77 @@
 //! This module provides a registry for storing script entries that should be
 //! automatically re-injected when pages load or navigate.
 
+use crate::monitor::current_timestamp;
 use serde::{Deserialize, Serialize};
-use std::collections::HashMap;
+use std::collections::{BTreeMap, HashMap, HashSet};
+use std::path::Path;
 use std::sync::{Arc, Mutex};
 
 /// Type of script to inject.
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[cfg_attr(feature = "ts-bindings", derive(ts_rs::TS))]
 #[serde(rename_all = "lowercase")]
 pub enum ScriptType {
     /// Inline JavaScript code to execute directly.
     Inline,
     /// URL to an external script file.
     Url,
+    /// ES module, injected as `<script type="module">`.
+    ///
+    /// The content is either a module URL or inline module code; see
+    /// [`ScriptEntry::is_module_url`]. Unlike classic scripts, modules run
+    /// in their own scope, so top-level declarations don't become globals
+    /// (assign to `window` explicitly), they are deferred until the document
+    /// is parsed, and they always run in strict mode. Inline module code is
+    /// loaded from a `blob:` URL, so it can only import absolute URLs and
+    /// requires `blob:` to be allowed by the page's `script-src` CSP.
+    Module,
+}
+
+/// Lifecycle phase a script is injected in.
+///
+/// All `Setup` scripts are injected before any `Main` script, and all `Main`
+/// scripts before any `Teardown` script.
+#[derive(
+    Debug, Clone, Copy, Default, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize,
+)]
+#[cfg_attr(feature = "ts-bindings", derive(ts_rs::TS))]
+#[serde(rename_all = "lowercase")]
+pub enum InjectionPhase {
+    /// Polyfills and other scripts the rest depend on.
+    Setup,
+    /// Feature scripts.
+    #[default]
+    Main,
+    /// Hooks that run after everything else.
+    Teardown,
+}
+
+/// When a script runs relative to the page's own scripts.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "ts-bindings", derive(ts_rs::TS))]
+#[serde(rename_all = "camelCase")]
+pub enum RunAt {
+    /// After the page has loaded, injected through `request_script_injection`.
+    #[default]
+    DocumentEnd,
+    /// Before any page script, as a webview initialization script.
+    ///
+    /// Only inline scripts passed to [`crate::Builder::script`] run this
+    /// early, since initialization scripts are fixed when the plugin is
+    /// built. Entries registered at runtime, and URL or module scripts, fall
+    /// back to [`RunAt::DocumentEnd`].
+    DocumentStart,
 }
 
 /// A script entry in the registry.
+///
+/// Create entries with [`ScriptEntry::new`] and set the optional fields on
+/// the result. The struct is non-exhaustive so that new fields can be added
+/// without breaking other crates.
 #[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "ts-bindings", derive(ts_rs::TS))]
+#[non_exhaustive]
 pub struct ScriptEntry {
     /// Unique identifier for this script.
     pub id: String,
@@ -26,15 +81,406 @@ pub struct ScriptEntry {
     pub script_type: ScriptType,
     /// The script content (JavaScript code) or URL.
     pub content: String,
+    /// When the script was added to the registry (epoch millis).
+    #[serde(default)]
+    #[cfg_attr(feature = "ts-bindings", ts(type = "number | null"))]
+    pub created_at: Option<u64>,
+    /// When the script was last injected into a webview (epoch millis).
+    #[serde(default)]
+    #[cfg_attr(feature = "ts-bindings", ts(type = "number | null"))]
+    pub last_injected_at: Option<u64>,
+    /// Cache the fetched source of a URL script in the page's session
+    /// storage, keyed by [`ScriptEntry::content_hash`], so same-origin
+    /// navigations re-inject it without downloading it again.
+    #[serde(default)]
+    pub cache: bool,
+    /// Phase the script is injected in.
+    #[serde(default)]
+    pub phase: InjectionPhase,
+    /// Inject the script once per document instead of on every navigation.
+    /// See [`ScopedScriptRegistry::scripts_for_document`].
+    #[serde(default)]
+    pub run_once: bool,
+    /// When the script runs; see [`RunAt`].
+    #[serde(default)]
+    pub run_at: RunAt,
+    /// Position within its phase: lower values are injected first, and
+    /// scripts with the same order are injected by ID.
+    #[serde(default)]
+    pub order: i32,
+    /// URL patterns of the pages the script is injected into, where `*`
+    /// matches any run of characters, e.g. `https://*.example.com/*`. Empty
+    /// means every page. See [`ScriptEntry::matches_url`].
+    #[serde(default)]
+    pub matches: Vec<String>,
+}
+
+impl ScriptEntry {
+    /// Creates a new script entry that has not been added or injected yet.
+    pub fn new(id: impl Into<String>, script_type: ScriptType, content: impl Into<String>) -> Self {
+        Self {
+            id: id.into(),
+            script_type,
+            content: content.into(),
+            created_at: None,
+            last_injected_at: None,
+            cache: false,
+            phase: InjectionPhase::Main,
+            run_once: false,
+            run_at: RunAt::DocumentEnd,
+            order: 0,
+            matches: Vec::new(),
+        }
+    }
+
+    /// Whether the script is injected into the page at `url`: true when it
+    /// has no match patterns, or one of them matches the whole URL.
+    pub fn matches_url(&self, url: &str) -> bool {
+        self.matches.is_empty()
+            || self
+                .matches
+                .iter()
+                .any(|pattern| wildcard_match(pattern, url))
+    }
+
+    /// Whether the entry runs as a webview initialization script when given
+    /// to [`crate::Builder::script`].
+    pub fn runs_at_document_start(&self) -> bool {
+        self.run_at == RunAt::DocumentStart && self.script_type == ScriptType::Inline
+    }
+
+    /// Whether a [`ScriptType::Module`] entry's content is a module URL
+    /// rather than inline module code.
+    ///
+    /// Content is treated as a URL when it is a single token starting with a
+    /// scheme or a path prefix (`/`, `./`, `../`).
+    pub fn is_module_url(&self) -> bool {
+        let content = self.content.trim();
+        !content.contains(char::is_whitespace)
+            && (content.starts_with('/')
+                || content.starts_with("./")
+                || content.starts_with("../")
+                || tauri::Url::parse(content).is_ok())
+    }
+
+    /// Stable hash of the script type and content, used by the page to
+    /// recognize unchanged scripts across navigations.
+    ///
+    /// Uses 64-bit FNV-1a, so the value is the same across builds and runs.
+    pub fn content_hash(&self) -> String {
+        let kind: &[u8] = match self.script_type {
+            ScriptType::Inline => b"inline",
+            ScriptType::Url => b"url",
+            ScriptType::Module => b"module",
+        };
+
+        let mut hash: u64 = 0xcbf2_9ce4_8422_2325;
+        for byte in kind.iter().chain(b":").chain(self.content.as_bytes()) {
+            hash ^= u64::from(*byte);
+            hash = hash.wrapping_mul(0x0100_0000_01b3);
+        }
+        format!("{hash:016x}")
+    }
+
+    /// Checks that the entry can be registered: its ID passes
+    /// [`normalize_script_id`], it has content, a URL script's content is a
+    /// URL, and none of its match patterns is blank.
+    pub fn validate(&self) -> Result<(), RegistryError> {
+        let invalid = |reason: &str| RegistryError::InvalidEntry {
+            id: self.id.clone(),
+            reason: reason.to_string(),
+        };
+
+        normalize_script_id(&self.id)?;
+        if self.content.trim().is_empty() {
+            return Err(invalid("content is empty"));
+        }
+        if self.script_type == ScriptType::Url
+            && !self.content.starts_with('/')
+            && tauri::Url::parse(&self.content).is_err()
+        {
+            return Err(invalid("content is not a URL"));
+        }
+        if self.matches.iter().any(|pattern| pattern.trim().is_empty()) {
+            return Err(invalid("a match pattern is empty"));
+        }
+        Ok(())
+    }
+}
+
+/// Matches `text` against `pattern`, where `*` stands for any run of
+/// characters, including none.
+fn wildcard_match(pattern: &str, text: &str) -> bool {
+    let (pattern, text) = (pattern.as_bytes(), text.as_bytes());
+    let (mut p, mut t) = (0, 0);
+    // Position of the last `*` seen, and where in `text` it started matching
+    let mut star: Option<(usize, usize)> = None;
+
+    while t < text.len() {
+        if p < pattern.len() && pattern[p] == b'*' {
+            star = Some((p, t));
+            p += 1;
+        } else if p < pattern.len() && pattern[p] == text[t] {
+            p += 1;
+            t += 1;
+        } else if let Some((star_p, star_t)) = star {
+            // Let the last `*` swallow one more character
+            p = star_p + 1;
+            t = star_t + 1;
+            star = Some((star_p, star_t + 1));
+        } else {
+            return false;
+        }
+    }
+    pattern[p..].iter().all(|&b| b == b'*')
+}
+
+/// A registered script without its content, from
+/// [`ScriptRegistry::summaries`], with the same snake_case field names as
+/// [`ScriptEntry`]. Cheap to list even when scripts embed large bundles.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[cfg_attr(feature = "ts-bindings", derive(ts_rs::TS))]
+pub struct ScriptSummary {
+    /// Unique identifier of the script.
+    pub id: String,
+    /// Type of script (inline code or external URL).
+    pub script_type: ScriptType,
+    /// Phase the script is injected in.
+    pub phase: InjectionPhase,
+    /// When the script runs.
+    pub run_at: RunAt,
+    /// Position within its phase.
+    pub order: i32,
+    /// Whether the script is injected once per document.
+    pub run_once: bool,
+    /// Whether a URL script's source is cached in the page.
+    pub cache: bool,
+    /// URL patterns of the pages the script is injected into.
+    pub matches: Vec<String>,
+    /// Size of the script content or URL in bytes.
+    #[cfg_attr(feature = "ts-bindings", ts(type = "number"))]
+    pub content_bytes: usize,
+    /// When the script was last injected into a webview (epoch millis).
+    #[cfg_attr(feature = "ts-bindings", ts(type = "number | null"))]
+    pub last_injected_at: Option<u64>,
+}
+
+impl From<&ScriptEntry> for ScriptSummary {
+    fn from(entry: &ScriptEntry) -> Self {
+        Self {
+            id: entry.id.clone(),
+            script_type: entry.script_type.clone(),
+            phase: entry.phase,
+            run_at: entry.run_at,
+            order: entry.order,
+            run_once: entry.run_once,
+            cache: entry.cache,
+            matches: entry.matches.clone(),
+            content_bytes: entry.content.len(),
+            last_injected_at: entry.last_injected_at,
+        }
+    }
+}
+
+/// Format of a startup scripts file; see [`load_scripts_file`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ScriptsFormat {
+    Json,
+    Toml,
+}
+
+impl ScriptsFormat {
+    /// Picks the format from a file extension: TOML for `.toml`, JSON
+    /// otherwise.
+    pub fn from_path(path: &Path) -> Self {
+        match path.extension().and_then(|ext| ext.to_str()) {
+            Some(ext) if ext.eq_ignore_ascii_case("toml") => Self::Toml,
+            _ => Self::Json,
+        }
+    }
+}
+
+/// Document listing startup scripts, as `{ "scripts": [...] }` in JSON or
+/// `[[scripts]]` tables in TOML.
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+struct ScriptsFile {
+    scripts: Vec<ScriptEntry>,
+}
+
+/// Parses and validates a list of startup scripts.
+///
+/// Fails on the first entry that doesn't pass [`ScriptEntry::validate`] and
+/// on IDs used more than once.
+pub fn parse_scripts(text: &str, format: ScriptsFormat) -> Result<Vec<ScriptEntry>, RegistryError> {
+    let file: ScriptsFile = match format {
+        ScriptsFormat::Json => serde_json::from_str(text).map_err(|e| e.to_string()),
+        ScriptsFormat::Toml => toml::from_str(text).map_err(|e| e.to_string()),
+    }
+    .map_err(RegistryError::InvalidConfig)?;
+
+    validate_entries(&file.scripts)?;
+    Ok(file.scripts)
+}
+
+/// Reads startup scripts from a JSON or TOML file, picking the format from
+/// the extension; see [`parse_scripts`].
+pub fn load_scripts_file(path: &Path) -> Result<Vec<ScriptEntry>, RegistryError> {
+    let text = std::fs::read_to_string(path).map_err(|e| {
+        RegistryError::InvalidConfig(format!("cannot read {}: {e}", path.display()))
+    })?;
+    parse_scripts(&text, ScriptsFormat::from_path(path)).map_err(|e| match e {
+        RegistryError::InvalidConfig(reason) => {
+            RegistryError::InvalidConfig(format!("{}: {reason}", path.display()))
+        }
+        e => e,
+    })
+}
+
+/// Validates each entry and checks that no two share an ID once trimmed.
+pub fn validate_entries(entries: &[ScriptEntry]) -> Result<(), RegistryError> {
+    let mut ids = HashSet::new();
+    for entry in entries {
+        entry.validate()?;
+        if !ids.insert(normalize_script_id(&entry.id)?) {
+            return Err(RegistryError::InvalidEntry {
+                id: entry.id.clone(),
+                reason: "id is used more than once".to_string(),
+            });
+        }
+    }
+    Ok(())
+}
+
+/// Longest script ID accepted by [`ScriptRegistry::add`].
+pub const MAX_SCRIPT_ID_LEN: usize = 128;
+
+/// Trims a script ID and checks that it is safe to use as a registry key
+/// and to embed in injected JSON and log lines: non-empty, at most
+/// [`MAX_SCRIPT_ID_LEN`] bytes, and made of ASCII letters, digits, `-`,
+/// `_`, `.` and `:`.
+pub fn normalize_script_id(id: &str) -> Result<String, RegistryError> {
+    let invalid = |reason: String| RegistryError::InvalidId {
+        id: id.to_string(),
+        reason,
+    };
+
+    let trimmed = id.trim();
+    if trimmed.is_empty() {
+        return Err(invalid("ID is empty".to_string()));
+    }
+    if trimmed.len() > MAX_SCRIPT_ID_LEN {
+        return Err(invalid(format!(
+            "ID is longer than {MAX_SCRIPT_ID_LEN} bytes"
+        )));
+    }
+    if let Some(c) = trimmed
+        .chars()
+        .find(|c| !(c.is_ascii_alphanumeric() || matches!(c, '-' | '_' | '.' | ':')))
+    {
+        return Err(invalid(format!("ID contains '{}'", c.escape_default())));
+    }
+    Ok(trimmed.to_string())
+}
+
+/// Current schema version of [`RegistryExport`] documents.
+///
+/// Version 2 added per-window scopes; version 1 documents are still accepted.
+pub const REGISTRY_SCHEMA_VERSION: u32 = 2;
+
+/// A portable snapshot of the registry, for sharing scripts between machines.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "ts-bindings", derive(ts_rs::TS))]
+pub struct RegistryExport {
+    /// Schema version of this document.
+    pub version: u32,
+    /// All registered scripts, sorted by ID.
+    pub scripts: Vec<ScriptEntry>,
+    /// Scripts scoped to a single window, keyed by window label.
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub windows: BTreeMap<String, Vec<ScriptEntry>>,
+}
+
+impl RegistryExport {
+    /// Parses an export document, validating its schema version first so
+    /// that documents from newer versions fail with a clear error.
+    pub fn from_json(value: serde_json::Value) -> Result<Self, RegistryError> {
+        let version = value
+            .get("version")
+            .and_then(|v| v.as_u64())
+            .ok_or_else(|| RegistryError::InvalidExport("missing version".to_string()))?;
+
+        if version == 0 || version > REGISTRY_SCHEMA_VERSION as u64 {
+            return Err(RegistryError::UnsupportedVersion { version });
+        }
+
+        serde_json::from_value(value).map_err(|e| RegistryError::InvalidExport(e.to_string()))
+    }
+}
+
+/// How [`ScriptRegistry::import`] combines incoming scripts with existing ones.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "ts-bindings", derive(ts_rs::TS))]
+#[serde(rename_all = "lowercase")]
+pub enum ImportMode {
+    /// Remove all existing scripts before importing.
+    Replace,
+    /// Keep existing scripts; on conflicting IDs the incoming script wins.
+    Merge,
+}
+
+/// Script registry error types
+#[derive(Debug, thiserror::Error)]
+pub enum RegistryError {
+    #[error("Invalid script ID '{id}': {reason}")]
+    InvalidId { id: String, reason: String },
+
+    #[error("Script registry is full ({max} scripts)")]
+    LimitReached { max: usize },
+
+    #[error(
+        "Unsupported registry export version {version} (supported: {REGISTRY_SCHEMA_VERSION})"
+    )]
+    UnsupportedVersion { version: u64 },
+
+    #[error("Invalid registry export: {0}")]
+    InvalidExport(String),
+
+    #[error("Invalid script '{id}': {reason}")]
+    InvalidEntry { id: String, reason: String },
+
+    #[error("Invalid scripts config: {0}")]
+    InvalidConfig(String),
 }
 
 /// Registry for managing persistent scripts.
 ///
 /// Scripts added to this registry will be automatically re-injected
 /// when pages load or navigate.
-#[derive(Debug, Default)]
+#[derive(Debug, Clone)]
 pub struct ScriptRegistry {
     scripts: HashMap<String, ScriptEntry>,
+    max_scripts: Option<usize>,
+}
+
+/// Prefix of script IDs generated by [`ScopedScriptRegistry::add_auto`].
+pub const AUTO_ID_PREFIX: &str = "script-";
+
+/// Returns the next `script-<n>` ID that `taken` doesn't reject.
+fn next_auto_id(last_auto_id: &mut u64, taken: impl Fn(&str) -> bool) -> String {
+    loop {
+        *last_auto_id += 1;
+        let id = format!("{AUTO_ID_PREFIX}{last_auto_id}");
+        if !taken(&id) {
+            return id;
+        }
+    }
+}
+
+impl Default for ScriptRegistry {
+    fn default() -> Self {
+        Self::new()
+    }
 }
 
 impl ScriptRegistry {
@@ -42,21 +488,38 @@ impl ScriptRegistry {
     pub fn new() -> Self {
         Self {
             scripts: HashMap::new(),
+            max_scripts: None,
         }
     }
 
-    /// Adds a script entry to the registry.
+    /// Adds a script entry to the registry, stamping its `created_at` time,
+    /// and returns the ID it was stored under.
     ///
-    /// If a script with the same ID already exists, it will be replaced.
-    pub fn add(&mut self, entry: ScriptEntry) {
-        self.scripts.insert(entry.id.clone(), entry);
+    /// The ID is trimmed first; see [`normalize_script_id`]. If a script
+    /// with the same ID already exists, it will be replaced. Fails if the
+    /// ID is invalid, or the registry is at its size limit and the ID is
+    /// new.
+    pub fn add(&mut self, mut entry: ScriptEntry) -> Result<String, RegistryError> {
+        entry.id = normalize_script_id(&entry.id)?;
+
+        if let Some(max) = self.max_scripts {
+            if self.scripts.len() >= max && !self.scripts.contains_key(&entry.id) {
+                return Err(RegistryError::LimitReached { max });
+            }
+        }
+
+        entry.created_at = Some(current_timestamp());
+        let id = entry.id.clone();
+        self.scripts.insert(id.clone(), entry);
+        Ok(id)
     }
 
     /// Removes a script from the registry by ID.
     ///
-    /// Returns the removed entry if it existed.
+    /// Like every lookup by ID, the ID is trimmed first, as [`Self::add`]
+    /// does. Returns the removed entry if it existed.
     pub fn remove(&mut self, id: &str) -> Option<ScriptEntry> {
-        self.scripts.remove(id)
+        self.scripts.remove(id.trim())
     }
 
     /// Gets all scripts in the registry.
@@ -64,19 +527,50 @@ impl ScriptRegistry {
         self.scripts.values().collect()
     }
 
+    /// Summarizes every script without copying its content, ordered by ID.
+    pub fn summaries(&self) -> Vec<ScriptSummary> {
+        let mut summaries: Vec<ScriptSummary> =
+            self.scripts.values().map(ScriptSummary::from).collect();
+        summaries.sort_by(|a, b| a.id.cmp(&b.id));
+        summaries
+    }
+
     /// Clears all scripts from the registry.
     pub fn clear(&mut self) {
         self.scripts.clear();
     }
 
+    /// Keeps only the scripts for which `f` returns true, like
+    /// [`HashMap::retain`].
+    ///
+    /// Returns the number of scripts removed.
+    pub fn retain(&mut self, mut f: impl FnMut(&ScriptEntry) -> bool) -> usize {
+        let before = self.scripts.len();
+        self.scripts.retain(|_, entry| f(entry));
+        before - self.scripts.len()
+    }
+
     /// Checks if a script with the given ID exists.
     pub fn contains(&self, id: &str) -> bool {
-        self.scripts.contains_key(id)
+        self.scripts.contains_key(id.trim())
     }
 
     /// Gets a script by ID.
     pub fn get(&self, id: &str) -> Option<&ScriptEntry> {
-        self.scripts.get(id)
+        self.scripts.get(id.trim())
+    }
+
+    /// Gets several scripts by ID, in the order requested.
+    ///
+    /// Lets callers holding the shared registry lock answer a batch of
+    /// lookups without re-locking per ID.
+    pub fn get_many(&self, ids: &[String]) -> Vec<Option<ScriptEntry>> {
+        ids.iter().map(|id| self.get(id).cloned()).collect()
+    }
+
+    /// Checks if any of the given IDs is registered.
+    pub fn contains_any(&self, ids: &[String]) -> bool {
+        ids.iter().any(|id| self.contains(id))
     }
 
     /// Returns the number of scripts in the registry.
@@ -88,30 +582,550 @@ impl ScriptRegistry {
     pub fn is_empty(&self) -> bool {
         self.scripts.is_empty()
     }
+
+    /// Sets the maximum number of scripts, or `None` for no limit.
+    ///
+    /// Existing entries are kept even if they exceed a new, lower limit.
+    pub fn set_max_scripts(&mut self, max: Option<usize>) {
+        self.max_scripts = max;
+    }
+
+    /// Records that the given scripts were injected at `timestamp` (epoch millis).
+    ///
+    /// Unknown IDs are ignored, since a script may be removed while it is
+    /// being injected.
+    pub fn mark_injected<'a>(&mut self, ids: impl IntoIterator<Item = &'a str>, timestamp: u64) {
+        for id in ids {
+            if let Some(entry) = self.scripts.get_mut(id.trim()) {
+                entry.last_injected_at = Some(timestamp);
+            }
+        }
+    }
+
+    /// Exports all scripts as a versioned document, sorted by ID.
+    pub fn export(&self) -> RegistryExport {
+        let mut scripts: Vec<ScriptEntry> = self.scripts.values().cloned().collect();
+        scripts.sort_by(|a, b| a.id.cmp(&b.id));
+
+        RegistryExport {
+            version: REGISTRY_SCHEMA_VERSION,
+            scripts,
+            windows: BTreeMap::new(),
+        }
+    }
+
+    /// Imports scripts from an export document and returns how many were
+    /// imported.
+    ///
+    /// Incoming IDs are trimmed like [`Self::add`] does. In
+    /// [`ImportMode::Merge`], an incoming script replaces any existing
+    /// script with the same ID. Timestamps from the document are kept. The
+    /// registry is left unchanged if any script fails
+    /// [`validate_entries`] or the result would exceed the size limit.
+    pub fn import(
+        &mut self,
+        mut export: RegistryExport,
+        mode: ImportMode,
+    ) -> Result<usize, RegistryError> {
+        if export.version == 0 || export.version > REGISTRY_SCHEMA_VERSION {
+            return Err(RegistryError::UnsupportedVersion {
+                version: export.version as u64,
+            });
+        }
+
+        validate_entries(&export.scripts)?;
+        for entry in &mut export.scripts {
+            entry.id = normalize_script_id(&entry.id)?;
+        }
+
+        if let Some(max) = self.max_scripts {
+            let mut ids: std::collections::HashSet<&str> = match mode {
+                ImportMode::Replace => Default::default(),
+                ImportMode::Merge => self.scripts.keys().map(|id| id.as_str()).collect(),
+            };
+            ids.extend(export.scripts.iter().map(|entry| entry.id.as_str()));
+            if ids.len() > max {
+                return Err(RegistryError::LimitReached { max });
+            }
+        }
+
+        if mode == ImportMode::Replace {
+            self.scripts.clear();
+        }
+
+        let count = export.scripts.len();
+        for entry in export.scripts {
+            self.scripts.insert(entry.id.clone(), entry);
+        }
+        Ok(count)
+    }
+}
+
+/// Which windows a registered script is injected into.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[cfg_attr(feature = "ts-bindings", derive(ts_rs::TS))]
+#[serde(rename_all = "lowercase")]
+pub enum ScriptScope {
+    /// Injected into every window.
+    #[default]
+    Global,
+    /// Injected only into the window with this label.
+    Window(String),
+}
+
+/// Script registries keyed by [`ScriptScope`].
+///
+/// The global scope holds scripts injected into every window, and behaves
+/// like a single registry when no window scopes are used. A window receives
+/// the global scripts merged with its own scope; on conflicting IDs the
+/// window's script wins. The size limit applies to each scope separately.
+#[derive(Debug, Clone)]
+pub struct ScopedScriptRegistry {
+    global: ScriptRegistry,
+    windows: HashMap<String, ScriptRegistry>,
+    injection_enabled: bool,
+    max_scripts: Option<usize>,
+    /// Run-once scripts already injected into each window's current document.
+    run_once: HashMap<String, RunOnceState>,
+    /// Counter behind generated script IDs.
+    last_auto_id: u64,
+}
+
+/// The document a window last reported and the run-once scripts it has run.
+#[derive(Debug, Clone)]
+struct RunOnceState {
+    document_id: String,
+    injected: HashSet<String>,
 }
 
-/// Thread-safe wrapper for the script registry.
-pub type SharedScriptRegistry = Arc<Mutex<ScriptRegistry>>;
+impl Default for ScopedScriptRegistry {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl ScopedScriptRegistry {
+    /// Creates an empty scoped registry with injection enabled.
+    pub fn new() -> Self {
+        Self {
+            global: ScriptRegistry::new(),
+            windows: HashMap::new(),
+            injection_enabled: true,
+            max_scripts: None,
+            run_once: HashMap::new(),
+            last_auto_id: 0,
+        }
+    }
+
+    /// Adds a script entry to the given scope and returns the trimmed ID it
+    /// was stored under.
+    ///
+    /// If a script with the same ID already exists in that scope, it is replaced.
+    pub fn add(&mut self, scope: ScriptScope, entry: ScriptEntry) -> Result<String, RegistryError> {
+        self.scope_mut(scope).add(entry)
+    }
+
+    /// Adds a script entry to the given scope under a generated ID, replacing
+    /// the entry's own, and returns the ID.
+    ///
+    /// The ID is unused in every scope, since removal by ID applies to all
+    /// of them.
+    pub fn add_auto(
+        &mut self,
+        scope: ScriptScope,
+        mut entry: ScriptEntry,
+    ) -> Result<String, RegistryError> {
+        let (global, windows) = (&self.global, &self.windows);
+        entry.id = next_auto_id(&mut self.last_auto_id, |id| {
+            global.contains(id) || windows.values().any(|registry| registry.contains(id))
+        });
+        self.add(scope, entry)
+    }
+
+    /// Adds a global script under a generated ID and returns the ID.
+    ///
+    /// IDs are `script-<n>` with an increasing `n`, skipping any ID already
+    /// in use, so they never replace an existing script. See
+    /// [`Self::add_auto`] for other scopes and entry options.
+    pub fn add_auto_global(
+        &mut self,
+        script_type: ScriptType,
+        content: impl Into<String>,
+    ) -> Result<String, RegistryError> {
+        self.add_auto(
+            ScriptScope::Global,
+            ScriptEntry::new("", script_type, content),
+        )
+    }
+
+    /// Removes a script from every scope by ID.
+    ///
+    /// Returns the removed entry if it existed in any scope.
+    pub fn remove(&mut self, id: &str) -> Option<ScriptEntry> {
+        let mut removed = self.global.remove(id);
+        for registry in self.windows.values_mut() {
+            removed = registry.remove(id).or(removed);
+        }
+        removed
+    }
+
+    /// Clears all scripts from every scope.
+    pub fn clear(&mut self) {
+        self.global.clear();
+        self.windows.clear();
+    }
+
+    /// Keeps only the scripts, in any scope, for which `f` returns true.
+    ///
+    /// Returns the number of scripts removed.
+    pub fn retain(&mut self, mut f: impl FnMut(&ScriptEntry) -> bool) -> usize {
+        let mut removed = self.global.retain(&mut f);
+        for registry in self.windows.values_mut() {
+            removed += registry.retain(&mut f);
+        }
+        removed
+    }
+
+    /// Returns the number of scripts across all scopes.
+    pub fn len(&self) -> usize {
+        self.global.len() + self.windows.values().map(|r| r.len()).sum::<usize>()
+    }
+
+    /// Returns true if no scope holds any scripts.
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Gets the registry for a scope, if it has been created.
+    pub fn scope(&self, scope: &ScriptScope) -> Option<&ScriptRegistry> {
+        match scope {
+            ScriptScope::Global => Some(&self.global),
+            ScriptScope::Window(label) => self.windows.get(label),
+        }
+    }
+
+    fn scope_mut(&mut self, scope: ScriptScope) -> &mut ScriptRegistry {
+        match scope {
+            ScriptScope::Global => &mut self.global,
+            ScriptScope::Window(label) => {
+                let max_scripts = self.max_scripts;
+                self.windows.entry(label).or_insert_with(|| {
+                    let mut registry = ScriptRegistry::new();
+                    registry.set_max_scripts(max_scripts);
+                    registry
+                })
+            }
+        }
+    }
+
+    /// Gets every script together with its scope.
+    pub fn get_all(&self) -> Vec<(ScriptScope, &ScriptEntry)> {
+        let global = self
+            .global
+            .get_all()
+            .into_iter()
+            .map(|entry| (ScriptScope::Global, entry));
+        let windows = self.windows.iter().flat_map(|(label, registry)| {
+            registry
+                .get_all()
+                .into_iter()
+                .map(|entry| (ScriptScope::Window(label.clone()), entry))
+        });
+        global.chain(windows).collect()
+    }
+
+    /// Summarizes every script together with its scope: the global scope
+    /// first, then window scopes by label.
+    pub fn summaries(&self) -> Vec<(ScriptScope, ScriptSummary)> {
+        let mut labels: Vec<&String> = self.windows.keys().collect();
+        labels.sort();
+
+        let global = self
+            .global
+            .summaries()
+            .into_iter()
+            .map(|summary| (ScriptScope::Global, summary));
+        let windows = labels.into_iter().flat_map(|label| {
+            self.windows[label]
+                .summaries()
+                .into_iter()
+                .map(|summary| (ScriptScope::Window(label.clone()), summary))
+        });
+        global.chain(windows).collect()
+    }
+
+    /// Gets the script a window receives under an ID: the window's own
+    /// script, or else the global one. Without a label, only the global
+    /// scope is searched.
+    pub fn get(&self, label: Option<&str>, id: &str) -> Option<&ScriptEntry> {
+        label
+            .and_then(|label| self.windows.get(label))
+            .and_then(|registry| registry.get(id))
+            .or_else(|| self.global.get(id))
+    }
+
+    /// Gets several scripts by ID, in the order requested, each resolved
+    /// like [`Self::get`].
+    pub fn get_many(&self, label: Option<&str>, ids: &[String]) -> Vec<Option<ScriptEntry>> {
+        ids.iter().map(|id| self.get(label, id).cloned()).collect()
+    }
+
+    /// Checks if any of the given IDs resolves to a script, like
+    /// [`Self::get`].
+    pub fn contains_any(&self, label: Option<&str>, ids: &[String]) -> bool {
+        ids.iter().any(|id| self.get(label, id).is_some())
+    }
+
+    /// Gets the scripts to inject into a window: the global scope merged
+    /// with the window's own scope, in injection order.
+    ///
+    /// Scripts are ordered by [`InjectionPhase`], then by
+    /// [`ScriptEntry::order`], then by ID. When the page's `url` is given,
+    /// scripts whose [`ScriptEntry::matches`] patterns don't match it are
+    /// left out.
+    pub fn scripts_for_window(&self, label: &str, url: Option<&str>) -> Vec<ScriptEntry> {
+        let mut scripts: BTreeMap<&str, &ScriptEntry> = self
+            .global
+            .get_all()
+            .into_iter()
+            .map(|entry| (entry.id.as_str(), entry))
+            .collect();
+
+        if let Some(registry) = self.windows.get(label) {
+            scripts.extend(
+                registry
+                    .get_all()
+                    .into_iter()
+                    .map(|entry| (entry.id.as_str(), entry)),
+            );
+        }
+
+        let mut scripts: Vec<ScriptEntry> = scripts
+            .into_values()
+            .filter(|entry| url.map_or(true, |url| entry.matches_url(url)))
+            .cloned()
+            .collect();
+        // Stable, so IDs stay sorted among scripts with the same phase and order
+        scripts.sort_by_key(|entry| (entry.phase, entry.order));
+        scripts
+    }
+
+    /// Gets the scripts to inject into a window's document, like
+    /// [`Self::scripts_for_window`] but without the run-once scripts that
+    /// document has already run.
+    ///
+    /// bridge.js generates `document_id` when it starts, so it changes
+    /// exactly when the page's JavaScript context is replaced: a reload or
+    /// a navigation to a new document. Client-side navigations (history
+    /// API, `popstate`, DOM replacement) keep the context and its ID, and
+    /// with it the effects of run-once scripts. A new ID forgets what the
+    /// window ran before.
+    pub fn scripts_for_document(
+        &mut self,
+        label: &str,
+        url: Option<&str>,
+        document_id: &str,
+    ) -> Vec<ScriptEntry> {
+        let state = self
+            .run_once
+            .entry(label.to_string())
+            .or_insert_with(|| RunOnceState {
+                document_id: document_id.to_string(),
+                injected: HashSet::new(),
+            });
+        if state.document_id != document_id {
+            state.document_id = document_id.to_string();
+            state.injected.clear();
+        }
+
+        let mut scripts = self.scripts_for_window(label, url);
+        if let Some(state) = self.run_once.get(label) {
+            scripts.retain(|entry| !(entry.run_once && state.injected.contains(&entry.id)));
+        }
+        scripts
+    }
+
+    /// Records that the given scripts were injected into a window.
+    ///
+    /// Run-once scripts count as run in the window's current document, once
+    /// [`Self::scripts_for_document`] has seen one.
+    pub fn mark_injected<'a>(
+        &mut self,
+        label: &str,
+        ids: impl IntoIterator<Item = &'a str> + Clone,
+        timestamp: u64,
+    ) {
+        if let Some(state) = self.run_once.get_mut(label) {
+            let window = self.windows.get(label);
+            for id in ids.clone() {
+                let entry = window
+                    .and_then(|registry| registry.get(id))
+                    .or_else(|| self.global.get(id));
+                if let Some(entry) = entry.filter(|entry| entry.run_once) {
+                    state.injected.insert(entry.id.clone());
+                }
+            }
+        }
+
+        self.global.mark_injected(ids.clone(), timestamp);
+        if let Some(registry) = self.windows.get_mut(label) {
+            registry.mark_injected(ids, timestamp);
+        }
+    }
+
+    /// Sets the maximum number of scripts per scope, or `None` for no limit.
+    pub fn set_max_scripts(&mut self, max: Option<usize>) {
+        self.max_scripts = max;
+        self.global.set_max_scripts(max);
+        for registry in self.windows.values_mut() {
+            registry.set_max_scripts(max);
+        }
+    }
+
+    /// Pauses or resumes injection of all registered scripts in every scope.
+    pub fn set_injection_enabled(&mut self, enabled: bool) {
+        self.injection_enabled = enabled;
+    }
+
+    /// Returns true if registered scripts should be injected.
+    pub fn is_injection_enabled(&self) -> bool {
+        self.injection_enabled
+    }
+
+    /// Exports all scopes as a versioned document.
+    pub fn export(&self) -> RegistryExport {
+        let mut export = self.global.export();
+        export.windows = self
+            .windows
+            .iter()
+            .filter(|(_, registry)| !registry.is_empty())
+            .map(|(label, registry)| (label.clone(), registry.export().scripts))
+            .collect();
+        export
+    }
+
+    /// Imports scripts from an export document into their scopes and returns
+    /// how many were imported.
+    ///
+    /// [`ImportMode::Replace`] clears every scope first. The registry is left
+    /// unchanged if any scope would exceed the size limit.
+    pub fn import(
+        &mut self,
+        export: RegistryExport,
+        mode: ImportMode,
+    ) -> Result<usize, RegistryError> {
+        let version = export.version;
+        let mut updated = self.clone();
+        if mode == ImportMode::Replace {
+            updated.clear();
+        }
+
+        let mut count = updated.global.import(
+            RegistryExport {
+                version,
+                scripts: export.scripts,
+                windows: BTreeMap::new(),
+            },
+            ImportMode::Merge,
+        )?;
+
+        for (label, scripts) in export.windows {
+            count += updated.scope_mut(ScriptScope::Window(label)).import(
+                RegistryExport {
+                    version,
+                    scripts,
+                    windows: BTreeMap::new(),
+                },
+                ImportMode::Merge,
+            )?;
+        }
+
+        *self = updated;
+        Ok(count)
+    }
+}
+
+/// Thread-safe wrapper for the scoped script registry.
+pub type SharedScriptRegistry = Arc<Mutex<ScopedScriptRegistry>>;
 
 /// Creates a new shared script registry.
 pub fn create_shared_registry() -> SharedScriptRegistry {
-    Arc::new(Mutex::new(ScriptRegistry::new()))
+    Arc::new(Mutex::new(ScopedScriptRegistry::new()))
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
 
+    #[test]
+    fn test_add_auto_global() {
+        let mut scoped = ScopedScriptRegistry::new();
+        scoped
+            .add(
+                ScriptScope::Global,
+                ScriptEntry::new("script-2", ScriptType::Inline, "a()"),
+            )
+            .unwrap();
+
+        let first = scoped.add_auto_global(ScriptType::Inline, "b()").unwrap();
+        let second = scoped
+            .add_auto_global(ScriptType::Url, "https://example.com/c.js")
+            .unwrap();
+        assert_eq!((first.as_str(), second.as_str()), ("script-1", "script-3"));
+
+        let global = scoped.scope(&ScriptScope::Global).unwrap();
+        assert_eq!(global.get("script-2").unwrap().content, "a()");
+        assert_eq!(global.get("script-3").unwrap().script_type, ScriptType::Url);
+        assert_eq!(global.len(), 3);
+    }
+
+    #[test]
+    fn test_add_auto_skips_taken_ids() {
+        let mut scoped = ScopedScriptRegistry::new();
+        scoped
+            .add(
+                ScriptScope::Window("main".into()),
+                ScriptEntry::new("script-1", ScriptType::Inline, "a()"),
+            )
+            .unwrap();
+        scoped
+            .add(
+                ScriptScope::Global,
+                ScriptEntry::new("script-3", ScriptType::Inline, "c()"),
+            )
+            .unwrap();
+
+        let id = scoped
+            .add_auto(
+                ScriptScope::Global,
+                ScriptEntry::new("", ScriptType::Inline, "b()"),
+            )
+            .unwrap();
+        assert_eq!(id, "script-2");
+        let next = scoped
+            .add_auto(
+                ScriptScope::Global,
+                ScriptEntry::new("", ScriptType::Url, "https://example.com/d.js"),
+            )
+            .unwrap();
+        assert_eq!(next, "script-4");
+        assert_eq!(
+            scoped
+                .scope(&ScriptScope::Global)
+                .unwrap()
+                .get(&id)
+                .unwrap()
+                .id,
+            id
+        );
+    }
+
     #[test]
     fn test_add_and_get() {
         let mut registry = ScriptRegistry::new();
-        let entry = ScriptEntry {
-            id: "test-script".to_string(),
-            script_type: ScriptType::Inline,
-            content: "console.log('hello')".to_string(),
-        };
+        let entry = ScriptEntry::new("test-script", ScriptType::Inline, "console.log('hello')");
 
-        registry.add(entry.clone());
+        registry.add(entry.clone()).unwrap();
 
         assert!(registry.contains("test-script"));
         assert_eq!(registry.len(), 1);
@@ -121,14 +1135,40 @@ mod tests {
         assert_eq!(retrieved.script_type, ScriptType::Inline);
     }
 
+    #[test]
+    fn test_get_many_and_contains_any() {
+        let mut registry = ScriptRegistry::new();
+        registry
+            .add(ScriptEntry::new("a", ScriptType::Inline, "1"))
+            .unwrap();
+        registry
+            .add(ScriptEntry::new("c", ScriptType::Inline, "3"))
+            .unwrap();
+
+        let ids = ["c", "b", "a"].map(String::from);
+        let found: Vec<_> = registry
+            .get_many(&ids)
+            .into_iter()
+            .map(|entry| entry.map(|e| e.content))
+            .collect();
+        assert_eq!(found, [Some("3".to_string()), None, Some("1".to_string())]);
+
+        assert!(registry.contains_any(&ids));
+        assert!(!registry.contains_any(&["b".to_string(), "d".to_string()]));
+        assert!(!registry.contains_any(&[]));
+        assert!(registry.get_many(&[]).is_empty());
+    }
+
     #[test]
     fn test_remove() {
         let mut registry = ScriptRegistry::new();
-        registry.add(ScriptEntry {
-            id: "to-remove".to_string(),
-            script_type: ScriptType::Url,
-            content: "https://example.com/script.js".to_string(),
-        });
+        registry
+            .add(ScriptEntry::new(
+                "to-remove",
+                ScriptType::Url,
+                "https://example.com/script.js",
+            ))
+            .unwrap();
 
         assert!(registry.contains("to-remove"));
 
@@ -138,19 +1178,60 @@ mod tests {
         assert!(registry.is_empty());
     }
 
+    #[test]
+    fn test_lookups_trim_ids() {
+        let mut registry = ScriptRegistry::new();
+        let id = registry
+            .add(ScriptEntry::new(" padded\t", ScriptType::Inline, "1"))
+            .unwrap();
+        assert_eq!(id, "padded");
+
+        assert!(registry.contains(" padded "));
+        assert_eq!(registry.get("padded\n").unwrap().id, "padded");
+        assert!(registry.contains_any(&[" padded".to_string()]));
+        assert!(registry.get_many(&["padded ".to_string()])[0].is_some());
+
+        registry.mark_injected([" padded "], 7);
+        assert_eq!(registry.get("padded").unwrap().last_injected_at, Some(7));
+
+        assert!(registry.remove("  padded  ").is_some());
+        assert!(registry.is_empty());
+    }
+
+    #[test]
+    fn test_retain() {
+        let mut registry = ScriptRegistry::new();
+        registry
+            .add(ScriptEntry::new("inline", ScriptType::Inline, "1"))
+            .unwrap();
+        registry
+            .add(ScriptEntry::new(
+                "cdn",
+                ScriptType::Url,
+                "https://cdn.example.com/lib.js",
+            ))
+            .unwrap();
+        registry
+            .add(ScriptEntry::new("module", ScriptType::Module, "export {}"))
+            .unwrap();
+
+        let removed = registry.retain(|entry| entry.script_type == ScriptType::Inline);
+        assert_eq!(removed, 2);
+        assert_eq!(registry.len(), 1);
+        assert!(registry.contains("inline"));
+
+        assert_eq!(registry.retain(|_| true), 0);
+    }
+
     #[test]
     fn test_clear() {
         let mut registry = ScriptRegistry::new();
-        registry.add(ScriptEntry {
-            id: "script1".to_string(),
-            script_type: ScriptType::Inline,
-            content: "1".to_string(),
-        });
-        registry.add(ScriptEntry {
-            id: "script2".to_string(),
-            script_type: ScriptType::Inline,
-            content: "2".to_string(),
-        });
+        registry
+            .add(ScriptEntry::new("script1", ScriptType::Inline, "1"))
+            .unwrap();
+        registry
+            .add(ScriptEntry::new("script2", ScriptType::Inline, "2"))
+            .unwrap();
 
         assert_eq!(registry.len(), 2);
 
@@ -161,36 +1242,691 @@ mod tests {
     #[test]
     fn test_get_all() {
         let mut registry = ScriptRegistry::new();
-        registry.add(ScriptEntry {
-            id: "a".to_string(),
-            script_type: ScriptType::Inline,
-            content: "a".to_string(),
-        });
-        registry.add(ScriptEntry {
-            id: "b".to_string(),
-            script_type: ScriptType::Url,
-            content: "b".to_string(),
-        });
+        registry
+            .add(ScriptEntry::new("a", ScriptType::Inline, "a"))
+            .unwrap();
+        registry
+            .add(ScriptEntry::new("b", ScriptType::Url, "b"))
+            .unwrap();
 
         let all = registry.get_all();
         assert_eq!(all.len(), 2);
     }
 
+    #[test]
+    fn test_summaries_omit_content() {
+        let mut registry = ScopedScriptRegistry::new();
+        registry
+            .add(
+                ScriptScope::Window("main".into()),
+                ScriptEntry::new("b", ScriptType::Inline, "x".repeat(1024)),
+            )
+            .unwrap();
+        registry
+            .add(
+                ScriptScope::Global,
+                ScriptEntry::new("a", ScriptType::Url, "https://example.com/a.js"),
+            )
+            .unwrap();
+
+        let summaries = registry.summaries();
+        assert_eq!(summaries[0].0, ScriptScope::Global);
+        assert_eq!(summaries[0].1.id, "a");
+        assert_eq!(summaries[1].0, ScriptScope::Window("main".into()));
+        assert_eq!(summaries[1].1.content_bytes, 1024);
+
+        let json = serde_json::to_value(&summaries[1].1).unwrap();
+        assert!(json.get("content").is_none());
+        assert_eq!(json["script_type"], "inline");
+        assert_eq!(json["content_bytes"], 1024);
+    }
+
     #[test]
     fn test_replace_existing() {
         let mut registry = ScriptRegistry::new();
-        registry.add(ScriptEntry {
-            id: "same-id".to_string(),
-            script_type: ScriptType::Inline,
-            content: "original".to_string(),
-        });
-        registry.add(ScriptEntry {
-            id: "same-id".to_string(),
-            script_type: ScriptType::Inline,
-            content: "replaced".to_string(),
-        });
+        registry
+            .add(ScriptEntry::new("same-id", ScriptType::Inline, "original"))
+            .unwrap();
+        registry
+            .add(ScriptEntry::new("same-id", ScriptType::Inline, "replaced"))
+            .unwrap();
 
         assert_eq!(registry.len(), 1);
         assert_eq!(registry.get("same-id").unwrap().content, "replaced");
     }
+
+    #[test]
+    fn test_timestamps() {
+        let mut registry = ScriptRegistry::new();
+        let entry = ScriptEntry::new("stamped", ScriptType::Inline, "1");
+        assert!(entry.created_at.is_none());
+
+        registry.add(entry).unwrap();
+        let added = registry.get("stamped").unwrap();
+        assert!(added.created_at.is_some());
+        assert!(added.last_injected_at.is_none());
+
+        registry.mark_injected(["stamped", "missing"], 42);
+        assert_eq!(registry.get("stamped").unwrap().last_injected_at, Some(42));
+    }
+
+    #[test]
+    fn test_deserialize_entry_without_timestamps() {
+        let entry: ScriptEntry =
+            serde_json::from_str(r#"{"id":"old","script_type":"inline","content":"1"}"#).unwrap();
+        assert!(entry.created_at.is_none());
+        assert!(entry.last_injected_at.is_none());
+        assert!(!entry.cache);
+        assert_eq!(entry.phase, InjectionPhase::Main);
+    }
+
+    #[test]
+    fn test_max_scripts_limit() {
+        let mut registry = ScriptRegistry::new();
+        registry.set_max_scripts(Some(1));
+
+        registry
+            .add(ScriptEntry::new("first", ScriptType::Inline, "1"))
+            .unwrap();
+        assert!(matches!(
+            registry.add(ScriptEntry::new("second", ScriptType::Inline, "2")),
+            Err(RegistryError::LimitReached { max: 1 })
+        ));
+
+        // Replacing an existing script doesn't count against the limit
+        registry
+            .add(ScriptEntry::new("first", ScriptType::Inline, "replaced"))
+            .unwrap();
+        assert_eq!(registry.get("first").unwrap().content, "replaced");
+    }
+
+    #[test]
+    fn test_add_rejects_invalid_ids() {
+        let mut registry = ScriptRegistry::new();
+
+        for id in [
+            "",
+            "   ",
+            "has space",
+            "quote\"",
+            &"x".repeat(MAX_SCRIPT_ID_LEN + 1),
+        ] {
+            assert!(matches!(
+                registry.add(ScriptEntry::new(id, ScriptType::Inline, "1")),
+                Err(RegistryError::InvalidId { .. })
+            ));
+        }
+        assert!(registry.is_empty());
+
+        // IDs are trimmed, so padded duplicates replace the same entry
+        let id = registry
+            .add(ScriptEntry::new(" my-script ", ScriptType::Inline, "1"))
+            .unwrap();
+        assert_eq!(id, "my-script");
+        registry
+            .add(ScriptEntry::new("my-script", ScriptType::Inline, "2"))
+            .unwrap();
+        assert_eq!(registry.len(), 1);
+        assert_eq!(registry.get("my-script").unwrap().content, "2");
+    }
+
+    #[test]
+    fn test_export_import_round_trip() {
+        let mut source = ScriptRegistry::new();
+        source
+            .add(ScriptEntry::new(
+                "b",
+                ScriptType::Url,
+                "https://example.com/b.js",
+            ))
+            .unwrap();
+        source
+            .add(ScriptEntry::new("a", ScriptType::Inline, "1"))
+            .unwrap();
+
+        let json = serde_json::to_value(source.export()).unwrap();
+        assert_eq!(json["version"], REGISTRY_SCHEMA_VERSION);
+
+        let mut target = ScriptRegistry::new();
+        target
+            .add(ScriptEntry::new("stale", ScriptType::Inline, "0"))
+            .unwrap();
+        let imported = target
+            .import(
+                RegistryExport::from_json(json).unwrap(),
+                ImportMode::Replace,
+            )
+            .unwrap();
+
+        assert_eq!(imported, 2);
+        assert!(!target.contains("stale"));
+        let ids: Vec<_> = target.export().scripts.into_iter().map(|e| e.id).collect();
+        assert_eq!(ids, ["a", "b"]);
+        assert_eq!(
+            target.get("a").unwrap().created_at,
+            source.get("a").unwrap().created_at
+        );
+    }
+
+    #[test]
+    fn test_import_merge_incoming_wins() {
+        let mut registry = ScriptRegistry::new();
+        registry
+            .add(ScriptEntry::new("shared", ScriptType::Inline, "old"))
+            .unwrap();
+        registry
+            .add(ScriptEntry::new("local", ScriptType::Inline, "kept"))
+            .unwrap();
+
+        let export = RegistryExport {
+            version: REGISTRY_SCHEMA_VERSION,
+            scripts: vec![ScriptEntry::new("shared", ScriptType::Inline, "new")],
+            windows: BTreeMap::new(),
+        };
+        registry.import(export, ImportMode::Merge).unwrap();
+
+        assert_eq!(registry.len(), 2);
+        assert_eq!(registry.get("shared").unwrap().content, "new");
+        assert_eq!(registry.get("local").unwrap().content, "kept");
+    }
+
+    #[test]
+    fn test_import_rejects_unknown_versions() {
+        let future = serde_json::json!({ "version": REGISTRY_SCHEMA_VERSION + 1, "scripts": [] });
+        assert!(matches!(
+            RegistryExport::from_json(future),
+            Err(RegistryError::UnsupportedVersion { .. })
+        ));
+        assert!(matches!(
+            RegistryExport::from_json(serde_json::json!({ "scripts": [] })),
+            Err(RegistryError::InvalidExport(_))
+        ));
+    }
+
+    #[test]
+    fn test_import_respects_limit() {
+        let mut registry = ScriptRegistry::new();
+        registry.set_max_scripts(Some(1));
+        registry
+            .add(ScriptEntry::new("existing", ScriptType::Inline, "1"))
+            .unwrap();
+
+        let export = RegistryExport {
+            version: REGISTRY_SCHEMA_VERSION,
+            scripts: vec![ScriptEntry::new("incoming", ScriptType::Inline, "2")],
+            windows: BTreeMap::new(),
+        };
+        assert!(matches!(
+            registry.import(export.clone(), ImportMode::Merge),
+            Err(RegistryError::LimitReached { max: 1 })
+        ));
+        assert!(registry.contains("existing"));
+
+        registry.import(export, ImportMode::Replace).unwrap();
+        assert!(registry.contains("incoming"));
+        assert!(!registry.contains("existing"));
+    }
+
+    #[test]
+    fn test_import_normalizes_and_validates_entries() {
+        let mut registry = ScriptRegistry::new();
+        registry
+            .add(ScriptEntry::new("existing", ScriptType::Inline, "1"))
+            .unwrap();
+
+        let export = |scripts| RegistryExport {
+            version: REGISTRY_SCHEMA_VERSION,
+            scripts,
+            windows: BTreeMap::new(),
+        };
+
+        registry
+            .import(
+                export(vec![ScriptEntry::new(" a ", ScriptType::Inline, "2")]),
+                ImportMode::Merge,
+            )
+            .unwrap();
+        assert!(registry.contains("a"));
+        assert!(!registry.contains(" a "));
+
+        let invalid = [
+            vec![ScriptEntry::new("a b", ScriptType::Inline, "3")],
+            vec![ScriptEntry::new("b", ScriptType::Inline, " ")],
+            vec![ScriptEntry::new("b", ScriptType::Url, "not a url")],
+            vec![
+                ScriptEntry::new(" b", ScriptType::Inline, "3"),
+                ScriptEntry::new("b", ScriptType::Inline, "4"),
+            ],
+        ];
+        for scripts in invalid {
+            assert!(registry
+                .import(export(scripts), ImportMode::Replace)
+                .is_err());
+            assert_eq!(registry.len(), 2);
+        }
+    }
+
+    #[test]
+    fn test_module_type_and_url_detection() {
+        let entry: ScriptEntry = serde_json::from_str(
+            r#"{"id":"m","script_type":"module","content":"https://cdn.example.com/m.js"}"#,
+        )
+        .unwrap();
+        assert_eq!(entry.script_type, ScriptType::Module);
+        assert!(entry.is_module_url());
+
+        assert!(ScriptEntry::new("m", ScriptType::Module, "./lib/m.js").is_module_url());
+        assert!(!ScriptEntry::new("m", ScriptType::Module, "export const a = 1;").is_module_url());
+        assert!(!ScriptEntry::new("m", ScriptType::Module, "import x from 'y'").is_module_url());
+    }
+
+    #[test]
+    fn test_content_hash_tracks_type_and_content() {
+        let entry = ScriptEntry::new("a", ScriptType::Url, "https://example.com/a.js");
+        assert_eq!(entry.content_hash(), entry.clone().content_hash());
+        assert_eq!(entry.content_hash().len(), 16);
+
+        let renamed = ScriptEntry::new("b", ScriptType::Url, "https://example.com/a.js");
+        assert_eq!(entry.content_hash(), renamed.content_hash());
+
+        let changed = ScriptEntry::new("a", ScriptType::Url, "https://example.com/b.js");
+        assert_ne!(entry.content_hash(), changed.content_hash());
+
+        let inline = ScriptEntry::new("a", ScriptType::Inline, "https://example.com/a.js");
+        assert_ne!(entry.content_hash(), inline.content_hash());
+    }
+
+    #[test]
+    fn test_injection_toggle_keeps_entries() {
+        let mut registry = ScopedScriptRegistry::new();
+        assert!(registry.is_injection_enabled());
+
+        registry
+            .add(
+                ScriptScope::Global,
+                ScriptEntry::new("kept", ScriptType::Inline, "1"),
+            )
+            .unwrap();
+
+        registry.set_injection_enabled(false);
+        assert!(!registry.is_injection_enabled());
+        assert_eq!(registry.scripts_for_window("main", None).len(), 1);
+
+        registry.set_injection_enabled(true);
+        assert!(registry.is_injection_enabled());
+    }
+
+    #[test]
+    fn test_scoped_registry_merges_global_and_window() {
+        let mut registry = ScopedScriptRegistry::new();
+        registry
+            .add(
+                ScriptScope::Global,
+                ScriptEntry::new("shared", ScriptType::Inline, "global"),
+            )
+            .unwrap();
+        registry
+            .add(
+                ScriptScope::Global,
+                ScriptEntry::new("everywhere", ScriptType::Inline, "1"),
+            )
+            .unwrap();
+        registry
+            .add(
+                ScriptScope::Window("main".to_string()),
+                ScriptEntry::new("shared", ScriptType::Inline, "main"),
+            )
+            .unwrap();
+
+        let main = registry.scripts_for_window("main", None);
+        assert_eq!(main.len(), 2);
+        assert_eq!(
+            main.iter().find(|e| e.id == "shared").unwrap().content,
+            "main"
+        );
+
+        let other = registry.scripts_for_window("other", None);
+        assert_eq!(other.len(), 2);
+        assert_eq!(
+            other.iter().find(|e| e.id == "shared").unwrap().content,
+            "global"
+        );
+
+        assert_eq!(registry.len(), 3);
+        assert!(registry.remove("shared").is_some());
+        assert_eq!(registry.len(), 1);
+    }
+
+    #[test]
+    fn test_scoped_get_many_and_contains_any() {
+        let mut registry = ScopedScriptRegistry::new();
+        for (scope, id, content) in [
+            (ScriptScope::Global, "shared", "global"),
+            (ScriptScope::Global, "global-only", "g"),
+            (ScriptScope::Window("main".to_string()), "shared", "main"),
+            (ScriptScope::Window("main".to_string()), "main-only", "m"),
+        ] {
+            registry
+                .add(scope, ScriptEntry::new(id, ScriptType::Inline, content))
+                .unwrap();
+        }
+
+        let ids = ["main-only", "missing", "shared", "global-only"].map(String::from);
+        let contents = |label: Option<&str>| -> Vec<Option<String>> {
+            registry
+                .get_many(label, &ids)
+                .into_iter()
+                .map(|entry| entry.map(|e| e.content))
+                .collect()
+        };
+        let some = |content: &str| Some(content.to_string());
+
+        assert_eq!(
+            contents(Some("main")),
+            [some("m"), None, some("main"), some("g")]
+        );
+        assert_eq!(
+            contents(Some("other")),
+            [None, None, some("global"), some("g")]
+        );
+        assert_eq!(contents(None), [None, None, some("global"), some("g")]);
+
+        let window_only = ["main-only".to_string(), "missing".to_string()];
+        assert!(registry.contains_any(Some("main"), &window_only));
+        assert!(!registry.contains_any(Some("other"), &window_only));
+        assert!(!registry.contains_any(None, &window_only));
+        assert!(!registry.contains_any(Some("main"), &[]));
+    }
+
+    #[test]
+    fn test_scripts_for_window_orders_by_phase() {
+        let mut registry = ScopedScriptRegistry::new();
+        let scripts = [
+            ("a-teardown", InjectionPhase::Teardown, ScriptScope::Global),
+            ("b-main", InjectionPhase::Main, ScriptScope::Global),
+            ("c-setup", InjectionPhase::Setup, ScriptScope::Global),
+            (
+                "a-main",
+                InjectionPhase::Main,
+                ScriptScope::Window("main".into()),
+            ),
+            (
+                "d-setup",
+                InjectionPhase::Setup,
+                ScriptScope::Window("main".into()),
+            ),
+        ];
+        for (id, phase, scope) in scripts {
+            let mut entry = ScriptEntry::new(id, ScriptType::Inline, "1");
+            entry.phase = phase;
+            registry.add(scope, entry).unwrap();
+        }
+
+        let ids: Vec<String> = registry
+            .scripts_for_window("main", None)
+            .into_iter()
+            .map(|entry| entry.id)
+            .collect();
+        assert_eq!(
+            ids,
+            ["c-setup", "d-setup", "a-main", "b-main", "a-teardown"]
+        );
+    }
+
+    #[test]
+    fn test_scripts_for_window_orders_by_order_within_phase() {
+        let mut registry = ScopedScriptRegistry::new();
+        for (id, order) in [("a", 10), ("b", -5), ("c", 0), ("d", 0)] {
+            let mut entry = ScriptEntry::new(id, ScriptType::Inline, "1");
+            entry.order = order;
+            registry.add(ScriptScope::Global, entry).unwrap();
+        }
+        let mut setup = ScriptEntry::new("z", ScriptType::Inline, "1");
+        setup.phase = InjectionPhase::Setup;
+        setup.order = 100;
+        registry.add(ScriptScope::Global, setup).unwrap();
+
+        let ids: Vec<String> = registry
+            .scripts_for_window("main", None)
+            .into_iter()
+            .map(|entry| entry.id)
+            .collect();
+        assert_eq!(ids, ["z", "b", "c", "d", "a"]);
+
+        // Entries written before the field existed default to order 0
+        let entry: ScriptEntry = serde_json::from_value(serde_json::json!({
+            "id": "old",
+            "script_type": "inline",
+            "content": "1"
+        }))
+        .unwrap();
+        assert_eq!(entry.order, 0);
+    }
+
+    #[test]
+    fn test_only_inline_scripts_run_at_document_start() {
+        let mut inline = ScriptEntry::new("hook", ScriptType::Inline, "1");
+        assert!(!inline.runs_at_document_start());
+        inline.run_at = RunAt::DocumentStart;
+        assert!(inline.runs_at_document_start());
+
+        let mut url = ScriptEntry::new("lib", ScriptType::Url, "https://example.com/a.js");
+        url.run_at = RunAt::DocumentStart;
+        assert!(!url.runs_at_document_start());
+
+        // Entries exported before `run_at` existed
+        let entry: ScriptEntry = serde_json::from_value(serde_json::json!({
+            "id": "old", "script_type": "inline", "content": "1"
+        }))
+        .unwrap();
+        assert_eq!(entry.run_at, RunAt::DocumentEnd);
+    }
+
+    #[test]
+    fn test_run_once_scripts_skip_until_new_document() {
+        let mut registry = ScopedScriptRegistry::new();
+        let mut once = ScriptEntry::new("once", ScriptType::Inline, "init()");
+        once.run_once = true;
+        registry.add(ScriptScope::Global, once).unwrap();
+        registry
+            .add(
+                ScriptScope::Global,
+                ScriptEntry::new("always", ScriptType::Inline, "1"),
+            )
+            .unwrap();
+
+        let ids = |scripts: Vec<ScriptEntry>| -> Vec<String> {
+            scripts.into_iter().map(|entry| entry.id).collect()
+        };
+
+        assert_eq!(
+            ids(registry.scripts_for_document("main", None, "doc-1")),
+            ["always", "once"]
+        );
+        registry.mark_injected("main", ["always", "once"], 1);
+
+        // Client-side navigation: same document
+        assert_eq!(
+            ids(registry.scripts_for_document("main", None, "doc-1")),
+            ["always"]
+        );
+        // Other windows track their own documents
+        assert_eq!(
+            ids(registry.scripts_for_document("other", None, "doc-1")),
+            ["always", "once"]
+        );
+        // Reload: new document
+        assert_eq!(
+            ids(registry.scripts_for_document("main", None, "doc-2")),
+            ["always", "once"]
+        );
+    }
+
+    #[test]
+    fn test_matches_url() {
+        let mut entry = ScriptEntry::new("a", ScriptType::Inline, "1");
+        assert!(entry.matches_url("https://example.com/"));
+
+        entry.matches = vec![
+            "https://*.example.com/*".to_string(),
+            "tauri://localhost/settings".to_string(),
+        ];
+        assert!(entry.matches_url("https://app.example.com/"));
+        assert!(entry.matches_url("https://a.b.example.com/page?q=1"));
+        assert!(entry.matches_url("tauri://localhost/settings"));
+        assert!(!entry.matches_url("https://example.com/"));
+        assert!(!entry.matches_url("tauri://localhost/settings/advanced"));
+        assert!(!entry.matches_url("http://app.example.com/"));
+
+        entry.matches = vec![" ".to_string()];
+        assert!(entry.validate().is_err());
+    }
+
+    #[test]
+    fn test_scripts_for_window_filters_by_url() {
+        let mut registry = ScopedScriptRegistry::new();
+        let mut settings = ScriptEntry::new("settings", ScriptType::Inline, "1");
+        settings.matches = vec!["*/settings*".to_string()];
+        registry.add(ScriptScope::Global, settings).unwrap();
+        registry
+            .add(
+                ScriptScope::Global,
+                ScriptEntry::new("everywhere", ScriptType::Inline, "2"),
+            )
+            .unwrap();
+
+        let ids = |scripts: Vec<ScriptEntry>| -> Vec<String> {
+            scripts.into_iter().map(|entry| entry.id).collect()
+        };
+
+        assert_eq!(
+            ids(registry.scripts_for_window("main", Some("tauri://localhost/settings"))),
+            ["everywhere", "settings"]
+        );
+        assert_eq!(
+            ids(registry.scripts_for_window("main", Some("tauri://localhost/"))),
+            ["everywhere"]
+        );
+        assert_eq!(
+            ids(registry.scripts_for_document("main", Some("tauri://localhost/"), "doc-1")),
+            ["everywhere"]
+        );
+        // Without a URL, as when listing scripts, nothing is filtered
+        assert_eq!(
+            ids(registry.scripts_for_window("main", None)),
+            ["everywhere", "settings"]
+        );
+    }
+
+    #[test]
+    fn test_scoped_export_import_round_trip() {
+        let mut source = ScopedScriptRegistry::new();
+        source
+            .add(
+                ScriptScope::Global,
+                ScriptEntry::new("g", ScriptType::Inline, "1"),
+            )
+            .unwrap();
+        source
+            .add(
+                ScriptScope::Window("settings".to_string()),
+                ScriptEntry::new("w", ScriptType::Inline, "2"),
+            )
+            .unwrap();
+
+        let json = serde_json::to_value(source.export()).unwrap();
+        assert_eq!(json["windows"]["settings"][0]["id"], "w");
+
+        let mut target = ScopedScriptRegistry::new();
+        target.set_max_scripts(Some(1));
+        let imported = target
+            .import(
+                RegistryExport::from_json(json).unwrap(),
+                ImportMode::Replace,
+            )
+            .unwrap();
+
+        assert_eq!(imported, 2);
+        assert!(target.scope(&ScriptScope::Global).unwrap().contains("g"));
+        assert_eq!(target.scripts_for_window("settings", None).len(), 2);
+
+        // A failed import leaves every scope untouched
+        let too_many = RegistryExport {
+            version: 1,
+            scripts: vec![
+                ScriptEntry::new("a", ScriptType::Inline, "a"),
+                ScriptEntry::new("b", ScriptType::Inline, "b"),
+            ],
+            windows: BTreeMap::new(),
+        };
+        assert!(target.import(too_many, ImportMode::Replace).is_err());
+        assert_eq!(target.len(), 2);
+    }
+
+    #[test]
+    fn test_parse_scripts_config() {
+        let toml = r#"
+            [[scripts]]
+            id = "hook"
+            script_type = "inline"
+            content = "window.hooked = true;"
+            run_at = "documentStart"
+
+            [[scripts]]
+            id = "lib"
+            script_type = "url"
+            content = "https://example.com/lib.js"
+            phase = "setup"
+        "#;
+        let scripts = parse_scripts(toml, ScriptsFormat::Toml).unwrap();
+        assert_eq!(scripts.len(), 2);
+        assert!(scripts[0].runs_at_document_start());
+        assert_eq!(scripts[1].phase, InjectionPhase::Setup);
+
+        let json = r#"{ "scripts": [{ "id": "a", "script_type": "inline", "content": "1" }] }"#;
+        assert_eq!(parse_scripts(json, ScriptsFormat::Json).unwrap()[0].id, "a");
+        assert_eq!(
+            ScriptsFormat::from_path(Path::new("scripts.TOML")),
+            ScriptsFormat::Toml
+        );
+
+        let duplicate = r#"{ "scripts": [
+            { "id": "a", "script_type": "inline", "content": "1" },
+            { "id": "a", "script_type": "inline", "content": "2" }
+        ] }"#;
+        assert!(matches!(
+            parse_scripts(duplicate, ScriptsFormat::Json),
+            Err(RegistryError::InvalidEntry { .. })
+        ));
+
+        let duplicate_once_trimmed = r#"{ "scripts": [
+            { "id": " a", "script_type": "inline", "content": "1" },
+            { "id": "a", "script_type": "inline", "content": "2" }
+        ] }"#;
+        assert!(matches!(
+            parse_scripts(duplicate_once_trimmed, ScriptsFormat::Json),
+            Err(RegistryError::InvalidEntry { .. })
+        ));
+
+        let bad_id = r#"{ "scripts": [{ "id": "a b", "script_type": "inline", "content": "1" }] }"#;
+        assert!(matches!(
+            parse_scripts(bad_id, ScriptsFormat::Json),
+            Err(RegistryError::InvalidId { .. })
+        ));
+
+        let not_a_url =
+            r#"{ "scripts": [{ "id": "a", "script_type": "url", "content": "lib js" }] }"#;
+        assert!(matches!(
+            parse_scripts(not_a_url, ScriptsFormat::Json),
+            Err(RegistryError::InvalidEntry { .. })
+        ));
+
+        let unknown_type =
+            r#"{ "scripts": [{ "id": "a", "script_type": "css", "content": "1" }] }"#;
+        assert!(matches!(
+            parse_scripts(unknown_type, ScriptsFormat::Json),
+            Err(RegistryError::InvalidConfig(_))
+        ));
+        assert!(load_scripts_file(Path::new("/nonexistent/scripts.json")).is_err());
+    }
 }
@@ -0,0 +1,91 @@
+//! Optional `tracing` instrumentation of commands and captures.
+//!
+//! Everything here compiles to nothing unless the `tracing` feature is
+//! enabled, and the host app decides where the spans and events go by
+//! installing a subscriber.
+
+use serde_json::Value;
+use std::future::Future;
+
+/// Emits a trace-level event when the `tracing` feature is enabled.
+macro_rules! trace_step {
+    ($($arg:tt)*) => {
+        #[cfg(feature = "tracing")]
+        {
+            tracing::trace!($($arg)*);
+        }
+    };
+}
+pub(crate) use trace_step;
+
+/// An `mcp_command` span for one WebSocket command, carrying the command
+/// name and target window. [`CommandSpan::finish`] records whether the
+/// response succeeded and how long it took.
+///
+/// Work run through [`CommandSpan::instrument`] happens inside the span, so
+/// spans opened by the captures a command triggers are its children.
+pub(crate) struct CommandSpan {
+    #[cfg(feature = "tracing")]
+    span: tracing::Span,
+    #[cfg(feature = "tracing")]
+    started: std::time::Instant,
+}
+
+impl CommandSpan {
+    /// Opens the span for a command about to run.
+    pub(crate) fn start(command: &str, window: Option<&str>) -> Self {
+        #[cfg(feature = "tracing")]
+        {
+            Self {
+                span: tracing::debug_span!(
+                    "mcp_command",
+                    command,
+                    window = window.unwrap_or_default(),
+                    success = tracing::field::Empty,
+                    elapsed_ms = tracing::field::Empty,
+                ),
+                started: std::time::Instant::now(),
+            }
+        }
+
+        #[cfg(not(feature = "tracing"))]
+        {
+            let _ = (command, window);
+            Self {}
+        }
+    }
+
+    /// Runs `future` with the span entered each time it is polled.
+    pub(crate) async fn instrument<F: Future>(&self, future: F) -> F::Output {
+        #[cfg(feature = "tracing")]
+        {
+            use tracing::Instrument;
+            future.instrument(self.span.clone()).await
+        }
+
+        #[cfg(not(feature = "tracing"))]
+        {
+            future.await
+        }
+    }
+
+    /// Records the command's outcome from its response and closes the span.
+    pub(crate) fn finish(self, response: &Value) {
+        #[cfg(feature = "tracing")]
+        {
+            let success = response
+                .get("success")
+                .and_then(Value::as_bool)
+                .unwrap_or(false);
+            let elapsed_ms = self.started.elapsed().as_millis() as u64;
+            self.span.record("success", success);
+            self.span.record("elapsed_ms", elapsed_ms);
+            tracing::debug!(parent: &self.span, success, elapsed_ms, "command finished");
+        }
+
+        #[cfg(not(feature = "tracing"))]
+        {
+            let _ = response;
+        }
+    }
+}
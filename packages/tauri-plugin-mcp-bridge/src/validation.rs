@@ -0,0 +1,569 @@
+//! Upfront validation of WebSocket command arguments.
+//!
+//! Each command with typed arguments lists its fields, mirroring the
+//! command signatures in [`crate::bindings`]. A test checks the lists
+//! against the arguments the WebSocket dispatcher actually reads. Arguments are checked before
+//! the command runs, so clients learn which field is wrong instead of
+//! getting a deserialization error, or a silently applied default, from
+//! deep inside the handler. Fields that aren't listed are ignored, and
+//! `null` counts as absent for optional fields.
+
+use serde_json::Value;
+
+use crate::commands::drag::MAX_DRAG_STEPS;
+
+/// Expected JSON type of an argument.
+#[derive(Debug, Clone, Copy)]
+pub enum ArgType {
+    String,
+    Bool,
+    /// Any finite number
+    Number,
+    /// A whole number within `min..=max`
+    Integer {
+        min: i64,
+        max: i64,
+    },
+    Object,
+    StringArray,
+    /// A CSS selector, or an `{ x, y }` point
+    SelectorOrPoint,
+    /// One of the given strings
+    OneOf(&'static [&'static str]),
+}
+
+/// One argument of a command.
+#[derive(Debug, Clone, Copy)]
+pub struct ArgSpec {
+    pub name: &'static str,
+    pub ty: ArgType,
+    pub required: bool,
+}
+
+const fn required(name: &'static str, ty: ArgType) -> ArgSpec {
+    ArgSpec {
+        name,
+        ty,
+        required: true,
+    }
+}
+
+const fn optional(name: &'static str, ty: ArgType) -> ArgSpec {
+    ArgSpec {
+        name,
+        ty,
+        required: false,
+    }
+}
+
+const UINT: ArgType = ArgType::Integer {
+    min: 0,
+    max: u32::MAX as i64,
+};
+
+const SCRIPT_TYPES: &[&str] = &["inline", "url", "module"];
+const IMAGE_FORMATS: &[&str] = &["png", "jpeg", "jpg", "auto"];
+
+const REGISTER_SCRIPT_ARGS: &[ArgSpec] = &[
+    optional("id", ArgType::String),
+    required("type", ArgType::OneOf(SCRIPT_TYPES)),
+    required("content", ArgType::String),
+    optional("scope", ArgType::OneOf(&["global", "window"])),
+    optional("cache", ArgType::Bool),
+    optional("phase", ArgType::OneOf(&["setup", "main", "teardown"])),
+    optional("runOnce", ArgType::Bool),
+    optional("runAt", ArgType::OneOf(&["documentEnd", "documentStart"])),
+    optional(
+        "order",
+        ArgType::Integer {
+            min: i32::MIN as i64,
+            max: i32::MAX as i64,
+        },
+    ),
+    optional("matches", ArgType::StringArray),
+];
+
+/// The options shared by the capture commands, after any `extra` specs.
+macro_rules! capture_args {
+    ($($extra:expr),* $(,)?) => {
+        &[
+            $($extra,)*
+            optional("format", ArgType::OneOf(IMAGE_FORMATS)),
+            optional("quality", ArgType::Integer { min: 0, max: 100 }),
+            optional(
+                "pngCompression",
+                ArgType::OneOf(&["fast", "default", "best"]),
+            ),
+            optional("maxWidth", UINT),
+            optional("maxBytes", UINT),
+            optional("compressTransport", ArgType::Bool),
+            optional("forceCompression", ArgType::Bool),
+            optional("waitForPaint", ArgType::Bool),
+            optional("waitForFonts", ArgType::Bool),
+            optional("fontFamilies", ArgType::StringArray),
+            optional("fontTimeoutMs", UINT),
+            optional("captureStrategy", ArgType::OneOf(&["draw", "pixelCopy"])),
+            optional(
+                "orientation",
+                ArgType::OneOf(&["natural", "portrait", "landscape"]),
+            ),
+            optional("includeChrome", ArgType::Bool),
+            optional("hideScrollbars", ArgType::Bool),
+            optional("hideSelectors", ArgType::StringArray),
+            optional("stripMetadata", ArgType::Bool),
+            optional("viewport", ArgType::Object),
+            optional("includeElements", ArgType::Bool),
+            optional("annotateElements", ArgType::Bool),
+            optional("keepHighlights", ArgType::Bool),
+        ]
+    };
+}
+
+const CAPTURE_ARGS: &[ArgSpec] = capture_args!();
+
+const CAPTURE_WITH_THUMBNAIL_ARGS: &[ArgSpec] = capture_args!(required(
+    "thumbMaxDim",
+    ArgType::Integer {
+        min: 1,
+        max: u32::MAX as i64,
+    }
+));
+
+const CAPTURE_AT_WIDTH_ARGS: &[ArgSpec] = capture_args!(required(
+    "width",
+    ArgType::Integer {
+        min: 1,
+        max: u32::MAX as i64,
+    }
+));
+
+const REMOVE_SCRIPT_ARGS: &[ArgSpec] = &[required("id", ArgType::String)];
+
+const LIST_SCRIPTS_ARGS: &[ArgSpec] = &[optional("includeContent", ArgType::Bool)];
+
+const LOAD_SCRIPT_MANIFEST_ARGS: &[ArgSpec] = &[required("url", ArgType::String)];
+
+const INJECT_SCRIPTS_ARGS: &[ArgSpec] = &[
+    required("ids", ArgType::StringArray),
+    optional("verify", ArgType::Bool),
+];
+
+const INJECT_ONCE_ARGS: &[ArgSpec] = &[
+    required("type", ArgType::OneOf(SCRIPT_TYPES)),
+    required("content", ArgType::String),
+];
+
+const VISUAL_DIFF_ARGS: &[ArgSpec] = &[
+    required("baselinePath", ArgType::String),
+    optional("tolerance", ArgType::Integer { min: 0, max: 255 }),
+    optional("threshold", ArgType::Number),
+    optional("includeDiffImage", ArgType::Bool),
+];
+
+const DIFF_SCREENSHOTS_ARGS: &[ArgSpec] = &[
+    required("imageA", ArgType::String),
+    required("imageB", ArgType::String),
+    optional("tolerance", ArgType::Integer { min: 0, max: 255 }),
+];
+
+const HIGHLIGHT_ARGS: &[ArgSpec] = &[
+    required("selector", ArgType::String),
+    optional("color", ArgType::String),
+    optional("durationMs", UINT),
+];
+
+const HIGHLIGHT_ELEMENT_ARGS: &[ArgSpec] = &[
+    required("selector", ArgType::String),
+    optional("durationMs", UINT),
+];
+
+const EMULATE_ARGS: &[ArgSpec] = &[
+    optional("preset", ArgType::OneOf(&["iphone", "pixel", "desktop"])),
+    optional("userAgent", ArgType::String),
+    optional("devicePixelRatio", ArgType::Number),
+    optional("viewport", ArgType::Object),
+];
+
+const SET_USER_AGENT_ARGS: &[ArgSpec] = &[required("userAgent", ArgType::String)];
+
+const SET_COLOR_SCHEME_ARGS: &[ArgSpec] = &[required(
+    "scheme",
+    ArgType::OneOf(&["light", "dark", "auto"]),
+)];
+
+const DRAG_ELEMENT_ARGS: &[ArgSpec] = &[
+    required("fromSelector", ArgType::String),
+    required("to", ArgType::SelectorOrPoint),
+    optional(
+        "steps",
+        ArgType::Integer {
+            min: 1,
+            max: MAX_DRAG_STEPS as i64,
+        },
+    ),
+];
+
+const HOVER_ELEMENT_ARGS: &[ArgSpec] = &[required("selector", ArgType::String)];
+
+const FOCUS_ELEMENT_ARGS: &[ArgSpec] = &[required("selector", ArgType::String)];
+
+const SET_INPUT_FILES_ARGS: &[ArgSpec] = &[
+    required("selector", ArgType::String),
+    required("paths", ArgType::StringArray),
+];
+
+const SELECT_OPTION_ARGS: &[ArgSpec] = &[
+    required("selector", ArgType::String),
+    required("by", ArgType::Object),
+];
+
+const PRESS_KEY_ARGS: &[ArgSpec] = &[
+    required("key", ArgType::String),
+    optional("modifiers", ArgType::Object),
+    optional("selector", ArgType::String),
+];
+
+const COMPUTED_STYLE_ARGS: &[ArgSpec] = &[
+    required("selector", ArgType::String),
+    optional("properties", ArgType::StringArray),
+];
+
+const SET_ZOOM_ARGS: &[ArgSpec] = &[required("factor", ArgType::Number)];
+
+const ACCESSIBILITY_TREE_ARGS: &[ArgSpec] = &[
+    optional("selector", ArgType::String),
+    optional("maxDepth", UINT),
+    optional("maxNodes", UINT),
+];
+
+const NETWORK_IDLE_ARGS: &[ArgSpec] = &[optional("idleMs", UINT), optional("timeoutMs", UINT)];
+
+const WAIT_FOR_CONDITION_ARGS: &[ArgSpec] = &[
+    required("predicate", ArgType::String),
+    optional("timeoutMs", UINT),
+    optional("mode", ArgType::OneOf(&["poll", "observe"])),
+    optional("intervalMs", UINT),
+];
+
+const RELOAD_ARGS: &[ArgSpec] = &[
+    optional("hard", ArgType::Bool),
+    optional("waitUntil", ArgType::OneOf(&["domContentLoaded", "load"])),
+    optional("timeoutMs", UINT),
+];
+
+/// Argument specs of a command, or `None` if its arguments aren't checked.
+pub fn command_args(command: &str) -> Option<&'static [ArgSpec]> {
+    match command {
+        "register_script" => Some(REGISTER_SCRIPT_ARGS),
+        "remove_script" => Some(REMOVE_SCRIPT_ARGS),
+        "inject_once" => Some(INJECT_ONCE_ARGS),
+        "inject_scripts" => Some(INJECT_SCRIPTS_ARGS),
+        "list_scripts" => Some(LIST_SCRIPTS_ARGS),
+        "load_script_manifest" => Some(LOAD_SCRIPT_MANIFEST_ARGS),
+        "capture_native_screenshot" | "capture_screen" | "capture_all_windows" => {
+            Some(CAPTURE_ARGS)
+        }
+        "capture_with_thumbnail" => Some(CAPTURE_WITH_THUMBNAIL_ARGS),
+        "capture_at_width" => Some(CAPTURE_AT_WIDTH_ARGS),
+        "visual_diff" => Some(VISUAL_DIFF_ARGS),
+        "diff_screenshots" => Some(DIFF_SCREENSHOTS_ARGS),
+        "highlight" => Some(HIGHLIGHT_ARGS),
+        "highlight_element" => Some(HIGHLIGHT_ELEMENT_ARGS),
+        "emulate" => Some(EMULATE_ARGS),
+        "set_color_scheme" => Some(SET_COLOR_SCHEME_ARGS),
+        "set_user_agent" => Some(SET_USER_AGENT_ARGS),
+        "drag_element" => Some(DRAG_ELEMENT_ARGS),
+        "hover_element" => Some(HOVER_ELEMENT_ARGS),
+        "focus_element" => Some(FOCUS_ELEMENT_ARGS),
+        "select_option" => Some(SELECT_OPTION_ARGS),
+        "set_input_files" => Some(SET_INPUT_FILES_ARGS),
+        "get_computed_style" => Some(COMPUTED_STYLE_ARGS),
+        "press_key" => Some(PRESS_KEY_ARGS),
+        "set_zoom" => Some(SET_ZOOM_ARGS),
+        "get_accessibility_tree" => Some(ACCESSIBILITY_TREE_ARGS),
+        "wait_for_network_idle" => Some(NETWORK_IDLE_ARGS),
+        "wait_for_condition" => Some(WAIT_FOR_CONDITION_ARGS),
+        "reload" => Some(RELOAD_ARGS),
+        _ => None,
+    }
+}
+
+/// Validation error types
+#[derive(Debug, Clone, PartialEq, thiserror::Error)]
+pub enum ValidationError {
+    #[error("Invalid argument '{field}': {reason}")]
+    InvalidArgs { field: String, reason: String },
+}
+
+impl ValidationError {
+    /// Machine-readable error code for clients.
+    pub fn code(&self) -> &'static str {
+        match self {
+            ValidationError::InvalidArgs { .. } => "INVALID_ARGS",
+        }
+    }
+
+    /// Name of the offending argument.
+    pub fn field(&self) -> &str {
+        match self {
+            ValidationError::InvalidArgs { field, .. } => field,
+        }
+    }
+
+    /// Why the argument was rejected.
+    pub fn reason(&self) -> &str {
+        match self {
+            ValidationError::InvalidArgs { reason, .. } => reason,
+        }
+    }
+}
+
+fn invalid(field: &str, reason: impl Into<String>) -> ValidationError {
+    ValidationError::InvalidArgs {
+        field: field.to_string(),
+        reason: reason.into(),
+    }
+}
+
+fn check_type(name: &str, ty: ArgType, value: &Value) -> Result<(), ValidationError> {
+    let expect = |matches: bool, expected: &str| (!matches).then(|| format!("expected {expected}"));
+    let reason = match ty {
+        ArgType::String => expect(value.is_string(), "a string"),
+        ArgType::Bool => expect(value.is_boolean(), "a boolean"),
+        ArgType::Number => expect(value.as_f64().is_some_and(f64::is_finite), "a number"),
+        ArgType::Integer { min, max } => match value.as_i64() {
+            Some(n) if (min..=max).contains(&n) => None,
+            Some(n) => Some(format!("{n} is out of range, expected {min} to {max}")),
+            None => Some(format!("expected an integer from {min} to {max}")),
+        },
+        ArgType::Object => expect(value.is_object(), "an object"),
+        ArgType::StringArray => expect(
+            value
+                .as_array()
+                .is_some_and(|items| items.iter().all(Value::is_string)),
+            "an array of strings",
+        ),
+        ArgType::SelectorOrPoint => expect(
+            value.is_string()
+                || ["x", "y"]
+                    .iter()
+                    .all(|axis| value.get(axis).is_some_and(Value::is_number)),
+            "a selector or an { x, y } point",
+        ),
+        ArgType::OneOf(allowed) => match value.as_str() {
+            Some(s) if allowed.contains(&s) => None,
+            Some(s) => Some(format!("'{s}' is not one of: {}", allowed.join(", "))),
+            None => Some(format!("expected one of: {}", allowed.join(", "))),
+        },
+    };
+
+    reason.map_or(Ok(()), |reason| Err(invalid(name, reason)))
+}
+
+/// Checks a command's arguments against its specs.
+///
+/// `windowLabel` must be a string for every command. Commands without
+/// specs accept any other arguments.
+pub fn validate_args(command: &str, args: Option<&Value>) -> Result<(), ValidationError> {
+    let args = match args {
+        None | Some(Value::Null) => None,
+        Some(Value::Object(map)) => Some(map),
+        Some(_) => return Err(invalid("args", "expected an object")),
+    };
+    let get = |name: &str| {
+        args.and_then(|map| map.get(name))
+            .filter(|value| !value.is_null())
+    };
+
+    if let Some(label) = get("windowLabel") {
+        check_type("windowLabel", ArgType::String, label)?;
+    }
+
+    for spec in command_args(command).unwrap_or_default() {
+        match get(spec.name) {
+            Some(value) => check_type(spec.name, spec.ty, value)?,
+            None if spec.required => return Err(invalid(spec.name, "missing required argument")),
+            None => {}
+        }
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_reports_offending_field() {
+        let args = json!({ "type": "inline", "content": "run()", "runOnce": "yes" });
+        let error = validate_args("register_script", Some(&args)).unwrap_err();
+        assert_eq!(error.code(), "INVALID_ARGS");
+        assert_eq!(error.field(), "runOnce");
+        assert_eq!(error.reason(), "expected a boolean");
+
+        let args = json!({ "type": "Inline", "content": "run()" });
+        let error = validate_args("register_script", Some(&args)).unwrap_err();
+        assert_eq!(error.field(), "type");
+        assert_eq!(
+            error.reason(),
+            "'Inline' is not one of: inline, url, module"
+        );
+
+        let error = validate_args("register_script", Some(&json!({ "type": "url" }))).unwrap_err();
+        assert_eq!(error.field(), "content");
+
+        let args = json!({ "quality": 150 });
+        let error = validate_args("capture_native_screenshot", Some(&args)).unwrap_err();
+        assert_eq!(error.reason(), "150 is out of range, expected 0 to 100");
+    }
+
+    #[test]
+    fn test_accepts_valid_and_unchecked_args() {
+        let args = json!({
+            "type": "module",
+            "content": "https://example.com/app.js",
+            "scope": "window",
+            "windowLabel": "main",
+            "phase": null,
+        });
+        assert!(validate_args("register_script", Some(&args)).is_ok());
+        assert!(validate_args("list_windows", None).is_ok());
+        assert!(validate_args("execute_js", Some(&json!({ "script": 1 }))).is_ok());
+
+        let error = validate_args("list_windows", Some(&json!({ "windowLabel": 1 }))).unwrap_err();
+        assert_eq!(error.field(), "windowLabel");
+    }
+
+    /// Branches of the dispatch chain in `websocket.rs`'s `dispatch`, as the
+    /// commands each one handles and its source.
+    fn dispatcher_branches() -> Vec<(Vec<&'static str>, &'static str)> {
+        const SOURCE: &str = include_str!("websocket.rs");
+        const INDENT: &str = "    ";
+        let offset = |line: &str| line.as_ptr() as usize - SOURCE.as_ptr() as usize;
+
+        let mut branches = Vec::new();
+        let mut start = None;
+        for line in SOURCE.lines() {
+            if line == "    if let Err(e) = validate_args(cmd_name, command.get(\"args\")) {" {
+                start = Some(offset(line));
+                continue;
+            }
+            let Some(rest) = line.strip_prefix(INDENT) else {
+                continue;
+            };
+            let ends_chain = rest == "}";
+            if start.is_none() || !(ends_chain || rest.starts_with("} else")) {
+                continue;
+            }
+
+            if let Some(start) = start.replace(offset(line)) {
+                let source = &SOURCE[start..offset(line)];
+                let condition = source
+                    .split_once('{')
+                    .map_or("", |(condition, _)| condition);
+                branches.push((string_literals(condition, "cmd_name == "), source));
+            }
+            if ends_chain {
+                break;
+            }
+        }
+        branches
+    }
+
+    /// Every `"<literal>"` in `source` that directly follows `prefix`.
+    fn string_literals<'a>(source: &'a str, prefix: &str) -> Vec<&'a str> {
+        source
+            .split(&format!("{prefix}\""))
+            .skip(1)
+            .filter_map(|rest| rest.split_once('"'))
+            .map(|(literal, _)| literal)
+            .collect()
+    }
+
+    /// Field names a struct deserializes, read by handing it a deserializer
+    /// that records the fields it asks for.
+    fn struct_fields<T: serde::de::DeserializeOwned>() -> &'static [&'static str] {
+        struct Probe<'a>(&'a mut &'static [&'static str]);
+
+        impl<'de> serde::Deserializer<'de> for Probe<'_> {
+            type Error = serde::de::value::Error;
+
+            fn deserialize_any<V: serde::de::Visitor<'de>>(
+                self,
+                _: V,
+            ) -> Result<V::Value, Self::Error> {
+                Err(serde::de::Error::custom("not a struct"))
+            }
+
+            fn deserialize_struct<V: serde::de::Visitor<'de>>(
+                self,
+                _: &'static str,
+                fields: &'static [&'static str],
+                _: V,
+            ) -> Result<V::Value, Self::Error> {
+                *self.0 = fields;
+                Err(serde::de::Error::custom("fields recorded"))
+            }
+
+            serde::forward_to_deserialize_any! {
+                bool i8 i16 i32 i64 i128 u8 u16 u32 u64 u128 f32 f64 char str string
+                bytes byte_buf option unit unit_struct newtype_struct seq tuple
+                tuple_struct map enum identifier ignored_any
+            }
+        }
+
+        let mut fields: &'static [&'static str] = &[];
+        let _ = T::deserialize(Probe(&mut fields));
+        fields
+    }
+
+    #[test]
+    fn test_specs_match_what_the_dispatcher_reads() {
+        let branches = dispatcher_branches();
+        assert!(
+            branches.len() > 20,
+            "dispatch chain not found in websocket.rs"
+        );
+
+        for (commands, source) in branches {
+            let specs: Vec<&str> = commands
+                .iter()
+                .filter_map(|command| command_args(command))
+                .flatten()
+                .map(|spec| spec.name)
+                .collect();
+            if specs.is_empty() {
+                continue;
+            }
+
+            // Arguments read one by one, plus the fields of the options
+            // structs some handlers deserialize `args` into
+            let mut read = string_literals(source, "get(");
+            for command in &commands {
+                read.extend(match *command {
+                    "visual_diff" => struct_fields::<crate::commands::VisualDiffOptions>(),
+                    "highlight" => struct_fields::<crate::commands::HighlightOptions>(),
+                    "emulate" => struct_fields::<crate::commands::EmulationOptions>(),
+                    _ => &[],
+                });
+            }
+
+            for command in &commands {
+                for spec in command_args(command).unwrap_or_default() {
+                    assert!(
+                        source.contains(&format!("\"{}\"", spec.name)) || read.contains(&spec.name),
+                        "{command} has a spec for '{}', which its handler never reads",
+                        spec.name
+                    );
+                }
+            }
+            for name in read {
+                assert!(
+                    ["args", "windowLabel"].contains(&name) || specs.contains(&name),
+                    "{commands:?} read '{name}', which has no spec"
+                );
+            }
+        }
+    }
+}
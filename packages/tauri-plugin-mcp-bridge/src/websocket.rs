@@ -4,9 +4,17 @@
 //! between the Tauri application and external MCP clients. It broadcasts events
 //! to all connected clients and can receive commands from them.
 
-use crate::commands::{resolve_window_with_context, WindowContext};
+use crate::commands::script_injection::eval_with_limit;
+use crate::commands::{resolve_window_with_context, InjectionStrategy, WindowContext};
+use crate::error::CommandError;
 use crate::logging::{mcp_log_error, mcp_log_info};
-use crate::script_registry::{ScriptEntry, ScriptType, SharedScriptRegistry};
+use crate::monitor::current_timestamp;
+use crate::rate_limit::{RateLimitError, RateLimiter};
+use crate::script_registry::{
+    ImportMode, RegistryError, RegistryExport, RunAt, ScriptEntry, ScriptScope, ScriptType,
+    SharedScriptRegistry,
+};
+use crate::validation::validate_args;
 use futures_util::{SinkExt, StreamExt};
 use serde_json;
 use std::net::SocketAddr;
@@ -231,587 +239,2709 @@ async fn handle_connection<R: Runtime>(
                         .and_then(|v| v.as_str())
                         .unwrap_or("unknown");
 
+                    let window_label = command
+                        .get("args")
+                        .and_then(|a| a.get("windowLabel"))
+                        .and_then(|v| v.as_str());
+
+                    // Check the command against the configured permissions, then take a
+                    // rate limit token only for permitted commands
+                    let key = permission_key(cmd_name, &command);
+                    let permission = app.state::<crate::Config>().permissions.check(key);
+                    let rate_limit = if permission.is_ok() {
+                        // A missing label targets "main", and labels of windows that don't
+                        // exist share one bucket so clients can't grow the limiter's map
+                        let bucket_label = Some(window_label.unwrap_or("main"))
+                            .filter(|label| app.get_webview_window(label).is_some());
+                        app.state::<RateLimiter>().check(key, bucket_label)
+                    } else {
+                        Ok(())
+                    };
+
                     // Handle commands
-                    let response = if cmd_name == "invoke_tauri" {
-                        // Handle Tauri IPC command invocation
-                        if let Some(args) = command.get("args") {
-                            if let Some(tauri_cmd) = args.get("command").and_then(|v| v.as_str()) {
-                                // Call the actual Tauri commands
-                                use crate::commands;
-
-                                // Get optional window_label from args for window targeting
-                                let window_label = args
-                                    .get("args")
-                                    .and_then(|a| a.get("windowLabel"))
-                                    .and_then(|v| v.as_str())
-                                    .map(|s| s.to_string());
-
-                                match tauri_cmd {
-                                    "plugin:mcp-bridge|get_window_info" => {
-                                        match commands::resolve_window(&app, window_label.clone()) {
-                                            Ok(window) => {
-                                                match commands::get_window_info(window).await {
-                                                    Ok(data) => serde_json::json!({
-                                                        "id": id,
-                                                        "success": true,
-                                                        "data": data
-                                                    }),
-                                                    Err(e) => serde_json::json!({
-                                                        "id": id,
-                                                        "success": false,
-                                                        "error": e
-                                                    }),
-                                                }
-                                            }
-                                            Err(e) => serde_json::json!({
-                                                "id": id,
-                                                "success": false,
-                                                "error": e
-                                            }),
-                                        }
-                                    }
-                                    "plugin:mcp-bridge|get_backend_state" => {
-                                        match commands::get_backend_state(app.clone()).await {
-                                            Ok(data) => serde_json::json!({
-                                                "id": id,
-                                                "success": true,
-                                                "data": data
-                                            }),
-                                            Err(e) => serde_json::json!({
-                                                "id": id,
-                                                "success": false,
-                                                "error": e
-                                            }),
-                                        }
-                                    }
-                                    "plugin:mcp-bridge|start_ipc_monitor" => {
-                                        match commands::start_ipc_monitor(app.state()).await {
-                                            Ok(data) => serde_json::json!({
-                                                "id": id,
-                                                "success": true,
-                                                "data": data
-                                            }),
-                                            Err(e) => serde_json::json!({
-                                                "id": id,
-                                                "success": false,
-                                                "error": e
-                                            }),
-                                        }
-                                    }
-                                    "plugin:mcp-bridge|stop_ipc_monitor" => {
-                                        match commands::stop_ipc_monitor(app.state()).await {
-                                            Ok(data) => serde_json::json!({
-                                                "id": id,
-                                                "success": true,
-                                                "data": data
-                                            }),
-                                            Err(e) => serde_json::json!({
-                                                "id": id,
-                                                "success": false,
-                                                "error": e
-                                            }),
-                                        }
-                                    }
-                                    "plugin:mcp-bridge|get_ipc_events" => {
-                                        match commands::get_ipc_events(app.state()).await {
-                                            Ok(data) => serde_json::json!({
-                                                "id": id,
-                                                "success": true,
-                                                "data": data
-                                            }),
-                                            Err(e) => serde_json::json!({
-                                                "id": id,
-                                                "success": false,
-                                                "error": e
-                                            }),
-                                        }
-                                    }
-                                    "plugin:mcp-bridge|emit_event" => {
-                                        if let Some(event_name) = args
-                                            .get("args")
-                                            .and_then(|a| a.get("eventName"))
-                                            .and_then(|v| v.as_str())
-                                        {
-                                            let payload = args
-                                                .get("args")
-                                                .and_then(|a| a.get("payload"))
-                                                .cloned()
-                                                .unwrap_or(serde_json::json!(null));
-                                            match commands::emit_event(
-                                                app.clone(),
-                                                event_name.to_string(),
-                                                payload,
-                                            )
-                                            .await
-                                            {
-                                                Ok(data) => serde_json::json!({
-                                                    "id": id,
-                                                    "success": true,
-                                                    "data": data
-                                                }),
-                                                Err(e) => serde_json::json!({
-                                                    "id": id,
-                                                    "success": false,
-                                                    "error": e
-                                                }),
-                                            }
-                                        } else {
-                                            serde_json::json!({
-                                                "id": id,
-                                                "success": false,
-                                                "error": "Missing eventName in args"
-                                            })
-                                        }
-                                    }
-                                    _ => {
-                                        serde_json::json!({
-                                            "id": id,
-                                            "success": false,
-                                            "error": format!("Unsupported Tauri command: {}", tauri_cmd)
-                                        })
-                                    }
-                                }
-                            } else {
+                    let span = crate::trace::CommandSpan::start(cmd_name, window_label);
+                    let response = if let Err(e) = permission {
+                        serde_json::json!({
+                            "id": id,
+                            "success": false,
+                            "error": e.to_string(),
+                            "errorCode": e.code()
+                        })
+                    } else if let Err(e) = rate_limit {
+                        let RateLimitError::RateLimited { retry_after, .. } = &e;
+                        serde_json::json!({
+                            "id": id,
+                            "success": false,
+                            "error": e.to_string(),
+                            "errorCode": e.code(),
+                            "retryAfterMs": retry_after.as_millis() as u64
+                        })
+                    } else {
+                        span.instrument(dispatch(app.clone(), &command, id, cmd_name))
+                            .await
+                    };
+                    span.finish(&response);
+
+                    let _ = response_tx.send(response.to_string());
+                } else {
+                    eprintln!("Failed to parse command: {text}");
+                }
+            }
+            Ok(Message::Close(_)) => {
+                println!("Client disconnected");
+                break;
+            }
+            Err(e) => {
+                eprintln!("WebSocket error: {e}");
+                break;
+            }
+            _ => {}
+        }
+    }
+
+    send_task.abort();
+    Ok(())
+}
+
+/// Runs a command that passed the permission and rate limit checks and
+/// builds its response.
+///
+/// Called inside the command's span, so spans opened while the command runs,
+/// such as a capture's, are its children.
+async fn dispatch<R: Runtime>(
+    app: AppHandle<R>,
+    command: &serde_json::Value,
+    id: &str,
+    cmd_name: &str,
+) -> serde_json::Value {
+    if let Err(e) = validate_args(cmd_name, command.get("args")) {
+        serde_json::json!({
+            "id": id,
+            "success": false,
+            "error": e.to_string(),
+            "errorCode": e.code(),
+            "field": e.field(),
+            "reason": e.reason()
+        })
+    } else if cmd_name == "invoke_tauri" {
+        // Handle Tauri IPC command invocation
+        if let Some(args) = command.get("args") {
+            if let Some(tauri_cmd) = args.get("command").and_then(|v| v.as_str()) {
+                // Call the actual Tauri commands
+                use crate::commands;
+
+                // Get optional window_label from args for window targeting
+                let window_label = args
+                    .get("args")
+                    .and_then(|a| a.get("windowLabel"))
+                    .and_then(|v| v.as_str())
+                    .map(|s| s.to_string());
+
+                match tauri_cmd {
+                    "plugin:mcp-bridge|get_window_info" => {
+                        match commands::resolve_window(&app, window_label.clone()) {
+                            Ok(window) => match commands::get_window_info(window).await {
+                                Ok(data) => serde_json::json!({
+                                    "id": id,
+                                    "success": true,
+                                    "data": data
+                                }),
+                                Err(e) => serde_json::json!({
+                                    "id": id,
+                                    "success": false,
+                                    "error": e
+                                }),
+                            },
+                            Err(e) => serde_json::json!({
+                                "id": id,
+                                "success": false,
+                                "error": e
+                            }),
+                        }
+                    }
+                    "plugin:mcp-bridge|get_backend_state" => {
+                        match commands::get_backend_state(app.clone()).await {
+                            Ok(data) => serde_json::json!({
+                                "id": id,
+                                "success": true,
+                                "data": data
+                            }),
+                            Err(e) => serde_json::json!({
+                                "id": id,
+                                "success": false,
+                                "error": e
+                            }),
+                        }
+                    }
+                    "plugin:mcp-bridge|start_ipc_monitor" => {
+                        match commands::start_ipc_monitor(app.state()).await {
+                            Ok(data) => serde_json::json!({
+                                "id": id,
+                                "success": true,
+                                "data": data
+                            }),
+                            Err(e) => serde_json::json!({
+                                "id": id,
+                                "success": false,
+                                "error": e
+                            }),
+                        }
+                    }
+                    "plugin:mcp-bridge|stop_ipc_monitor" => {
+                        match commands::stop_ipc_monitor(app.state()).await {
+                            Ok(data) => serde_json::json!({
+                                "id": id,
+                                "success": true,
+                                "data": data
+                            }),
+                            Err(e) => serde_json::json!({
+                                "id": id,
+                                "success": false,
+                                "error": e
+                            }),
+                        }
+                    }
+                    "plugin:mcp-bridge|get_ipc_events" => {
+                        match commands::get_ipc_events(app.state()).await {
+                            Ok(data) => serde_json::json!({
+                                "id": id,
+                                "success": true,
+                                "data": data
+                            }),
+                            Err(e) => serde_json::json!({
+                                "id": id,
+                                "success": false,
+                                "error": e
+                            }),
+                        }
+                    }
+                    "plugin:mcp-bridge|emit_event" => {
+                        if let Some(event_name) = args
+                            .get("args")
+                            .and_then(|a| a.get("eventName"))
+                            .and_then(|v| v.as_str())
+                        {
+                            let payload = args
+                                .get("args")
+                                .and_then(|a| a.get("payload"))
+                                .cloned()
+                                .unwrap_or(serde_json::json!(null));
+                            match commands::emit_event(app.clone(), event_name.to_string(), payload)
+                                .await
+                            {
+                                Ok(data) => serde_json::json!({
+                                    "id": id,
+                                    "success": true,
+                                    "data": data
+                                }),
+                                Err(e) => serde_json::json!({
+                                    "id": id,
+                                    "success": false,
+                                    "error": e
+                                }),
+                            }
+                        } else {
+                            serde_json::json!({
+                                "id": id,
+                                "success": false,
+                                "error": "Missing eventName in args"
+                            })
+                        }
+                    }
+                    _ => {
+                        serde_json::json!({
+                            "id": id,
+                            "success": false,
+                            "error": format!("Unsupported Tauri command: {}", tauri_cmd)
+                        })
+                    }
+                }
+            } else {
+                serde_json::json!({
+                    "id": id,
+                    "success": false,
+                    "error": "Missing command in args"
+                })
+            }
+        } else {
+            serde_json::json!({
+                "id": id,
+                "success": false,
+                "error": "Missing args for invoke_tauri"
+            })
+        }
+    } else if cmd_name == "list_windows" {
+        // Handle window listing
+        match crate::commands::list_windows(app.clone()).await {
+            Ok(data) => serde_json::json!({
+                "id": id,
+                "success": true,
+                "data": data
+            }),
+            Err(e) => serde_json::json!({
+                "id": id,
+                "success": false,
+                "error": e
+            }),
+        }
+    } else if cmd_name == "get_window_info" {
+        // Handle window info retrieval
+        let window_id = command
+            .get("args")
+            .and_then(|a| a.get("windowId"))
+            .and_then(|v| v.as_str())
+            .map(|s| s.to_string());
+
+        match crate::commands::resolve_window(&app, window_id) {
+            Ok(window) => match crate::commands::get_window_info(window).await {
+                Ok(data) => serde_json::json!({
+                    "id": id,
+                    "success": true,
+                    "data": data
+                }),
+                Err(e) => serde_json::json!({
+                    "id": id,
+                    "success": false,
+                    "error": e
+                }),
+            },
+            Err(e) => serde_json::json!({
+                "id": id,
+                "success": false,
+                "error": e
+            }),
+        }
+    } else if cmd_name == "evaluate_script" || cmd_name == "eval_async" {
+        // Handle evaluation that awaits returned Promises
+        let args = command.get("args");
+        let script = args
+            .and_then(|a| a.get("script"))
+            .and_then(|v| v.as_str())
+            .map(|s| s.to_string());
+        let timeout = args
+            .and_then(|a| a.get("timeoutMs"))
+            .and_then(|v| v.as_u64())
+            .map(std::time::Duration::from_millis)
+            .unwrap_or(app.state::<crate::Config>().script_timeout);
+        let frame = args
+            .and_then(|a| a.get("frame"))
+            .map(|v| serde_json::from_value::<crate::commands::FrameSelector>(v.clone()));
+        let window_label = args
+            .and_then(|a| a.get("windowLabel"))
+            .and_then(|v| v.as_str())
+            .map(|s| s.to_string());
+
+        match (script, frame) {
+            (_, Some(Err(_))) => serde_json::json!({
+                "id": id,
+                "success": false,
+                "error": "Invalid frame: expected { index }, { name } or { selector }"
+            }),
+            (Some(script), frame) => {
+                match crate::commands::resolve_window_with_context(&app, window_label) {
+                    Ok(resolved) => {
+                        let result = match frame {
+                            Some(Ok(frame)) => crate::commands::evaluate_in_frame(
+                                &resolved.window,
+                                &script,
+                                &frame,
+                                timeout,
+                            )
+                            .await
+                            .map(|evaluation| (evaluation.value, Some(evaluation.frame))),
+                            _ => {
+                                crate::commands::evaluate_script(&resolved.window, &script, timeout)
+                                    .await
+                                    .map(|value| (value, None))
+                            }
+                        };
+
+                        match result {
+                            Ok((data, frame)) => serde_json::json!({
+                                "id": id,
+                                "success": true,
+                                "data": data,
+                                "frame": frame,
+                                "windowContext": resolved.context
+                            }),
+                            Err(e) => serde_json::json!({
+                                "id": id,
+                                "success": false,
+                                "error": e.to_string(),
+                                "errorCode": e.code(),
+                                "stack": e.stack(),
+                                "windowContext": resolved.context
+                            }),
+                        }
+                    }
+                    Err(e) => serde_json::json!({
+                        "id": id,
+                        "success": false,
+                        "error": e
+                    }),
+                }
+            }
+            (None, _) => serde_json::json!({
+                "id": id,
+                "success": false,
+                "error": "Missing script argument"
+            }),
+        }
+    } else if cmd_name == "execute_js" {
+        if let Some(args) = command.get("args") {
+            if let Some(script) = args.get("script").and_then(|v| v.as_str()) {
+                // Get optional window_label, defaulting to "main"
+                let window_label = args
+                    .get("windowLabel")
+                    .and_then(|v| v.as_str())
+                    .map(|s| s.to_string());
+
+                // Resolve the target window with context
+                match crate::commands::resolve_window_with_context(&app, window_label) {
+                    Ok(resolved) => {
+                        // Get the script executor state and create State wrapper
+                        let executor_state = app.state::<crate::commands::ScriptExecutor>();
+                        // Call the execute_js command with state
+                        match crate::commands::execute_js(
+                            resolved.window.clone(),
+                            script.to_string(),
+                            executor_state,
+                        )
+                        .await
+                        {
+                            Ok(result) => {
+                                serde_json::json!({
+                                    "id": id,
+                                    "success": result.get("success").and_then(|v| v.as_bool()).unwrap_or(true),
+                                    "data": result.get("data").cloned(),
+                                    "error": result.get("error").and_then(|v| v.as_str()),
+                                    "windowContext": resolved.context
+                                })
+                            }
+                            Err(e) => {
                                 serde_json::json!({
                                     "id": id,
                                     "success": false,
-                                    "error": "Missing command in args"
+                                    "error": e,
+                                    "windowContext": resolved.context
                                 })
                             }
+                        }
+                    }
+                    Err(e) => {
+                        serde_json::json!({
+                            "id": id,
+                            "success": false,
+                            "error": e
+                        })
+                    }
+                }
+            } else {
+                serde_json::json!({
+                    "id": id,
+                    "success": false,
+                    "error": "Missing script argument"
+                })
+            }
+        } else {
+            serde_json::json!({
+                "id": id,
+                "success": false,
+                "error": "Missing args"
+            })
+        }
+    } else if cmd_name == "capture_native_screenshot"
+        || cmd_name == "capture_screen"
+        || cmd_name == "capture_all_windows"
+        || cmd_name == "capture_with_thumbnail"
+        || cmd_name == "capture_at_width"
+    {
+        // Handle native screenshot capture (viewport, full screen, every
+        // window, viewport with a thumbnail, or viewport at a CSS width)
+        let args = command.get("args");
+        let format = args
+            .and_then(|a| a.get("format"))
+            .and_then(|v| v.as_str())
+            .map(|s| s.to_string());
+        let quality = args
+            .and_then(|a| a.get("quality"))
+            .and_then(|v| v.as_u64())
+            .map(|q| q as u8);
+        let max_width = args
+            .and_then(|a| a.get("maxWidth"))
+            .and_then(|v| v.as_u64())
+            .map(|w| w as u32);
+        let max_bytes = args
+            .and_then(|a| a.get("maxBytes"))
+            .and_then(|v| v.as_u64())
+            .map(|b| b as usize);
+        let compress_transport = args
+            .and_then(|a| a.get("compressTransport"))
+            .and_then(|v| v.as_bool())
+            .unwrap_or(false);
+        let force_compression = args
+            .and_then(|a| a.get("forceCompression"))
+            .and_then(|v| v.as_bool())
+            .unwrap_or(false);
+        let viewport = args
+            .and_then(|a| a.get("viewport"))
+            .and_then(|v| serde_json::from_value(v.clone()).ok());
+        let wait_for_paint = args
+            .and_then(|a| a.get("waitForPaint"))
+            .and_then(|v| v.as_bool())
+            .unwrap_or(false);
+        let wait_for_fonts = args
+            .and_then(|a| a.get("waitForFonts"))
+            .and_then(|v| v.as_bool())
+            .unwrap_or(false);
+        let font_families = args
+            .and_then(|a| a.get("fontFamilies"))
+            .and_then(|v| serde_json::from_value(v.clone()).ok())
+            .unwrap_or_default();
+        let font_timeout = args
+            .and_then(|a| a.get("fontTimeoutMs"))
+            .and_then(|v| v.as_u64())
+            .map(std::time::Duration::from_millis)
+            .unwrap_or(crate::screenshot::DEFAULT_FONT_TIMEOUT);
+        let include_elements = args
+            .and_then(|a| a.get("includeElements"))
+            .and_then(|v| v.as_bool())
+            .unwrap_or(false);
+        let annotate_elements = args
+            .and_then(|a| a.get("annotateElements"))
+            .and_then(|v| v.as_bool())
+            .unwrap_or(false);
+        let png_compression = args
+            .and_then(|a| a.get("pngCompression"))
+            .and_then(|v| serde_json::from_value(v.clone()).ok());
+        let keep_highlights = args
+            .and_then(|a| a.get("keepHighlights"))
+            .and_then(|v| v.as_bool())
+            .unwrap_or(false);
+        let include_chrome = args
+            .and_then(|a| a.get("includeChrome"))
+            .and_then(|v| v.as_bool())
+            .unwrap_or(false);
+        let hide_scrollbars = args
+            .and_then(|a| a.get("hideScrollbars"))
+            .and_then(|v| v.as_bool())
+            .unwrap_or(false);
+        let hide_selectors = args
+            .and_then(|a| a.get("hideSelectors"))
+            .and_then(|v| serde_json::from_value(v.clone()).ok())
+            .unwrap_or_default();
+        let strip_metadata = args
+            .and_then(|a| a.get("stripMetadata"))
+            .and_then(|v| v.as_bool())
+            .unwrap_or(false);
+        let strategy = args
+            .and_then(|a| a.get("captureStrategy"))
+            .and_then(|v| serde_json::from_value(v.clone()).ok())
+            .unwrap_or_default();
+        let orientation = args
+            .and_then(|a| a.get("orientation"))
+            .and_then(|v| serde_json::from_value(v.clone()).ok())
+            .unwrap_or_default();
+        let window_label = args
+            .and_then(|a| a.get("windowLabel"))
+            .and_then(|v| v.as_str())
+            .map(|s| s.to_string());
+
+        let defaults = crate::screenshot::ScreenshotOptions::default();
+        let options = crate::screenshot::ScreenshotOptions {
+            format: format
+                .as_deref()
+                .map(crate::screenshot::ImageFormat::from_name)
+                .unwrap_or(defaults.format),
+            quality: quality.unwrap_or(defaults.quality),
+            png_compression,
+            max_width,
+            max_bytes,
+            strip_metadata,
+            auto_format_threshold: app.state::<crate::Config>().auto_format_threshold,
+            compress_transport,
+            force_compression,
+            wait_for_paint,
+            wait_for_fonts,
+            font_families,
+            font_timeout,
+            viewport,
+            include_elements,
+            annotate_elements,
+            keep_highlights,
+            capture: crate::screenshot::CaptureOptions {
+                strategy,
+                orientation,
+                timeout: app.state::<crate::Config>().capture_timeout,
+                include_chrome,
+                hide_scrollbars,
+                hide_selectors,
+            },
+        };
+
+        if cmd_name == "capture_all_windows" {
+            let captures = crate::commands::capture_all_windows(&app, &options).await;
+            serde_json::json!({
+                "id": id,
+                "success": true,
+                "data": captures
+            })
+        } else if cmd_name == "capture_with_thumbnail" {
+            let thumb_max_dim = args
+                .and_then(|a| a.get("thumbMaxDim"))
+                .and_then(|v| v.as_u64())
+                .map(|d| d.min(u32::MAX as u64) as u32);
+
+            match (
+                thumb_max_dim,
+                crate::commands::resolve_window_with_context(&app, window_label),
+            ) {
+                (Some(thumb_max_dim), Ok(resolved)) => {
+                    match crate::commands::capture_with_thumbnail(
+                        &resolved.window,
+                        thumb_max_dim,
+                        &options,
+                    )
+                    .await
+                    {
+                        Ok(data) => serde_json::json!({
+                            "id": id,
+                            "success": true,
+                            "data": data,
+                            "windowContext": resolved.context
+                        }),
+                        Err(e) => error_response(id, e, Some(resolved.context)),
+                    }
+                }
+                (None, _) => serde_json::json!({
+                    "id": id,
+                    "success": false,
+                    "error": "Missing thumbMaxDim argument"
+                }),
+                (_, Err(e)) => serde_json::json!({
+                    "id": id,
+                    "success": false,
+                    "error": e
+                }),
+            }
+        } else if cmd_name == "capture_at_width" {
+            let width = args
+                .and_then(|a| a.get("width"))
+                .and_then(|v| v.as_u64())
+                .map(|w| w.min(u32::MAX as u64) as u32);
+
+            match (
+                width,
+                crate::commands::resolve_window_with_context(&app, window_label),
+            ) {
+                (Some(width), Ok(resolved)) => {
+                    match crate::commands::capture_at_width(&resolved.window, width, &options).await
+                    {
+                        Ok(data) => serde_json::json!({
+                            "id": id,
+                            "success": true,
+                            "data": data,
+                            "windowContext": resolved.context
+                        }),
+                        Err(e) => error_response(id, e, Some(resolved.context)),
+                    }
+                }
+                (None, _) => serde_json::json!({
+                    "id": id,
+                    "success": false,
+                    "error": "Missing width argument"
+                }),
+                (_, Err(e)) => serde_json::json!({
+                    "id": id,
+                    "success": false,
+                    "error": e
+                }),
+            }
+        } else {
+            // Resolve the target window with context
+            match crate::commands::resolve_window_with_context(&app, window_label) {
+                Ok(resolved) => {
+                    let capture = if cmd_name == "capture_screen" {
+                        crate::commands::take_screen_screenshot(&resolved.window, &options).await
+                    } else {
+                        crate::commands::take_viewport_screenshot(&resolved.window, &options).await
+                    };
+
+                    match capture {
+                        Ok(screenshot) => {
+                            serde_json::json!({
+                                "id": id,
+                                "success": true,
+                                "data": screenshot.data,
+                                "contentEncoding": screenshot.content_encoding,
+                                "format": screenshot.format,
+                                "source": screenshot.source,
+                                "bounds": screenshot.bounds,
+                                "elements": screenshot.elements,
+                                "cacheHit": screenshot.cache_hit,
+                                "fontsReady": screenshot.fonts_ready,
+                                "budgetFit": screenshot.budget_fit,
+                                "warning": screenshot.warning,
+                                "windowContext": resolved.context
+                            })
+                        }
+                        Err(e) => error_response(id, e, Some(resolved.context)),
+                    }
+                }
+                Err(e) => {
+                    serde_json::json!({
+                        "id": id,
+                        "success": false,
+                        "error": e
+                    })
+                }
+            }
+        }
+    } else if cmd_name == "visual_diff" {
+        // Handle comparing a fresh capture against a baseline image
+        let args = command.get("args");
+        let baseline_path = args
+            .and_then(|a| a.get("baselinePath"))
+            .and_then(|v| v.as_str())
+            .map(|s| s.to_string());
+        let options = args
+            .map(|a| serde_json::from_value(a.clone()))
+            .unwrap_or_else(|| Ok(crate::commands::VisualDiffOptions::default()));
+        let window_label = args
+            .and_then(|a| a.get("windowLabel"))
+            .and_then(|v| v.as_str())
+            .map(|s| s.to_string());
+
+        match (baseline_path, options) {
+            (Some(baseline_path), Ok(options)) => {
+                match crate::commands::resolve_window_with_context(&app, window_label) {
+                    Ok(resolved) => {
+                        match crate::commands::visual_diff(
+                            &resolved.window,
+                            &baseline_path,
+                            &options,
+                        )
+                        .await
+                        {
+                            Ok(result) => serde_json::json!({
+                                "id": id,
+                                "success": true,
+                                "data": result,
+                                "windowContext": resolved.context
+                            }),
+                            Err(e) => error_response(id, e, Some(resolved.context)),
+                        }
+                    }
+                    Err(e) => serde_json::json!({
+                        "id": id,
+                        "success": false,
+                        "error": e
+                    }),
+                }
+            }
+            (None, _) => serde_json::json!({
+                "id": id,
+                "success": false,
+                "error": "Missing baselinePath argument"
+            }),
+            (_, Err(e)) => serde_json::json!({
+                "id": id,
+                "success": false,
+                "error": format!("Invalid visual_diff options: {e}")
+            }),
+        }
+    } else if cmd_name == "diff_screenshots" {
+        // Handle comparing two captures the client already has
+        use crate::screenshot::diff;
+
+        let args = command.get("args");
+        let image = |name: &str| {
+            args.and_then(|a| a.get(name))
+                .and_then(|v| v.as_str())
+                .ok_or_else(|| format!("Missing {name} argument"))
+                .and_then(diff::screenshot_from_base64)
+        };
+        let tolerance = args
+            .and_then(|a| a.get("tolerance"))
+            .and_then(|v| v.as_u64())
+            .map_or(diff::DEFAULT_NOISE_TOLERANCE, |t| t.min(255) as u8);
+
+        let result = image("imageA").and_then(|a| {
+            let b = image("imageB")?;
+            diff::diff_screenshots(&a, &b, tolerance)
+        });
+
+        match result {
+            Ok(data) => serde_json::json!({
+                "id": id,
+                "success": true,
+                "data": data
+            }),
+            Err(e) => serde_json::json!({
+                "id": id,
+                "success": false,
+                "error": e
+            }),
+        }
+    } else if cmd_name == "get_cookies" || cmd_name == "set_cookie" {
+        // Handle cookie inspection and seeding
+        let args = command.get("args");
+        let window_label = args
+            .and_then(|a| a.get("windowLabel"))
+            .and_then(|v| v.as_str())
+            .map(|s| s.to_string());
+
+        match crate::commands::resolve_window_with_context(&app, window_label) {
+            Ok(resolved) => {
+                let result = if cmd_name == "get_cookies" {
+                    // Default to the page currently loaded in the window
+                    let url = args
+                        .and_then(|a| a.get("url"))
+                        .and_then(|v| v.as_str())
+                        .map(|s| s.to_string())
+                        .or_else(|| resolved.window.url().ok().map(|u| u.to_string()))
+                        .unwrap_or_default();
+
+                    crate::commands::get_cookies(&resolved.window, &url)
+                        .await
+                        .map(|cookies| serde_json::json!(cookies))
+                } else {
+                    match args.and_then(|a| a.get("cookie")) {
+                        Some(spec) => match crate::commands::parse_cookie_spec(spec) {
+                            Ok(cookie) => crate::commands::set_cookie(&resolved.window, cookie)
+                                .await
+                                .map(|cookie| serde_json::json!(cookie)),
+                            Err(e) => Err(e),
+                        },
+                        None => Err("Missing cookie argument".to_string()),
+                    }
+                };
+
+                match result {
+                    Ok(data) => serde_json::json!({
+                        "id": id,
+                        "success": true,
+                        "data": data,
+                        "windowContext": resolved.context
+                    }),
+                    Err(e) => serde_json::json!({
+                        "id": id,
+                        "success": false,
+                        "error": e,
+                        "windowContext": resolved.context
+                    }),
+                }
+            }
+            Err(e) => serde_json::json!({
+                "id": id,
+                "success": false,
+                "error": e
+            }),
+        }
+    } else if cmd_name == "get_performance_timing" {
+        // Handle page load timings
+        let window_label = command
+            .get("args")
+            .and_then(|a| a.get("windowLabel"))
+            .and_then(|v| v.as_str())
+            .map(|s| s.to_string());
+
+        match crate::commands::resolve_window_with_context(&app, window_label) {
+            Ok(resolved) => match crate::commands::get_performance_timing(&resolved.window).await {
+                Ok(data) => serde_json::json!({
+                    "id": id,
+                    "success": true,
+                    "data": data,
+                    "windowContext": resolved.context
+                }),
+                Err(e) => serde_json::json!({
+                    "id": id,
+                    "success": false,
+                    "error": e,
+                    "windowContext": resolved.context
+                }),
+            },
+            Err(e) => serde_json::json!({
+                "id": id,
+                "success": false,
+                "error": e
+            }),
+        }
+    } else if cmd_name == "get_viewport_metrics" {
+        // Handle viewport and layout metrics
+        let window_label = command
+            .get("args")
+            .and_then(|a| a.get("windowLabel"))
+            .and_then(|v| v.as_str())
+            .map(|s| s.to_string());
+
+        match crate::commands::resolve_window_with_context(&app, window_label) {
+            Ok(resolved) => match crate::commands::get_viewport_metrics(&resolved.window).await {
+                Ok(data) => serde_json::json!({
+                    "id": id,
+                    "success": true,
+                    "data": data,
+                    "windowContext": resolved.context
+                }),
+                Err(e) => serde_json::json!({
+                    "id": id,
+                    "success": false,
+                    "error": e,
+                    "windowContext": resolved.context
+                }),
+            },
+            Err(e) => serde_json::json!({
+                "id": id,
+                "success": false,
+                "error": e
+            }),
+        }
+    } else if cmd_name == "wait_for_network_idle" {
+        // Handle waiting for fetch/XHR activity to settle
+        let args = command.get("args");
+        let idle_ms = args
+            .and_then(|a| a.get("idleMs"))
+            .and_then(|v| v.as_u64())
+            .unwrap_or(500);
+        let timeout_ms = args
+            .and_then(|a| a.get("timeoutMs"))
+            .and_then(|v| v.as_u64())
+            .unwrap_or(10_000);
+        let window_label = args
+            .and_then(|a| a.get("windowLabel"))
+            .and_then(|v| v.as_str())
+            .map(|s| s.to_string());
+
+        match crate::commands::resolve_window_with_context(&app, window_label) {
+            Ok(resolved) => {
+                match crate::commands::wait_for_network_idle(
+                    &resolved.window,
+                    std::time::Duration::from_millis(idle_ms),
+                    std::time::Duration::from_millis(timeout_ms),
+                )
+                .await
+                {
+                    Ok(data) => serde_json::json!({
+                        "id": id,
+                        "success": true,
+                        "data": data,
+                        "windowContext": resolved.context
+                    }),
+                    Err(e) => serde_json::json!({
+                        "id": id,
+                        "success": false,
+                        "error": e,
+                        "windowContext": resolved.context
+                    }),
+                }
+            }
+            Err(e) => serde_json::json!({
+                "id": id,
+                "success": false,
+                "error": e
+            }),
+        }
+    } else if cmd_name == "wait_for_condition" {
+        // Handle waiting for a JavaScript condition to hold
+        let args = command.get("args");
+        let predicate = args
+            .and_then(|a| a.get("predicate"))
+            .and_then(|v| v.as_str())
+            .map(|s| s.to_string());
+        let timeout_ms = args
+            .and_then(|a| a.get("timeoutMs"))
+            .and_then(|v| v.as_u64())
+            .unwrap_or(10_000);
+        let wait = match args.and_then(|a| a.get("mode")).and_then(|v| v.as_str()) {
+            Some("observe") => crate::commands::ConditionWait::Observe,
+            _ => crate::commands::ConditionWait::Poll(
+                args.and_then(|a| a.get("intervalMs"))
+                    .and_then(|v| v.as_u64())
+                    .map(std::time::Duration::from_millis)
+                    .unwrap_or(crate::commands::DEFAULT_POLL_INTERVAL),
+            ),
+        };
+        let window_label = args
+            .and_then(|a| a.get("windowLabel"))
+            .and_then(|v| v.as_str())
+            .map(|s| s.to_string());
+
+        match crate::commands::resolve_window_with_context(&app, window_label) {
+            Ok(resolved) => {
+                let result = match predicate {
+                    Some(predicate) => {
+                        crate::commands::wait_for_condition(
+                            &resolved.window,
+                            &predicate,
+                            std::time::Duration::from_millis(timeout_ms),
+                            wait,
+                        )
+                        .await
+                    }
+                    None => Err("Missing predicate argument".to_string()),
+                };
+
+                match result {
+                    Ok(data) => serde_json::json!({
+                        "id": id,
+                        "success": true,
+                        "data": data,
+                        "windowContext": resolved.context
+                    }),
+                    Err(e) => serde_json::json!({
+                        "id": id,
+                        "success": false,
+                        "error": e,
+                        "windowContext": resolved.context
+                    }),
+                }
+            }
+            Err(e) => serde_json::json!({
+                "id": id,
+                "success": false,
+                "error": e
+            }),
+        }
+    } else if cmd_name == "reload" {
+        // Handle reloading the page and waiting for the new document
+        let args = command.get("args");
+        let hard = args
+            .and_then(|a| a.get("hard"))
+            .and_then(|v| v.as_bool())
+            .unwrap_or(false);
+        let wait_until = args
+            .and_then(|a| a.get("waitUntil"))
+            .and_then(|v| serde_json::from_value(v.clone()).ok())
+            .unwrap_or_default();
+        let timeout_ms = args
+            .and_then(|a| a.get("timeoutMs"))
+            .and_then(|v| v.as_u64())
+            .unwrap_or(30_000);
+        let window_label = args
+            .and_then(|a| a.get("windowLabel"))
+            .and_then(|v| v.as_str())
+            .map(|s| s.to_string());
+
+        match crate::commands::resolve_window_with_context(&app, window_label) {
+            Ok(resolved) => {
+                match crate::commands::reload(
+                    &resolved.window,
+                    hard,
+                    wait_until,
+                    std::time::Duration::from_millis(timeout_ms),
+                )
+                .await
+                {
+                    Ok(data) => serde_json::json!({
+                        "id": id,
+                        "success": true,
+                        "data": data,
+                        "windowContext": resolved.context
+                    }),
+                    Err(e) => serde_json::json!({
+                        "id": id,
+                        "success": false,
+                        "error": e,
+                        "windowContext": resolved.context
+                    }),
+                }
+            }
+            Err(e) => serde_json::json!({
+                "id": id,
+                "success": false,
+                "error": e
+            }),
+        }
+    } else if cmd_name == "emulate" || cmd_name == "reset_emulation" {
+        // Handle device emulation
+        let args = command.get("args");
+        let window_label = args
+            .and_then(|a| a.get("windowLabel"))
+            .and_then(|v| v.as_str())
+            .map(|s| s.to_string());
+
+        match crate::commands::resolve_window_with_context(&app, window_label) {
+            Ok(resolved) => {
+                let registry: tauri::State<'_, SharedScriptRegistry> = app.state();
+                let state: tauri::State<'_, crate::commands::EmulationState> = app.state();
+
+                let result = if cmd_name == "emulate" {
+                    match args
+                        .cloned()
+                        .map(serde_json::from_value::<crate::commands::EmulationOptions>)
+                        .unwrap_or_else(|| Ok(Default::default()))
+                    {
+                        Ok(options) => {
+                            crate::commands::emulate(&resolved.window, &registry, &state, &options)
+                                .await
+                                .map(|result| serde_json::json!(result))
+                        }
+                        Err(e) => Err(format!("Invalid emulate options: {e}")),
+                    }
+                } else {
+                    crate::commands::reset_emulation(&resolved.window, &registry, &state)
+                        .map(|reset| serde_json::json!({ "reset": reset }))
+                };
+
+                match result {
+                    Ok(data) => serde_json::json!({
+                        "id": id,
+                        "success": true,
+                        "data": data,
+                        "windowContext": resolved.context
+                    }),
+                    Err(e) => serde_json::json!({
+                        "id": id,
+                        "success": false,
+                        "error": e,
+                        "windowContext": resolved.context
+                    }),
+                }
+            }
+            Err(e) => serde_json::json!({
+                "id": id,
+                "success": false,
+                "error": e
+            }),
+        }
+    } else if cmd_name == "get_color_scheme" || cmd_name == "set_color_scheme" {
+        // Handle reading or forcing the light/dark color scheme
+        let args = command.get("args");
+        let scheme = args
+            .and_then(|a| a.get("scheme"))
+            .map(|v| serde_json::from_value::<crate::commands::ColorScheme>(v.clone()));
+        let window_label = args
+            .and_then(|a| a.get("windowLabel"))
+            .and_then(|v| v.as_str())
+            .map(|s| s.to_string());
+
+        match crate::commands::resolve_window_with_context(&app, window_label) {
+            Ok(resolved) => {
+                let result = if cmd_name == "get_color_scheme" {
+                    crate::commands::get_color_scheme(&resolved.window).await
+                } else {
+                    match scheme {
+                        Some(Ok(scheme)) => {
+                            let registry: tauri::State<'_, SharedScriptRegistry> = app.state();
+                            crate::commands::set_color_scheme(&resolved.window, &registry, scheme)
+                                .await
+                        }
+                        Some(Err(_)) => {
+                            Err("Invalid scheme: expected light, dark or auto".to_string())
+                        }
+                        None => Err("Missing scheme argument".to_string()),
+                    }
+                };
+
+                match result {
+                    Ok(data) => serde_json::json!({
+                        "id": id,
+                        "success": true,
+                        "data": data,
+                        "windowContext": resolved.context
+                    }),
+                    Err(e) => serde_json::json!({
+                        "id": id,
+                        "success": false,
+                        "error": e,
+                        "windowContext": resolved.context
+                    }),
+                }
+            }
+            Err(e) => serde_json::json!({
+                "id": id,
+                "success": false,
+                "error": e
+            }),
+        }
+    } else if cmd_name == "get_user_agent" || cmd_name == "set_user_agent" {
+        // Handle reading or overriding the webview's User-Agent
+        let args = command.get("args");
+        let user_agent = args
+            .and_then(|a| a.get("userAgent"))
+            .and_then(|v| v.as_str())
+            .map(|s| s.to_string());
+        let window_label = args
+            .and_then(|a| a.get("windowLabel"))
+            .and_then(|v| v.as_str())
+            .map(|s| s.to_string());
+
+        match crate::commands::resolve_window_with_context(&app, window_label) {
+            Ok(resolved) => {
+                let result = if cmd_name == "get_user_agent" {
+                    crate::commands::get_user_agent(&resolved.window)
+                        .await
+                        .map(|info| serde_json::json!(info))
+                } else {
+                    match user_agent {
+                        Some(user_agent) => {
+                            crate::commands::set_user_agent(&resolved.window, &user_agent)
+                                .await
+                                .map(|result| serde_json::json!(result))
+                        }
+                        None => Err("Missing userAgent argument".to_string()),
+                    }
+                };
+
+                match result {
+                    Ok(data) => serde_json::json!({
+                        "id": id,
+                        "success": true,
+                        "data": data,
+                        "windowContext": resolved.context
+                    }),
+                    Err(e) => serde_json::json!({
+                        "id": id,
+                        "success": false,
+                        "error": e,
+                        "windowContext": resolved.context
+                    }),
+                }
+            }
+            Err(e) => serde_json::json!({
+                "id": id,
+                "success": false,
+                "error": e
+            }),
+        }
+    } else if cmd_name == "set_zoom" {
+        // Handle zooming the webview
+        let args = command.get("args");
+        let factor = args.and_then(|a| a.get("factor")).and_then(|v| v.as_f64());
+        let window_label = args
+            .and_then(|a| a.get("windowLabel"))
+            .and_then(|v| v.as_str())
+            .map(|s| s.to_string());
+
+        match crate::commands::resolve_window_with_context(&app, window_label) {
+            Ok(resolved) => {
+                let result = match factor {
+                    Some(factor) => crate::commands::set_zoom(&resolved.window, factor).await,
+                    None => Err("Missing factor argument".to_string()),
+                };
+
+                match result {
+                    Ok(data) => serde_json::json!({
+                        "id": id,
+                        "success": true,
+                        "data": data,
+                        "windowContext": resolved.context
+                    }),
+                    Err(e) => serde_json::json!({
+                        "id": id,
+                        "success": false,
+                        "error": e,
+                        "windowContext": resolved.context
+                    }),
+                }
+            }
+            Err(e) => serde_json::json!({
+                "id": id,
+                "success": false,
+                "error": e
+            }),
+        }
+    } else if cmd_name == "hover_element" {
+        // Handle moving the pointer over an element
+        let args = command.get("args");
+        let selector = args
+            .and_then(|a| a.get("selector"))
+            .and_then(|v| v.as_str())
+            .map(|s| s.to_string());
+        let window_label = args
+            .and_then(|a| a.get("windowLabel"))
+            .and_then(|v| v.as_str())
+            .map(|s| s.to_string());
+
+        match crate::commands::resolve_window_with_context(&app, window_label) {
+            Ok(resolved) => {
+                let result = match selector {
+                    Some(selector) => {
+                        crate::commands::hover_element(&resolved.window, &selector).await
+                    }
+                    None => Err("Missing selector argument".to_string()),
+                };
+
+                match result {
+                    Ok(data) => serde_json::json!({
+                        "id": id,
+                        "success": true,
+                        "data": data,
+                        "windowContext": resolved.context
+                    }),
+                    Err(e) => serde_json::json!({
+                        "id": id,
+                        "success": false,
+                        "error": e,
+                        "windowContext": resolved.context
+                    }),
+                }
+            }
+            Err(e) => serde_json::json!({
+                "id": id,
+                "success": false,
+                "error": e
+            }),
+        }
+    } else if cmd_name == "focus_element" || cmd_name == "blur_active" {
+        // Handle moving focus to an element, or off the focused one
+        let args = command.get("args");
+        let selector = args
+            .and_then(|a| a.get("selector"))
+            .and_then(|v| v.as_str())
+            .map(|s| s.to_string());
+        let window_label = args
+            .and_then(|a| a.get("windowLabel"))
+            .and_then(|v| v.as_str())
+            .map(|s| s.to_string());
+
+        match crate::commands::resolve_window_with_context(&app, window_label) {
+            Ok(resolved) => {
+                let result = if cmd_name == "blur_active" {
+                    crate::commands::blur_active(&resolved.window).await
+                } else {
+                    match selector {
+                        Some(selector) => {
+                            crate::commands::focus_element(&resolved.window, &selector).await
+                        }
+                        None => Err("Missing selector argument".to_string()),
+                    }
+                };
+
+                match result {
+                    Ok(data) => serde_json::json!({
+                        "id": id,
+                        "success": true,
+                        "data": data,
+                        "windowContext": resolved.context
+                    }),
+                    Err(e) => serde_json::json!({
+                        "id": id,
+                        "success": false,
+                        "error": e,
+                        "windowContext": resolved.context
+                    }),
+                }
+            }
+            Err(e) => serde_json::json!({
+                "id": id,
+                "success": false,
+                "error": e
+            }),
+        }
+    } else if cmd_name == "select_option" {
+        // Handle choosing an option in a <select>
+        let args = command.get("args");
+        let selector = args
+            .and_then(|a| a.get("selector"))
+            .and_then(|v| v.as_str())
+            .map(|s| s.to_string());
+        let by = args
+            .and_then(|a| a.get("by"))
+            .map(|v| serde_json::from_value::<crate::commands::OptionMatch>(v.clone()));
+        let window_label = args
+            .and_then(|a| a.get("windowLabel"))
+            .and_then(|v| v.as_str())
+            .map(|s| s.to_string());
+
+        match crate::commands::resolve_window_with_context(&app, window_label) {
+            Ok(resolved) => {
+                let result = match (selector, by) {
+                    (Some(selector), Some(Ok(by))) => {
+                        crate::commands::select_option(&resolved.window, &selector, &by).await
+                    }
+                    (None, _) => Err("Missing selector argument".to_string()),
+                    (_, None) => Err("Missing by argument".to_string()),
+                    (_, Some(Err(_))) => Err(
+                        "Invalid by argument: expected { value }, { label } or { index }"
+                            .to_string(),
+                    ),
+                };
+
+                match result {
+                    Ok(data) => serde_json::json!({
+                        "id": id,
+                        "success": true,
+                        "data": data,
+                        "windowContext": resolved.context
+                    }),
+                    Err(e) => serde_json::json!({
+                        "id": id,
+                        "success": false,
+                        "error": e,
+                        "windowContext": resolved.context
+                    }),
+                }
+            }
+            Err(e) => serde_json::json!({
+                "id": id,
+                "success": false,
+                "error": e
+            }),
+        }
+    } else if cmd_name == "set_input_files" {
+        // Handle attaching files from disk to a file input
+        let args = command.get("args");
+        let selector = args
+            .and_then(|a| a.get("selector"))
+            .and_then(|v| v.as_str())
+            .map(|s| s.to_string());
+        let paths = args
+            .and_then(|a| a.get("paths"))
+            .and_then(|v| v.as_array())
+            .map(|paths| {
+                paths
+                    .iter()
+                    .filter_map(|p| p.as_str())
+                    .map(std::path::PathBuf::from)
+                    .collect::<Vec<_>>()
+            });
+        let window_label = args
+            .and_then(|a| a.get("windowLabel"))
+            .and_then(|v| v.as_str())
+            .map(|s| s.to_string());
+
+        match crate::commands::resolve_window_with_context(&app, window_label) {
+            Ok(resolved) => {
+                let result = match (selector, paths) {
+                    (Some(selector), Some(paths)) => {
+                        crate::commands::set_input_files(&resolved.window, &selector, &paths).await
+                    }
+                    (None, _) => Err("Missing selector argument".to_string()),
+                    (_, None) => Err("Missing paths argument".to_string()),
+                };
+
+                match result {
+                    Ok(data) => serde_json::json!({
+                        "id": id,
+                        "success": true,
+                        "data": data,
+                        "windowContext": resolved.context
+                    }),
+                    Err(e) => serde_json::json!({
+                        "id": id,
+                        "success": false,
+                        "error": e,
+                        "windowContext": resolved.context
+                    }),
+                }
+            }
+            Err(e) => serde_json::json!({
+                "id": id,
+                "success": false,
+                "error": e
+            }),
+        }
+    } else if cmd_name == "press_key" {
+        // Handle dispatching a key press with modifiers
+        let args = command.get("args");
+        let key = args
+            .and_then(|a| a.get("key"))
+            .and_then(|v| v.as_str())
+            .map(|s| s.to_string());
+        let modifiers = args
+            .and_then(|a| a.get("modifiers"))
+            .cloned()
+            .map(serde_json::from_value::<crate::commands::KeyModifiers>)
+            .unwrap_or_else(|| Ok(Default::default()))
+            .map_err(|e| format!("Invalid modifiers: {e}"));
+        let selector = args
+            .and_then(|a| a.get("selector"))
+            .and_then(|v| v.as_str())
+            .map(|s| s.to_string());
+        let window_label = args
+            .and_then(|a| a.get("windowLabel"))
+            .and_then(|v| v.as_str())
+            .map(|s| s.to_string());
+
+        match crate::commands::resolve_window_with_context(&app, window_label) {
+            Ok(resolved) => {
+                let result = match (key, modifiers) {
+                    (Some(key), Ok(modifiers)) => {
+                        crate::commands::press_key(
+                            &resolved.window,
+                            &key,
+                            &modifiers,
+                            selector.as_deref(),
+                        )
+                        .await
+                    }
+                    (None, _) => Err("Missing key argument".to_string()),
+                    (_, Err(e)) => Err(e),
+                };
+
+                match result {
+                    Ok(data) => serde_json::json!({
+                        "id": id,
+                        "success": true,
+                        "data": data,
+                        "windowContext": resolved.context
+                    }),
+                    Err(e) => serde_json::json!({
+                        "id": id,
+                        "success": false,
+                        "error": e,
+                        "windowContext": resolved.context
+                    }),
+                }
+            }
+            Err(e) => serde_json::json!({
+                "id": id,
+                "success": false,
+                "error": e
+            }),
+        }
+    } else if cmd_name == "drag_element" {
+        // Handle dragging an element onto another element or a point
+        let args = command.get("args");
+        let from_selector = args
+            .and_then(|a| a.get("fromSelector"))
+            .and_then(|v| v.as_str())
+            .map(|s| s.to_string());
+        let to = args
+            .and_then(|a| a.get("to"))
+            .cloned()
+            .map(serde_json::from_value::<crate::commands::DropTarget>);
+        let steps = args
+            .and_then(|a| a.get("steps"))
+            .and_then(|v| v.as_u64())
+            .map(|v| v as u32)
+            .unwrap_or(crate::commands::DEFAULT_DRAG_STEPS);
+        let window_label = args
+            .and_then(|a| a.get("windowLabel"))
+            .and_then(|v| v.as_str())
+            .map(|s| s.to_string());
+
+        match crate::commands::resolve_window_with_context(&app, window_label) {
+            Ok(resolved) => {
+                let result = match (from_selector, to) {
+                    (Some(from_selector), Some(Ok(to))) => {
+                        crate::commands::drag_element(&resolved.window, &from_selector, &to, steps)
+                            .await
+                    }
+                    (None, _) => Err("Missing fromSelector argument".to_string()),
+                    (_, None) => Err("Missing to argument".to_string()),
+                    (_, Some(Err(e))) => Err(format!("Invalid drop target: {e}")),
+                };
+
+                match result {
+                    Ok(data) => serde_json::json!({
+                        "id": id,
+                        "success": true,
+                        "data": data,
+                        "windowContext": resolved.context
+                    }),
+                    Err(e) => serde_json::json!({
+                        "id": id,
+                        "success": false,
+                        "error": e,
+                        "windowContext": resolved.context
+                    }),
+                }
+            }
+            Err(e) => serde_json::json!({
+                "id": id,
+                "success": false,
+                "error": e
+            }),
+        }
+    } else if cmd_name == "start_network_log"
+        || cmd_name == "stop_network_log"
+        || cmd_name == "get_network_log"
+    {
+        // Handle page network logging
+        let args = command.get("args");
+        let window_label = args
+            .and_then(|a| a.get("windowLabel"))
+            .and_then(|v| v.as_str())
+            .map(|s| s.to_string());
+
+        match crate::commands::resolve_window_with_context(&app, window_label) {
+            Ok(resolved) => {
+                let registry: tauri::State<'_, SharedScriptRegistry> = app.state();
+                let log: tauri::State<'_, crate::network_log::NetworkLogState> = app.state();
+
+                let result = match cmd_name {
+                    "start_network_log" => {
+                        let capture_bodies = args
+                            .and_then(|a| a.get("captureBodies"))
+                            .and_then(|v| v.as_bool())
+                            .unwrap_or(false);
+                        crate::commands::start_network_log(
+                            &resolved.window,
+                            &registry,
+                            &log,
+                            capture_bodies,
+                        )
+                        .map(|_| serde_json::json!({ "recording": true }))
+                    }
+                    "stop_network_log" => {
+                        crate::commands::stop_network_log(&resolved.window, &registry)
+                            .map(|stopped| serde_json::json!({ "stopped": stopped }))
+                    }
+                    _ => args
+                        .and_then(|a| a.get("filter"))
+                        .cloned()
+                        .map(serde_json::from_value::<crate::network_log::NetworkLogFilter>)
+                        .unwrap_or_else(|| Ok(Default::default()))
+                        .map_err(|e| format!("Invalid network log filter: {e}"))
+                        .map(|filter| {
+                            let limit = args
+                                .and_then(|a| a.get("limit"))
+                                .and_then(|v| v.as_u64())
+                                .map(|n| n as usize);
+                            serde_json::json!(crate::commands::get_network_log(
+                                &log,
+                                &resolved.context.window_label,
+                                &filter,
+                                limit,
+                            ))
+                        }),
+                };
+
+                match result {
+                    Ok(data) => serde_json::json!({
+                        "id": id,
+                        "success": true,
+                        "data": data,
+                        "windowContext": resolved.context
+                    }),
+                    Err(e) => serde_json::json!({
+                        "id": id,
+                        "success": false,
+                        "error": e,
+                        "windowContext": resolved.context
+                    }),
+                }
+            }
+            Err(e) => serde_json::json!({
+                "id": id,
+                "success": false,
+                "error": e
+            }),
+        }
+    } else if cmd_name == "get_interactive_elements" {
+        // Handle listing interactive elements without drawing overlays
+        let window_label = command
+            .get("args")
+            .and_then(|a| a.get("windowLabel"))
+            .and_then(|v| v.as_str())
+            .map(|s| s.to_string());
+
+        match crate::commands::resolve_window_with_context(&app, window_label) {
+            Ok(resolved) => {
+                match crate::commands::get_interactive_elements(&resolved.window).await {
+                    Ok(result) => serde_json::json!({
+                        "id": id,
+                        "success": true,
+                        "data": result,
+                        "windowContext": resolved.context
+                    }),
+                    Err(e) => serde_json::json!({
+                        "id": id,
+                        "success": false,
+                        "error": e,
+                        "windowContext": resolved.context
+                    }),
+                }
+            }
+            Err(e) => serde_json::json!({
+                "id": id,
+                "success": false,
+                "error": e
+            }),
+        }
+    } else if cmd_name == "get_accessibility_tree" {
+        // Handle building the semantic tree of a page or element
+        let args = command.get("args");
+        let selector = args
+            .and_then(|a| a.get("selector"))
+            .and_then(|v| v.as_str())
+            .map(|s| s.to_string());
+        let max_depth = args
+            .and_then(|a| a.get("maxDepth"))
+            .and_then(|v| v.as_u64())
+            .map(|n| n.min(u32::MAX as u64) as u32)
+            .unwrap_or(crate::commands::accessibility::DEFAULT_A11Y_MAX_DEPTH);
+        let max_nodes = args
+            .and_then(|a| a.get("maxNodes"))
+            .and_then(|v| v.as_u64())
+            .map(|n| n.min(u32::MAX as u64) as u32)
+            .unwrap_or(crate::commands::accessibility::DEFAULT_A11Y_MAX_NODES);
+        let window_label = args
+            .and_then(|a| a.get("windowLabel"))
+            .and_then(|v| v.as_str())
+            .map(|s| s.to_string());
+
+        match crate::commands::resolve_window_with_context(&app, window_label) {
+            Ok(resolved) => {
+                match crate::commands::get_accessibility_tree(
+                    &resolved.window,
+                    selector.as_deref(),
+                    max_depth,
+                    max_nodes,
+                )
+                .await
+                {
+                    Ok(result) => serde_json::json!({
+                        "id": id,
+                        "success": true,
+                        "data": result,
+                        "windowContext": resolved.context
+                    }),
+                    Err(e) => serde_json::json!({
+                        "id": id,
+                        "success": false,
+                        "error": e,
+                        "windowContext": resolved.context
+                    }),
+                }
+            }
+            Err(e) => serde_json::json!({
+                "id": id,
+                "success": false,
+                "error": e
+            }),
+        }
+    } else if cmd_name == "get_computed_style" {
+        // Handle reading computed CSS of the first element matching a selector
+        let args = command.get("args");
+        let selector = args
+            .and_then(|a| a.get("selector"))
+            .and_then(|v| v.as_str())
+            .map(|s| s.to_string());
+        let properties: Vec<String> = args
+            .and_then(|a| a.get("properties"))
+            .and_then(|v| serde_json::from_value(v.clone()).ok())
+            .unwrap_or_default();
+        let window_label = args
+            .and_then(|a| a.get("windowLabel"))
+            .and_then(|v| v.as_str())
+            .map(|s| s.to_string());
+
+        match crate::commands::resolve_window_with_context(&app, window_label) {
+            Ok(resolved) => {
+                let result = match selector {
+                    Some(selector) => {
+                        crate::commands::get_computed_style(
+                            &resolved.window,
+                            &selector,
+                            &properties,
+                        )
+                        .await
+                    }
+                    None => Err("Missing selector argument".to_string().into()),
+                };
+
+                match result {
+                    Ok(data) => serde_json::json!({
+                        "id": id,
+                        "success": true,
+                        "data": data,
+                        "windowContext": resolved.context
+                    }),
+                    Err(e) => error_response(id, e, Some(resolved.context)),
+                }
+            }
+            Err(e) => serde_json::json!({
+                "id": id,
+                "success": false,
+                "error": e
+            }),
+        }
+    } else if cmd_name == "get_bounding_rects" {
+        // Handle geometry lookup for every element matching a selector
+        let args = command.get("args");
+        let selector = args
+            .and_then(|a| a.get("selector"))
+            .and_then(|v| v.as_str())
+            .map(|s| s.to_string());
+        let max_nodes = args
+            .and_then(|a| a.get("maxNodes"))
+            .and_then(|v| v.as_u64())
+            .map(|n| n.min(u32::MAX as u64) as u32)
+            .unwrap_or(crate::commands::bounding_rects::DEFAULT_MAX_NODES);
+        let window_label = args
+            .and_then(|a| a.get("windowLabel"))
+            .and_then(|v| v.as_str())
+            .map(|s| s.to_string());
+
+        match selector {
+            Some(selector) => {
+                match crate::commands::resolve_window_with_context(&app, window_label) {
+                    Ok(resolved) => {
+                        match crate::commands::get_bounding_rects(
+                            &resolved.window,
+                            &selector,
+                            max_nodes,
+                        )
+                        .await
+                        {
+                            Ok(result) => serde_json::json!({
+                                "id": id,
+                                "success": true,
+                                "data": result,
+                                "windowContext": resolved.context
+                            }),
+                            Err(e) => serde_json::json!({
+                                "id": id,
+                                "success": false,
+                                "error": e,
+                                "windowContext": resolved.context
+                            }),
+                        }
+                    }
+                    Err(e) => serde_json::json!({
+                        "id": id,
+                        "success": false,
+                        "error": e
+                    }),
+                }
+            }
+            None => serde_json::json!({
+                "id": id,
+                "success": false,
+                "error": "Missing selector argument"
+            }),
+        }
+    } else if cmd_name == "capture_text" {
+        // Handle OCR of a viewport capture
+        let args = command.get("args");
+        let options = args
+            .map(|a| serde_json::from_value(a.clone()))
+            .unwrap_or_else(|| Ok(crate::commands::CaptureTextOptions::default()));
+        let window_label = args
+            .and_then(|a| a.get("windowLabel"))
+            .and_then(|v| v.as_str())
+            .map(|s| s.to_string());
+
+        match options {
+            Ok(options) => match crate::commands::resolve_window_with_context(&app, window_label) {
+                Ok(resolved) => {
+                    match crate::commands::capture_text(&resolved.window, &options).await {
+                        Ok(result) => serde_json::json!({
+                            "id": id,
+                            "success": true,
+                            "data": result,
+                            "windowContext": resolved.context
+                        }),
+                        Err(e) => error_response(id, e, Some(resolved.context)),
+                    }
+                }
+                Err(e) => serde_json::json!({
+                    "id": id,
+                    "success": false,
+                    "error": e
+                }),
+            },
+            Err(e) => serde_json::json!({
+                "id": id,
+                "success": false,
+                "error": format!("Invalid capture_text options: {e}")
+            }),
+        }
+    } else if cmd_name == "get_console_logs" {
+        // Handle reading the page's buffered console output
+        let args = command.get("args");
+        let clear = args
+            .and_then(|a| a.get("clear"))
+            .and_then(|v| v.as_bool())
+            .unwrap_or(false);
+        let window_label = args
+            .and_then(|a| a.get("windowLabel"))
+            .and_then(|v| v.as_str())
+            .map(|s| s.to_string());
+
+        match crate::commands::resolve_window_with_context(&app, window_label) {
+            Ok(resolved) => {
+                let log: tauri::State<'_, crate::console_log::ConsoleLogState> = app.state();
+                match crate::commands::get_console_logs(&resolved.window, &log, clear).await {
+                    Ok(entries) => serde_json::json!({
+                        "id": id,
+                        "success": true,
+                        "data": entries,
+                        "windowContext": resolved.context
+                    }),
+                    Err(e) => serde_json::json!({
+                        "id": id,
+                        "success": false,
+                        "error": e,
+                        "windowContext": resolved.context
+                    }),
+                }
+            }
+            Err(e) => serde_json::json!({
+                "id": id,
+                "success": false,
+                "error": e
+            }),
+        }
+    } else if cmd_name == "inject_once" {
+        // Handle one-shot injection that bypasses the registry
+        let args = command.get("args");
+        let script_type = match args.and_then(|a| a.get("type")).and_then(|v| v.as_str()) {
+            Some("url") => ScriptType::Url,
+            Some("module") => ScriptType::Module,
+            _ => ScriptType::Inline,
+        };
+        let content = args
+            .and_then(|a| a.get("content"))
+            .and_then(|v| v.as_str())
+            .map(|s| s.to_string());
+        let window_label = args
+            .and_then(|a| a.get("windowLabel"))
+            .and_then(|v| v.as_str())
+            .map(|s| s.to_string());
+
+        match (
+            content,
+            crate::commands::resolve_window_with_context(&app, window_label),
+        ) {
+            (None, _) => serde_json::json!({
+                "id": id,
+                "success": false,
+                "error": "Missing content argument"
+            }),
+            (Some(content), Ok(resolved)) => {
+                match crate::commands::inject_once(&resolved.window, script_type, &content).await {
+                    Ok(data) => serde_json::json!({
+                        "id": id,
+                        "success": true,
+                        "data": data,
+                        "windowContext": resolved.context
+                    }),
+                    Err(e) => serde_json::json!({
+                        "id": id,
+                        "success": false,
+                        "error": e,
+                        "windowContext": resolved.context
+                    }),
+                }
+            }
+            (Some(_), Err(e)) => serde_json::json!({
+                "id": id,
+                "success": false,
+                "error": e
+            }),
+        }
+    } else if cmd_name == "highlight" {
+        // Handle outlining every element matching a selector
+        let args = command.get("args");
+        let selector = args
+            .and_then(|a| a.get("selector"))
+            .and_then(|v| v.as_str())
+            .map(|s| s.to_string());
+        let options = args
+            .cloned()
+            .map(serde_json::from_value::<crate::commands::HighlightOptions>)
+            .unwrap_or_else(|| Ok(Default::default()))
+            .map_err(|e| format!("Invalid highlight options: {e}"));
+        let window_label = args
+            .and_then(|a| a.get("windowLabel"))
+            .and_then(|v| v.as_str())
+            .map(|s| s.to_string());
+
+        match crate::commands::resolve_window_with_context(&app, window_label) {
+            Ok(resolved) => {
+                let result = match (selector, options) {
+                    (Some(selector), Ok(options)) => {
+                        crate::commands::highlight(&resolved.window, &selector, &options).await
+                    }
+                    (None, _) => Err("Missing selector argument".to_string()),
+                    (_, Err(e)) => Err(e),
+                };
+
+                match result {
+                    Ok(data) => serde_json::json!({
+                        "id": id,
+                        "success": true,
+                        "data": data,
+                        "windowContext": resolved.context
+                    }),
+                    Err(e) => serde_json::json!({
+                        "id": id,
+                        "success": false,
+                        "error": e,
+                        "windowContext": resolved.context
+                    }),
+                }
+            }
+            Err(e) => serde_json::json!({
+                "id": id,
+                "success": false,
+                "error": e
+            }),
+        }
+    } else if cmd_name == "highlight_element" || cmd_name == "clear_highlights" {
+        // Handle drawing and removing debug highlights
+        let args = command.get("args");
+        let selector = args
+            .and_then(|a| a.get("selector"))
+            .and_then(|v| v.as_str())
+            .map(|s| s.to_string());
+        let duration_ms = args
+            .and_then(|a| a.get("durationMs"))
+            .and_then(|v| v.as_u64())
+            .unwrap_or(crate::commands::DEFAULT_HIGHLIGHT_DURATION_MS);
+        let window_label = args
+            .and_then(|a| a.get("windowLabel"))
+            .and_then(|v| v.as_str())
+            .map(|s| s.to_string());
+
+        match crate::commands::resolve_window_with_context(&app, window_label) {
+            Ok(resolved) => {
+                let result = if cmd_name == "highlight_element" {
+                    match selector {
+                        Some(selector) => crate::commands::highlight_element(
+                            &resolved.window,
+                            &selector,
+                            duration_ms,
+                        )
+                        .await
+                        .map(|result| serde_json::json!(result)),
+                        None => Err("Missing selector argument".to_string()),
+                    }
+                } else {
+                    crate::commands::clear_highlights(&resolved.window)
+                        .await
+                        .map(|removed| serde_json::json!({ "removed": removed }))
+                };
+
+                match result {
+                    Ok(data) => serde_json::json!({
+                        "id": id,
+                        "success": true,
+                        "data": data,
+                        "windowContext": resolved.context
+                    }),
+                    Err(e) => serde_json::json!({
+                        "id": id,
+                        "success": false,
+                        "error": e,
+                        "windowContext": resolved.context
+                    }),
+                }
+            }
+            Err(e) => serde_json::json!({
+                "id": id,
+                "success": false,
+                "error": e
+            }),
+        }
+    } else if cmd_name == "clear_browsing_data" {
+        // Handle clearing storage, cookies and caches between test runs
+        let args = command.get("args");
+        let options = args
+            .cloned()
+            .map(serde_json::from_value::<crate::commands::ClearBrowsingDataOptions>)
+            .unwrap_or_else(|| Ok(Default::default()))
+            // No categories means a full clean slate
+            .map(|options| {
+                if options.is_empty() {
+                    crate::commands::ClearBrowsingDataOptions::all()
+                } else {
+                    options
+                }
+            });
+        let window_label = args
+            .and_then(|a| a.get("windowLabel"))
+            .and_then(|v| v.as_str())
+            .map(|s| s.to_string());
+
+        match options {
+            Ok(options) => match crate::commands::resolve_window_with_context(&app, window_label) {
+                Ok(resolved) => {
+                    let result =
+                        crate::commands::clear_browsing_data(&resolved.window, &options).await;
+
+                    serde_json::json!({
+                        "id": id,
+                        "success": true,
+                        "data": result,
+                        "windowContext": resolved.context
+                    })
+                }
+                Err(e) => serde_json::json!({
+                    "id": id,
+                    "success": false,
+                    "error": e
+                }),
+            },
+            Err(e) => serde_json::json!({
+                "id": id,
+                "success": false,
+                "error": format!("Invalid clear_browsing_data options: {e}")
+            }),
+        }
+    } else if cmd_name == "storage_set" || cmd_name == "storage_get" {
+        // Handle localStorage/sessionStorage access
+        let args = command.get("args");
+        let area = args
+            .and_then(|a| a.get("area"))
+            .cloned()
+            .map(serde_json::from_value::<crate::commands::StorageArea>);
+        let key = args
+            .and_then(|a| a.get("key"))
+            .and_then(|v| v.as_str())
+            .map(|s| s.to_string());
+        let value = args
+            .and_then(|a| a.get("value"))
+            .and_then(|v| v.as_str())
+            .map(|s| s.to_string());
+        let window_label = args
+            .and_then(|a| a.get("windowLabel"))
+            .and_then(|v| v.as_str())
+            .map(|s| s.to_string());
+
+        let missing_value = cmd_name == "storage_set" && value.is_none();
+
+        match (area, key) {
+            (Some(Ok(area)), Some(key)) if !missing_value => {
+                match crate::commands::resolve_window_with_context(&app, window_label) {
+                    Ok(resolved) => {
+                        let result = if cmd_name == "storage_set" {
+                            crate::commands::storage_set(
+                                &resolved.window,
+                                area,
+                                &key,
+                                value.as_deref().unwrap_or_default(),
+                            )
+                            .await
                         } else {
-                            serde_json::json!({
-                                "id": id,
-                                "success": false,
-                                "error": "Missing args for invoke_tauri"
-                            })
-                        }
-                    } else if cmd_name == "list_windows" {
-                        // Handle window listing
-                        match crate::commands::list_windows(app.clone()).await {
+                            crate::commands::storage_get(&resolved.window, area, &key).await
+                        };
+
+                        match result {
                             Ok(data) => serde_json::json!({
                                 "id": id,
                                 "success": true,
-                                "data": data
+                                "data": data,
+                                "windowContext": resolved.context
                             }),
                             Err(e) => serde_json::json!({
                                 "id": id,
                                 "success": false,
-                                "error": e
+                                "error": e.to_string(),
+                                "errorCode": e.code(),
+                                "windowContext": resolved.context
                             }),
                         }
-                    } else if cmd_name == "get_window_info" {
-                        // Handle window info retrieval
-                        let window_id = command
-                            .get("args")
-                            .and_then(|a| a.get("windowId"))
-                            .and_then(|v| v.as_str())
-                            .map(|s| s.to_string());
+                    }
+                    Err(e) => serde_json::json!({
+                        "id": id,
+                        "success": false,
+                        "error": e
+                    }),
+                }
+            }
+            _ => serde_json::json!({
+                "id": id,
+                "success": false,
+                "error": "Missing or invalid area ('local' or 'session'), key, or value argument"
+            }),
+        }
+    } else if cmd_name == "focus_window"
+        || cmd_name == "minimize_window"
+        || cmd_name == "close_window"
+    {
+        // Handle window management operations
+        let window_id = command
+            .get("args")
+            .and_then(|a| a.get("windowId"))
+            .and_then(|v| v.as_str())
+            .map(|s| s.to_string());
 
-                        match crate::commands::resolve_window(&app, window_id) {
-                            Ok(window) => match crate::commands::get_window_info(window).await {
-                                Ok(data) => serde_json::json!({
-                                    "id": id,
-                                    "success": true,
-                                    "data": data
-                                }),
-                                Err(e) => serde_json::json!({
-                                    "id": id,
-                                    "success": false,
-                                    "error": e
-                                }),
-                            },
-                            Err(e) => serde_json::json!({
-                                "id": id,
-                                "success": false,
-                                "error": e
-                            }),
-                        }
-                    } else if cmd_name == "execute_js" {
-                        if let Some(args) = command.get("args") {
-                            if let Some(script) = args.get("script").and_then(|v| v.as_str()) {
-                                // Get optional window_label, defaulting to "main"
-                                let window_label = args
-                                    .get("windowLabel")
-                                    .and_then(|v| v.as_str())
-                                    .map(|s| s.to_string());
-
-                                // Resolve the target window with context
-                                match crate::commands::resolve_window_with_context(
-                                    &app,
-                                    window_label,
-                                ) {
-                                    Ok(resolved) => {
-                                        // Get the script executor state and create State wrapper
-                                        let executor_state =
-                                            app.state::<crate::commands::ScriptExecutor>();
-                                        // Call the execute_js command with state
-                                        match crate::commands::execute_js(
-                                            resolved.window.clone(),
-                                            script.to_string(),
-                                            executor_state,
-                                        )
-                                        .await
-                                        {
-                                            Ok(result) => {
-                                                serde_json::json!({
-                                                    "id": id,
-                                                    "success": result.get("success").and_then(|v| v.as_bool()).unwrap_or(true),
-                                                    "data": result.get("data").cloned(),
-                                                    "error": result.get("error").and_then(|v| v.as_str()),
-                                                    "windowContext": resolved.context
-                                                })
-                                            }
-                                            Err(e) => {
-                                                serde_json::json!({
-                                                    "id": id,
-                                                    "success": false,
-                                                    "error": e,
-                                                    "windowContext": resolved.context
-                                                })
-                                            }
-                                        }
-                                    }
-                                    Err(e) => {
-                                        serde_json::json!({
-                                            "id": id,
-                                            "success": false,
-                                            "error": e
-                                        })
-                                    }
-                                }
-                            } else {
-                                serde_json::json!({
-                                    "id": id,
-                                    "success": false,
-                                    "error": "Missing script argument"
-                                })
-                            }
-                        } else {
-                            serde_json::json!({
-                                "id": id,
-                                "success": false,
-                                "error": "Missing args"
+        let result = match cmd_name {
+            "focus_window" => crate::commands::focus_window(app.clone(), window_id).await,
+            "minimize_window" => crate::commands::minimize_window(app.clone(), window_id).await,
+            _ => crate::commands::close_window(app.clone(), window_id).await,
+        };
+
+        match result {
+            Ok(state) => serde_json::json!({
+                "id": id,
+                "success": true,
+                "data": state
+            }),
+            Err(e) => serde_json::json!({
+                "id": id,
+                "success": false,
+                "error": e.to_string(),
+                "errorCode": e.code()
+            }),
+        }
+    } else if cmd_name == "resize_window" || cmd_name == "set_window_size" {
+        // Handle window resize
+        if let Some(args) = command.get("args") {
+            let width = args.get("width").and_then(|v| v.as_u64()).map(|w| w as u32);
+            let height = args
+                .get("height")
+                .and_then(|v| v.as_u64())
+                .map(|h| h as u32);
+            let window_id = args
+                .get("windowId")
+                .and_then(|v| v.as_str())
+                .map(|s| s.to_string());
+            let logical = args
+                .get("logical")
+                .and_then(|v| v.as_bool())
+                .unwrap_or(true);
+
+            match (width, height) {
+                (Some(w), Some(h)) => {
+                    let params = crate::commands::ResizeWindowParams {
+                        width: w,
+                        height: h,
+                        window_id,
+                        logical,
+                    };
+
+                    match crate::commands::resize_window(app.clone(), params).await {
+                        Ok(result) => serde_json::json!({
+                            "id": id,
+                            "success": result.success,
+                            "data": result,
+                            "error": result.error
+                        }),
+                        Err(e) => serde_json::json!({
+                            "id": id,
+                            "success": false,
+                            "error": e
+                        }),
+                    }
+                }
+                _ => serde_json::json!({
+                    "id": id,
+                    "success": false,
+                    "error": "Missing width or height argument"
+                }),
+            }
+        } else {
+            serde_json::json!({
+                "id": id,
+                "success": false,
+                "error": format!("Missing args for {cmd_name}")
+            })
+        }
+    } else if cmd_name == "register_script" {
+        // Handle script registration
+        if let Some(args) = command.get("args") {
+            let script_id = args.get("id").and_then(|v| v.as_str());
+            let script_type_str = args.get("type").and_then(|v| v.as_str());
+            let content = args.get("content").and_then(|v| v.as_str());
+
+            match (script_type_str, content) {
+                (Some(type_str), Some(content_str)) => {
+                    let script_type = match type_str {
+                        "url" => ScriptType::Url,
+                        "module" => ScriptType::Module,
+                        _ => ScriptType::Inline,
+                    };
+
+                    // Without an ID the registry generates one
+                    let mut entry =
+                        ScriptEntry::new(script_id.unwrap_or_default(), script_type, content_str);
+                    entry.cache = args.get("cache").and_then(|v| v.as_bool()).unwrap_or(false);
+                    entry.phase = args
+                        .get("phase")
+                        .and_then(|v| serde_json::from_value(v.clone()).ok())
+                        .unwrap_or_default();
+                    entry.run_once = args
+                        .get("runOnce")
+                        .and_then(|v| v.as_bool())
+                        .unwrap_or(false);
+                    entry.run_at = args
+                        .get("runAt")
+                        .and_then(|v| serde_json::from_value(v.clone()).ok())
+                        .unwrap_or_default();
+                    entry.order = args
+                        .get("order")
+                        .and_then(|v| v.as_i64())
+                        .map(|o| o as i32)
+                        .unwrap_or(0);
+                    entry.matches = args
+                        .get("matches")
+                        .and_then(|v| serde_json::from_value(v.clone()).ok())
+                        .unwrap_or_default();
+                    // Initialization scripts are fixed when the plugin is built
+                    let run_at_warning = (entry.run_at == RunAt::DocumentStart)
+                        .then_some(
+                            "documentStart only applies to inline scripts passed to Builder::script; this script is injected after load",
+                        );
+
+                    let window_label = args
+                        .get("windowLabel")
+                        .and_then(|v| v.as_str())
+                        .map(|s| s.to_string());
+
+                    // Window-scoped scripts are keyed by the resolved window's label
+                    let scope = match args.get("scope").and_then(|v| v.as_str()) {
+                        Some("window") => resolve_window_with_context(&app, window_label.clone())
+                            .map(|resolved| ScriptScope::Window(resolved.context.window_label)),
+                        _ => Ok(ScriptScope::Global),
+                    };
+
+                    // Add to registry
+                    let registry: tauri::State<'_, SharedScriptRegistry> = app.state();
+                    let added = scope.and_then(|scope| {
+                        let mut reg = registry.lock().unwrap();
+                        let added = match script_id {
+                            Some(_) => reg.add(scope, entry.clone()),
+                            None => reg.add_auto(scope, entry.clone()),
+                        };
+                        added
+                            .map(|script_id| {
+                                entry.id = script_id;
+                                reg.is_injection_enabled()
                             })
-                        }
-                    } else if cmd_name == "capture_native_screenshot" {
-                        // Handle native screenshot capture
-                        let args = command.get("args");
-                        let format = args
-                            .and_then(|a| a.get("format"))
-                            .and_then(|v| v.as_str())
-                            .map(|s| s.to_string());
-                        let quality = args
-                            .and_then(|a| a.get("quality"))
-                            .and_then(|v| v.as_u64())
-                            .map(|q| q as u8);
-                        let max_width = args
-                            .and_then(|a| a.get("maxWidth"))
-                            .and_then(|v| v.as_u64())
-                            .map(|w| w as u32);
-                        let window_label = args
-                            .and_then(|a| a.get("windowLabel"))
-                            .and_then(|v| v.as_str())
-                            .map(|s| s.to_string());
-
-                        // Resolve the target window with context
-                        match crate::commands::resolve_window_with_context(&app, window_label) {
-                            Ok(resolved) => {
-                                match crate::commands::capture_native_screenshot(
-                                    resolved.window,
-                                    format,
-                                    quality,
-                                    max_width,
-                                )
-                                .await
-                                {
-                                    Ok(data_url) => {
-                                        serde_json::json!({
-                                            "id": id,
-                                            "success": true,
-                                            "data": data_url,
-                                            "windowContext": resolved.context
-                                        })
-                                    }
-                                    Err(e) => {
-                                        serde_json::json!({
-                                            "id": id,
-                                            "success": false,
-                                            "error": e,
-                                            "windowContext": resolved.context
-                                        })
-                                    }
-                                }
-                            }
-                            Err(e) => {
-                                serde_json::json!({
-                                    "id": id,
-                                    "success": false,
-                                    "error": e
-                                })
-                            }
-                        }
-                    } else if cmd_name == "resize_window" {
-                        // Handle window resize
-                        if let Some(args) = command.get("args") {
-                            let width =
-                                args.get("width").and_then(|v| v.as_u64()).map(|w| w as u32);
-                            let height = args
-                                .get("height")
-                                .and_then(|v| v.as_u64())
-                                .map(|h| h as u32);
-                            let window_id = args
-                                .get("windowId")
-                                .and_then(|v| v.as_str())
-                                .map(|s| s.to_string());
-                            let logical = args
-                                .get("logical")
-                                .and_then(|v| v.as_bool())
-                                .unwrap_or(true);
-
-                            match (width, height) {
-                                (Some(w), Some(h)) => {
-                                    let params = crate::commands::ResizeWindowParams {
-                                        width: w,
-                                        height: h,
-                                        window_id,
-                                        logical,
-                                    };
-
-                                    match crate::commands::resize_window(app.clone(), params).await
-                                    {
-                                        Ok(result) => serde_json::json!({
-                                            "id": id,
-                                            "success": result.success,
-                                            "data": result,
-                                            "error": result.error
-                                        }),
-                                        Err(e) => serde_json::json!({
-                                            "id": id,
-                                            "success": false,
-                                            "error": e
-                                        }),
-                                    }
-                                }
-                                _ => serde_json::json!({
-                                    "id": id,
-                                    "success": false,
-                                    "error": "Missing width or height argument"
-                                }),
-                            }
-                        } else {
+                            .map_err(|e| e.to_string())
+                    });
+
+                    // Inject the script into the webview
+                    match added {
+                        Err(e) => serde_json::json!({
+                            "id": id,
+                            "success": false,
+                            "error": e
+                        }),
+                        Ok(false) => {
+                            // Keep the entry registered but leave the DOM untouched
                             serde_json::json!({
                                 "id": id,
-                                "success": false,
-                                "error": "Missing args for resize_window"
+                                "success": true,
+                                "data": {
+                                    "registered": true,
+                                    "scriptId": entry.id,
+                                    "paused": true,
+                                    "warning": run_at_warning
+                                }
                             })
                         }
-                    } else if cmd_name == "register_script" {
-                        // Handle script registration
-                        if let Some(args) = command.get("args") {
-                            let script_id = args.get("id").and_then(|v| v.as_str());
-                            let script_type_str = args.get("type").and_then(|v| v.as_str());
-                            let content = args.get("content").and_then(|v| v.as_str());
-
-                            match (script_id, script_type_str, content) {
-                                (Some(id_str), Some(type_str), Some(content_str)) => {
-                                    let script_type = match type_str {
-                                        "url" => ScriptType::Url,
-                                        _ => ScriptType::Inline,
-                                    };
-
-                                    let entry = ScriptEntry {
-                                        id: id_str.to_string(),
-                                        script_type,
-                                        content: content_str.to_string(),
-                                    };
-
-                                    // Add to registry
-                                    let registry: tauri::State<'_, SharedScriptRegistry> =
-                                        app.state();
-                                    {
-                                        let mut reg = registry.lock().unwrap();
-                                        reg.add(entry.clone());
-                                    }
-
-                                    // Inject the script into the webview
-                                    let window_label = args
-                                        .get("windowLabel")
-                                        .and_then(|v| v.as_str())
-                                        .map(|s| s.to_string());
-
-                                    match inject_script_to_webview(&app, &entry, window_label) {
-                                        Ok(result) => serde_json::json!({
-                                            "id": id,
-                                            "success": true,
-                                            "data": { "registered": true, "scriptId": id_str },
-                                            "windowContext": {
-                                                "windowLabel": result.window_context.window_label,
-                                                "totalWindows": result.window_context.total_windows,
-                                                "warning": result.window_context.warning
-                                            }
-                                        }),
-                                        Err(e) => serde_json::json!({
-                                            "id": id,
-                                            "success": false,
-                                            "error": e
-                                        }),
-                                    }
-                                }
-                                _ => serde_json::json!({
-                                    "id": id,
-                                    "success": false,
-                                    "error": "Missing required args: id, type, content"
-                                }),
-                            }
-                        } else {
+                        Ok(true) if !page_matches(&app, &entry, window_label.clone()) => {
+                            // Injected on the next page load that matches
                             serde_json::json!({
                                 "id": id,
-                                "success": false,
-                                "error": "Missing args for register_script"
+                                "success": true,
+                                "data": {
+                                    "registered": true,
+                                    "scriptId": entry.id,
+                                    "warning": "The current page doesn't match the script's patterns; it is injected on the next page that does"
+                                }
                             })
                         }
-                    } else if cmd_name == "remove_script" {
-                        // Handle script removal
-                        if let Some(args) = command.get("args") {
-                            if let Some(script_id) = args.get("id").and_then(|v| v.as_str()) {
-                                let registry: tauri::State<'_, SharedScriptRegistry> = app.state();
-                                let removed = {
-                                    let mut reg = registry.lock().unwrap();
-                                    reg.remove(script_id).is_some()
-                                };
-
-                                // Remove from DOM
-                                let window_label = args
-                                    .get("windowLabel")
-                                    .and_then(|v| v.as_str())
-                                    .map(|s| s.to_string());
-
-                                match remove_script_from_webview(&app, script_id, window_label) {
-                                    Ok(result) => serde_json::json!({
-                                        "id": id,
-                                        "success": true,
-                                        "data": { "removed": removed, "scriptId": script_id },
-                                        "windowContext": {
-                                            "windowLabel": result.window_context.window_label,
-                                            "totalWindows": result.window_context.total_windows,
-                                            "warning": result.window_context.warning
-                                        }
-                                    }),
-                                    Err(e) => {
-                                        eprintln!("Failed to remove script from DOM: {e}");
-                                        serde_json::json!({
-                                            "id": id,
-                                            "success": true,
-                                            "data": { "removed": removed, "scriptId": script_id },
-                                            "error": format!("Script removed from registry but DOM removal failed: {e}")
-                                        })
-                                    }
-                                }
-                            } else {
+                        Ok(true) => match inject_script_to_webview(&app, &entry, window_label) {
+                            Ok((result, strategy)) => {
+                                registry.lock().unwrap().mark_injected(
+                                    &result.window_context.window_label,
+                                    [entry.id.as_str()],
+                                    current_timestamp(),
+                                );
                                 serde_json::json!({
                                     "id": id,
-                                    "success": false,
-                                    "error": "Missing script id"
+                                    "success": true,
+                                    "data": {
+                                        "registered": true,
+                                        "scriptId": entry.id,
+                                        "strategy": strategy,
+                                        "warning": run_at_warning
+                                    },
+                                    "windowContext": {
+                                        "windowLabel": result.window_context.window_label,
+                                        "totalWindows": result.window_context.total_windows,
+                                        "warning": result.window_context.warning
+                                    }
                                 })
                             }
-                        } else {
-                            serde_json::json!({
+                            Err(e) => serde_json::json!({
                                 "id": id,
                                 "success": false,
-                                "error": "Missing args for remove_script"
-                            })
-                        }
-                    } else if cmd_name == "clear_scripts" {
-                        // Handle clearing all scripts
-                        let registry: tauri::State<'_, SharedScriptRegistry> = app.state();
-                        let count = {
-                            let mut reg = registry.lock().unwrap();
-                            let count = reg.len();
-                            reg.clear();
-                            count
-                        };
+                                "error": e
+                            }),
+                        },
+                    }
+                }
+                _ => serde_json::json!({
+                    "id": id,
+                    "success": false,
+                    "error": "Missing required args: type, content"
+                }),
+            }
+        } else {
+            serde_json::json!({
+                "id": id,
+                "success": false,
+                "error": "Missing args for register_script"
+            })
+        }
+    } else if cmd_name == "remove_script" {
+        // Handle script removal
+        if let Some(args) = command.get("args") {
+            // Trimmed like the registry's keys, so the DOM lookup matches too
+            if let Some(script_id) = args.get("id").and_then(|v| v.as_str()).map(str::trim) {
+                let registry: tauri::State<'_, SharedScriptRegistry> = app.state();
+                let removed = {
+                    let mut reg = registry.lock().unwrap();
+                    reg.remove(script_id).is_some()
+                };
 
-                        // Clear from DOM
-                        let window_label = command
-                            .get("args")
-                            .and_then(|a| a.get("windowLabel"))
-                            .and_then(|v| v.as_str())
-                            .map(|s| s.to_string());
+                // Remove from DOM
+                let window_label = args
+                    .get("windowLabel")
+                    .and_then(|v| v.as_str())
+                    .map(|s| s.to_string());
 
-                        match clear_scripts_from_webview(&app, window_label) {
-                            Ok(result) => serde_json::json!({
-                                "id": id,
-                                "success": true,
-                                "data": { "cleared": count },
-                                "windowContext": {
-                                    "windowLabel": result.window_context.window_label,
-                                    "totalWindows": result.window_context.total_windows,
-                                    "warning": result.window_context.warning
-                                }
-                            }),
-                            Err(e) => {
-                                eprintln!("Failed to clear scripts from DOM: {e}");
-                                serde_json::json!({
-                                    "id": id,
-                                    "success": true,
-                                    "data": { "cleared": count },
-                                    "error": format!("Scripts cleared from registry but DOM clear failed: {e}")
-                                })
-                            }
+                match remove_script_from_webview(&app, script_id, window_label) {
+                    Ok(result) => serde_json::json!({
+                        "id": id,
+                        "success": true,
+                        "data": { "removed": removed, "scriptId": script_id },
+                        "windowContext": {
+                            "windowLabel": result.window_context.window_label,
+                            "totalWindows": result.window_context.total_windows,
+                            "warning": result.window_context.warning
                         }
-                    } else if cmd_name == "get_scripts" {
-                        // Handle getting all registered scripts
-                        let registry: tauri::State<'_, SharedScriptRegistry> = app.state();
-                        let scripts: Vec<serde_json::Value> = {
-                            let reg = registry.lock().unwrap();
-                            reg.get_all()
-                                .iter()
-                                .map(|entry| {
-                                    serde_json::json!({
-                                        "id": entry.id,
-                                        "type": match entry.script_type {
-                                            ScriptType::Inline => "inline",
-                                            ScriptType::Url => "url",
-                                        },
-                                        "content": entry.content
-                                    })
-                                })
-                                .collect()
-                        };
-
+                    }),
+                    Err(e) => {
+                        eprintln!("Failed to remove script from DOM: {e}");
                         serde_json::json!({
                             "id": id,
                             "success": true,
-                            "data": { "scripts": scripts }
-                        })
-                    } else {
-                        // Unknown command
-                        serde_json::json!({
-                            "id": id,
-                            "success": false,
-                            "error": format!("Unknown command: {}", cmd_name)
+                            "data": { "removed": removed, "scriptId": script_id },
+                            "error": format!("Script removed from registry but DOM removal failed: {e}")
                         })
-                    };
+                    }
+                }
+            } else {
+                serde_json::json!({
+                    "id": id,
+                    "success": false,
+                    "error": "Missing script id"
+                })
+            }
+        } else {
+            serde_json::json!({
+                "id": id,
+                "success": false,
+                "error": "Missing args for remove_script"
+            })
+        }
+    } else if cmd_name == "clear_scripts" {
+        // Handle clearing all scripts
+        let registry: tauri::State<'_, SharedScriptRegistry> = app.state();
+        let count = {
+            let mut reg = registry.lock().unwrap();
+            let count = reg.len();
+            reg.clear();
+            count
+        };
 
-                    let _ = response_tx.send(response.to_string());
-                } else {
-                    eprintln!("Failed to parse command: {text}");
+        // Clear from DOM
+        let window_label = command
+            .get("args")
+            .and_then(|a| a.get("windowLabel"))
+            .and_then(|v| v.as_str())
+            .map(|s| s.to_string());
+
+        match clear_scripts_from_webview(&app, window_label) {
+            Ok(result) => serde_json::json!({
+                "id": id,
+                "success": true,
+                "data": { "cleared": count },
+                "windowContext": {
+                    "windowLabel": result.window_context.window_label,
+                    "totalWindows": result.window_context.total_windows,
+                    "warning": result.window_context.warning
                 }
+            }),
+            Err(e) => {
+                eprintln!("Failed to clear scripts from DOM: {e}");
+                serde_json::json!({
+                    "id": id,
+                    "success": true,
+                    "data": { "cleared": count },
+                    "error": format!("Scripts cleared from registry but DOM clear failed: {e}")
+                })
             }
-            Ok(Message::Close(_)) => {
-                println!("Client disconnected");
-                break;
+        }
+    } else if cmd_name == "set_injection_enabled" {
+        // Handle pausing/resuming injection of all registered scripts
+        match command
+            .get("args")
+            .and_then(|a| a.get("enabled"))
+            .and_then(|v| v.as_bool())
+        {
+            Some(enabled) => {
+                let registry: tauri::State<'_, SharedScriptRegistry> = app.state();
+                let count = {
+                    let mut reg = registry.lock().unwrap();
+                    reg.set_injection_enabled(enabled);
+                    reg.len()
+                };
+
+                serde_json::json!({
+                    "id": id,
+                    "success": true,
+                    "data": { "enabled": enabled, "registered": count }
+                })
             }
-            Err(e) => {
-                eprintln!("WebSocket error: {e}");
-                break;
+            None => serde_json::json!({
+                "id": id,
+                "success": false,
+                "error": "Missing enabled argument"
+            }),
+        }
+    } else if cmd_name == "export_registry" {
+        // Handle exporting the whole registry as a versioned document
+        let registry: tauri::State<'_, SharedScriptRegistry> = app.state();
+        let export = registry.lock().unwrap().export();
+
+        serde_json::json!({
+            "id": id,
+            "success": true,
+            "data": export
+        })
+    } else if cmd_name == "import_registry" {
+        // Handle importing a registry export document. Imported scripts
+        // are injected on the next page load.
+        let args = command.get("args");
+        let document = args.and_then(|a| a.get("json")).map(|v| match v {
+            serde_json::Value::String(raw) => {
+                serde_json::from_str(raw).map_err(|e| RegistryError::InvalidExport(e.to_string()))
             }
-            _ => {}
+            other => Ok(other.clone()),
+        });
+        let mode = args
+            .and_then(|a| a.get("mode"))
+            .cloned()
+            .map(serde_json::from_value::<ImportMode>);
+
+        match (document, mode) {
+            (Some(document), Some(Ok(mode))) => {
+                let registry: tauri::State<'_, SharedScriptRegistry> = app.state();
+                let result = document
+                    .and_then(RegistryExport::from_json)
+                    .and_then(|export| registry.lock().unwrap().import(export, mode));
+
+                match result {
+                    Ok(imported) => serde_json::json!({
+                        "id": id,
+                        "success": true,
+                        "data": {
+                            "imported": imported,
+                            "registered": registry.lock().unwrap().len()
+                        }
+                    }),
+                    Err(e) => serde_json::json!({
+                        "id": id,
+                        "success": false,
+                        "error": e.to_string()
+                    }),
+                }
+            }
+            _ => serde_json::json!({
+                "id": id,
+                "success": false,
+                "error": "Missing or invalid json or mode ('replace' or 'merge') argument"
+            }),
+        }
+    } else if cmd_name == "load_script_manifest" {
+        // Handle registering the scripts listed in a remote manifest.
+        // They are injected on the next page load.
+        let url = command
+            .get("args")
+            .and_then(|a| a.get("url"))
+            .and_then(|v| v.as_str());
+
+        match url {
+            Some(url) => {
+                let registry: tauri::State<'_, SharedScriptRegistry> = app.state();
+                let config: tauri::State<'_, crate::Config> = app.state();
+
+                match crate::commands::load_script_manifest(&registry, &config, url).await {
+                    Ok(data) => serde_json::json!({
+                        "id": id,
+                        "success": true,
+                        "data": data
+                    }),
+                    Err(e) => serde_json::json!({
+                        "id": id,
+                        "success": false,
+                        "error": e
+                    }),
+                }
+            }
+            None => serde_json::json!({
+                "id": id,
+                "success": false,
+                "error": "Missing url argument"
+            }),
+        }
+    } else if cmd_name == "inject_scripts" {
+        // Handle injecting a subset of registered scripts now
+        let args = command.get("args");
+        let ids: Option<Vec<String>> = args
+            .and_then(|a| a.get("ids"))
+            .and_then(|v| serde_json::from_value(v.clone()).ok());
+        let verify = args
+            .and_then(|a| a.get("verify"))
+            .and_then(|v| v.as_bool())
+            .unwrap_or(false);
+        let window_label = args
+            .and_then(|a| a.get("windowLabel"))
+            .and_then(|v| v.as_str())
+            .map(|s| s.to_string());
+
+        match crate::commands::resolve_window_with_context(&app, window_label) {
+            Ok(resolved) => {
+                let registry: tauri::State<'_, SharedScriptRegistry> = app.state();
+                let result = match ids {
+                    Some(ids) => {
+                        crate::commands::inject_scripts(&resolved.window, &registry, &ids, verify)
+                            .await
+                    }
+                    None => Err("Missing ids argument".to_string()),
+                };
+
+                match result {
+                    Ok(data) => serde_json::json!({
+                        "id": id,
+                        "success": true,
+                        "data": data,
+                        "windowContext": resolved.context
+                    }),
+                    Err(e) => serde_json::json!({
+                        "id": id,
+                        "success": false,
+                        "error": e,
+                        "windowContext": resolved.context
+                    }),
+                }
+            }
+            Err(e) => serde_json::json!({
+                "id": id,
+                "success": false,
+                "error": e
+            }),
         }
+    } else if cmd_name == "list_scripts" {
+        // Handle listing registered scripts without their content
+        let include_content = command
+            .get("args")
+            .and_then(|a| a.get("includeContent"))
+            .and_then(|v| v.as_bool())
+            .unwrap_or(false);
+        let registry: tauri::State<'_, SharedScriptRegistry> = app.state();
+        let metrics: tauri::State<'_, crate::commands::InjectionMetrics> = app.state();
+        let reg = registry.lock().unwrap();
+        let scripts: Vec<serde_json::Value> = reg
+            .summaries()
+            .into_iter()
+            .map(|(scope, summary)| {
+                let mut item = serde_json::json!(summary);
+                if include_content {
+                    item["content"] = serde_json::json!(reg
+                        .scope(&scope)
+                        .and_then(|r| r.get(&summary.id))
+                        .map(|entry| entry.content.as_str()));
+                }
+                item["scope"] = serde_json::json!(scope);
+                item["cost"] = serde_json::json!(metrics.cost(&summary.id));
+                item
+            })
+            .collect();
+
+        serde_json::json!({
+            "id": id,
+            "success": true,
+            "data": {
+                "scripts": scripts,
+                "injectionEnabled": reg.is_injection_enabled()
+            }
+        })
+    } else if cmd_name == "get_scripts" {
+        // Handle getting all registered scripts
+        let registry: tauri::State<'_, SharedScriptRegistry> = app.state();
+        let scripts: Vec<serde_json::Value> = {
+            let reg = registry.lock().unwrap();
+            reg.get_all()
+                .into_iter()
+                .map(|(scope, entry)| {
+                    serde_json::json!({
+                        "id": entry.id,
+                        "scope": scope,
+                        "type": match entry.script_type {
+                            ScriptType::Inline => "inline",
+                            ScriptType::Url => "url",
+                            ScriptType::Module => "module",
+                        },
+                        "content": entry.content,
+                        "cache": entry.cache,
+                        "phase": entry.phase,
+                        "runOnce": entry.run_once,
+                        "runAt": entry.run_at,
+                        "order": entry.order,
+                        "matches": entry.matches,
+                        "createdAt": entry.created_at,
+                        "lastInjectedAt": entry.last_injected_at
+                    })
+                })
+                .collect()
+        };
+
+        let injection_enabled = registry.lock().unwrap().is_injection_enabled();
+
+        serde_json::json!({
+            "id": id,
+            "success": true,
+            "data": { "scripts": scripts, "injectionEnabled": injection_enabled }
+        })
+    } else {
+        // Unknown command
+        serde_json::json!({
+            "id": id,
+            "success": false,
+            "error": format!("Unknown command: {}", cmd_name)
+        })
     }
+}
 
-    send_task.abort();
-    Ok(())
+/// Name a command is permission-checked under.
+///
+/// `invoke_tauri` is checked by the plugin command it invokes, so
+/// `plugin:mcp-bridge|get_window_info` is allowed by `get_window_info`.
+fn permission_key<'a>(cmd_name: &'a str, command: &'a serde_json::Value) -> &'a str {
+    if cmd_name != "invoke_tauri" {
+        return cmd_name;
+    }
+
+    command
+        .get("args")
+        .and_then(|a| a.get("command"))
+        .and_then(|v| v.as_str())
+        .map(|c| c.strip_prefix("plugin:mcp-bridge|").unwrap_or(c))
+        .unwrap_or(cmd_name)
+}
+
+/// Failure response for a command error, in MCP's `{ code, message,
+/// retryable }` shape spread over the response's `error`, `errorCode` and
+/// `retryable` fields.
+fn error_response(
+    id: &str,
+    error: CommandError,
+    window_context: Option<WindowContext>,
+) -> serde_json::Value {
+    let mcp_error = error.to_mcp_error();
+    let mut response = serde_json::json!({
+        "id": id,
+        "success": false,
+        "error": mcp_error["message"],
+        "errorCode": mcp_error["code"],
+        "retryable": mcp_error["retryable"]
+    });
+    if let Some(window_context) = window_context {
+        response["windowContext"] = serde_json::json!(window_context);
+    }
+    response
 }
 
 /// Result of a script operation with window context.
@@ -823,7 +2953,7 @@ struct ScriptOperationResult {
 fn inject_script_to_window<R: Runtime>(
     window: &WebviewWindow<R>,
     entry: &ScriptEntry,
-) -> Result<(), String> {
+) -> Result<InjectionStrategy, String> {
     let script = match entry.script_type {
         ScriptType::Inline => format!(
             r#"
@@ -860,28 +2990,71 @@ fn inject_script_to_window<R: Runtime>(
             entry.id,
             serde_json::to_string(&entry.content).unwrap_or_else(|_| "''".to_string())
         ),
+        ScriptType::Module => format!(
+            r#"
+            (function() {{
+                var existing = document.querySelector('script[data-mcp-script-id="{}"]');
+                if (existing) {{
+                    existing.remove();
+                }}
+                var script = document.createElement('script');
+                script.setAttribute('data-mcp-script-id', '{}');
+                script.type = 'module';
+                var src = {};
+                if (!{}) {{
+                    src = URL.createObjectURL(new Blob([src], {{ type: 'text/javascript' }}));
+                    script.onload = script.onerror = function() {{
+                        URL.revokeObjectURL(src);
+                    }};
+                }}
+                script.src = src;
+                document.head.appendChild(script);
+            }})();
+            "#,
+            entry.id,
+            entry.id,
+            serde_json::to_string(&entry.content).unwrap_or_else(|_| "''".to_string()),
+            entry.is_module_url()
+        ),
     };
 
-    window
-        .eval(&script)
-        .map_err(|e| format!("Failed to inject script: {e}"))
+    eval_with_limit(window, &script).map_err(|e| format!("Failed to inject script: {e}"))
 }
 
 /// Injects a script into the webview DOM.
 /// If a script with the same ID already exists, it is removed first.
-/// Returns window context for the response.
+/// Returns window context and the injection strategy for the response.
 fn inject_script_to_webview<R: Runtime>(
     app: &AppHandle<R>,
     entry: &ScriptEntry,
     window_label: Option<String>,
-) -> Result<ScriptOperationResult, String> {
+) -> Result<(ScriptOperationResult, InjectionStrategy), String> {
     let resolved = resolve_window_with_context(app, window_label)?;
 
-    inject_script_to_window(&resolved.window, entry)?;
+    let strategy = inject_script_to_window(&resolved.window, entry)?;
 
-    Ok(ScriptOperationResult {
-        window_context: resolved.context,
-    })
+    Ok((
+        ScriptOperationResult {
+            window_context: resolved.context,
+        },
+        strategy,
+    ))
+}
+
+/// Whether the page in the target window matches the entry's patterns.
+/// True when the window can't be resolved, so injecting reports the error.
+fn page_matches<R: Runtime>(
+    app: &AppHandle<R>,
+    entry: &ScriptEntry,
+    window_label: Option<String>,
+) -> bool {
+    match resolve_window_with_context(app, window_label) {
+        Ok(resolved) => resolved
+            .window
+            .url()
+            .map_or(true, |url| entry.matches_url(url.as_str())),
+        Err(_) => true,
+    }
 }
 
 /// Removes a script from a specific window's DOM.
@@ -950,23 +3123,36 @@ fn clear_scripts_from_webview<R: Runtime>(
     })
 }
 
-/// Injects all registered scripts into the webview.
+/// Injects the global scripts and the window's own scripts that match the
+/// page's URL into the webview.
 /// Called when a page loads to re-inject persistent scripts.
+/// Returns 0 without touching the webview while injection is paused.
 pub fn inject_all_scripts<R: Runtime>(
     app: &AppHandle<R>,
     window_label: Option<String>,
 ) -> Result<usize, String> {
     let registry: tauri::State<'_, SharedScriptRegistry> = app.state();
+    let resolved = resolve_window_with_context(app, window_label)?;
+    let label = resolved.context.window_label;
+
     let scripts: Vec<ScriptEntry> = {
         let reg = registry.lock().unwrap();
-        reg.get_all().iter().map(|e| (*e).clone()).collect()
+        if !reg.is_injection_enabled() {
+            return Ok(0);
+        }
+        let url = resolved.window.url().ok().map(|url| url.to_string());
+        reg.scripts_for_window(&label, url.as_deref())
     };
 
-    let resolved = resolve_window_with_context(app, window_label)?;
-
     for entry in &scripts {
         inject_script_to_window(&resolved.window, entry)?;
     }
 
+    registry.lock().unwrap().mark_injected(
+        &label,
+        scripts.iter().map(|s| s.id.as_str()),
+        current_timestamp(),
+    );
+
     Ok(scripts.len())
 }